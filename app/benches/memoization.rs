@@ -0,0 +1,70 @@
+//! Benchmark of the memoization of evaluation results
+//!
+//! Every example is typechecked and run once with memoization of definition reductions and
+//! closure applications, and once without. Since memoization must not change the result of
+//! evaluation, the elaborated modules and the results of both runs are compared as well.
+//!
+//! Run with `cargo bench --bench memoization`.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use driver::{Database, DEFAULT_FUEL};
+use elaborator::normalizer::evaluator::EvalConfig;
+use printer::Print;
+
+const EXAMPLES_PATH: &str = "../examples";
+
+/// Number of times each example is checked and run per configuration
+const ITERATIONS: u32 = 10;
+
+fn main() {
+    let runtime = tokio::runtime::Builder::new_multi_thread().enable_all().build().unwrap();
+
+    let mut examples: Vec<PathBuf> = std::fs::read_dir(EXAMPLES_PATH)
+        .expect("Could not read the examples")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "pol"))
+        .collect();
+    examples.sort();
+
+    println!("{:<30} {:>12} {:>12}", "example", "memoized", "uncached");
+    for example in examples {
+        let (memoized, memoized_output) = runtime.block_on(bench(&example, true));
+        let (uncached, uncached_output) = runtime.block_on(bench(&example, false));
+        assert_eq!(
+            memoized_output,
+            uncached_output,
+            "Memoization changes the result of {}",
+            example.display()
+        );
+        let name = example.file_name().unwrap().to_string_lossy();
+        println!("{name:<30} {memoized:>12.2?} {uncached:>12.2?}");
+    }
+}
+
+/// The average time it takes to check and run the module at `path`,
+/// together with the printed elaborated module and the result of running it
+async fn bench(path: &Path, memoize: bool) -> (Duration, String) {
+    let eval_config = EvalConfig { fuel: Some(DEFAULT_FUEL), memoize, ..Default::default() };
+    let mut total = Duration::ZERO;
+    let mut output = String::new();
+    for _ in 0..ITERATIONS {
+        let mut db = Database::from_path(path);
+        db.eval_config = eval_config.clone();
+        let uri = db.resolve_path(path).unwrap();
+
+        let started = Instant::now();
+        let result = db.run(&uri).await;
+        total += started.elapsed();
+
+        output = match (db.ast(&uri).await, result) {
+            (Ok(module), Ok(result)) => {
+                let result = result.map(|exp| exp.print_to_string(None)).unwrap_or_default();
+                format!("{}\n{result}", module.print_to_string(None))
+            }
+            (Err(err), _) | (_, Err(err)) => err.to_string(),
+        };
+    }
+    (total / ITERATIONS, output)
+}
@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 
 use driver::Database;
+use elaborator::normalizer::evaluator::EvalConfig;
 
 #[derive(clap::Args)]
 pub struct Args {
@@ -8,10 +9,21 @@ pub struct Args {
     filepath: PathBuf,
 }
 
-pub async fn exec(cmd: Args) -> miette::Result<()> {
+pub async fn exec(cmd: Args, eval_config: EvalConfig) -> miette::Result<()> {
     let mut db = Database::from_path(&cmd.filepath);
+    db.eval_config = eval_config;
     let uri = db.resolve_path(&cmd.filepath)?;
-    let _ = db.ast(&uri).await.map_err(|err| db.pretty_error(&uri, err))?;
+    let diagnostics = db.check_all(&uri).await;
+    let errors = diagnostics.iter().filter(|diagnostic| diagnostic.is_error()).count();
+    for diagnostic in diagnostics {
+        eprintln!("{:?}", db.pretty_error(&uri, diagnostic));
+    }
+    if errors > 0 {
+        return Err(miette::miette!(
+            "{} failed to typecheck with {errors} error(s)",
+            cmd.filepath.display()
+        ));
+    }
     println!("{} typechecked successfully!", cmd.filepath.display());
     Ok(())
 }
@@ -0,0 +1,23 @@
+use driver::{error_index, ErrorCode};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Only show the diagnostic with the given code, e.g. T-002
+    #[clap(value_name = "CODE")]
+    code: Option<String>,
+}
+
+pub async fn exec(cmd: Args) -> miette::Result<()> {
+    let index = error_index();
+    let codes: Vec<&ErrorCode> = match &cmd.code {
+        Some(code) => index.iter().filter(|err| err.code.eq_ignore_ascii_case(code)).collect(),
+        None => index.iter().collect(),
+    };
+    if let (Some(code), []) = (&cmd.code, codes.as_slice()) {
+        return Err(miette::miette!("Unknown diagnostic code {code}"));
+    }
+    for ErrorCode { code, message } in codes {
+        println!("{code}  {message}");
+    }
+    Ok(())
+}
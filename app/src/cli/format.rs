@@ -2,43 +2,44 @@ use std::fs::File;
 use std::path::PathBuf;
 
 use driver::Database;
+use elaborator::normalizer::evaluator::EvalConfig;
 use printer::{ColorChoice, Print, PrintCfg, StandardStream, WriteColor};
 
 use crate::utils::ignore_colors::IgnoreColors;
 
 #[derive(clap::Args)]
 pub struct Args {
-    #[clap(value_parser, value_name = "FILE")]
-    filepath: PathBuf,
-    #[clap(long)]
+    #[clap(value_parser, value_name = "FILE", required = true)]
+    filepaths: Vec<PathBuf>,
+    #[clap(long, conflicts_with_all = ["inplace", "check"])]
     width: Option<usize>,
-    #[clap(long, num_args = 0)]
+    #[clap(long, num_args = 0, conflicts_with_all = ["inplace", "check"])]
     omit_lambda_sugar: bool,
-    #[clap(long, num_args = 0)]
+    #[clap(long, num_args = 0, conflicts_with_all = ["inplace", "check"])]
     omit_function_sugar: bool,
+    #[clap(long, num_args = 0, conflicts_with_all = ["inplace", "check"])]
+    omit_nat_sugar: bool,
+    /// Format the files in place, using the same configuration as the LSP server
     #[clap(long, num_args = 0)]
     inplace: bool,
-    #[clap(long, default_value_t = 4)]
+    /// Only report which files are not formatted and fail if there are any
+    #[clap(long, num_args = 0, conflicts_with = "inplace")]
+    check: bool,
+    #[clap(long, default_value_t = 4, conflicts_with_all = ["inplace", "check"])]
     indent: isize,
-    #[clap(short, long, value_name = "FILE")]
+    #[clap(short, long, value_name = "FILE", conflicts_with_all = ["inplace", "check"])]
     output: Option<PathBuf>,
-    #[clap(long, num_args = 0)]
+    #[clap(long, num_args = 0, conflicts_with_all = ["inplace", "check"])]
     de_bruijn: bool,
     /// Print the typechecked instead of renamed syntax tree
-    #[clap(long, num_args = 0)]
+    #[clap(long, num_args = 0, conflicts_with_all = ["inplace", "check"])]
     checked: bool,
 }
 
 /// Compute the output stream for the "fmt" subcommand.
 /// If an output filepath is specified, then that filepath is used.
 /// Otherwise, the formatted output is printed on the terminal.
-/// If the --inplace flag is specified, then the input file is overwritten.
 fn compute_output_stream(cmd: &Args) -> Box<dyn WriteColor> {
-    if cmd.inplace {
-        return Box::new(IgnoreColors::new(
-            File::create(cmd.filepath.clone()).expect("Failed to create file"),
-        ));
-    }
     match &cmd.output {
         Some(path) => {
             Box::new(IgnoreColors::new(File::create(path).expect("Failed to create file")))
@@ -47,9 +48,17 @@ fn compute_output_stream(cmd: &Args) -> Box<dyn WriteColor> {
     }
 }
 
-pub async fn exec(cmd: Args) -> miette::Result<()> {
-    let mut db = Database::from_path(&cmd.filepath);
-    let uri = db.resolve_path(&cmd.filepath)?;
+pub async fn exec(cmd: Args, eval_config: EvalConfig) -> miette::Result<()> {
+    if cmd.inplace || cmd.check {
+        return format_files(&cmd.filepaths, cmd.check).await;
+    }
+
+    let [filepath] = cmd.filepaths.as_slice() else {
+        return Err(miette::miette!("Formatting multiple files requires --inplace or --check"));
+    };
+    let mut db = Database::from_path(filepath);
+    db.eval_config = eval_config;
+    let uri = db.resolve_path(filepath)?;
     let prg = if cmd.checked { db.ast(&uri).await } else { db.ust(&uri).await }
         .map_err(|err| db.pretty_error(&uri, err))?;
 
@@ -64,7 +73,10 @@ pub async fn exec(cmd: Args) -> miette::Result<()> {
         indent: cmd.indent,
         print_lambda_sugar: !cmd.omit_lambda_sugar,
         print_function_sugar: !cmd.omit_function_sugar,
+        print_nat_sugar: !cmd.omit_nat_sugar,
         print_metavar_ids: false,
+        use_color: true,
+        source_map: false,
     };
 
     print_prg(&prg, &cfg, &mut stream);
@@ -72,6 +84,38 @@ pub async fn exec(cmd: Args) -> miette::Result<()> {
     Ok(())
 }
 
+/// Format all files in place, or only report which files are not formatted if `check` is set.
+/// Files which cannot be formatted are left untouched and reported as skipped, together with
+/// the error which prevented formatting them.
+async fn format_files(filepaths: &[PathBuf], check: bool) -> miette::Result<()> {
+    let mut unformatted = 0;
+    for filepath in filepaths {
+        let mut db = Database::from_path(filepath);
+        let uri = db.resolve_path(filepath)?;
+        let changed = if check {
+            db.is_formatted(&uri).await.map(|formatted| !formatted)
+        } else {
+            db.format_on_save(&uri).await
+        };
+        match changed {
+            Ok(true) if check => {
+                unformatted += 1;
+                println!("Would reformat {}", filepath.display());
+            }
+            Ok(true) => println!("Reformatted {}", filepath.display()),
+            Ok(false) => {}
+            Err(err) => {
+                println!("Skipped {}", filepath.display());
+                eprintln!("{:?}", db.pretty_error(&uri, err));
+            }
+        }
+    }
+    if unformatted > 0 {
+        return Err(miette::miette!("{unformatted} file(s) are not formatted"));
+    }
+    Ok(())
+}
+
 fn print_prg<W: WriteColor>(prg: &ast::Module, cfg: &PrintCfg, stream: &mut W) {
     prg.print_colored(cfg, stream).expect("Failed to print to stdout");
     println!();
@@ -3,6 +3,7 @@ use std::io;
 use std::path::PathBuf;
 
 use driver::Database;
+use elaborator::normalizer::evaluator::EvalConfig;
 use printer::{Print, PrintCfg};
 
 #[derive(clap::Args)]
@@ -15,8 +16,9 @@ pub struct Args {
     output: Option<PathBuf>,
 }
 
-pub async fn exec(cmd: Args) -> miette::Result<()> {
+pub async fn exec(cmd: Args, eval_config: EvalConfig) -> miette::Result<()> {
     let mut db = Database::from_path(&cmd.filepath);
+    db.eval_config = eval_config;
     let uri = db.resolve_path(&cmd.filepath)?;
     let prg = db.lift(&uri, &cmd.r#type).await.map_err(miette::Report::msg)?;
 
@@ -1,8 +1,11 @@
 use clap::{Parser, Subcommand};
+use elaborator::normalizer::evaluator::EvalConfig;
+use elaborator::normalizer::order::EvaluationOrder;
 
 mod check;
 mod clean;
 mod doc;
+mod errors;
 mod format;
 mod gen_completions;
 mod lift;
@@ -25,19 +28,40 @@ pub fn exec() -> miette::Result<()> {
         builder.filter_level(log::LevelFilter::Info);
     }
 
+    let mut eval_config = EvalConfig { fuel: Some(driver::DEFAULT_FUEL), ..Default::default() };
+
+    // Tracing the normalization of individual declarations implies trace logging for the normalizer
+    if !cli.trace_decl.is_empty() {
+        builder.filter_module("elaborator::normalizer", log::LevelFilter::Trace);
+        eval_config.trace_filter = Some(cli.trace_decl.into_iter().collect());
+    }
+
+    if cli.no_memo {
+        eval_config.memoize = false;
+    }
+
+    if cli.opaque {
+        eval_config.opaque_by_default = true;
+    }
+
+    if cli.call_by_name {
+        eval_config.order = EvaluationOrder::CallByName;
+    }
+
     builder.init();
 
     use Command::*;
     let fut = async {
         match cli.command {
-            Run(args) => run::exec(args).await,
-            Check(args) => check::exec(args).await,
-            Fmt(args) => format::exec(args).await,
+            Run(args) => run::exec(args, eval_config).await,
+            Check(args) => check::exec(args, eval_config).await,
+            Fmt(args) => format::exec(args, eval_config).await,
             Texify(args) => texify::exec(args).await,
-            Xfunc(args) => xfunc::exec(args).await,
+            Xfunc(args) => xfunc::exec(args, eval_config).await,
             Lsp(args) => lsp::exec(args).await,
-            Lift(args) => lift::exec(args).await,
+            Lift(args) => lift::exec(args, eval_config).await,
             Doc(args) => doc::exec(args).await,
+            Errors(args) => errors::exec(args).await,
             Clean => clean::exec().await,
             GenerateCompletion(args) => gen_completions::exec(args).await,
         }
@@ -55,6 +79,18 @@ struct Cli {
     /// Enable debug logging
     #[clap(long)]
     debug: bool,
+    /// Only trace the normalization of the declarations with the given names
+    #[clap(long, value_name = "NAME")]
+    trace_decl: Vec<String>,
+    /// Disable the memoization of evaluation results, e.g. to compare the performance
+    #[clap(long)]
+    no_memo: bool,
+    /// Do not unfold toplevel (co)definitions during normalization unless they are transparent
+    #[clap(long)]
+    opaque: bool,
+    /// Evaluate the arguments of calls only when they are used instead of before the call
+    #[clap(long)]
+    call_by_name: bool,
     #[clap(subcommand)]
     command: Command,
 }
@@ -78,6 +114,8 @@ enum Command {
     Lift(lift::Args),
     /// Generate documentation for a file
     Doc(doc::Args),
+    /// List the codes of all diagnostics together with their messages
+    Errors(errors::Args),
     /// Clean target_pol directory
     Clean,
     /// Generate completion scripts for various shells
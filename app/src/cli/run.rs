@@ -4,6 +4,7 @@ use miette::Diagnostic;
 use thiserror::Error;
 
 use driver::Database;
+use elaborator::normalizer::evaluator::EvalConfig;
 use printer::{ColorChoice, Print, StandardStream};
 
 #[derive(clap::Args)]
@@ -12,8 +13,9 @@ pub struct Args {
     filepath: PathBuf,
 }
 
-pub async fn exec(cmd: Args) -> miette::Result<()> {
+pub async fn exec(cmd: Args, eval_config: EvalConfig) -> miette::Result<()> {
     let mut db = Database::from_path(&cmd.filepath);
+    db.eval_config = eval_config;
     let uri = db.resolve_path(&cmd.filepath)?;
     let nf = db.run(&uri).await.map_err(|err| db.pretty_error(&uri, err))?;
 
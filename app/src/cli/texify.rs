@@ -53,10 +53,15 @@ pub struct Args {
     omit_lambda_sugar: bool,
     #[clap(long, num_args = 0)]
     omit_function_sugar: bool,
+    #[clap(long, num_args = 0)]
+    omit_nat_sugar: bool,
     #[clap(long, default_value_t = 4)]
     indent: isize,
     #[clap(short, long, value_name = "FILE")]
     output: Option<PathBuf>,
+    /// Render the module in math notation as an `align*` environment
+    #[clap(long, num_args = 0)]
+    math: bool,
 }
 
 /// Compute the output stream for the "texify" subcommand.
@@ -88,9 +93,18 @@ pub async fn exec(cmd: Args) -> miette::Result<()> {
         indent: cmd.indent,
         print_lambda_sugar: !cmd.omit_lambda_sugar,
         print_function_sugar: !cmd.omit_function_sugar,
+        print_nat_sugar: !cmd.omit_nat_sugar,
         print_metavar_ids: false,
+        use_color: false,
+        source_map: false,
     };
 
+    if cmd.math {
+        let cfg = PrintCfg { omit_decl_sep: false, ..cfg };
+        stream.write_all(prg.print_to_latex(Some(&cfg)).as_bytes()).unwrap();
+        return Ok(());
+    }
+
     stream.write_all(latex_start(&cmd.fontsize).as_bytes()).unwrap();
     print_prg(&prg, &cfg, &mut stream);
     stream.write_all(LATEX_END.as_bytes()).unwrap();
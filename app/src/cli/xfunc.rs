@@ -3,6 +3,8 @@ use std::io;
 use std::path::PathBuf;
 
 use driver::{Database, Xfunc};
+use elaborator::normalizer::evaluator::EvalConfig;
+use printer::PrintCfg;
 
 #[derive(clap::Args)]
 pub struct Args {
@@ -12,12 +14,17 @@ pub struct Args {
     filepath: PathBuf,
     #[clap(short, long, value_name = "FILE")]
     output: Option<PathBuf>,
+    #[clap(long, value_name = "WIDTH")]
+    width: Option<usize>,
 }
 
-pub async fn exec(cmd: Args) -> miette::Result<()> {
+pub async fn exec(cmd: Args, eval_config: EvalConfig) -> miette::Result<()> {
     let mut db = Database::from_path(&cmd.filepath);
+    db.eval_config = eval_config;
     let uri = db.resolve_path(&cmd.filepath)?;
-    let Xfunc { edits, .. } = db.xfunc(&uri, &cmd.r#type).await.map_err(miette::Report::msg)?;
+    let cfg = PrintCfg { width: cmd.width.unwrap_or(printer::DEFAULT_WIDTH), ..Default::default() };
+    let Xfunc { edits, .. } =
+        db.xfunc(&uri, &cmd.r#type, Some(&cfg)).await.map_err(miette::Report::msg)?;
 
     let output = db.edited(&uri, edits);
 
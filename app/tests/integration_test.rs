@@ -34,7 +34,33 @@ fn run_command() {
     let mut cmd = Command::cargo_bin(BINARY).unwrap();
     let assert =
         cmd.env("NO_COLOR", "1").args(vec!["run", "../test/suites/success/037-vect.pol"]).assert();
-    assert
-        .success()
-        .stdout("Cons(S(S(S(Z))), Z, Cons(S(S(Z)), Z, Cons(S(Z), Z, Cons(Z, Z, Nil))))\n");
+    assert.success().stdout("Cons(3, 0, Cons(2, 0, Cons(1, 0, Cons(0, 0, Nil))))\n");
+}
+
+/// Check that "pol fmt --check" skips files which cannot be parsed
+#[test]
+fn fmt_check_skips_unparsable() {
+    let mut cmd = Command::cargo_bin(BINARY).unwrap();
+    let assert = cmd
+        .env("NO_COLOR", "1")
+        .args(vec!["fmt", "--check", "../test/suites/fail-parse/P-001.pol"])
+        .assert();
+    let assert = assert.success().stdout("Skipped ../test/suites/fail-parse/P-001.pol\n");
+    let stderr = String::from_utf8(assert.get_output().stderr.clone()).unwrap();
+    assert!(stderr.contains("P-005"));
+    assert!(stderr.contains("InvalidToken"));
+}
+
+/// Check that "pol --trace-decl" only traces the evaluation of the given declaration
+#[test]
+fn trace_decl_option() {
+    let mut cmd = Command::cargo_bin(BINARY).unwrap();
+    let assert = cmd
+        .env("NO_COLOR", "1")
+        .args(vec!["--trace-decl", "example1", "run", "../test/suites/success/037-vect.pol"])
+        .assert()
+        .success();
+    let stderr = String::from_utf8(assert.get_output().stderr.clone()).unwrap();
+    assert!(stderr.contains("Nil"));
+    assert!(!stderr.contains("append"));
 }
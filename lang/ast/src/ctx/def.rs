@@ -7,6 +7,7 @@ use crate::{Idx, Lvl, Var};
 use super::LevelCtx;
 
 #[derive(Debug, Clone, Default, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Eq, PartialEq, Hash)]
 pub struct GenericCtx<T> {
     pub bound: Vec<Vec<T>>,
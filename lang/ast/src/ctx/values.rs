@@ -35,7 +35,13 @@ impl TypeCtx {
             .bound
             .iter()
             .map(|stack| {
-                stack.iter().map(|b| Ok(Binder { name: b.name.clone(), typ: f(&b.typ)? })).collect()
+                stack
+                    .iter()
+                    .map(|b| {
+                        let value = b.value.as_deref().map(&f).transpose()?;
+                        Ok(Binder { name: b.name.clone(), typ: f(&b.typ)?, value })
+                    })
+                    .collect()
             })
             .collect();
 
@@ -95,13 +101,17 @@ impl Print for TypeCtx {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Binder {
     pub name: VarBind,
     pub typ: Box<Exp>,
+    /// The bound expression if the variable is bound by a local let
+    pub value: Option<Box<Exp>>,
 }
 
 impl Shift for Binder {
     fn shift_in_range<R: ShiftRange>(&mut self, range: &R, by: (isize, isize)) {
         self.typ.shift_in_range(range, by);
+        self.value.shift_in_range(range, by);
     }
 }
@@ -1,3 +1,7 @@
+use std::iter::Peekable;
+use std::slice;
+
+use codespan::ByteIndex;
 use codespan::Span;
 use derivative::Derivative;
 use pretty::DocAllocator;
@@ -6,16 +10,21 @@ use printer::theme::ThemeExt;
 use printer::tokens::CODATA;
 use printer::tokens::CODEF;
 use printer::tokens::COLON;
+use printer::tokens::COLONEQ;
 use printer::tokens::COMMA;
 use printer::tokens::DATA;
 use printer::tokens::DEF;
 use printer::tokens::DOT;
+use printer::tokens::FAT_ARROW;
 use printer::tokens::HASH;
 use printer::tokens::IMPLICIT;
+use printer::tokens::LAZY;
 use printer::tokens::LET;
 use printer::tokens::USE;
+use printer::tokens::WHERE;
 use printer::util::BracesExt;
 use printer::util::IsNilExt;
+use printer::util::SourceAnnoExt;
 use printer::Alloc;
 use printer::Builder;
 use printer::Print;
@@ -46,7 +55,8 @@ fn print_return_type<'a, T: Print>(
         .nest(cfg.indent)
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DocComment {
     pub docs: Vec<String>,
 }
@@ -65,6 +75,7 @@ impl Print for DocComment {
 
 /// A single attribute.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Attribute {
     /// Declarations with this annotation are omitted during prettyprinting.
     OmitPrint,
@@ -72,6 +83,11 @@ pub enum Attribute {
     Transparent,
     /// An opaque let-binding is not expanded during normalization.
     Opaque,
+    /// The termination checker skips (co)definitions with this annotation.
+    NoTerminationCheck,
+    /// (Co)definitions with this annotation are used from outside the program.
+    /// They are not reported as unused.
+    Entry,
     /// The compiler does not know about the meaning of this annotation.
     Other(String),
 }
@@ -82,6 +98,8 @@ impl Print for Attribute {
             Attribute::OmitPrint => alloc.text("omit_print"),
             Attribute::Opaque => alloc.text("opaque"),
             Attribute::Transparent => alloc.text("transparent"),
+            Attribute::NoTerminationCheck => alloc.text("no_termination_check"),
+            Attribute::Entry => alloc.text("entry"),
             Attribute::Other(s) => alloc.text(s),
         }
     }
@@ -89,7 +107,8 @@ impl Print for Attribute {
 
 /// An attribute can be attached to various nodes in the syntax tree.
 /// We use the same syntax for attributes as Rust, that is `#[attr1,attr2]`.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Attributes {
     pub attrs: Vec<Attribute>,
 }
@@ -124,6 +143,7 @@ impl Attributes {
 /// A metavariable is always annotated with a local context which specifies
 /// which free variables may occur in the solution.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MetaVarState {
     /// We know what the metavariable stands for.
     Solved { ctx: LevelCtx, solution: Box<Exp> },
@@ -154,8 +174,11 @@ impl MetaVarState {
 /// ```text
 /// use "Data/Bool.pol"
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derivative(Eq, PartialEq)]
 pub struct UseDecl {
+    #[derivative(PartialEq = "ignore")]
     pub span: Span,
     pub path: String,
 }
@@ -167,10 +190,39 @@ impl Print for UseDecl {
     }
 }
 
+/// A regular comment, i.e. a comment which is not a doc comment
+///
+/// ```text
+/// -- This is a comment
+/// ```
+///
+/// Comments are not attached to the syntax tree, but are printed with the declaration
+/// which follows them, or at the end of the line of the declaration which precedes them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Comment {
+    pub span: Span,
+    /// The text of the comment including the leading `--`
+    pub text: String,
+    /// Whether the comment follows other code on the same line
+    pub is_end_of_line: bool,
+    /// Whether the comment is followed by an empty line
+    pub blank_line_after: bool,
+}
+
+impl Print for Comment {
+    fn print<'a>(&'a self, _cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
+        alloc.comment(&self.text)
+    }
+}
+
 /// A module containing declarations
 ///
 /// There is a 1-1 correspondence between modules and files in our system.
-#[derive(Debug, Clone)]
+/// Modules are compared structurally, ignoring source locations and metavariable solutions.
+#[derive(Debug, Clone, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derivative(Eq, PartialEq)]
 pub struct Module {
     /// The location of the module on disk
     pub uri: Url,
@@ -179,7 +231,12 @@ pub struct Module {
     /// Declarations contained in the module other than imports.
     pub decls: Vec<Decl>,
     /// Metavariables that were generated for this module during lowering.
+    #[cfg_attr(feature = "serde", serde(with = "crate::map_as_seq"))]
+    #[derivative(PartialEq = "ignore")]
     pub meta_vars: HashMap<MetaVar, MetaVarState>,
+    /// Regular comments of the module, ordered by their location.
+    #[derivative(PartialEq = "ignore")]
+    pub comments: Vec<Comment>,
 }
 
 impl Module {
@@ -235,6 +292,24 @@ impl Module {
         self.decls.iter().find(|decl| decl.ident() == name)
     }
 
+    /// The symbols declared at the top level of the module, in source order
+    pub fn symbols(&self) -> impl Iterator<Item = Symbol<'_>> {
+        self.decls.iter().map(|decl| {
+            let xtors = match decl {
+                Decl::Data(data) => data.ctors.iter().map(|ctor| &ctor.name).collect(),
+                Decl::Codata(codata) => codata.dtors.iter().map(|dtor| &dtor.name).collect(),
+                Decl::Def(_) | Decl::Codef(_) | Decl::Let(_) => vec![],
+            };
+            Symbol {
+                name: decl.ident(),
+                kind: decl.kind(),
+                span: decl.span(),
+                hidden: !decl.attributes().is_visible(),
+                xtors,
+            }
+        })
+    }
+
     pub fn find_main(&self) -> Option<Box<Exp>> {
         self.decls.iter().find_map(|decl| match decl {
             Decl::Let(tl_let) if tl_let.is_main() => Some(tl_let.body.clone()),
@@ -245,12 +320,18 @@ impl Module {
 
 impl Print for Module {
     fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
-        let Module { use_decls, decls, .. } = self;
+        let Module { use_decls, decls, comments, .. } = self;
+        let mut comments = comments.iter().peekable();
 
         // UseDecls
         //
         //
 
+        // Comments before the first import form the header of the module
+        let header = match use_decls.first() {
+            Some(first) => print_comments_before(&mut comments, first.span.start(), cfg, alloc),
+            None => alloc.nil(),
+        };
         let use_decls =
             alloc.intersperse(use_decls.iter().map(|decl| decl.print(cfg, alloc)), alloc.line());
 
@@ -260,33 +341,125 @@ impl Print for Module {
 
         // We usually separate declarations with an empty line, except when the `omit_decl_sep` option is set.
         // This is useful for typesetting examples in papers which have to make economic use of vertical space.
-        let sep = if cfg.omit_decl_sep { alloc.line() } else { alloc.line().append(alloc.line()) };
+        let sep = || {
+            if cfg.omit_decl_sep {
+                alloc.line()
+            } else {
+                alloc.line().append(alloc.line())
+            }
+        };
 
-        let decls = decls
-            .iter()
-            .filter(|decl| decl.attributes().is_visible())
-            .map(|decl| decl.print(cfg, alloc));
+        // Declarations which were lifted from the `where` clause of another declaration
+        // are printed within that clause again.
+        let is_nested = |decl: &Decl| {
+            decl.ident()
+                .where_parent()
+                .is_some_and(|parent| decls.iter().any(|other| other.ident().id == parent))
+        };
+        let mut printed_decls = vec![];
+        for decl in decls.iter().filter(|decl| decl.attributes().is_visible() && !is_nested(decl)) {
+            let doc = print_with_where_decls(decl, decls, cfg, alloc);
+            let Some(span) = decl.span() else {
+                printed_decls.push(doc);
+                continue;
+            };
+            // Comments within a declaration are printed in front of it
+            let doc = print_comments_before(&mut comments, span.end(), cfg, alloc).append(doc);
+            let doc = match comments.next_if(|comment| comment.is_end_of_line) {
+                Some(comment) => doc.append(alloc.space()).append(comment.print(cfg, alloc)),
+                None => doc,
+            };
+            printed_decls.push(doc);
+        }
+        let has_decls = !printed_decls.is_empty();
+        let decls = alloc.intersperse(printed_decls, sep());
+
+        // Comments after the last declaration
+        let mut trailing = alloc.nil();
+        while let Some(comment) = comments.next() {
+            trailing = trailing.append(comment.print(cfg, alloc));
+            if comments.peek().is_some() {
+                trailing = trailing.append(alloc.hardline());
+                if comment.blank_line_after {
+                    trailing = trailing.append(alloc.hardline());
+                }
+            }
+        }
 
         // UseDecls + Decls
         //
         //
 
-        if use_decls.is_nil() {
-            alloc.intersperse(decls, sep)
+        let body = if use_decls.is_nil() {
+            decls
         } else {
-            use_decls
-                .append(alloc.line())
-                .append(alloc.line())
-                .append(alloc.intersperse(decls, sep))
+            use_decls.append(alloc.line()).append(alloc.line()).append(decls)
+        };
+        let body = if trailing.is_nil() {
+            body
+        } else if has_decls {
+            body.append(sep()).append(trailing)
+        } else {
+            body.append(trailing)
+        };
+        header.append(body)
+    }
+}
+
+/// Print the comments which start before `idx`, each on a line of its own
+fn print_comments_before<'a>(
+    comments: &mut Peekable<slice::Iter<'a, Comment>>,
+    idx: ByteIndex,
+    cfg: &PrintCfg,
+    alloc: &'a Alloc<'a>,
+) -> Builder<'a> {
+    let mut out = alloc.nil();
+    while let Some(comment) = comments.next_if(|comment| comment.span.start() < idx) {
+        out = out.append(comment.print(cfg, alloc)).append(alloc.hardline());
+        if comment.blank_line_after {
+            out = out.append(alloc.hardline());
         }
     }
+    out
+}
+
+/// Print a declaration together with the declarations of its `where` clause
+fn print_with_where_decls<'a>(
+    decl: &'a Decl,
+    decls: &'a [Decl],
+    cfg: &PrintCfg,
+    alloc: &'a Alloc<'a>,
+) -> Builder<'a> {
+    let where_decls: Vec<_> = decls
+        .iter()
+        .filter(|other| other.attributes().is_visible())
+        .filter(|other| other.ident().where_parent() == Some(decl.ident().id.as_str()))
+        .map(|other| print_with_where_decls(other, decls, cfg, alloc))
+        .collect();
+    if where_decls.is_empty() {
+        return decl.print(cfg, alloc);
+    }
+
+    let sep = if cfg.omit_decl_sep { alloc.line() } else { alloc.line().append(alloc.line()) };
+    let where_decls = alloc
+        .line()
+        .append(alloc.intersperse(where_decls, sep))
+        .nest(cfg.indent)
+        .append(alloc.line())
+        .braces_anno();
+    decl.print(cfg, alloc)
+        .append(alloc.space())
+        .append(alloc.keyword(WHERE))
+        .append(alloc.space())
+        .append(where_decls)
 }
 
 // Decl
 //
 //
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Decl {
     Data(Data),
     Codata(Codata),
@@ -325,7 +498,41 @@ impl From<Let> for Decl {
     }
 }
 
+/// The kind of a top-level declaration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeclKind {
+    Data,
+    Codata,
+    Def,
+    Codef,
+    Let,
+}
+
+/// A top-level declaration as listed by [`Module::symbols`]
+#[derive(Debug, Clone)]
+pub struct Symbol<'a> {
+    pub name: &'a IdBind,
+    pub kind: DeclKind,
+    pub span: Option<Span>,
+    /// Whether the declaration is annotated with `#[omit_print]`
+    pub hidden: bool,
+    /// The constructors of a data type or the destructors of a codata type, in source order.
+    /// Empty for all other declarations.
+    pub xtors: Vec<&'a IdBind>,
+}
+
 impl Decl {
+    /// The kind of the declaration.
+    pub fn kind(&self) -> DeclKind {
+        match self {
+            Decl::Data(_) => DeclKind::Data,
+            Decl::Codata(_) => DeclKind::Codata,
+            Decl::Def(_) => DeclKind::Def,
+            Decl::Codef(_) => DeclKind::Codef,
+            Decl::Let(_) => DeclKind::Let,
+        }
+    }
+
     /// A list of all attributes attached to the declaration.
     pub fn attributes(&self) -> &Attributes {
         match self {
@@ -371,13 +578,14 @@ impl HasSpan for Decl {
 
 impl Print for Decl {
     fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
-        match self {
+        let doc = match self {
             Decl::Data(data) => data.print(cfg, alloc),
             Decl::Codata(codata) => codata.print(cfg, alloc),
             Decl::Def(def) => def.print(cfg, alloc),
             Decl::Codef(codef) => codef.print(cfg, alloc),
             Decl::Let(tl_let) => tl_let.print(cfg, alloc),
-        }
+        };
+        doc.source_anno(cfg, self.span().map(|span| (span.start().0, span.end().0)))
     }
 }
 
@@ -409,8 +617,11 @@ impl ContainsMetaVars for Decl {
 //
 //
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derivative(Eq, PartialEq)]
 pub struct Data {
+    #[derivative(PartialEq = "ignore")]
     pub span: Option<Span>,
     pub doc: Option<DocComment>,
     pub name: IdBind,
@@ -477,8 +688,11 @@ impl ContainsMetaVars for Data {
 //
 //
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derivative(Eq, PartialEq)]
 pub struct Codata {
+    #[derivative(PartialEq = "ignore")]
     pub span: Option<Span>,
     pub doc: Option<DocComment>,
     pub name: IdBind,
@@ -545,8 +759,11 @@ impl ContainsMetaVars for Codata {
 //
 //
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derivative(Eq, PartialEq)]
 pub struct Ctor {
+    #[derivative(PartialEq = "ignore")]
     pub span: Option<Span>,
     pub doc: Option<DocComment>,
     pub name: IdBind,
@@ -559,7 +776,7 @@ impl Print for Ctor {
         let Ctor { span: _, doc, name, params, typ } = self;
 
         let doc = doc.print(cfg, alloc);
-        let head = alloc.ctor(&name.id).append(params.print(cfg, alloc));
+        let head = alloc.ctor(name.local_name()).append(params.print(cfg, alloc));
 
         let head = if typ.is_simple() {
             head
@@ -593,19 +810,25 @@ impl ContainsMetaVars for Ctor {
 //
 //
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derivative(Eq, PartialEq)]
 pub struct Dtor {
+    #[derivative(PartialEq = "ignore")]
     pub span: Option<Span>,
     pub doc: Option<DocComment>,
     pub name: IdBind,
     pub params: Telescope,
     pub self_param: SelfParam,
     pub ret_typ: Box<Exp>,
+    /// Default implementation which is used if a comatch does not contain a case for this
+    /// destructor. Only the parameters of the destructor are in scope, but not the self parameter.
+    pub default: Option<Box<Exp>>,
 }
 
 impl Print for Dtor {
     fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
-        let Dtor { span: _, doc, name, params, self_param, ret_typ } = self;
+        let Dtor { span: _, doc, name, params, self_param, ret_typ, default } = self;
 
         let doc = doc.print(cfg, alloc);
         let head = if self_param.is_simple() {
@@ -614,29 +837,42 @@ impl Print for Dtor {
             self_param.print(&PrintCfg { print_function_sugar: false, ..*cfg }, alloc).append(DOT)
         };
         let head = head
-            .append(alloc.dtor(&name.id))
+            .append(alloc.dtor(name.local_name()))
             .append(params.print(cfg, alloc))
-            .append(print_return_type(cfg, alloc, ret_typ))
-            .group();
-        doc.append(head)
+            .append(print_return_type(cfg, alloc, ret_typ));
+        let head = match default {
+            None => head,
+            Some(default) => head.append(alloc.space()).append(
+                alloc
+                    .text(FAT_ARROW)
+                    .append(alloc.line())
+                    .append(default.print(cfg, alloc))
+                    .nest(cfg.indent),
+            ),
+        };
+        doc.append(head.group())
     }
 }
 
 impl Zonk for Dtor {
     fn zonk(&mut self, meta_vars: &HashMap<MetaVar, MetaVarState>) -> Result<(), crate::ZonkError> {
-        let Dtor { span: _, doc: _, name: _, params, self_param, ret_typ } = self;
+        let Dtor { span: _, doc: _, name: _, params, self_param, ret_typ, default } = self;
         params.zonk(meta_vars)?;
         self_param.zonk(meta_vars)?;
         ret_typ.zonk(meta_vars)?;
+        default.zonk(meta_vars)?;
         Ok(())
     }
 }
 
 impl ContainsMetaVars for Dtor {
     fn contains_metavars(&self) -> bool {
-        let Dtor { span: _, doc: _, name: _, params, self_param, ret_typ } = self;
+        let Dtor { span: _, doc: _, name: _, params, self_param, ret_typ, default } = self;
 
-        params.contains_metavars() || self_param.contains_metavars() || ret_typ.contains_metavars()
+        params.contains_metavars()
+            || self_param.contains_metavars()
+            || ret_typ.contains_metavars()
+            || default.contains_metavars()
     }
 }
 
@@ -644,8 +880,11 @@ impl ContainsMetaVars for Dtor {
 //
 //
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derivative(Eq, PartialEq)]
 pub struct Def {
+    #[derivative(PartialEq = "ignore")]
     pub span: Option<Span>,
     pub doc: Option<DocComment>,
     pub name: IdBind,
@@ -665,6 +904,7 @@ impl Def {
             params: self.params.clone(),
             self_param: self.self_param.clone(),
             ret_typ: self.ret_typ.clone(),
+            default: None,
         }
     }
 }
@@ -683,7 +923,7 @@ impl Print for Def {
             .append(alloc.space())
             .append(self_param.print(cfg, alloc))
             .append(DOT)
-            .append(alloc.dtor(&name.id))
+            .append(alloc.dtor(name.local_name()))
             .append(params.print(cfg, alloc))
             .append(print_return_type(cfg, alloc, ret_typ))
             .group();
@@ -722,8 +962,11 @@ impl ContainsMetaVars for Def {
 //
 //
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derivative(Eq, PartialEq)]
 pub struct Codef {
+    #[derivative(PartialEq = "ignore")]
     pub span: Option<Span>,
     pub doc: Option<DocComment>,
     pub name: IdBind,
@@ -757,7 +1000,7 @@ impl Print for Codef {
         let head = alloc
             .keyword(CODEF)
             .append(alloc.space())
-            .append(alloc.ctor(&name.id))
+            .append(alloc.ctor(name.local_name()))
             .append(params.print(cfg, alloc))
             .append(print_return_type(
                 &PrintCfg { print_function_sugar: false, ..*cfg },
@@ -796,8 +1039,11 @@ impl ContainsMetaVars for Codef {
 //
 //
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derivative(Eq, PartialEq)]
 pub struct Let {
+    #[derivative(PartialEq = "ignore")]
     pub span: Option<Span>,
     pub doc: Option<DocComment>,
     pub name: IdBind,
@@ -826,7 +1072,7 @@ impl Print for Let {
         let head = alloc
             .keyword(LET)
             .append(alloc.space())
-            .append(&name.id)
+            .append(name.local_name())
             .append(params.print(cfg, alloc))
             .append(print_return_type(cfg, alloc, typ))
             .group();
@@ -859,8 +1105,11 @@ impl ContainsMetaVars for Let {
 //
 //
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derivative(Eq, PartialEq)]
 pub struct SelfParam {
+    #[derivative(PartialEq = "ignore")]
     pub info: Option<Span>,
     pub name: Option<VarBind>,
     pub typ: TypCtor,
@@ -871,8 +1120,10 @@ impl SelfParam {
         Telescope {
             params: vec![Param {
                 implicit: false,
+                lazy: false,
                 name: self.name.clone().unwrap_or_else(|| VarBind::from_string("")),
                 typ: Box::new(self.typ.to_exp()),
+                default: None,
             }],
         }
     }
@@ -928,6 +1179,7 @@ impl ContainsMetaVars for SelfParam {
 /// semantics. I.e. each parameter binding in the parameter list is in scope
 /// for the following parameters.
 #[derive(Debug, Clone, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Eq, PartialEq, Hash)]
 pub struct Telescope {
     pub params: Vec<Param>,
@@ -970,62 +1222,73 @@ impl Print for Telescope {
     ///
     /// 2) We cannot chunk two parameters if one is implicit and the other isn't, even if they have
     ///    the same type. For example: `implicit a: Type, b: Type` cannot be chunked.
+    ///    The same holds for lazy and strict parameters.
+    ///
+    /// 3) Parameters with a default value are never chunked.
     fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
         let Telescope { params } = self;
         let mut output = alloc.nil();
         if params.is_empty() {
             return output;
         };
-        // Running stands for the type and implicitness of the current "chunk" we are building.
-        let mut running: Option<(&Exp, bool)> = None;
-        for Param { implicit, name, typ } in params {
+        // Running stands for the type, implicitness, laziness and default value of the current
+        // "chunk" we are building.
+        let mut running: Option<(&Exp, bool, bool, &Option<Box<Exp>>)> = None;
+        for Param { implicit, lazy, name, typ, default } in params {
             match running {
                 // We need to shift before comparing to ensure we compare the correct De-Bruijn indices
-                Some((rtype, rimplicit))
-                    if shift_and_clone(rtype, (0, 1)) == **typ && rimplicit == *implicit =>
+                Some((rtype, rimplicit, rlazy, None))
+                    if shift_and_clone(rtype, (0, 1)) == **typ
+                        && rimplicit == *implicit
+                        && rlazy == *lazy
+                        && default.is_none() =>
                 {
                     // We are adding another parameter of the same type.
                     output = output.append(alloc.space()).append(alloc.text(&name.id));
                 }
-                Some((rtype, _)) => {
+                Some((rtype, _, _, rdefault)) => {
                     // We are adding another parameter with a different type,
                     // and have to close the previous list first.
-                    output = output
-                        .append(COLON)
-                        .append(alloc.space())
-                        .append(rtype.print(cfg, alloc))
+                    output =
+                        output.append(COLON).append(alloc.space()).append(rtype.print(cfg, alloc));
+                    output = print_default(output, rdefault, cfg, alloc)
                         .append(COMMA)
                         .append(alloc.line());
                     if *implicit {
-                        output = output
-                            .append(IMPLICIT)
-                            .append(alloc.space())
-                            .append(alloc.text(&name.id));
-                    } else {
-                        output = output.append(alloc.text(&name.id));
+                        output = output.append(IMPLICIT).append(alloc.space());
+                    }
+                    if *lazy {
+                        output = output.append(LAZY).append(alloc.space());
                     }
+                    output = output.append(alloc.text(&name.id));
                 }
                 None => {
                     // We are starting a new chunk and adding the very first parameter.
-                    // If we are starting a chunk of implicit parameters then we also have to
-                    // add the "implicit" keyword at this point.
+                    // If we are starting a chunk of implicit or lazy parameters then we also have to
+                    // add the "implicit" or "lazy" keyword at this point.
                     if *implicit {
                         output = output.append(IMPLICIT).append(alloc.space())
                     }
+                    if *lazy {
+                        output = output.append(LAZY).append(alloc.space())
+                    }
 
                     output = output.append(alloc.text(&name.id));
                 }
             }
-            running = Some((typ, *implicit));
+            running = Some((typ, *implicit, *lazy, default));
         }
         // Close the last parameter
         match running {
             None => {}
-            Some((rtype, _)) => {
+            Some((rtype, _, _, rdefault)) => {
                 output = output.append(COLON).append(alloc.space()).append(rtype.print(cfg, alloc));
+                output = print_default(output, rdefault, cfg, alloc);
             }
         }
-        output.append(alloc.line_()).align().parens().group()
+        // The telescope stays on a single line if it fits, otherwise every chunk is
+        // printed on its own line.
+        alloc.line_().append(output).nest(cfg.indent).append(alloc.line_()).parens().group()
     }
 }
 
@@ -1061,13 +1324,17 @@ mod print_telescope_tests {
     fn print_simple_chunk() {
         let param1 = Param {
             implicit: false,
+            lazy: false,
             name: VarBind::from_string("x"),
             typ: Box::new(TypeUniv::new().into()),
+            default: None,
         };
         let param2 = Param {
             implicit: false,
+            lazy: false,
             name: VarBind::from_string("y"),
             typ: Box::new(TypeUniv::new().into()),
+            default: None,
         };
         let tele = Telescope { params: vec![param1, param2] };
         assert_eq!(tele.print_to_string(Default::default()), "(x y: Type)")
@@ -1077,13 +1344,17 @@ mod print_telescope_tests {
     fn print_simple_implicit_chunk() {
         let param1 = Param {
             implicit: true,
+            lazy: false,
             name: VarBind::from_string("x"),
             typ: Box::new(TypeUniv::new().into()),
+            default: None,
         };
         let param2 = Param {
             implicit: true,
+            lazy: false,
             name: VarBind::from_string("y"),
             typ: Box::new(TypeUniv::new().into()),
+            default: None,
         };
         let tele = Telescope { params: vec![param1, param2] };
         assert_eq!(tele.print_to_string(Default::default()), "(implicit x y: Type)")
@@ -1093,13 +1364,17 @@ mod print_telescope_tests {
     fn print_mixed_implicit_chunk_1() {
         let param1 = Param {
             implicit: true,
+            lazy: false,
             name: VarBind::from_string("x"),
             typ: Box::new(TypeUniv::new().into()),
+            default: None,
         };
         let param2 = Param {
             implicit: false,
+            lazy: false,
             name: VarBind::from_string("y"),
             typ: Box::new(TypeUniv::new().into()),
+            default: None,
         };
         let tele = Telescope { params: vec![param1, param2] };
         assert_eq!(tele.print_to_string(Default::default()), "(implicit x: Type, y: Type)")
@@ -1109,27 +1384,61 @@ mod print_telescope_tests {
     fn print_mixed_implicit_chunk_2() {
         let param1 = Param {
             implicit: false,
+            lazy: false,
             name: VarBind::from_string("x"),
             typ: Box::new(TypeUniv::new().into()),
+            default: None,
         };
         let param2 = Param {
             implicit: true,
+            lazy: false,
             name: VarBind::from_string("y"),
             typ: Box::new(TypeUniv::new().into()),
+            default: None,
         };
         let tele = Telescope { params: vec![param1, param2] };
         assert_eq!(tele.print_to_string(Default::default()), "(x: Type, implicit y: Type)")
     }
 
+    #[test]
+    fn print_mixed_lazy_chunk() {
+        let param1 = Param {
+            implicit: false,
+            lazy: true,
+            name: VarBind::from_string("x"),
+            typ: Box::new(TypeUniv::new().into()),
+            default: None,
+        };
+        let param2 = Param {
+            implicit: false,
+            lazy: true,
+            name: VarBind::from_string("y"),
+            typ: Box::new(TypeUniv::new().into()),
+            default: None,
+        };
+        let param3 = Param {
+            implicit: false,
+            lazy: false,
+            name: VarBind::from_string("z"),
+            typ: Box::new(TypeUniv::new().into()),
+            default: None,
+        };
+        let tele = Telescope { params: vec![param1, param2, param3] };
+        assert_eq!(tele.print_to_string(Default::default()), "(lazy x y: Type, z: Type)")
+    }
+
     #[test]
     fn print_shifting_example() {
         let param1 = Param {
             implicit: false,
+            lazy: false,
             name: VarBind::from_string("a"),
             typ: Box::new(TypeUniv::new().into()),
+            default: None,
         };
         let param2 = Param {
             implicit: false,
+            lazy: false,
             name: VarBind::from_string("x"),
             typ: Box::new(Exp::Variable(Variable {
                 span: None,
@@ -1137,9 +1446,11 @@ mod print_telescope_tests {
                 name: VarBound::from_string("a"),
                 inferred_type: None,
             })),
+            default: None,
         };
         let param3 = Param {
             implicit: false,
+            lazy: false,
             name: VarBind::from_string("y"),
             typ: Box::new(Exp::Variable(Variable {
                 span: None,
@@ -1147,10 +1458,59 @@ mod print_telescope_tests {
                 name: VarBound::from_string("a"),
                 inferred_type: None,
             })),
+            default: None,
         };
         let tele = Telescope { params: vec![param1, param2, param3] };
         assert_eq!(tele.print_to_string(Default::default()), "(a: Type, x y: a)")
     }
+
+    #[test]
+    fn print_default() {
+        let param1 = Param {
+            implicit: false,
+            lazy: false,
+            name: VarBind::from_string("a"),
+            typ: Box::new(TypeUniv::new().into()),
+            default: None,
+        };
+        let param2 = Param {
+            implicit: false,
+            lazy: false,
+            name: VarBind::from_string("b"),
+            typ: Box::new(TypeUniv::new().into()),
+            default: Some(Box::new(Exp::Variable(Variable {
+                span: None,
+                idx: Idx { fst: 0, snd: 0 },
+                name: VarBound::from_string("a"),
+                inferred_type: None,
+            }))),
+        };
+        let tele = Telescope { params: vec![param1, param2] };
+        assert_eq!(tele.print_to_string(Default::default()), "(a: Type, b: Type := a)")
+    }
+
+    #[test]
+    fn print_exceeding_width() {
+        let param1 = Param {
+            implicit: false,
+            lazy: false,
+            name: VarBind::from_string("a"),
+            typ: Box::new(TypeUniv::new().into()),
+            default: None,
+        };
+        let param2 = Param {
+            implicit: true,
+            lazy: false,
+            name: VarBind::from_string("b"),
+            typ: Box::new(TypeUniv::new().into()),
+            default: None,
+        };
+        let tele = Telescope { params: vec![param1, param2] };
+        let cfg = PrintCfg { width: 80, ..Default::default() };
+        assert_eq!(tele.print_to_string(Some(&cfg)), "(a: Type, implicit b: Type)");
+        let cfg = PrintCfg { width: 10, ..Default::default() };
+        assert_eq!(tele.print_to_string(Some(&cfg)), "(\n    a: Type,\n    implicit b: Type\n)")
+    }
 }
 
 // Param
@@ -1158,50 +1518,83 @@ mod print_telescope_tests {
 //
 
 #[derive(Debug, Clone, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Eq, PartialEq, Hash)]
 pub struct Param {
     pub implicit: bool,
+    /// Whether the argument for this parameter is evaluated lazily.
+    /// This only has an effect on the parameters of constructors.
+    pub lazy: bool,
     #[derivative(PartialEq = "ignore", Hash = "ignore")]
     pub name: VarBind,
     pub typ: Box<Exp>,
+    /// The value which is used if the argument for this parameter is omitted.
+    /// It may only refer to the earlier parameters of the telescope.
+    pub default: Option<Box<Exp>>,
 }
 
 impl Substitutable for Param {
     type Result = Param;
     fn subst<S: Substitution>(&self, ctx: &mut LevelCtx, by: &S) -> Self {
-        let Param { implicit, name, typ } = self;
-        Param { implicit: *implicit, name: name.clone(), typ: typ.subst(ctx, by) }
+        let Param { implicit, lazy, name, typ, default } = self;
+        Param {
+            implicit: *implicit,
+            lazy: *lazy,
+            name: name.clone(),
+            typ: typ.subst(ctx, by),
+            default: default.subst(ctx, by),
+        }
     }
 }
 
 impl Print for Param {
     fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
-        let Param { implicit, name, typ } = self;
+        let Param { implicit, lazy, name, typ, default } = self;
+        let mut output = alloc.nil();
         if *implicit {
-            alloc
-                .text(IMPLICIT)
-                .append(alloc.space())
-                .append(&name.id)
-                .append(COLON)
-                .append(alloc.space())
-                .append(typ.print(cfg, alloc))
-        } else {
-            alloc.text(&name.id).append(COLON).append(alloc.space()).append(typ.print(cfg, alloc))
+            output = output.append(IMPLICIT).append(alloc.space());
+        }
+        if *lazy {
+            output = output.append(LAZY).append(alloc.space());
         }
+        output = output
+            .append(&name.id)
+            .append(COLON)
+            .append(alloc.space())
+            .append(typ.print(cfg, alloc));
+        print_default(output, default, cfg, alloc)
+    }
+}
+
+/// Append the default value ` := default` of a parameter, if any
+fn print_default<'a>(
+    output: Builder<'a>,
+    default: &'a Option<Box<Exp>>,
+    cfg: &PrintCfg,
+    alloc: &'a Alloc<'a>,
+) -> Builder<'a> {
+    match default {
+        Some(default) => output
+            .append(alloc.space())
+            .append(COLONEQ)
+            .append(alloc.space())
+            .append(default.print(cfg, alloc)),
+        None => output,
     }
 }
 
 impl Zonk for Param {
     fn zonk(&mut self, meta_vars: &HashMap<MetaVar, MetaVarState>) -> Result<(), crate::ZonkError> {
-        let Param { implicit: _, name: _, typ } = self;
-        typ.zonk(meta_vars)
+        let Param { implicit: _, lazy: _, name: _, typ, default } = self;
+        typ.zonk(meta_vars)?;
+        default.zonk(meta_vars)
     }
 }
 
 impl ContainsMetaVars for Param {
     fn contains_metavars(&self) -> bool {
-        let Param { implicit: _, name: _, typ } = self;
+        let Param { implicit: _, lazy: _, name: _, typ, default } = self;
 
-        typ.contains_metavars()
+        typ.contains_metavars() || default.contains_metavars()
     }
 }
@@ -1,16 +1,22 @@
+use std::collections::BTreeSet;
+
 use codespan::Span;
 use derivative::Derivative;
-use printer::{tokens::COLON, Alloc, Builder, Precedence, Print, PrintCfg};
+use pretty::DocAllocator;
+use printer::{
+    prec, tokens::COLON, util::ParensIfExt, Alloc, Builder, Precedence, Print, PrintCfg,
+};
 
 use crate::{
-    ctx::LevelCtx, ContainsMetaVars, HasSpan, HasType, Occurs, Shift, ShiftRange, Substitutable,
-    Substitution, Zonk, ZonkError,
+    ctx::LevelCtx, ContainsMetaVars, FreeVariables, HasSpan, HasType, Shift, ShiftRange,
+    Substitutable, Substitution, Zonk, ZonkError,
 };
 
-use super::{Exp, Lvl, MetaVar};
+use super::{Exp, Idx, MetaVar};
 
 /// Type annotated term `e : t`
 #[derive(Debug, Clone, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Eq, PartialEq, Hash)]
 pub struct Anno {
     /// Source code location
@@ -47,10 +53,11 @@ impl Shift for Anno {
     }
 }
 
-impl Occurs for Anno {
-    fn occurs(&self, ctx: &mut LevelCtx, lvl: Lvl) -> bool {
+impl FreeVariables for Anno {
+    fn collect_free_vars(&self, ctx: &mut LevelCtx, fvs: &mut BTreeSet<Idx>) {
         let Anno { exp, typ, .. } = self;
-        exp.occurs(ctx, lvl) || typ.occurs(ctx, lvl)
+        exp.collect_free_vars(ctx, fvs);
+        typ.collect_free_vars(ctx, fvs)
     }
 }
 
@@ -79,10 +86,15 @@ impl Print for Anno {
         &'a self,
         cfg: &PrintCfg,
         alloc: &'a Alloc<'a>,
-        _prec: Precedence,
+        prec: Precedence,
     ) -> Builder<'a> {
         let Anno { exp, typ, .. } = self;
-        exp.print(cfg, alloc).append(COLON).append(typ.print(cfg, alloc)).parens()
+        exp.print_prec(cfg, alloc, prec::OPS)
+            .append(alloc.space())
+            .append(COLON)
+            .append(alloc.space())
+            .append(typ.print_prec(cfg, alloc, prec::EXP))
+            .parens_if(prec > prec::EXP)
     }
 }
 
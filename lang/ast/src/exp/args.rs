@@ -1,17 +1,19 @@
+use std::collections::BTreeSet;
+
 use codespan::Span;
 use derivative::Derivative;
 use pretty::DocAllocator;
 use printer::{
     tokens::{COLONEQ, COMMA},
-    Alloc, Builder, Print, PrintCfg,
+    Alloc, Builder, Precedence, Print, PrintCfg,
 };
 
 use crate::{
-    ctx::LevelCtx, ContainsMetaVars, HasSpan, HasType, Occurs, Shift, ShiftRange, Substitutable,
-    Substitution, Zonk, ZonkError,
+    ctx::LevelCtx, ContainsMetaVars, FreeVariables, HasSpan, HasType, Shift, ShiftRange,
+    Substitutable, Substitution, Zonk, ZonkError,
 };
 
-use super::{Exp, Hole, Lvl, MetaVar, VarBound};
+use super::{Exp, Hole, Idx, MetaVar, VarBound};
 
 // Arg
 //
@@ -21,6 +23,7 @@ use super::{Exp, Hole, Lvl, MetaVar, VarBound};
 /// Example for named arguments: `f(x := 1, y := 2)`
 /// Example for unnamed arguments: `f(1, 2)`
 #[derive(Debug, Clone, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Eq, PartialEq, Hash)]
 pub enum Arg {
     UnnamedArg(Box<Exp>),
@@ -62,12 +65,12 @@ impl Shift for Arg {
     }
 }
 
-impl Occurs for Arg {
-    fn occurs(&self, ctx: &mut LevelCtx, lvl: Lvl) -> bool {
+impl FreeVariables for Arg {
+    fn collect_free_vars(&self, ctx: &mut LevelCtx, fvs: &mut BTreeSet<Idx>) {
         match self {
-            Arg::UnnamedArg(e) => e.occurs(ctx, lvl),
-            Arg::NamedArg(_, e) => e.occurs(ctx, lvl),
-            Arg::InsertedImplicitArg(hole) => hole.occurs(ctx, lvl),
+            Arg::UnnamedArg(e) => e.collect_free_vars(ctx, fvs),
+            Arg::NamedArg(_, e) => e.collect_free_vars(ctx, fvs),
+            Arg::InsertedImplicitArg(hole) => hole.collect_free_vars(ctx, fvs),
         }
     }
 }
@@ -94,9 +97,14 @@ impl Substitutable for Arg {
 }
 
 impl Print for Arg {
-    fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
+    fn print_prec<'a>(
+        &'a self,
+        cfg: &PrintCfg,
+        alloc: &'a Alloc<'a>,
+        prec: Precedence,
+    ) -> Builder<'a> {
         match self {
-            Arg::UnnamedArg(e) => e.print(cfg, alloc),
+            Arg::UnnamedArg(e) => e.print_prec(cfg, alloc, prec),
             Arg::NamedArg(i, e) => alloc.text(&i.id).append(COLONEQ).append(e.print(cfg, alloc)),
             Arg::InsertedImplicitArg(_) => {
                 panic!("Inserted implicit arguments should not be printed")
@@ -140,6 +148,7 @@ impl ContainsMetaVars for Arg {
 /// Unifiers are another example of context morphisms and applying a unifier to an expression mean substituting various terms,
 /// which are not necessarily part of a single argument list.
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Args {
     pub args: Vec<Arg>,
 }
@@ -171,26 +180,51 @@ impl Substitutable for Args {
     }
 }
 
+impl Args {
+    /// The arguments which are printed, in the order in which they were written
+    ///
+    /// The arguments are stored in the order of the parameters, but named arguments may have
+    /// been written in a different order. Named arguments are therefore printed in the order
+    /// of the spans of their names, while unnamed arguments keep their position.
+    fn printed_args(&self) -> Vec<&Arg> {
+        let mut args: Vec<&Arg> =
+            self.args.iter().filter(|arg| !arg.is_inserted_implicit()).collect();
+        let name_span = |arg: &Arg| match arg {
+            Arg::NamedArg(name, _) => name.span,
+            _ => None,
+        };
+        let named: Vec<usize> =
+            (0..args.len()).filter(|idx| matches!(args[*idx], Arg::NamedArg(..))).collect();
+        if named.iter().all(|idx| name_span(args[*idx]).is_some()) {
+            let mut sorted: Vec<&Arg> = named.iter().map(|idx| args[*idx]).collect();
+            sorted.sort_by_key(|arg| name_span(arg).map(|span| span.start()));
+            for (idx, arg) in named.into_iter().zip(sorted) {
+                args[idx] = arg;
+            }
+        }
+        args
+    }
+}
+
 impl Print for Args {
     fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
-        if !self.args.iter().any(|x| !x.is_inserted_implicit()) {
+        let args = self.printed_args();
+        if args.is_empty() {
             return alloc.nil();
         }
 
         let mut doc = alloc.nil();
         let mut first = true;
 
-        for arg in &self.args {
-            if !arg.is_inserted_implicit() {
-                if !first {
-                    doc = doc.append(COMMA).append(alloc.line());
-                }
-                doc = doc.append(arg.print(cfg, alloc));
-                first = false;
+        for arg in args {
+            if !first {
+                doc = doc.append(COMMA).append(alloc.line());
             }
+            doc = doc.append(arg.print(cfg, alloc));
+            first = false;
         }
 
-        doc.align().parens().group()
+        alloc.line_().append(doc).nest(cfg.indent).append(alloc.line_()).parens().group()
     }
 }
 
@@ -218,10 +252,11 @@ impl ContainsMetaVars for Args {
 
 #[cfg(test)]
 mod args_tests {
+    use codespan::Span;
     use printer::Print;
     use url::Url;
 
-    use crate::{Arg, Call, CallKind, Exp, Hole, IdBound, MetaVarKind};
+    use crate::{Arg, Call, CallKind, Exp, Hole, IdBound, MetaVarKind, VarBound};
 
     use super::Args;
 
@@ -265,6 +300,7 @@ mod args_tests {
         let hole: Hole = Hole {
             span: None,
             kind: MetaVarKind::Inserted,
+            name: None,
             metavar: crate::MetaVar { span: None, kind: crate::MetaVarKind::Inserted, id: 42 },
             inferred_type: None,
             inferred_ctx: None,
@@ -277,4 +313,37 @@ mod args_tests {
             "".to_string()
         )
     }
+    #[test]
+    fn print_named_args_in_written_order() {
+        let ctor = |id: &str| -> Box<Exp> {
+            Box::new(
+                Call {
+                    span: None,
+                    kind: CallKind::Constructor,
+                    name: IdBound {
+                        span: None,
+                        id: id.to_owned(),
+                        uri: Url::parse("inmemory:///scratch.pol").unwrap(),
+                    },
+                    args: Args { args: vec![] },
+                    inferred_type: None,
+                }
+                .into(),
+            )
+        };
+        let name = |id: &str, start: u32| VarBound {
+            span: Some(Span::new(start, start + 1)),
+            id: id.to_owned(),
+        };
+
+        // Written as `(T, z := V, y := U)`, stored in the order of the parameters
+        let args = Args {
+            args: vec![
+                Arg::UnnamedArg(ctor("T")),
+                Arg::NamedArg(name("y", 12), ctor("U")),
+                Arg::NamedArg(name("z", 4), ctor("V")),
+            ],
+        };
+        assert_eq!(args.print_to_string(Default::default()), "(T, z:=V, y:=U)".to_string())
+    }
 }
@@ -1,19 +1,25 @@
+use std::collections::BTreeSet;
+
 use codespan::Span;
 use derivative::Derivative;
+use pretty::DocAllocator;
+use printer::tokens::COMMA;
 use printer::{theme::ThemeExt, Alloc, Builder, Precedence, Print, PrintCfg};
+use url::Url;
 
 use crate::{
-    ctx::LevelCtx, ContainsMetaVars, HasSpan, HasType, Occurs, Shift, ShiftRange, Substitutable,
-    Substitution, Zonk, ZonkError,
+    ctx::LevelCtx, ContainsMetaVars, FreeVariables, HasSpan, HasType, Shift, ShiftRange,
+    Substitutable, Substitution, Zonk, ZonkError,
 };
 
-use super::{Args, Exp, IdBound, Lvl, MetaVar};
+use super::{Arg, Args, Exp, IdBound, Idx, MetaVar, MetaVarKind};
 
 /// A Call expression can be one of three different kinds:
 /// - A constructor introduced by a data type declaration
 /// - A codefinition introduced at the toplevel
 /// - A LetBound definition introduced at the toplevel
 #[derive(Debug, Clone, Copy, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Eq, PartialEq, Hash)]
 pub enum CallKind {
     Constructor,
@@ -24,6 +30,7 @@ pub enum CallKind {
 /// A Call invokes a constructor, a codefinition or a toplevel let-bound definition.
 /// Examples: `Zero`, `Cons(True, Nil)`, `minimum(x,y)`
 #[derive(Debug, Clone, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Eq, PartialEq, Hash)]
 pub struct Call {
     /// Source code location
@@ -43,6 +50,205 @@ pub struct Call {
     pub inferred_type: Option<Box<Exp>>,
 }
 
+impl Call {
+    /// The character denoted by `self`, if it is of the form `MkChar(n)`
+    ///
+    /// This is the form into which character literals are desugared during lowering.
+    /// The code point `n` is a binary number `B1(B0(...BNil))`, least significant bit first,
+    /// whose constructors are declared in the same module as `MkChar`.
+    pub fn as_char_lit(&self) -> Option<char> {
+        if !self.is_of_type("Char") {
+            return None;
+        }
+        match (self.kind, self.name.id.as_str(), self.args.args.as_slice()) {
+            (CallKind::Constructor, "MkChar", [Arg::UnnamedArg(code)]) => {
+                char::from_u32(bin_lit(code, &self.name.uri)?)
+            }
+            _ => None,
+        }
+    }
+
+    /// The natural number denoted by `self`, if it is of the form `S(...S(Z))`
+    ///
+    /// This is the form into which numeric literals are desugared during lowering.
+    /// `S` and `Z` have to be constructors of the data type `Nat` declared in the same module.
+    pub fn as_nat_lit(&self) -> Option<u64> {
+        let mut n: u64 = 0;
+        let mut call = self;
+        loop {
+            if call.kind != CallKind::Constructor
+                || call.name.uri != self.name.uri
+                || !call.is_of_type("Nat")
+            {
+                return None;
+            }
+            match (call.name.id.as_str(), call.args.args.as_slice()) {
+                ("Z", []) => return Some(n),
+                ("S", [Arg::UnnamedArg(pred)]) => {
+                    let Exp::Call(pred) = &**pred else {
+                        return None;
+                    };
+                    n = n.checked_add(1)?;
+                    call = pred;
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    /// The elements of the tuple denoted by `self`, if it is of the form `MkPair(_, _, x, y)`
+    ///
+    /// This is the form into which tuples `(x, y)` are desugared during lowering.
+    /// Pairs nested in the second component are flattened, i.e. the elements of
+    /// `MkPair(_, _, x, MkPair(_, _, y, z))` are `x`, `y` and `z`.
+    pub fn as_tuple(&self) -> Option<Vec<&Exp>> {
+        let (fst, mut snd) = as_pair(self)?;
+        let mut elems = vec![fst];
+        while let Some((x, y)) = match snd {
+            Exp::Call(call) => as_pair(call),
+            _ => None,
+        } {
+            elems.push(x);
+            snd = y;
+        }
+        elems.push(snd);
+        Some(elems)
+    }
+
+    /// The elements of the list denoted by `self`, if it is of the form
+    /// `Cons(_, x1, ... Cons(_, xn, Nil(_)))`
+    ///
+    /// This is the form into which list literals `[x1, ..., xn]` are desugared during lowering.
+    /// `Nil` and `Cons` have to be constructors of the data type `List` declared in the same
+    /// module.
+    pub fn as_list_lit(&self) -> Option<Vec<&Exp>> {
+        let mut elems = vec![];
+        let mut call = self;
+        loop {
+            if call.kind != CallKind::Constructor
+                || call.name.uri != self.name.uri
+                || !call.is_of_type("List")
+            {
+                return None;
+            }
+            match (call.name.id.as_str(), call.args.args.as_slice()) {
+                ("Nil", type_args) if is_list_type_args(type_args) => return Some(elems),
+                ("Cons", [type_args @ .., Arg::UnnamedArg(head), Arg::UnnamedArg(tail)])
+                    if is_list_type_args(type_args) =>
+                {
+                    let Exp::Call(tail) = &**tail else {
+                        return None;
+                    };
+                    elems.push(&**head);
+                    call = tail;
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    /// The string denoted by `self`, if it is of the form `SCons(c1, ... SCons(cn, SNil))`
+    ///
+    /// This is the form into which string literals are desugared during lowering.
+    pub fn as_str_lit(&self) -> Option<String> {
+        let mut out = String::new();
+        let mut call = self;
+        loop {
+            if call.kind != CallKind::Constructor
+                || call.name.uri != self.name.uri
+                || !call.is_of_type("String")
+            {
+                return None;
+            }
+            match (call.name.id.as_str(), call.args.args.as_slice()) {
+                ("SNil", []) => return Some(out),
+                ("SCons", [Arg::UnnamedArg(head), Arg::UnnamedArg(tail)]) => {
+                    let (Exp::Call(head), Exp::Call(tail)) = (&**head, &**tail) else {
+                        return None;
+                    };
+                    out.push(head.as_char_lit()?);
+                    call = tail;
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    /// Whether `self` is known to be of the data type `name` declared in the same module
+    ///
+    /// The type is known after elaboration and for literals desugared during lowering.
+    fn is_of_type(&self, name: &str) -> bool {
+        match self.inferred_type.as_deref() {
+            Some(Exp::TypCtor(typ)) => typ.name.id == name && typ.name.uri == self.name.uri,
+            _ => false,
+        }
+    }
+}
+
+/// The components of `call`, if it is of the form `MkPair(_, .., x, y)`
+///
+/// All explicit arguments except for the components have to be holes `_`, such that
+/// the components alone determine the pair.
+fn as_pair(call: &Call) -> Option<(&Exp, &Exp)> {
+    if call.kind != CallKind::Constructor || call.name.id != "MkPair" {
+        return None;
+    }
+    let [type_args @ .., Arg::UnnamedArg(fst), Arg::UnnamedArg(snd)] = call.args.args.as_slice()
+    else {
+        return None;
+    };
+    type_args.iter().all(is_hole).then_some((fst, snd))
+}
+
+/// Whether `arg` is a hole `_` which is left to be inferred
+fn is_hole(arg: &Arg) -> bool {
+    match arg {
+        Arg::UnnamedArg(exp) => {
+            matches!(&**exp, Exp::Hole(hole) if hole.kind == MetaVarKind::MustSolve)
+        }
+        Arg::NamedArg(..) => false,
+        Arg::InsertedImplicitArg(_) => true,
+    }
+}
+
+/// Whether `args` are the type arguments of a list literal, i.e. at least one hole `_`
+///
+/// Lists of monomorphic element types are never printed as list literals, since their
+/// constructors `Nil` and `Cons` are too common to be identified with the list data type.
+fn is_list_type_args(args: &[Arg]) -> bool {
+    !args.is_empty() && args.iter().all(is_hole)
+}
+
+/// The value of the binary number `exp`, if it is of the form `B1(B0(...BNil))`
+///
+/// All constructors have to be declared in the module `uri`. Only the canonical form without
+/// leading zeros, i.e. without `B0(BNil)`, denotes a value, such that each value has exactly
+/// one form.
+fn bin_lit(exp: &Exp, uri: &Url) -> Option<u32> {
+    let mut bits = vec![];
+    let mut exp = exp;
+    loop {
+        let Exp::Call(Call { kind: CallKind::Constructor, name, args, .. }) = exp else {
+            return None;
+        };
+        if name.uri != *uri {
+            return None;
+        }
+        let (bit, rest) = match (name.id.as_str(), args.args.as_slice()) {
+            ("BNil", []) => break,
+            ("B0", [Arg::UnnamedArg(rest)]) => (false, rest),
+            ("B1", [Arg::UnnamedArg(rest)]) => (true, rest),
+            _ => return None,
+        };
+        bits.push(bit);
+        exp = rest;
+    }
+    if bits.last() == Some(&false) || bits.len() > u32::BITS as usize {
+        return None;
+    }
+    Some(bits.into_iter().rev().fold(0, |n, bit| (n << 1) | u32::from(bit)))
+}
+
 impl HasSpan for Call {
     fn span(&self) -> Option<Span> {
         self.span
@@ -62,10 +268,10 @@ impl Shift for Call {
     }
 }
 
-impl Occurs for Call {
-    fn occurs(&self, ctx: &mut LevelCtx, lvl: Lvl) -> bool {
+impl FreeVariables for Call {
+    fn collect_free_vars(&self, ctx: &mut LevelCtx, fvs: &mut BTreeSet<Idx>) {
         let Call { args, .. } = self;
-        args.args.iter().any(|arg| arg.occurs(ctx, lvl))
+        args.args.collect_free_vars(ctx, fvs)
     }
 }
 
@@ -96,8 +302,42 @@ impl Print for Call {
         alloc: &'a Alloc<'a>,
         _prec: Precedence,
     ) -> Builder<'a> {
+        // Print the desugaring of numeric, character and string literals as literals
+        if let Some(n) = self.as_nat_lit().filter(|_| cfg.print_nat_sugar) {
+            return alloc.text(n.to_string());
+        }
+        if let Some(c) = self.as_char_lit() {
+            return alloc.text(format!("'{}'", c.escape_debug()));
+        }
+        if let Some(s) = self.as_str_lit() {
+            return alloc.text(format!("\"{}\"", s.escape_debug()));
+        }
+        // Print the desugaring of tuples as tuples
+        if let Some(elems) = self.as_tuple() {
+            let elems = elems.into_iter().map(|elem| elem.print(cfg, alloc));
+            let doc = alloc.intersperse(elems, alloc.text(COMMA).append(alloc.line()));
+            return alloc
+                .line_()
+                .append(doc)
+                .nest(cfg.indent)
+                .append(alloc.line_())
+                .parens()
+                .group();
+        }
+        // Print the desugaring of list literals as list literals
+        if let Some(elems) = self.as_list_lit() {
+            let elems = elems.into_iter().map(|elem| elem.print(cfg, alloc));
+            let doc = alloc.intersperse(elems, alloc.text(COMMA).append(alloc.line()));
+            return alloc
+                .line_()
+                .append(doc)
+                .nest(cfg.indent)
+                .append(alloc.line_())
+                .brackets()
+                .group();
+        }
         let Call { name, args, .. } = self;
-        alloc.ctor(&name.id).append(args.print(cfg, alloc))
+        alloc.ctor(name.local_name()).append(args.print(cfg, alloc))
     }
 }
 
@@ -120,3 +360,161 @@ impl ContainsMetaVars for Call {
         args.contains_metavars() || inferred_type.contains_metavars()
     }
 }
+
+#[cfg(test)]
+mod print_lit_tests {
+    use super::*;
+    use crate::test_support::{call, ctor, id};
+    use crate::{Hole, TypCtor};
+
+    fn bin(n: u32) -> Box<Exp> {
+        match n {
+            0 => ctor("BNil", vec![]),
+            n if n % 2 == 0 => ctor("B0", vec![bin(n / 2)]),
+            n => ctor("B1", vec![bin(n / 2)]),
+        }
+    }
+
+    /// The constructor call `name(args)` whose type is known to be the data type `typ`
+    fn typed_ctor(name: &str, args: Vec<Box<Exp>>, typ: &str) -> Box<Exp> {
+        let mut exp = ctor(name, args);
+        let Exp::Call(call) = &mut *exp else { unreachable!() };
+        call.inferred_type = Some(Box::new(
+            TypCtor { span: None, name: id(typ), args: Args { args: vec![] } }.into(),
+        ));
+        exp
+    }
+
+    fn char_lit(c: char) -> Box<Exp> {
+        typed_ctor("MkChar", vec![bin(c as u32)], "Char")
+    }
+
+    fn str_lit(s: &str) -> Box<Exp> {
+        s.chars().rev().fold(typed_ctor("SNil", vec![], "String"), |tail, c| {
+            typed_ctor("SCons", vec![char_lit(c), tail], "String")
+        })
+    }
+
+    #[test]
+    fn print_char_lit() {
+        assert_eq!(char_lit('a').print_to_string(None), "'a'");
+        assert_eq!(char_lit('\'').print_to_string(None), r"'\''");
+        assert_eq!(char_lit('\u{10FFFF}').print_to_string(None), r"'\u{10ffff}'");
+    }
+
+    #[test]
+    fn print_char_lit_not_literal() {
+        let exp = typed_ctor("MkChar", vec![ctor("B0", vec![ctor("BNil", vec![])])], "Char");
+        assert_eq!(exp.print_to_string(None), "MkChar(B0(BNil))");
+        let exp = ctor("MkChar", vec![bin(97)]);
+        assert_eq!(exp.print_to_string(None), "MkChar(B1(B0(B0(B0(B0(B1(B1(BNil))))))))");
+        let mut exp = char_lit('a');
+        let Exp::Call(call) = &mut *exp else { unreachable!() };
+        call.name.uri = Url::parse("inmemory:///other.pol").unwrap();
+        assert_eq!(exp.print_to_string(None), "MkChar(B1(B0(B0(B0(B0(B1(B1(BNil))))))))");
+    }
+
+    #[test]
+    fn print_str_lit() {
+        assert_eq!(str_lit("a\"b\n").print_to_string(None), r#""a\"b\n""#);
+        assert_eq!(str_lit("").print_to_string(None), r#""""#);
+    }
+
+    #[test]
+    fn print_str_lit_not_literal() {
+        let exp = typed_ctor("SCons", vec![ctor("T", vec![]), str_lit("")], "String");
+        assert_eq!(exp.print_to_string(None), r#"SCons(T, "")"#);
+    }
+
+    fn nat_lit(n: u32) -> Box<Exp> {
+        (0..n).fold(typed_ctor("Z", vec![], "Nat"), |n, _| typed_ctor("S", vec![n], "Nat"))
+    }
+
+    #[test]
+    fn print_nat_lit() {
+        assert_eq!(nat_lit(0).print_to_string(None), "0");
+        assert_eq!(nat_lit(3).print_to_string(None), "3");
+        let cfg = PrintCfg { print_nat_sugar: false, ..Default::default() };
+        assert_eq!(nat_lit(2).print_to_string(Some(&cfg)), "S(S(Z))");
+    }
+
+    #[test]
+    fn print_nat_lit_not_literal() {
+        let exp = typed_ctor("S", vec![typed_ctor("S", vec![ctor("T", vec![])], "Nat")], "Nat");
+        assert_eq!(exp.print_to_string(None), "S(S(T))");
+        let exp = call(CallKind::Codefinition, "S", vec![nat_lit(0)]);
+        assert_eq!(exp.print_to_string(None), "S(0)");
+        // The type of `S` and `Z` is unknown
+        let exp = ctor("S", vec![ctor("Z", vec![])]);
+        assert_eq!(exp.print_to_string(None), "S(Z)");
+        // `S` and `Z` are constructors of a data type other than `Nat`
+        let exp = typed_ctor("S", vec![typed_ctor("Z", vec![], "Peano")], "Peano");
+        assert_eq!(exp.print_to_string(None), "S(Z)");
+    }
+
+    fn hole() -> Box<Exp> {
+        let kind = MetaVarKind::MustSolve;
+        Box::new(Exp::Hole(Hole {
+            span: None,
+            kind,
+            name: None,
+            metavar: MetaVar { span: None, kind, id: 0 },
+            inferred_type: None,
+            inferred_ctx: None,
+            args: vec![],
+            solution: None,
+        }))
+    }
+
+    fn pair(fst: Box<Exp>, snd: Box<Exp>) -> Box<Exp> {
+        ctor("MkPair", vec![hole(), hole(), fst, snd])
+    }
+
+    #[test]
+    fn print_tuple() {
+        let t = || ctor("T", vec![]);
+        assert_eq!(pair(t(), pair(t(), t())).print_to_string(None), "(T, T, T)");
+        assert_eq!(pair(pair(t(), t()), t()).print_to_string(None), "((T, T), T)");
+    }
+
+    #[test]
+    fn print_tuple_not_sugar() {
+        let exp = ctor("MkPair", vec![ctor("Bool", vec![]), hole(), ctor("T", vec![]), hole()]);
+        assert_eq!(exp.print_to_string(None), "MkPair(Bool, _, T, _)");
+    }
+
+    fn list(elems: Vec<Box<Exp>>) -> Box<Exp> {
+        elems.into_iter().rev().fold(typed_ctor("Nil", vec![hole()], "List"), |tail, head| {
+            typed_ctor("Cons", vec![hole(), head, tail], "List")
+        })
+    }
+
+    #[test]
+    fn print_list_lit() {
+        let t = || ctor("T", vec![]);
+        assert_eq!(list(vec![t(), t()]).print_to_string(None), "[T, T]");
+        assert_eq!(list(vec![list(vec![t()])]).print_to_string(None), "[[T]]");
+        assert_eq!(list(vec![]).print_to_string(None), "[]");
+    }
+
+    #[test]
+    fn print_list_lit_not_sugar() {
+        let exp = ctor("Cons", vec![ctor("T", vec![]), ctor("Nil", vec![])]);
+        assert_eq!(exp.print_to_string(None), "Cons(T, Nil)");
+        // The type of `Cons` and `Nil` is unknown
+        let exp = ctor("Cons", vec![hole(), ctor("T", vec![]), ctor("Nil", vec![hole()])]);
+        assert_eq!(exp.print_to_string(None), "Cons(_, T, Nil(_))");
+        // `Cons` and `Nil` are constructors of a data type other than `List`
+        let exp = typed_ctor(
+            "Cons",
+            vec![hole(), ctor("T", vec![]), typed_ctor("Nil", vec![hole()], "Stream")],
+            "Stream",
+        );
+        assert_eq!(exp.print_to_string(None), "Cons(_, T, Nil(_))");
+        // `Cons` and `Nil` are declared in another module
+        let mut exp = list(vec![ctor("T", vec![])]);
+        let Exp::Call(call) = &mut *exp else { unreachable!() };
+        call.name.uri = Url::parse("inmemory:///other.pol").unwrap();
+        assert_eq!(exp.print_to_string(None), "Cons(_, T, [])");
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::BTreeSet;
+
 use codespan::Span;
 use derivative::Derivative;
 use pretty::DocAllocator;
@@ -10,17 +12,18 @@ use printer::{
 
 use crate::{
     ctx::{BindContext, LevelCtx},
-    ContainsMetaVars, Occurs, Shift, ShiftRange, ShiftRangeExt, Substitutable, Substitution, Zonk,
-    ZonkError,
+    ContainsMetaVars, FreeVariables, Shift, ShiftRange, ShiftRangeExt, Substitutable, Substitution,
+    Zonk, ZonkError,
 };
 
-use super::{Exp, IdBound, Lvl, MetaVar, TelescopeInst};
+use super::{Exp, IdBound, Idx, MetaVar, TelescopeInst};
 
 // Pattern
 //
 //
 
 #[derive(Debug, Clone, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Eq, PartialEq, Hash)]
 pub struct Pattern {
     pub is_copattern: bool,
@@ -32,9 +35,9 @@ impl Print for Pattern {
     fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
         let Pattern { is_copattern, name, params } = self;
         if *is_copattern {
-            alloc.text(DOT).append(alloc.ctor(&name.id)).append(params.print(cfg, alloc))
+            alloc.text(DOT).append(alloc.ctor(name.local_name())).append(params.print(cfg, alloc))
         } else {
-            alloc.ctor(&name.id).append(params.print(cfg, alloc))
+            alloc.ctor(name.local_name()).append(params.print(cfg, alloc))
         }
     }
 }
@@ -54,6 +57,7 @@ impl Zonk for Pattern {
 //
 
 #[derive(Debug, Clone, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Eq, PartialEq, Hash)]
 pub struct Case {
     #[derivative(PartialEq = "ignore", Hash = "ignore")]
@@ -69,10 +73,12 @@ impl Shift for Case {
     }
 }
 
-impl Occurs for Case {
-    fn occurs(&self, ctx: &mut LevelCtx, lvl: Lvl) -> bool {
+impl FreeVariables for Case {
+    fn collect_free_vars(&self, ctx: &mut LevelCtx, fvs: &mut BTreeSet<Idx>) {
         let Case { pattern, body, .. } = self;
-        ctx.bind_iter(pattern.params.params.iter().map(|_| ()), |ctx| body.occurs(ctx, lvl))
+        ctx.bind_iter(pattern.params.params.iter().map(|_| ()), |ctx| {
+            body.collect_free_vars(ctx, fvs)
+        })
     }
 }
 
@@ -126,6 +132,10 @@ impl Zonk for Case {
     }
 }
 
+/// Print a block of cases
+///
+/// A single case stays on the same line as the braces if it fits within the configured width.
+/// Several cases are always printed on separate lines.
 pub fn print_cases<'a>(cases: &'a [Case], cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
     match cases.len() {
         0 => empty_braces(alloc),
@@ -156,3 +166,40 @@ impl ContainsMetaVars for Case {
         body.contains_metavars()
     }
 }
+
+#[cfg(test)]
+mod print_cases_tests {
+    use crate::test_support::{self, ctor, pattern};
+
+    use super::*;
+
+    fn case(pattern_name: &str, body: &str) -> Case {
+        test_support::case(pattern(false, pattern_name, &[]), ctor(body, vec![]))
+    }
+
+    fn print_with_width(cases: &[Case], width: usize) -> String {
+        let cfg = PrintCfg { width, ..Default::default() };
+        let alloc = Alloc::new();
+        let mut buf = Vec::new();
+        print_cases(cases, &cfg, &alloc).1.render(width, &mut buf).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn print_case_fitting_width() {
+        let cases = vec![case("True", "False")];
+        assert_eq!(print_with_width(&cases, 80), "{ True => False }")
+    }
+
+    #[test]
+    fn print_case_exceeding_width() {
+        let cases = vec![case("True", "False")];
+        assert_eq!(print_with_width(&cases, 10), "{\n    True => False\n}")
+    }
+
+    #[test]
+    fn print_cases_on_separate_lines() {
+        let cases = vec![case("True", "False"), case("False", "True")];
+        assert_eq!(print_with_width(&cases, 80), "{\n    True => False,\n    False => True\n}")
+    }
+}
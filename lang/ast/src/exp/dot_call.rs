@@ -1,19 +1,22 @@
+use std::collections::BTreeSet;
+
 use codespan::Span;
 use derivative::Derivative;
 use pretty::DocAllocator;
-use printer::{theme::ThemeExt, tokens::DOT, Alloc, Builder, Precedence, Print, PrintCfg};
+use printer::{prec, theme::ThemeExt, tokens::DOT, Alloc, Builder, Precedence, Print, PrintCfg};
 
 use crate::{
-    ctx::LevelCtx, ContainsMetaVars, HasSpan, HasType, Occurs, Shift, ShiftRange, Substitutable,
-    Substitution, Zonk, ZonkError,
+    ctx::LevelCtx, ContainsMetaVars, FreeVariables, HasSpan, HasType, Shift, ShiftRange,
+    Substitutable, Substitution, Zonk, ZonkError,
 };
 
-use super::{Args, Exp, IdBound, Lvl, MetaVar};
+use super::{Args, Exp, IdBound, Idx, MetaVar};
 
 /// A DotCall expression can be one of two different kinds:
 /// - A destructor introduced by a codata type declaration
 /// - A definition introduced at the toplevel
 #[derive(Debug, Clone, Copy, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Eq, PartialEq, Hash)]
 pub enum DotCallKind {
     Destructor,
@@ -23,6 +26,7 @@ pub enum DotCallKind {
 /// A DotCall is either a destructor or a definition, applied to a destructee
 /// Examples: `e.head` `xs.append(ys)`
 #[derive(Debug, Clone, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Eq, PartialEq, Hash)]
 pub struct DotCall {
     /// Source code location
@@ -65,10 +69,11 @@ impl Shift for DotCall {
     }
 }
 
-impl Occurs for DotCall {
-    fn occurs(&self, ctx: &mut LevelCtx, lvl: Lvl) -> bool {
+impl FreeVariables for DotCall {
+    fn collect_free_vars(&self, ctx: &mut LevelCtx, fvs: &mut BTreeSet<Idx>) {
         let DotCall { exp, args, .. } = self;
-        exp.occurs(ctx, lvl) || args.args.iter().any(|arg| arg.occurs(ctx, lvl))
+        exp.collect_free_vars(ctx, fvs);
+        args.args.collect_free_vars(ctx, fvs)
     }
 }
 
@@ -127,7 +132,7 @@ impl Print for DotCall {
         // First DotCall
         dtors_group = alloc
             .text(DOT)
-            .append(alloc.dtor(&self.name.id))
+            .append(alloc.dtor(self.name.local_name()))
             .append(self.args.print(cfg, alloc))
             .append(dtors_group);
 
@@ -136,10 +141,13 @@ impl Print for DotCall {
         while let Exp::DotCall(DotCall { exp, name, args, .. }) = &dtor {
             let psubst = if args.is_empty() { alloc.nil() } else { args.print(cfg, alloc) };
             dtors_group = alloc.line_().append(dtors_group);
-            dtors_group =
-                alloc.text(DOT).append(alloc.dtor(&name.id)).append(psubst).append(dtors_group);
+            dtors_group = alloc
+                .text(DOT)
+                .append(alloc.dtor(name.local_name()))
+                .append(psubst)
+                .append(dtors_group);
             dtor = exp;
         }
-        dtor.print(cfg, alloc).append(dtors_group.align().group())
+        dtor.print_prec(cfg, alloc, prec::OPS).append(dtors_group.align().group())
     }
 }
@@ -1,3 +1,5 @@
+use std::collections::BTreeSet;
+
 use codespan::Span;
 use derivative::Derivative;
 use pretty::DocAllocator;
@@ -9,19 +11,23 @@ use printer::{
 
 use crate::{
     ctx::{values::TypeCtx, LevelCtx},
-    ContainsMetaVars, HasSpan, HasType, Occurs, Shift, ShiftRange, SubstUnderCtx, Substitutable,
-    Substitution, Zonk, ZonkError,
+    ContainsMetaVars, FreeVariables, HasSpan, HasType, Shift, ShiftRange, SubstUnderCtx,
+    Substitutable, Substitution, Zonk, ZonkError,
 };
 
-use super::{Exp, Lvl, MetaVar, MetaVarKind};
+use super::{Exp, Idx, MetaVar, MetaVarKind};
 
 #[derive(Debug, Clone, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Eq, PartialEq, Hash)]
 pub struct Hole {
     /// Source code location
     pub span: Option<Span>,
     /// Whether the hole must be solved during typechecking or not.
     pub kind: MetaVarKind,
+    /// The name of a named hole `?name`
+    #[derivative(PartialEq = "ignore", Hash = "ignore")]
+    pub name: Option<String>,
     /// The metavariable that we want to solve for that hole
     pub metavar: MetaVar,
     /// The inferred type of the hole annotated during elaboration.
@@ -60,8 +66,16 @@ impl From<Hole> for Exp {
 
 impl Shift for Hole {
     fn shift_in_range<R: ShiftRange>(&mut self, range: &R, by: (isize, isize)) {
-        let Hole { span: _, kind: _, metavar: _, inferred_type, inferred_ctx, args, solution } =
-            self;
+        let Hole {
+            span: _,
+            kind: _,
+            name: _,
+            metavar: _,
+            inferred_type,
+            inferred_ctx,
+            args,
+            solution,
+        } = self;
 
         *inferred_type = None;
         *inferred_ctx = None;
@@ -70,9 +84,10 @@ impl Shift for Hole {
     }
 }
 
-impl Occurs for Hole {
-    fn occurs(&self, _ctx: &mut LevelCtx, _lvl: Lvl) -> bool {
-        false
+impl FreeVariables for Hole {
+    fn collect_free_vars(&self, ctx: &mut LevelCtx, fvs: &mut BTreeSet<Idx>) {
+        let Hole { args, .. } = self;
+        args.collect_free_vars(ctx, fvs)
     }
 }
 
@@ -86,10 +101,11 @@ impl Substitutable for Hole {
     type Result = Hole;
 
     fn subst<S: Substitution>(&self, ctx: &mut LevelCtx, by: &S) -> Self::Result {
-        let Hole { span, kind, metavar, args, .. } = self;
+        let Hole { span, kind, name, metavar, args, .. } = self;
         Hole {
             span: *span,
             kind: *kind,
+            name: name.clone(),
             metavar: *metavar,
             inferred_type: None,
             inferred_ctx: None,
@@ -125,6 +141,8 @@ impl Print for Hole {
 
                 if cfg.print_metavar_ids {
                     doc = doc.append(self.metavar.id.to_string());
+                } else if let Some(name) = &self.name {
+                    doc = doc.append(alloc.keyword(name));
                 }
 
                 if let Some(solution) = &self.solution {
@@ -179,8 +197,16 @@ impl Zonk for Hole {
 
 impl ContainsMetaVars for Hole {
     fn contains_metavars(&self) -> bool {
-        let Hole { span: _, kind: _, metavar, inferred_type, inferred_ctx: _, args, solution } =
-            self;
+        let Hole {
+            span: _,
+            kind: _,
+            name: _,
+            metavar,
+            inferred_type,
+            inferred_ctx: _,
+            args,
+            solution,
+        } = self;
 
         inferred_type.contains_metavars()
             || args.contains_metavars()
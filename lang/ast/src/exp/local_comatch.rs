@@ -1,22 +1,26 @@
+use std::collections::BTreeSet;
+
 use codespan::Span;
 use derivative::Derivative;
 use pretty::DocAllocator;
 use printer::{
+    prec,
     theme::ThemeExt,
     tokens::{COMATCH, DOT},
-    util::BackslashExt,
+    util::{BackslashExt, ParensIfExt},
     Alloc, Builder, Precedence, Print, PrintCfg,
 };
 
 use crate::{
     ctx::{values::TypeCtx, LevelCtx},
-    ContainsMetaVars, HasSpan, HasType, Occurs, Shift, ShiftRange, Substitutable, Substitution,
-    Zonk, ZonkError,
+    ContainsMetaVars, FreeVariables, HasSpan, HasType, Shift, ShiftRange, Substitutable,
+    Substitution, Zonk, ZonkError,
 };
 
-use super::{print_cases, Case, Exp, Label, Lvl, MetaVar, TypCtor};
+use super::{print_cases, Case, Exp, Idx, Label, MetaVar, TypCtor};
 
 #[derive(Debug, Clone, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Eq, PartialEq, Hash)]
 pub struct LocalComatch {
     #[derivative(PartialEq = "ignore", Hash = "ignore")]
@@ -50,10 +54,10 @@ impl Shift for LocalComatch {
     }
 }
 
-impl Occurs for LocalComatch {
-    fn occurs(&self, ctx: &mut LevelCtx, lvl: Lvl) -> bool {
+impl FreeVariables for LocalComatch {
+    fn collect_free_vars(&self, ctx: &mut LevelCtx, fvs: &mut BTreeSet<Idx>) {
         let LocalComatch { cases, .. } = self;
-        cases.occurs(ctx, lvl)
+        cases.collect_free_vars(ctx, fvs)
     }
 }
 
@@ -80,20 +84,34 @@ impl Substitutable for LocalComatch {
 }
 
 /// Print the Comatch as a lambda abstraction.
+/// Directly nested lambda abstractions `\x. \y. e` are printed as `\x y. e`.
 /// Only invoke this function if the comatch contains exactly
 /// one cocase "ap" with three arguments; the function will
 /// panic otherwise.
 fn print_lambda_sugar<'a>(cases: &'a [Case], cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
-    let Case { pattern, body, .. } = cases.first().expect("Empty comatch marked as lambda sugar");
-    let var_name = &pattern
-        .params
-        .params
-        .get(2) // The variable we want to print is at the third position: comatch { ap(_,_,x) => ...}
-        .expect("No parameter bound in comatch marked as lambda sugar")
-        .name;
+    let mut var_names = vec![];
+    let mut cases = cases;
+    let body = loop {
+        let Case { pattern, body, .. } =
+            cases.first().expect("Empty comatch marked as lambda sugar");
+        // The variable we want to print is at the third position: comatch { ap(_,_,x) => ...}
+        let var_name = &pattern
+            .params
+            .params
+            .get(2)
+            .expect("No parameter bound in comatch marked as lambda sugar")
+            .name;
+        var_names.push(alloc.text(&var_name.id));
+        match body.as_deref() {
+            Some(Exp::LocalComatch(LocalComatch {
+                is_lambda_sugar: true, cases: inner, ..
+            })) => cases = inner,
+            _ => break body,
+        }
+    };
     alloc
         .backslash_anno(cfg)
-        .append(&var_name.id)
+        .append(alloc.intersperse(var_names, alloc.space()))
         .append(DOT)
         .append(alloc.space())
         .append(body.print(cfg, alloc))
@@ -104,11 +122,11 @@ impl Print for LocalComatch {
         &'a self,
         cfg: &PrintCfg,
         alloc: &'a Alloc<'a>,
-        _prec: Precedence,
+        prec: Precedence,
     ) -> Builder<'a> {
         let LocalComatch { name, is_lambda_sugar, cases, .. } = self;
         if *is_lambda_sugar && cfg.print_lambda_sugar {
-            print_lambda_sugar(cases, cfg, alloc)
+            print_lambda_sugar(cases, cfg, alloc).parens_if(prec > prec::EXP)
         } else {
             alloc
                 .keyword(COMATCH)
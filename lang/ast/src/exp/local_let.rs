@@ -0,0 +1,215 @@
+use std::collections::BTreeSet;
+
+use codespan::Span;
+use derivative::Derivative;
+use pretty::DocAllocator;
+use printer::{
+    prec,
+    theme::ThemeExt,
+    tokens::{COLON, COLONEQ, IN, LET},
+    util::ParensIfExt,
+    Alloc, Builder, Precedence, Print, PrintCfg,
+};
+
+use crate::{
+    ctx::{BindContext, LevelCtx},
+    ContainsMetaVars, FreeVariables, HasSpan, HasType, Shift, ShiftRange, ShiftRangeExt,
+    Substitutable, Substitution, Zonk, ZonkError,
+};
+
+use super::{Exp, Idx, MetaVar, VarBind};
+
+/// Local let binding `let x: t := e in body`
+///
+/// The bound variable `x` is in scope in `body`, but not in `t` or `e`.
+#[derive(Debug, Clone, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derivative(Eq, PartialEq, Hash)]
+pub struct LocalLet {
+    /// Source code location
+    #[derivative(PartialEq = "ignore", Hash = "ignore")]
+    pub span: Option<Span>,
+    /// The bound variable, i.e. `x` in `let x: t := e in body`
+    #[derivative(PartialEq = "ignore", Hash = "ignore")]
+    pub name: VarBind,
+    /// The optional type annotation, i.e. `t` in `let x: t := e in body`
+    pub typ: Option<Box<Exp>>,
+    /// The bound expression, i.e. `e` in `let x: t := e in body`
+    pub bound: Box<Exp>,
+    /// The body, in which the bound variable is in scope
+    pub body: Box<Exp>,
+    /// The type of the bound expression, as annotated or inferred during elaboration
+    #[derivative(PartialEq = "ignore", Hash = "ignore")]
+    pub bound_type: Option<Box<Exp>>,
+    #[derivative(PartialEq = "ignore", Hash = "ignore")]
+    pub inferred_type: Option<Box<Exp>>,
+}
+
+impl HasSpan for LocalLet {
+    fn span(&self) -> Option<Span> {
+        self.span
+    }
+}
+
+impl From<LocalLet> for Exp {
+    fn from(val: LocalLet) -> Self {
+        Exp::LocalLet(val)
+    }
+}
+
+impl Shift for LocalLet {
+    fn shift_in_range<R: ShiftRange>(&mut self, range: &R, by: (isize, isize)) {
+        self.typ.shift_in_range(range, by);
+        self.bound.shift_in_range(range, by);
+        self.body.shift_in_range(&range.clone().shift(1), by);
+        self.bound_type = None;
+        self.inferred_type = None;
+    }
+}
+
+impl FreeVariables for LocalLet {
+    fn collect_free_vars(&self, ctx: &mut LevelCtx, fvs: &mut BTreeSet<Idx>) {
+        let LocalLet { typ, bound, body, .. } = self;
+        typ.collect_free_vars(ctx, fvs);
+        bound.collect_free_vars(ctx, fvs);
+        ctx.bind_single((), |ctx| body.collect_free_vars(ctx, fvs))
+    }
+}
+
+impl HasType for LocalLet {
+    fn typ(&self) -> Option<Box<Exp>> {
+        self.inferred_type.clone()
+    }
+}
+
+impl Substitutable for LocalLet {
+    type Result = LocalLet;
+
+    fn subst<S: Substitution>(&self, ctx: &mut LevelCtx, by: &S) -> Self::Result {
+        let LocalLet { span, name, typ, bound, body, .. } = self;
+        LocalLet {
+            span: *span,
+            name: name.clone(),
+            typ: typ.subst(ctx, by),
+            bound: bound.subst(ctx, by),
+            body: ctx.bind_single((), |ctx| {
+                let mut by = (*by).clone();
+                by.shift((1, 0));
+                body.subst(ctx, &by)
+            }),
+            bound_type: None,
+            inferred_type: None,
+        }
+    }
+}
+
+impl Print for LocalLet {
+    fn print_prec<'a>(
+        &'a self,
+        cfg: &PrintCfg,
+        alloc: &'a Alloc<'a>,
+        prec: Precedence,
+    ) -> Builder<'a> {
+        let LocalLet { name, typ, bound, body, .. } = self;
+        let typ = match typ {
+            Some(typ) => alloc
+                .text(COLON)
+                .append(alloc.space())
+                .append(typ.print(cfg, alloc))
+                .append(alloc.space()),
+            None => alloc.space(),
+        };
+        alloc
+            .keyword(LET)
+            .append(alloc.space())
+            .append(alloc.text(&name.id))
+            .append(typ)
+            .append(COLONEQ)
+            .append(alloc.space())
+            .append(bound.print(cfg, alloc))
+            .append(alloc.space())
+            .append(alloc.keyword(IN))
+            .group()
+            .append(alloc.line())
+            .append(body.print(cfg, alloc))
+            .group()
+            .parens_if(prec > prec::EXP)
+    }
+}
+
+impl Zonk for LocalLet {
+    fn zonk(
+        &mut self,
+        meta_vars: &crate::HashMap<MetaVar, crate::MetaVarState>,
+    ) -> Result<(), ZonkError> {
+        let LocalLet { span: _, name: _, typ, bound, body, bound_type, inferred_type } = self;
+        typ.zonk(meta_vars)?;
+        bound.zonk(meta_vars)?;
+        body.zonk(meta_vars)?;
+        bound_type.zonk(meta_vars)?;
+        inferred_type.zonk(meta_vars)?;
+        Ok(())
+    }
+}
+
+impl ContainsMetaVars for LocalLet {
+    fn contains_metavars(&self) -> bool {
+        let LocalLet { span: _, name: _, typ, bound, body, bound_type, inferred_type } = self;
+
+        typ.contains_metavars()
+            || bound.contains_metavars()
+            || body.contains_metavars()
+            || bound_type.contains_metavars()
+            || inferred_type.contains_metavars()
+    }
+}
+
+#[cfg(test)]
+mod local_let_tests {
+    use crate::test_support::{ctor, var};
+    use crate::Anno;
+
+    use super::*;
+
+    fn local_let(typ: Option<Box<Exp>>, bound: Box<Exp>, body: Box<Exp>) -> LocalLet {
+        LocalLet {
+            span: None,
+            name: VarBind::from_string("x"),
+            typ,
+            bound,
+            body,
+            bound_type: None,
+            inferred_type: None,
+        }
+    }
+
+    #[test]
+    fn print_local_let() {
+        let exp = local_let(None, ctor("T", vec![]), var("x", 0, 0));
+        assert_eq!(exp.print_to_string(None), "let x := T in x");
+        let exp = local_let(Some(ctor("Bool", vec![])), ctor("T", vec![]), var("x", 0, 0));
+        assert_eq!(exp.print_to_string(None), "let x: Bool := T in x");
+    }
+
+    #[test]
+    fn print_local_let_in_operand() {
+        let exp = local_let(None, ctor("T", vec![]), var("x", 0, 0));
+        let anno = Anno {
+            span: None,
+            exp: Box::new(exp.into()),
+            typ: ctor("Bool", vec![]),
+            normalized_type: None,
+        };
+        assert_eq!(anno.print_to_string(None), "(let x := T in x) : Bool");
+    }
+
+    #[test]
+    fn shift_skips_bound_variable() {
+        let mut exp =
+            local_let(None, var("y", 0, 0), ctor("Pair", vec![var("x", 0, 0), var("y", 1, 0)]));
+        exp.shift((1, 0));
+        let expected =
+            local_let(None, var("y", 1, 0), ctor("Pair", vec![var("x", 0, 0), var("y", 2, 0)]));
+        assert_eq!(exp, expected);
+    }
+}
@@ -1,7 +1,10 @@
+use std::collections::BTreeSet;
+
 use codespan::Span;
 use derivative::Derivative;
 use pretty::DocAllocator;
 use printer::{
+    prec,
     theme::ThemeExt,
     tokens::{DOT, MATCH},
     Alloc, Builder, Precedence, Print, PrintCfg,
@@ -9,13 +12,14 @@ use printer::{
 
 use crate::{
     ctx::{values::TypeCtx, LevelCtx},
-    ContainsMetaVars, HasSpan, HasType, Occurs, Shift, ShiftRange, Substitutable, Substitution,
-    Zonk, ZonkError,
+    ContainsMetaVars, FreeVariables, HasSpan, HasType, Shift, ShiftRange, Substitutable,
+    Substitution, Zonk, ZonkError,
 };
 
-use super::{print_cases, Case, Exp, Label, Lvl, MetaVar, Motive, TypCtor};
+use super::{print_cases, Case, Exp, Idx, Label, MetaVar, Motive, TypCtor};
 
 #[derive(Debug, Clone, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Eq, PartialEq, Hash)]
 pub struct LocalMatch {
     #[derivative(PartialEq = "ignore", Hash = "ignore")]
@@ -55,10 +59,12 @@ impl Shift for LocalMatch {
     }
 }
 
-impl Occurs for LocalMatch {
-    fn occurs(&self, ctx: &mut LevelCtx, lvl: Lvl) -> bool {
-        let LocalMatch { on_exp, cases, .. } = self;
-        on_exp.occurs(ctx, lvl) || cases.occurs(ctx, lvl)
+impl FreeVariables for LocalMatch {
+    fn collect_free_vars(&self, ctx: &mut LevelCtx, fvs: &mut BTreeSet<Idx>) {
+        let LocalMatch { on_exp, motive, cases, .. } = self;
+        on_exp.collect_free_vars(ctx, fvs);
+        motive.collect_free_vars(ctx, fvs);
+        cases.collect_free_vars(ctx, fvs)
     }
 }
 
@@ -94,7 +100,7 @@ impl Print for LocalMatch {
     ) -> Builder<'a> {
         let LocalMatch { name, on_exp, motive, cases, .. } = self;
         on_exp
-            .print(cfg, alloc)
+            .print_prec(cfg, alloc, prec::OPS)
             .append(DOT)
             .append(alloc.keyword(MATCH))
             .append(match &name.user_name {
@@ -1,18 +1,19 @@
+use std::collections::BTreeSet;
 use std::fmt;
 
 use codespan::Span;
 use derivative::Derivative;
 use pretty::DocAllocator;
 use printer::theme::ThemeExt;
-use printer::tokens::{AS, FAT_ARROW};
+use printer::tokens::{AS, FAT_ARROW, WITH};
+use printer::util::SourceAnnoExt;
 use printer::{Alloc, Builder, Precedence, Print, PrintCfg};
 
 use crate::ctx::{BindContext, LevelCtx};
-use crate::{ContainsMetaVars, Zonk, ZonkError};
+use crate::{ContainsMetaVars, FreeVariables, Zonk, ZonkError};
 
 use super::subst::{Substitutable, Substitution};
 use super::traits::HasSpan;
-use super::traits::Occurs;
 use super::HasType;
 use super::{ident::*, Shift, ShiftRange, ShiftRangeExt};
 
@@ -23,6 +24,7 @@ mod case;
 mod dot_call;
 mod hole;
 mod local_comatch;
+mod local_let;
 mod local_match;
 mod telescope_inst;
 mod typ_ctor;
@@ -35,6 +37,7 @@ pub use case::*;
 pub use dot_call::*;
 pub use hole::*;
 pub use local_comatch::*;
+pub use local_let::*;
 pub use local_match::*;
 pub use telescope_inst::*;
 pub use typ_ctor::*;
@@ -42,6 +45,7 @@ pub use type_univ::*;
 pub use variable::*;
 
 #[derive(Debug, Clone, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Eq, PartialEq, Hash)]
 pub struct Label {
     /// A machine-generated, unique id
@@ -64,7 +68,13 @@ impl fmt::Display for Label {
 //
 //
 
+/// Expressions are compared up to alpha equivalence
+///
+/// The derived `PartialEq` ignores source locations, the names of bound variables and the
+/// metadata annotated during elaboration. Variables are compared by their de Bruijn indices,
+/// and references to top-level declarations by their names.
 #[derive(Debug, Clone, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Eq, PartialEq, Hash)]
 pub enum Exp {
     Variable(Variable),
@@ -75,6 +85,7 @@ pub enum Exp {
     TypeUniv(TypeUniv),
     LocalMatch(LocalMatch),
     LocalComatch(LocalComatch),
+    LocalLet(LocalLet),
     Hole(Hole),
 }
 
@@ -98,6 +109,7 @@ impl HasSpan for Exp {
             Exp::TypeUniv(e) => e.span(),
             Exp::LocalMatch(e) => e.span(),
             Exp::LocalComatch(e) => e.span(),
+            Exp::LocalLet(e) => e.span(),
             Exp::Hole(e) => e.span(),
         }
     }
@@ -114,23 +126,25 @@ impl Shift for Exp {
             Exp::TypeUniv(e) => e.shift_in_range(range, by),
             Exp::LocalMatch(e) => e.shift_in_range(range, by),
             Exp::LocalComatch(e) => e.shift_in_range(range, by),
+            Exp::LocalLet(e) => e.shift_in_range(range, by),
             Exp::Hole(e) => e.shift_in_range(range, by),
         }
     }
 }
 
-impl Occurs for Exp {
-    fn occurs(&self, ctx: &mut LevelCtx, lvl: Lvl) -> bool {
+impl FreeVariables for Exp {
+    fn collect_free_vars(&self, ctx: &mut LevelCtx, fvs: &mut BTreeSet<Idx>) {
         match self {
-            Exp::Variable(e) => e.occurs(ctx, lvl),
-            Exp::TypCtor(e) => e.occurs(ctx, lvl),
-            Exp::Call(e) => e.occurs(ctx, lvl),
-            Exp::DotCall(e) => e.occurs(ctx, lvl),
-            Exp::Anno(e) => e.occurs(ctx, lvl),
-            Exp::TypeUniv(e) => e.occurs(ctx, lvl),
-            Exp::LocalMatch(e) => e.occurs(ctx, lvl),
-            Exp::LocalComatch(e) => e.occurs(ctx, lvl),
-            Exp::Hole(e) => e.occurs(ctx, lvl),
+            Exp::Variable(e) => e.collect_free_vars(ctx, fvs),
+            Exp::TypCtor(e) => e.collect_free_vars(ctx, fvs),
+            Exp::Call(e) => e.collect_free_vars(ctx, fvs),
+            Exp::DotCall(e) => e.collect_free_vars(ctx, fvs),
+            Exp::Anno(e) => e.collect_free_vars(ctx, fvs),
+            Exp::TypeUniv(e) => e.collect_free_vars(ctx, fvs),
+            Exp::LocalMatch(e) => e.collect_free_vars(ctx, fvs),
+            Exp::LocalComatch(e) => e.collect_free_vars(ctx, fvs),
+            Exp::LocalLet(e) => e.collect_free_vars(ctx, fvs),
+            Exp::Hole(e) => e.collect_free_vars(ctx, fvs),
         }
     }
 }
@@ -146,6 +160,7 @@ impl HasType for Exp {
             Exp::TypeUniv(e) => e.typ(),
             Exp::LocalMatch(e) => e.typ(),
             Exp::LocalComatch(e) => e.typ(),
+            Exp::LocalLet(e) => e.typ(),
             Exp::Hole(e) => e.typ(),
         }
     }
@@ -163,6 +178,7 @@ impl Substitutable for Exp {
             Exp::TypeUniv(e) => e.subst(ctx, by).into(),
             Exp::LocalMatch(e) => e.subst(ctx, by).into(),
             Exp::LocalComatch(e) => e.subst(ctx, by).into(),
+            Exp::LocalLet(e) => e.subst(ctx, by).into(),
             Exp::Hole(e) => e.subst(ctx, by).into(),
         }
     }
@@ -175,7 +191,7 @@ impl Print for Exp {
         alloc: &'a Alloc<'a>,
         prec: Precedence,
     ) -> Builder<'a> {
-        match self {
+        let doc = match self {
             Exp::Variable(e) => e.print_prec(cfg, alloc, prec),
             Exp::TypCtor(e) => e.print_prec(cfg, alloc, prec),
             Exp::Call(e) => e.print_prec(cfg, alloc, prec),
@@ -184,8 +200,10 @@ impl Print for Exp {
             Exp::TypeUniv(e) => e.print_prec(cfg, alloc, prec),
             Exp::LocalMatch(e) => e.print_prec(cfg, alloc, prec),
             Exp::LocalComatch(e) => e.print_prec(cfg, alloc, prec),
+            Exp::LocalLet(e) => e.print_prec(cfg, alloc, prec),
             Exp::Hole(e) => e.print_prec(cfg, alloc, prec),
-        }
+        };
+        doc.source_anno(cfg, self.span().map(|span| (span.start().0, span.end().0)))
     }
 }
 
@@ -203,6 +221,7 @@ impl Zonk for Exp {
             Exp::TypeUniv(e) => e.zonk(meta_vars),
             Exp::LocalMatch(e) => e.zonk(meta_vars),
             Exp::LocalComatch(e) => e.zonk(meta_vars),
+            Exp::LocalLet(e) => e.zonk(meta_vars),
             Exp::Hole(e) => e.zonk(meta_vars),
         }
     }
@@ -219,6 +238,7 @@ impl ContainsMetaVars for Exp {
             Exp::TypeUniv(type_univ) => type_univ.contains_metavars(),
             Exp::LocalMatch(local_match) => local_match.contains_metavars(),
             Exp::LocalComatch(local_comatch) => local_comatch.contains_metavars(),
+            Exp::LocalLet(local_let) => local_let.contains_metavars(),
             Exp::Hole(hole) => hole.contains_metavars(),
         }
     }
@@ -229,12 +249,16 @@ impl ContainsMetaVars for Exp {
 //
 
 #[derive(Debug, Clone, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Eq, PartialEq, Hash)]
 pub struct Motive {
     #[derivative(PartialEq = "ignore", Hash = "ignore")]
     pub span: Option<Span>,
     pub param: ParamInst,
-    pub ret_typ: Box<Exp>,
+    /// The return type of the match, which may depend on `param`.
+    /// Before elaboration, this is `None` for a `with` abstraction `e.match with x { .. }`.
+    /// The typechecker then computes the return type by abstracting `e` in the expected type.
+    pub ret_typ: Option<Box<Exp>>,
 }
 
 impl Shift for Motive {
@@ -260,19 +284,33 @@ impl Substitutable for Motive {
     }
 }
 
+impl FreeVariables for Motive {
+    fn collect_free_vars(&self, ctx: &mut LevelCtx, fvs: &mut BTreeSet<Idx>) {
+        let Motive { ret_typ, .. } = self;
+        ctx.bind_single((), |ctx| ret_typ.collect_free_vars(ctx, fvs))
+    }
+}
+
 impl Print for Motive {
     fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
         let Motive { span: _, param, ret_typ } = self;
 
-        alloc
-            .space()
-            .append(alloc.keyword(AS))
-            .append(alloc.space())
-            .append(param.print(cfg, alloc))
-            .append(alloc.space())
-            .append(alloc.text(FAT_ARROW))
-            .append(alloc.space())
-            .append(ret_typ.print(cfg, alloc))
+        match ret_typ {
+            Some(ret_typ) => alloc
+                .space()
+                .append(alloc.keyword(AS))
+                .append(alloc.space())
+                .append(param.print(cfg, alloc))
+                .append(alloc.space())
+                .append(alloc.text(FAT_ARROW))
+                .append(alloc.space())
+                .append(ret_typ.print(cfg, alloc)),
+            None => alloc
+                .space()
+                .append(alloc.keyword(WITH))
+                .append(alloc.space())
+                .append(param.print(cfg, alloc)),
+        }
     }
 }
 
@@ -295,3 +333,42 @@ impl ContainsMetaVars for Motive {
         param.contains_metavars() || ret_typ.contains_metavars()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use codespan::Span;
+
+    use crate::test_support::{ctor, var};
+
+    use super::*;
+
+    #[test]
+    fn eq_ignores_spans() {
+        let Exp::Variable(x) = *var("x", 0, 0) else { unreachable!() };
+        let lhs = Variable { span: Some(Span::new(0, 1)), ..x.clone() };
+        let rhs = Variable { span: Some(Span::new(4, 5)), ..x };
+        assert_eq!(Exp::from(lhs), Exp::from(rhs));
+    }
+
+    #[test]
+    fn eq_ignores_bound_names() {
+        assert_eq!(var("x", 0, 0), var("y", 0, 0));
+    }
+
+    #[test]
+    fn eq_ignores_inferred_types() {
+        let Exp::Call(z) = *ctor("Z", vec![]) else { unreachable!() };
+        let lhs = Call { inferred_type: Some(Box::new(TypeUniv::new().into())), ..z };
+        assert_eq!(Box::new(Exp::from(lhs)), ctor("Z", vec![]));
+    }
+
+    #[test]
+    fn eq_respects_indices() {
+        assert_ne!(var("x", 0, 0), var("x", 0, 1));
+    }
+
+    #[test]
+    fn eq_respects_global_names() {
+        assert_ne!(ctor("Z", vec![]), ctor("S", vec![]));
+    }
+}
@@ -12,6 +12,7 @@ use super::{Exp, MetaVar, VarBind};
 
 /// Instantiation of a previously declared telescope
 #[derive(Debug, Clone, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Eq, PartialEq, Hash)]
 pub struct TelescopeInst {
     pub params: Vec<ParamInst>,
@@ -65,6 +66,7 @@ impl ContainsMetaVars for TelescopeInst {
 
 /// Instantiation of a previously declared parameter
 #[derive(Debug, Clone, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Eq, PartialEq, Hash)]
 pub struct ParamInst {
     #[derivative(PartialEq = "ignore", Hash = "ignore")]
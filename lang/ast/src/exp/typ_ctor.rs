@@ -1,19 +1,28 @@
+use std::collections::BTreeSet;
+
 use codespan::Span;
 use derivative::Derivative;
 use pretty::DocAllocator;
-use printer::{theme::ThemeExt, tokens::ARROW, Alloc, Builder, Precedence, Print, PrintCfg};
+use printer::{
+    prec,
+    theme::ThemeExt,
+    tokens::{ARROW, COLON},
+    util::ParensIfExt,
+    Alloc, Builder, Precedence, Print, PrintCfg,
+};
 
 use crate::{
-    ctx::LevelCtx, ContainsMetaVars, HasSpan, HasType, Occurs, Shift, ShiftRange, Substitutable,
-    Substitution, Zonk, ZonkError,
+    ctx::LevelCtx, ContainsMetaVars, FreeVariables, HasSpan, HasType, Shift, ShiftRange,
+    Substitutable, Substitution, Zonk, ZonkError,
 };
 
-use super::{Args, Exp, IdBound, Lvl, MetaVar, TypeUniv};
+use super::{Arg, Args, Case, Exp, IdBound, Idx, LocalComatch, MetaVar, TypeUniv};
 
 /// A type constructor applied to arguments. The type of `TypCtor`
 /// is always the type universe `Type`.
 /// Examples: `Nat`, `List(Nat)`
 #[derive(Debug, Clone, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Eq, PartialEq, Hash)]
 pub struct TypCtor {
     /// Source code location
@@ -54,10 +63,10 @@ impl Shift for TypCtor {
     }
 }
 
-impl Occurs for TypCtor {
-    fn occurs(&self, ctx: &mut LevelCtx, lvl: Lvl) -> bool {
+impl FreeVariables for TypCtor {
+    fn collect_free_vars(&self, ctx: &mut LevelCtx, fvs: &mut BTreeSet<Idx>) {
         let TypCtor { args, .. } = self;
-        args.args.iter().any(|arg| arg.occurs(ctx, lvl))
+        args.args.collect_free_vars(ctx, fvs)
     }
 }
 
@@ -75,6 +84,41 @@ impl Substitutable for TypCtor {
     }
 }
 
+impl TypCtor {
+    /// The bound variable, domain and codomain of a dependent function type `Pi(a, \x. b)`,
+    /// which is printed as `(x : a) -> b`
+    ///
+    /// Only dependent function types whose codomain refers to the bound variable are
+    /// considered, since `(x : a) -> b` denotes the function type `a -> b` otherwise.
+    fn dependent_fun<'a>(&'a self) -> Option<(&'a str, &'a Exp, &'a Exp)> {
+        let TypCtor { name, args, .. } = self;
+        if name.id != "Pi" {
+            return None;
+        }
+        let explicit = |arg: &'a Arg| match arg {
+            Arg::UnnamedArg(exp) | Arg::NamedArg(_, exp) => Some(&**exp),
+            Arg::InsertedImplicitArg(_) => None,
+        };
+        let [from, to] = args.args.as_slice() else {
+            return None;
+        };
+        let (Some(from), Some(to)) = (explicit(from), explicit(to)) else {
+            return None;
+        };
+        let Exp::LocalComatch(LocalComatch { is_lambda_sugar: true, cases, .. }) = to else {
+            return None;
+        };
+        let [Case { pattern, body: Some(body), .. }] = cases.as_slice() else {
+            return None;
+        };
+        // The bound variable is the third parameter of the cocase `.ap(_, _, x)`
+        let var = &pattern.params.params.get(2)?.name.id;
+        let mut fvs = BTreeSet::new();
+        body.collect_free_vars(&mut LevelCtx::empty(), &mut fvs);
+        fvs.contains(&Idx { fst: 0, snd: 0 }).then_some((var, from, body))
+    }
+}
+
 impl Print for TypCtor {
     fn print_prec<'a>(
         &'a self,
@@ -84,14 +128,26 @@ impl Print for TypCtor {
     ) -> Builder<'a> {
         let TypCtor { span: _, name, args } = self;
         if name.id == "Fun" && args.len() == 2 && cfg.print_function_sugar {
-            let arg = args.args[0].print_prec(cfg, alloc, 1);
-            let res = args.args[1].print_prec(cfg, alloc, 0);
+            let arg = args.args[0].print_prec(cfg, alloc, prec::OPS);
+            let res = args.args[1].print_prec(cfg, alloc, prec::EXP);
             let fun = arg.append(alloc.space()).append(ARROW).append(alloc.space()).append(res);
-            if prec == 0 {
-                fun
-            } else {
-                fun.parens()
-            }
+            fun.parens_if(prec > prec::EXP)
+        } else if let Some((var, from, to)) =
+            self.dependent_fun().filter(|_| cfg.print_function_sugar)
+        {
+            let domain = alloc
+                .text(var)
+                .append(alloc.space())
+                .append(COLON)
+                .append(alloc.space())
+                .append(from.print_prec(cfg, alloc, prec::EXP))
+                .parens();
+            let fun = domain
+                .append(alloc.space())
+                .append(ARROW)
+                .append(alloc.space())
+                .append(to.print_prec(cfg, alloc, prec::EXP));
+            fun.parens_if(prec > prec::EXP)
         } else {
             alloc.typ(&name.id).append(args.print(cfg, alloc))
         }
@@ -116,3 +172,70 @@ impl ContainsMetaVars for TypCtor {
         args.contains_metavars()
     }
 }
+
+#[cfg(test)]
+mod print_fun_tests {
+    use url::Url;
+
+    use crate::Arg;
+
+    use super::*;
+
+    fn typ(id: &str, args: Vec<TypCtor>) -> TypCtor {
+        TypCtor {
+            span: None,
+            name: IdBound {
+                span: None,
+                id: id.to_owned(),
+                uri: Url::parse("inmemory:///scratch.pol").unwrap(),
+            },
+            args: Args {
+                args: args.into_iter().map(|arg| Arg::UnnamedArg(Box::new(arg.into()))).collect(),
+            },
+        }
+    }
+
+    fn fun(from: TypCtor, to: TypCtor) -> TypCtor {
+        typ("Fun", vec![from, to])
+    }
+
+    #[test]
+    fn print_fun_right_nested() {
+        let exp = fun(typ("A", vec![]), fun(typ("B", vec![]), typ("C", vec![])));
+        assert_eq!(exp.print_to_string(None), "A -> B -> C")
+    }
+
+    #[test]
+    fn print_fun_left_nested() {
+        let exp = fun(fun(typ("A", vec![]), typ("B", vec![])), typ("C", vec![]));
+        assert_eq!(exp.print_to_string(None), "(A -> B) -> C")
+    }
+
+    #[test]
+    fn print_fun_to_latex() {
+        let exp = fun(typ("A", vec![]), fun(typ("B0", vec![]), typ("C", vec![])));
+        assert_eq!(
+            exp.print_to_latex(None),
+            "\\begin{align*}\n&\\mathsf{A}\\ \\to \\ \\mathsf{B_0}\\ \\to \\ \\mathsf{C}\n\\end{align*}\n"
+        )
+    }
+
+    #[test]
+    fn print_fun_with_source_map() {
+        let a = TypCtor { span: Some(Span::new(10, 11)), ..typ("A", vec![]) };
+        let b = TypCtor { span: Some(Span::new(15, 16)), ..typ("B", vec![]) };
+        let (out, source_map) = fun(a, b).print_with_source_map(None);
+        assert_eq!(out, "A -> B");
+        assert_eq!(source_map.to_source(0), Some(10..11));
+        assert_eq!(source_map.to_source(5), Some(15..16));
+        assert_eq!(source_map.to_source(2), None);
+        assert_eq!(source_map.to_output(15), Some(5..6));
+    }
+
+    #[test]
+    fn print_fun_without_sugar() {
+        let exp = fun(fun(typ("A", vec![]), typ("B", vec![])), typ("C", vec![]));
+        let cfg = PrintCfg { print_function_sugar: false, ..Default::default() };
+        assert_eq!(exp.print_to_string(Some(&cfg)), "Fun(Fun(A, B), C)")
+    }
+}
@@ -1,13 +1,15 @@
+use std::collections::BTreeSet;
+
 use codespan::Span;
 use derivative::Derivative;
 use printer::{theme::ThemeExt, tokens::TYPE, Alloc, Builder, Precedence, Print, PrintCfg};
 
 use crate::{
-    ctx::LevelCtx, ContainsMetaVars, HasSpan, HasType, Occurs, Shift, ShiftRange, Substitutable,
-    Substitution, Zonk, ZonkError,
+    ctx::LevelCtx, ContainsMetaVars, FreeVariables, HasSpan, HasType, Shift, ShiftRange,
+    Substitutable, Substitution, Zonk, ZonkError,
 };
 
-use super::{Exp, Lvl, MetaVar};
+use super::{Exp, Idx, MetaVar};
 
 /// The impredicative type universe "Type" is used
 /// for typing data and codata types. I.e. we have
@@ -15,6 +17,7 @@ use super::{Exp, Lvl, MetaVar};
 /// - `Stream(Nat) : Type`
 /// - `Type : Type`
 #[derive(Debug, Clone, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Eq, PartialEq, Hash)]
 pub struct TypeUniv {
     /// Source code location
@@ -50,10 +53,8 @@ impl Shift for TypeUniv {
     fn shift_in_range<R: ShiftRange>(&mut self, _range: &R, _by: (isize, isize)) {}
 }
 
-impl Occurs for TypeUniv {
-    fn occurs(&self, _ctx: &mut LevelCtx, _lvl: Lvl) -> bool {
-        false
-    }
+impl FreeVariables for TypeUniv {
+    fn collect_free_vars(&self, _ctx: &mut LevelCtx, _fvs: &mut BTreeSet<Idx>) {}
 }
 
 impl HasType for TypeUniv {
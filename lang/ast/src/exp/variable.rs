@@ -1,19 +1,22 @@
+use std::collections::BTreeSet;
+
 use codespan::Span;
 use derivative::Derivative;
 use pretty::DocAllocator;
 use printer::{Alloc, Builder, Precedence, Print, PrintCfg};
 
 use crate::{
-    ctx::LevelCtx, ContainsMetaVars, HasSpan, HasType, Occurs, Shift, ShiftRange, Substitutable,
-    Substitution, Zonk, ZonkError,
+    ctx::LevelCtx, ContainsMetaVars, FreeVariables, HasSpan, HasType, Shift, ShiftRange,
+    Substitutable, Substitution, Zonk, ZonkError,
 };
 
-use super::{Exp, Idx, Lvl, MetaVar, VarBound};
+use super::{Exp, Idx, MetaVar, VarBound};
 
 /// A bound variable occurrence. The variable is represented
 /// using a de-Bruijn index, but we keep the information
 /// about the name that was originally annotated in the program.
 #[derive(Debug, Clone, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Eq, PartialEq, Hash)]
 pub struct Variable {
     /// Source code location
@@ -51,10 +54,13 @@ impl Shift for Variable {
     }
 }
 
-impl Occurs for Variable {
-    fn occurs(&self, ctx: &mut LevelCtx, lvl: Lvl) -> bool {
+impl FreeVariables for Variable {
+    fn collect_free_vars(&self, ctx: &mut LevelCtx, fvs: &mut BTreeSet<Idx>) {
         let Variable { idx, .. } = self;
-        ctx.idx_to_lvl(*idx) == lvl
+        // Variables bound within the term refer to one of the `ctx.len()` innermost telescopes
+        if idx.fst >= ctx.len() {
+            fvs.insert(Idx { fst: idx.fst - ctx.len(), snd: idx.snd });
+        }
     }
 }
 
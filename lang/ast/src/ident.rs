@@ -19,6 +19,7 @@ use crate::HasSpan;
 ///
 /// E.g. on the left-hand side of a pattern or in a parameter list
 #[derive(Debug, Clone, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Eq, PartialEq, Hash)]
 pub struct VarBind {
     #[derivative(PartialEq = "ignore", Hash = "ignore")]
@@ -50,6 +51,7 @@ impl HasSpan for VarBind {
 
 /// A bound occurence of a local variable
 #[derive(Debug, Clone, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Eq, PartialEq, Hash)]
 pub struct VarBound {
     #[derivative(PartialEq = "ignore", Hash = "ignore")]
@@ -89,6 +91,7 @@ impl From<VarBind> for VarBound {
 ///
 /// E.g. the names for (co)data type declarations, (co)def declarations, and top-level let bindings
 #[derive(Debug, Clone, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Eq, PartialEq, Hash)]
 pub struct IdBind {
     #[derivative(PartialEq = "ignore", Hash = "ignore")]
@@ -100,6 +103,33 @@ impl IdBind {
     pub fn from_string(id: &str) -> Self {
         IdBind { span: None, id: id.to_owned() }
     }
+
+    /// The name of the declaration as it is written in its `where` clause
+    pub fn local_name(&self) -> &str {
+        split_where_name(&self.id).1
+    }
+
+    /// The name of the declaration in whose `where` clause this declaration is declared
+    pub fn where_parent(&self) -> Option<&str> {
+        split_where_name(&self.id).0
+    }
+}
+
+/// The name of the declaration `name` which is declared in the `where` clause of `parent`
+///
+/// Declarations in the `where` clause of another declaration are lifted to the top level
+/// during lowering and named `parent::name`.
+/// Since `:` cannot occur in identifiers, these names cannot clash with any other declaration.
+pub fn where_decl_name(parent: &str, name: &str) -> String {
+    format!("{parent}::{name}")
+}
+
+/// Split the name of a declaration into the name of its parent declaration and its local name
+fn split_where_name(id: &str) -> (Option<&str>, &str) {
+    match id.rsplit_once("::") {
+        Some((parent, name)) => (Some(parent), name),
+        None => (None, id),
+    }
 }
 
 impl fmt::Display for IdBind {
@@ -140,6 +170,7 @@ impl PartialEq<IdBind> for IdBound {
 ///
 /// E.g. the name in a (type) constructor or destructor call, or in a call to a top-level let binding
 #[derive(Debug, Clone, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Eq, PartialEq, Hash)]
 pub struct IdBound {
     #[derivative(PartialEq = "ignore", Hash = "ignore")]
@@ -149,6 +180,13 @@ pub struct IdBound {
     pub uri: Url,
 }
 
+impl IdBound {
+    /// The name of the referenced declaration as it is written in its `where` clause
+    pub fn local_name(&self) -> &str {
+        split_where_name(&self.id).1
+    }
+}
+
 impl fmt::Display for IdBound {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.id)
@@ -164,6 +202,7 @@ impl HasSpan for IdBound {
 /// Whether the metavariable corresponds to a typed hole written by the user
 /// or whether it was inserted during lowering for an implicit argument.
 #[derive(Debug, Clone, Copy, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Eq, PartialEq, Hash)]
 pub enum MetaVarKind {
     /// A typed hole written `_` that must be solved during type inference.
@@ -180,6 +219,7 @@ pub enum MetaVarKind {
 /// A metavariable which stands for unknown terms which
 /// have to be determined during elaboration.
 #[derive(Debug, Clone, Copy, Derivative)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Eq, PartialEq, Hash)]
 pub struct MetaVar {
     pub span: Option<Span>,
@@ -268,7 +308,8 @@ impl Print for MetaVar {
 /// The second component counts the number of binders in that binder list between the end
 /// of the binder list and the binder this variable originated from.
 ///
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Idx {
     pub fst: usize,
     pub snd: usize,
@@ -294,6 +335,7 @@ impl Print for Idx {
 /// The second component counts the number of binders in that binder list between the start
 /// of the binder list and the binder this variable originated from.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Lvl {
     pub fst: usize,
     pub snd: usize,
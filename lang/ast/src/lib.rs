@@ -2,6 +2,10 @@ pub mod ctx;
 mod decls;
 mod exp;
 mod ident;
+#[cfg(feature = "serde")]
+mod map_as_seq;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
 pub mod traits;
 
 pub use decls::*;
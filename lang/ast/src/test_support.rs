@@ -0,0 +1,83 @@
+//! Constructors for syntax trees in unit tests
+//!
+//! Spans and inferred types are left empty, and all global names are bound in the module [uri].
+//! Other crates can use this module in their tests by enabling the feature `test-support`.
+
+use url::Url;
+
+use crate::*;
+
+/// The URI of the module in which all global names are bound
+pub fn uri() -> Url {
+    Url::parse("inmemory:///scratch.pol").unwrap()
+}
+
+/// An empty module at [uri] which contains `decls`
+pub fn module(decls: Vec<Decl>) -> Module {
+    Module { uri: uri(), use_decls: vec![], decls, meta_vars: HashMap::default(), comments: vec![] }
+}
+
+pub fn id(name: &str) -> IdBound {
+    IdBound { span: None, id: name.to_owned(), uri: uri() }
+}
+
+pub fn var(name: &str, fst: usize, snd: usize) -> Box<Exp> {
+    Box::new(Exp::Variable(Variable {
+        span: None,
+        idx: Idx { fst, snd },
+        name: VarBound::from_string(name),
+        inferred_type: None,
+    }))
+}
+
+pub fn type_univ() -> Box<Exp> {
+    Box::new(TypeUniv::new().into())
+}
+
+pub fn call(kind: CallKind, name: &str, args: Vec<Box<Exp>>) -> Box<Exp> {
+    Box::new(Exp::Call(Call {
+        span: None,
+        kind,
+        name: id(name),
+        args: Args { args: args.into_iter().map(Arg::UnnamedArg).collect() },
+        inferred_type: None,
+    }))
+}
+
+pub fn ctor(name: &str, args: Vec<Box<Exp>>) -> Box<Exp> {
+    call(CallKind::Constructor, name, args)
+}
+
+pub fn dot_call(kind: DotCallKind, exp: Box<Exp>, name: &str, args: Vec<Box<Exp>>) -> Box<Exp> {
+    Box::new(Exp::DotCall(DotCall {
+        span: None,
+        kind,
+        exp,
+        name: id(name),
+        args: Args { args: args.into_iter().map(Arg::UnnamedArg).collect() },
+        inferred_type: None,
+    }))
+}
+
+/// The pattern `name(params)`, or the copattern `.name(params)` if `is_copattern` is set
+pub fn pattern(is_copattern: bool, name: &str, params: &[&str]) -> Pattern {
+    let params = params
+        .iter()
+        .map(|param| ParamInst {
+            span: None,
+            info: None,
+            name: VarBind::from_string(param),
+            typ: None,
+        })
+        .collect();
+    Pattern { is_copattern, name: id(name), params: TelescopeInst { params } }
+}
+
+pub fn case(pattern: Pattern, body: Box<Exp>) -> Case {
+    Case { span: None, pattern, body: Some(body) }
+}
+
+/// An explicit parameter without a default value
+pub fn param(name: &str, typ: Box<Exp>) -> Param {
+    Param { implicit: false, lazy: false, name: VarBind::from_string(name), typ, default: None }
+}
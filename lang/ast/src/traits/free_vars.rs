@@ -0,0 +1,101 @@
+use std::collections::BTreeSet;
+
+use crate::ctx::values::{Binder, TypeCtx};
+use crate::ctx::{Context, GenericCtx, LevelCtx};
+use crate::Idx;
+
+pub trait FreeVariables {
+    /// Collect the free variables of `self` into `fvs`
+    ///
+    /// `ctx` contains the binders between the root of the term and `self`.
+    /// The collected indices are relative to the root of the term.
+    fn collect_free_vars(&self, ctx: &mut LevelCtx, fvs: &mut BTreeSet<Idx>);
+
+    /// The free variables of `self`, relative to the root of `self`
+    fn free_vars(&self) -> BTreeSet<Idx> {
+        let mut fvs = BTreeSet::new();
+        self.collect_free_vars(&mut GenericCtx::empty(), &mut fvs);
+        fvs
+    }
+
+    /// The free variables of `self` together with their binders
+    ///
+    /// `ctx` is the context in which `self` is well-scoped.
+    /// The types of the returned binders are valid in `ctx`.
+    fn free_vars_typed(&self, ctx: &TypeCtx) -> Vec<(Idx, Binder)> {
+        self.free_vars().into_iter().map(|idx| (idx, ctx.lookup(idx))).collect()
+    }
+}
+
+impl<T: FreeVariables> FreeVariables for Box<T> {
+    fn collect_free_vars(&self, ctx: &mut LevelCtx, fvs: &mut BTreeSet<Idx>) {
+        (**self).collect_free_vars(ctx, fvs)
+    }
+}
+
+impl<T: FreeVariables> FreeVariables for Vec<T> {
+    fn collect_free_vars(&self, ctx: &mut LevelCtx, fvs: &mut BTreeSet<Idx>) {
+        for x in self {
+            x.collect_free_vars(ctx, fvs)
+        }
+    }
+}
+
+impl<T: FreeVariables> FreeVariables for Option<T> {
+    fn collect_free_vars(&self, ctx: &mut LevelCtx, fvs: &mut BTreeSet<Idx>) {
+        if let Some(x) = self {
+            x.collect_free_vars(ctx, fvs)
+        }
+    }
+}
+
+#[cfg(test)]
+mod free_vars_tests {
+    use crate::test_support::{case, ctor, pattern, var};
+    use crate::*;
+
+    use super::*;
+
+    fn pair(lhs: Box<Exp>, rhs: Box<Exp>) -> Box<Exp> {
+        ctor("Pair", vec![lhs, rhs])
+    }
+
+    /// A comatch with a single cocase binding the given parameters
+    fn comatch(params: &[&str], body: Box<Exp>) -> Box<Exp> {
+        Box::new(Exp::LocalComatch(LocalComatch {
+            span: None,
+            ctx: None,
+            name: Label { id: 0, user_name: None },
+            is_lambda_sugar: false,
+            cases: vec![case(pattern(true, "ap", params), body)],
+            inferred_type: None,
+        }))
+    }
+
+    #[test]
+    fn free_vars_variable() {
+        let exp = var("x", 2, 1);
+        assert_eq!(exp.free_vars(), BTreeSet::from([Idx { fst: 2, snd: 1 }]))
+    }
+
+    #[test]
+    fn free_vars_bound_in_term() {
+        // \x. (x, y)
+        let exp = comatch(&["x"], pair(var("x", 0, 0), var("y", 1, 0)));
+        assert_eq!(exp.free_vars(), BTreeSet::from([Idx { fst: 0, snd: 0 }]))
+    }
+
+    #[test]
+    fn free_vars_shadowing() {
+        // \x. \x. (x, y), where the inner x shadows the outer x
+        let exp = comatch(&["x"], comatch(&["x"], pair(var("x", 0, 0), var("y", 2, 1))));
+        assert_eq!(exp.free_vars(), BTreeSet::from([Idx { fst: 0, snd: 1 }]))
+    }
+
+    #[test]
+    fn free_vars_same_telescope() {
+        // \(x, x). (x, x), where the second x shadows the first x in the same telescope
+        let exp = comatch(&["x", "x"], pair(var("x", 0, 0), var("x", 0, 1)));
+        assert_eq!(exp.free_vars(), BTreeSet::new())
+    }
+}
@@ -1,15 +1,15 @@
 mod contains_metavars;
+mod free_vars;
 mod has_span;
 mod has_type;
-mod occurs;
 mod shift;
 pub mod subst;
 mod zonk;
 
 pub use contains_metavars::*;
+pub use free_vars::*;
 pub use has_span::*;
 pub use has_type::*;
-pub use occurs::*;
 pub use shift::*;
 pub use subst::*;
 pub use zonk::*;
@@ -0,0 +1,52 @@
+use codespan::ByteIndex;
+use url::Url;
+
+use ast::{CallKind, DotCallKind, IdBound};
+
+use crate::database::Database;
+use crate::info::*;
+
+impl Database {
+    /// A canonical name of the symbol at the given index
+    ///
+    /// The canonical name consists of the URI of the module which declares the symbol and the
+    /// name of the symbol, e.g. `file:///lib/nat.pol#Nat`. Constructors and destructors are
+    /// qualified by the type they belong to, e.g. `file:///lib/nat.pol#Nat.S`.
+    /// The canonical name does not change as long as the symbol is neither renamed nor moved.
+    pub async fn canonical_name(&mut self, uri: &Url, idx: ByteIndex) -> Option<String> {
+        let info = self.hoverinfo_at_index(uri, idx).await?;
+        // References point to their declaration, declarations are located in the current module
+        let decl_uri = match info.content.definition_site() {
+            Some((decl_uri, _)) => decl_uri.clone(),
+            None => uri.clone(),
+        };
+        let name = |id: &String| IdBound { span: None, id: id.clone(), uri: decl_uri.clone() };
+
+        let qualified_name = match &info.content {
+            InfoContent::TypeCtorInfo(TypeCtorInfo { name: id, .. })
+            | InfoContent::DataInfo(DataInfo { name: id, .. })
+            | InfoContent::CodataInfo(CodataInfo { name: id, .. }) => id.clone(),
+            InfoContent::CallInfo(CallInfo { kind: CallKind::Constructor, name: id, .. })
+            | InfoContent::CtorInfo(CtorInfo { name: id, .. }) => {
+                let type_info_table = self.type_info_table(&decl_uri).await.ok()?;
+                let ctor = type_info_table.lookup_ctor(&name(id)).ok()?;
+                format!("{}.{}", ctor.typ.name.id, id)
+            }
+            InfoContent::DotCallInfo(DotCallInfo {
+                kind: DotCallKind::Destructor,
+                name: id,
+                ..
+            })
+            | InfoContent::DtorInfo(DtorInfo { name: id, .. }) => {
+                let type_info_table = self.type_info_table(&decl_uri).await.ok()?;
+                let dtor = type_info_table.lookup_dtor(&name(id)).ok()?;
+                format!("{}.{}", dtor.self_param.typ.name.id, id)
+            }
+            InfoContent::CallInfo(CallInfo { name: id, .. })
+            | InfoContent::DotCallInfo(DotCallInfo { name: id, .. }) => id.clone(),
+            _ => return None,
+        };
+
+        Some(format!("{decl_uri}#{qualified_name}"))
+    }
+}
@@ -0,0 +1,100 @@
+//! Checking a module while reporting all of its diagnostics at once
+
+use std::rc::Rc;
+
+use ast::*;
+use elaborator::typechecker::ModuleErrors;
+use miette_util::ToMiette;
+use url::Url;
+
+use crate::database::Database;
+use crate::references::Occurrences;
+use crate::result::{DriverError, ModuleDiagnostic};
+use crate::Error;
+
+impl Database {
+    /// Check the module at `uri` and compute all of its errors and warnings
+    ///
+    /// Unlike [Database::ast], which stops at the first error, the declarations of the module
+    /// are all checked even if some of them contain errors.
+    /// Syntax and lowering errors are still reported on their own, since checking requires the
+    /// module to be lowered successfully.
+    pub async fn check_all(&mut self, uri: &Url) -> Vec<ModuleDiagnostic> {
+        let mut diagnostics = vec![];
+        match self.ast(uri).await {
+            Ok(_) => {}
+            Err(Error::Type(_)) => diagnostics.extend(self.type_errors(uri).await),
+            Err(err) => return vec![err.into()],
+        }
+        match self.warnings(uri).await {
+            Ok(warnings) => diagnostics.extend(warnings.into_iter().map(ModuleDiagnostic::Warning)),
+            Err(err) => diagnostics.push(err.into()),
+        }
+        diagnostics
+    }
+
+    /// The type errors of all declarations of the module at `uri`
+    ///
+    /// The declarations which refer to a declaration with errors get a secondary diagnostic,
+    /// since their own errors, if any, may be caused by it.
+    async fn type_errors(&mut self, uri: &Url) -> Vec<ModuleDiagnostic> {
+        let info_table = match self.type_info_table(uri).await {
+            Ok(info_table) => info_table,
+            Err(err) => return vec![err.into()],
+        };
+        let ust = match self.ust(uri).await {
+            Ok(ust) => ust,
+            Err(err) => return vec![err.into()],
+        };
+        let result = elaborator::typechecker::check_all_with_lookup_table(
+            Rc::new((*ust).clone()),
+            &info_table,
+            &self.eval_config,
+        );
+        let Err(ModuleErrors { errors, failed_decls }) = result else {
+            return vec![];
+        };
+        let mut diagnostics: Vec<ModuleDiagnostic> =
+            errors.into_iter().map(|err| Error::Type(Box::new(err)).into()).collect();
+        diagnostics.extend(
+            dependents_of_failed(uri, &ust, &failed_decls).into_iter().map(|err| err.into()),
+        );
+        diagnostics
+    }
+}
+
+/// A secondary error for each declaration of `module` which refers to one of `failed_decls`
+///
+/// References to the constructors and destructors of a failed type also refer to the type.
+fn dependents_of_failed(uri: &Url, module: &Module, failed_decls: &[String]) -> Vec<Error> {
+    let mut failed: HashMap<&str, &str> = HashMap::default();
+    for symbol in module.symbols().filter(|symbol| failed_decls.contains(&symbol.name.id)) {
+        let name = symbol.name.id.as_str();
+        failed.insert(name, name);
+        failed.extend(symbol.xtors.iter().map(|xtor| (xtor.id.as_str(), name)));
+    }
+
+    let mut out = vec![];
+    for decl in module.decls.iter() {
+        let name = &decl.ident().id;
+        let mut occurrences = vec![];
+        decl.occurrences(uri, &mut occurrences);
+        let mut reported: HashSet<&str> = HashSet::default();
+        for occ in occurrences.iter().filter(|occ| !occ.is_decl && occ.name.uri == *uri) {
+            let Some(dependency) = failed.get(occ.name.id.as_str()).copied() else {
+                continue;
+            };
+            if dependency != name.as_str() && reported.insert(dependency) {
+                out.push(
+                    DriverError::DependsOnFailedDecl {
+                        name: name.clone(),
+                        dependency: dependency.to_owned(),
+                        span: occ.span.map(|span| span.to_miette()),
+                    }
+                    .into(),
+                );
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,203 @@
+use codespan::{ByteIndex, Span};
+use elaborator::result::TypeError;
+use printer::tokens::{FAT_ARROW, QUESTION_MARK};
+use printer::Print;
+use url::Url;
+
+use ast::*;
+
+use crate::database::Database;
+use crate::fill_hole::indentation;
+
+use super::Edit;
+
+pub struct MatchCompletion {
+    pub title: String,
+    pub edits: Vec<Edit>,
+}
+
+impl Database {
+    /// Add the missing cases to the pattern match at the given index
+    ///
+    /// This is only offered if the module does not typecheck because a pattern match lacks cases.
+    /// The cases for all constructors which are not covered by the innermost match containing
+    /// the index are inserted before its closing brace.
+    /// Each case binds the parameters of its constructor under their declared names
+    /// and has a hole `?` as its body.
+    pub async fn complete_match(
+        &mut self,
+        uri: &Url,
+        idx: ByteIndex,
+    ) -> Result<Option<MatchCompletion>, crate::Error> {
+        match self.ast(uri).await {
+            Err(crate::Error::Type(err))
+                if matches!(*err, TypeError::InvalidMatch { missing_cases: Some(_), .. }) => {}
+            _ => return Ok(None),
+        }
+
+        // The typechecked module is not available, so we work on the lowered module
+        let module = self.ust(uri).await?;
+        let Some(MatchAt { span, typ, cases }) =
+            module.decls.iter().find_map(|decl| decl.match_at(idx))
+        else {
+            return Ok(None);
+        };
+
+        // The scrutinee of a local match is not annotated with its type in the lowered module,
+        // so we find the data type by the constructors of the cases which are already present
+        let Some(name) = typ.or_else(|| cases.first().map(|case| &case.pattern.name)) else {
+            return Ok(None);
+        };
+        let ust = self.ust(&name.uri).await?;
+        let is_scrutinee_type = |data: &Data| match typ {
+            Some(typ) => data.name.id == typ.id,
+            None => data.ctors.iter().any(|ctor| ctor.name.id == name.id),
+        };
+        let Some(data) = ust.decls.iter().find_map(|decl| match decl {
+            Decl::Data(data) if is_scrutinee_type(data) => Some(data),
+            _ => None,
+        }) else {
+            return Ok(None);
+        };
+
+        let covered: HashSet<&str> =
+            cases.iter().map(|case| case.pattern.name.id.as_str()).collect();
+        let missing: Vec<String> = ust
+            .xtors_for_type(&data.name.id)
+            .into_iter()
+            .filter(|name| !covered.contains(name.id.as_str()))
+            .filter_map(|name| data.ctors.iter().find(|ctor| ctor.name.id == name.id))
+            .map(|ctor| {
+                let pattern = Pattern {
+                    is_copattern: false,
+                    name: IdBound { span: None, id: ctor.name.id.clone(), uri: ust.uri.clone() },
+                    params: ctor.params.instantiate(),
+                };
+                format!("{} {FAT_ARROW} {QUESTION_MARK}", pattern.print_to_string(None))
+            })
+            .collect();
+        if missing.is_empty() {
+            return Ok(None);
+        }
+
+        let source = self.source(uri).await?;
+        let edit = insert_cases(&source, span, cases, &missing);
+        Ok(Some(MatchCompletion { title: "Add missing cases".to_owned(), edits: vec![edit] }))
+    }
+}
+
+/// Insert the printed `missing` cases in front of the closing brace of the match at `span`
+///
+/// If the match spans multiple lines, every case is put on its own line,
+/// aligned with the last present case.
+fn insert_cases(source: &str, span: Span, cases: &[Case], missing: &[String]) -> Edit {
+    let brace = span.end().to_usize() - 1;
+    let before_brace = source[..brace].trim_end();
+    let at = ByteIndex(before_brace.len() as u32);
+    let sep = if cases.is_empty() || before_brace.ends_with(',') { "" } else { "," };
+
+    let text = if source[span.start().to_usize()..brace].contains('\n') {
+        let indent = match cases.last().and_then(|case| case.span) {
+            Some(case_span) => indentation(source, case_span),
+            None => format!("{}    ", indentation(source, span)),
+        };
+        let cases: Vec<String> = missing.iter().map(|case| format!("\n{indent}{case}")).collect();
+        format!("{sep}{}", cases.join(","))
+    } else {
+        format!("{sep} {}", missing.join(", "))
+    };
+
+    Edit { span: Span::new(at, at), text }
+}
+
+/// The span of a pattern match which contains the given index, together with its cases
+///
+/// The type of the scrutinee is only known for top-level definitions.
+struct MatchAt<'a> {
+    span: Span,
+    typ: Option<&'a IdBound>,
+    cases: &'a [Case],
+}
+
+/// Find the innermost pattern match whose span contains the given index
+trait FindMatch {
+    fn match_at(&self, idx: ByteIndex) -> Option<MatchAt<'_>>;
+}
+
+impl<T: FindMatch> FindMatch for Box<T> {
+    fn match_at(&self, idx: ByteIndex) -> Option<MatchAt<'_>> {
+        (**self).match_at(idx)
+    }
+}
+
+impl<T: FindMatch> FindMatch for Option<T> {
+    fn match_at(&self, idx: ByteIndex) -> Option<MatchAt<'_>> {
+        self.as_ref().and_then(|x| x.match_at(idx))
+    }
+}
+
+impl<T: FindMatch> FindMatch for Vec<T> {
+    fn match_at(&self, idx: ByteIndex) -> Option<MatchAt<'_>> {
+        self.iter().find_map(|x| x.match_at(idx))
+    }
+}
+
+fn contains(span: Option<Span>, idx: ByteIndex) -> Option<Span> {
+    span.filter(|span| span.start() <= idx && idx < span.end())
+}
+
+impl FindMatch for Decl {
+    fn match_at(&self, idx: ByteIndex) -> Option<MatchAt<'_>> {
+        match self {
+            Decl::Def(Def { span, self_param, cases, .. }) => {
+                let span = contains(*span, idx)?;
+                cases.match_at(idx).or(Some(MatchAt {
+                    span,
+                    typ: Some(&self_param.typ.name),
+                    cases,
+                }))
+            }
+            Decl::Codef(Codef { cases, .. }) => cases.match_at(idx),
+            Decl::Let(Let { body, .. }) => body.match_at(idx),
+            Decl::Data(_) | Decl::Codata(_) => None,
+        }
+    }
+}
+
+impl FindMatch for Case {
+    fn match_at(&self, idx: ByteIndex) -> Option<MatchAt<'_>> {
+        self.body.match_at(idx)
+    }
+}
+
+impl FindMatch for Args {
+    fn match_at(&self, idx: ByteIndex) -> Option<MatchAt<'_>> {
+        self.args.iter().find_map(|arg| match arg {
+            Arg::UnnamedArg(exp) | Arg::NamedArg(_, exp) => exp.match_at(idx),
+            Arg::InsertedImplicitArg(_) => None,
+        })
+    }
+}
+
+impl FindMatch for Exp {
+    fn match_at(&self, idx: ByteIndex) -> Option<MatchAt<'_>> {
+        let span = contains(self.span(), idx)?;
+
+        match self {
+            Exp::Variable(_) | Exp::TypeUniv(_) | Exp::Hole(_) => None,
+            Exp::TypCtor(TypCtor { args, .. }) | Exp::Call(Call { args, .. }) => args.match_at(idx),
+            Exp::DotCall(DotCall { exp, args, .. }) => {
+                exp.match_at(idx).or_else(|| args.match_at(idx))
+            }
+            Exp::Anno(Anno { exp, .. }) => exp.match_at(idx),
+            Exp::LocalMatch(LocalMatch { on_exp, cases, .. }) => on_exp
+                .match_at(idx)
+                .or_else(|| cases.match_at(idx))
+                .or(Some(MatchAt { span, typ: None, cases })),
+            Exp::LocalComatch(LocalComatch { cases, .. }) => cases.match_at(idx),
+            Exp::LocalLet(LocalLet { typ, bound, body, .. }) => {
+                typ.match_at(idx).or_else(|| bound.match_at(idx)).or_else(|| body.match_at(idx))
+            }
+        }
+    }
+}
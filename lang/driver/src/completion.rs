@@ -0,0 +1,295 @@
+use std::sync::Arc;
+
+use codespan::ByteIndex;
+use rust_lapper::Lapper;
+use url::Url;
+
+use ast::*;
+use printer::tokens::TYPE;
+use printer::Print;
+
+use crate::database::Database;
+use crate::info::{CasesInfo, DefInfo, Info, InfoContent, LocalMatchInfo};
+
+/// A single completion candidate
+pub struct Completion {
+    /// The text which is inserted
+    pub label: String,
+    /// The signature of the completed item
+    pub detail: String,
+    /// Doc comments of the completed item
+    pub doc: Option<Vec<String>>,
+    /// The kind of the completed item
+    pub kind: CompletionKind,
+}
+
+pub enum CompletionKind {
+    TypeCtor,
+    Ctor,
+    Dtor,
+    Def,
+    Codef,
+    Let,
+}
+
+impl Database {
+    /// Compute the completions at the given index
+    ///
+    /// If the index directly follows a `.` after an expression, the destructors of its type
+    /// and the definitions on its type are offered.
+    /// If the type of the expression is not known, no completions are offered.
+    ///
+    /// If the index is located between the cases of a pattern match,
+    /// the constructors which are not yet covered by the match are offered.
+    ///
+    /// Otherwise, the top-level names in scope are offered.
+    /// If the expected type at the index is known, the names whose result type has the same
+    /// head as the expected type are ranked first.
+    pub async fn completion(&mut self, uri: &Url, idx: ByteIndex) -> Vec<Completion> {
+        let Ok(source) = self.source(uri).await else {
+            return vec![];
+        };
+        let is_dot = |i: &usize| source.as_bytes().get(*i) == Some(&b'.');
+        let dot = idx.to_usize().checked_sub(1).filter(is_dot);
+        let completions = match dot {
+            Some(dot) => self.dtor_completions(uri, ByteIndex(dot as u32)).await,
+            None => match self.case_completions(uri, idx).await {
+                Some(completions) => Some(completions),
+                None => self.name_completions(uri, idx).await,
+            },
+        };
+        completions.unwrap_or_default()
+    }
+
+    /// The lowered modules whose top-level names are in scope in the module at `uri`
+    pub(crate) async fn usts_in_scope(&mut self, uri: &Url) -> Option<Vec<Arc<Module>>> {
+        let mut modules = vec![uri.clone()];
+        modules.extend(self.deps(uri).await.ok()?);
+
+        let mut usts = vec![];
+        for module_uri in modules {
+            // The module may not even be lowered if the user is currently typing
+            match self.ust(&module_uri).await {
+                Ok(ust) => usts.push(ust),
+                Err(_) => match self.ust.get_even_if_stale(&module_uri) {
+                    Some(Ok(ust)) => usts.push(ust.clone()),
+                    _ => continue,
+                },
+            }
+        }
+        Some(usts)
+    }
+
+    /// The information index of the module at `uri`
+    ///
+    /// While the user is typing, the module usually does not typecheck.
+    /// In that case we fall back to the index of the last successfully typechecked module.
+    /// Since the text in front of the cursor has not changed, the spans there are still valid.
+    async fn info_for_completion(&mut self, uri: &Url) -> Option<Lapper<u32, Info>> {
+        match self.info_by_id(uri).await {
+            Ok(lapper) => Some(lapper),
+            Err(_) => self.info_by_id.get_even_if_stale(uri).cloned(),
+        }
+    }
+
+    /// The type of the outermost expression whose span ends at the given index
+    pub(crate) async fn typ_ending_at(&mut self, uri: &Url, end: ByteIndex) -> Option<Box<Exp>> {
+        let lapper = self.info_for_completion(uri).await?;
+        let end = u32::from(end);
+        lapper
+            .find(end.saturating_sub(1), end)
+            .filter(|interval| interval.stop == end && interval.val.typ.is_some())
+            .max_by_key(|interval| interval.stop - interval.start)
+            .and_then(|interval| interval.val.typ.clone())
+    }
+
+    /// The type of the innermost expression whose span contains the given index
+    async fn typ_at(&mut self, uri: &Url, idx: ByteIndex) -> Option<Box<Exp>> {
+        let lapper = self.info_for_completion(uri).await?;
+        let idx = u32::from(idx);
+        lapper
+            .find(idx.saturating_sub(1), idx + 1)
+            .filter(|interval| interval.start <= idx && interval.val.typ.is_some())
+            .min_by_key(|interval| interval.stop - interval.start)
+            .and_then(|interval| interval.val.typ.clone())
+    }
+
+    /// The cases of the innermost pattern match which contains the given index between its cases
+    async fn cases_at(&mut self, uri: &Url, idx: ByteIndex) -> Option<CasesInfo> {
+        let lapper = self.info_for_completion(uri).await?;
+        let raw_idx = u32::from(idx);
+        lapper
+            .find(raw_idx, raw_idx + 1)
+            .filter_map(|interval| {
+                let cases = match &interval.val.content {
+                    InfoContent::DefInfo(DefInfo { cases, .. })
+                    | InfoContent::LocalMatchInfo(LocalMatchInfo { cases, .. }) => {
+                        cases.as_ref()?
+                    }
+                    _ => return None,
+                };
+                cases.is_between_cases(idx).then_some((interval.stop - interval.start, cases))
+            })
+            .min_by_key(|(len, _)| *len)
+            .map(|(_, cases)| cases.clone())
+    }
+
+    async fn dtor_completions(&mut self, uri: &Url, dot: ByteIndex) -> Option<Vec<Completion>> {
+        let typ = self.typ_ending_at(uri, dot).await?;
+        let Exp::TypCtor(TypCtor { name, .. }) = *typ else {
+            return None;
+        };
+
+        let mut completions = vec![];
+        if let Some(Ok(ust)) = self.ust.get_even_if_stale(&name.uri) {
+            if let Some(codata) = ust.decls.iter().find_map(|decl| match decl {
+                Decl::Codata(codata) if codata.name == name => Some(codata),
+                _ => None,
+            }) {
+                completions.extend(codata.dtors.iter().map(dtor_completion));
+            }
+        }
+        // Definitions on the type may be located in any module in scope
+        for ust in self.usts_in_scope(uri).await? {
+            completions.extend(ust.decls.iter().filter_map(|decl| match decl {
+                Decl::Def(def) if def.self_param.typ.name == name => Some(def_completion(def)),
+                _ => None,
+            }));
+        }
+        Some(completions)
+    }
+
+    async fn case_completions(&mut self, uri: &Url, idx: ByteIndex) -> Option<Vec<Completion>> {
+        let CasesInfo { scrutinee, covered, .. } = self.cases_at(uri, idx).await?;
+
+        let ust = self.ust.get_even_if_stale(&scrutinee.name.uri)?.as_ref().ok()?;
+        let data = ust.decls.iter().find_map(|decl| match decl {
+            Decl::Data(data) if data.name == scrutinee.name => Some(data),
+            _ => None,
+        })?;
+
+        let completions = ust
+            .xtors_for_type(&data.name.id)
+            .into_iter()
+            .filter(|name| !covered.contains(&name.id))
+            .filter_map(|name| data.ctors.iter().find(|ctor| ctor.name == name))
+            .map(ctor_completion)
+            .collect();
+        Some(completions)
+    }
+
+    async fn name_completions(&mut self, uri: &Url, idx: ByteIndex) -> Option<Vec<Completion>> {
+        let expected = self.typ_at(uri, idx).await.and_then(|typ| Head::of(&typ));
+
+        let mut completions = vec![];
+        for ust in self.usts_in_scope(uri).await? {
+            completions.extend(ust.decls.iter().flat_map(candidates));
+        }
+
+        // The sort is stable, so the candidates stay in declaration order otherwise
+        let matches_expected = |head: &Option<Head>| expected.is_some() && *head == expected;
+        completions.sort_by_key(|(head, _)| !matches_expected(head));
+        Some(completions.into_iter().map(|(_, completion)| completion).collect())
+    }
+}
+
+/// The head of the result type of a top-level name
+#[derive(PartialEq)]
+enum Head {
+    TypeUniv,
+    TypCtor(IdBound),
+}
+
+impl Head {
+    fn of(typ: &Exp) -> Option<Head> {
+        match typ {
+            Exp::TypeUniv(_) => Some(Head::TypeUniv),
+            Exp::TypCtor(TypCtor { name, .. }) => Some(Head::TypCtor(name.clone())),
+            _ => None,
+        }
+    }
+}
+
+/// The top-level names introduced by a declaration, together with the heads of their result types
+///
+/// Definitions are not included, since they can only be used after a `.`.
+fn candidates(decl: &Decl) -> Vec<(Option<Head>, Completion)> {
+    let doc = |doc: &Option<DocComment>| doc.clone().map(|doc| doc.docs);
+
+    match decl {
+        Decl::Data(Data { name, doc: d, typ, ctors, .. }) => {
+            let type_ctor = Completion {
+                label: name.id.clone(),
+                detail: signature(name, typ, TYPE.to_owned()),
+                doc: doc(d),
+                kind: CompletionKind::TypeCtor,
+            };
+            let ctors = ctors
+                .iter()
+                .map(|ctor| (Some(Head::TypCtor(ctor.typ.name.clone())), ctor_completion(ctor)));
+            std::iter::once((Some(Head::TypeUniv), type_ctor)).chain(ctors).collect()
+        }
+        Decl::Codata(Codata { name, doc: d, typ, .. }) => {
+            let type_ctor = Completion {
+                label: name.id.clone(),
+                detail: signature(name, typ, TYPE.to_owned()),
+                doc: doc(d),
+                kind: CompletionKind::TypeCtor,
+            };
+            vec![(Some(Head::TypeUniv), type_ctor)]
+        }
+        Decl::Codef(Codef { name, doc: d, params, typ, .. }) => {
+            let completion = Completion {
+                label: name.id.clone(),
+                detail: signature(name, params, typ.print_to_string(None)),
+                doc: doc(d),
+                kind: CompletionKind::Codef,
+            };
+            vec![(Some(Head::TypCtor(typ.name.clone())), completion)]
+        }
+        Decl::Let(Let { name, doc: d, params, typ, .. }) => {
+            let completion = Completion {
+                label: name.id.clone(),
+                detail: signature(name, params, typ.print_to_string(None)),
+                doc: doc(d),
+                kind: CompletionKind::Let,
+            };
+            vec![(Head::of(typ), completion)]
+        }
+        Decl::Def(_) => vec![],
+    }
+}
+
+fn signature(name: &IdBind, params: &Telescope, typ: String) -> String {
+    format!("{}{}: {}", name.id, params.print_to_string(None), typ)
+}
+
+fn ctor_completion(ctor: &Ctor) -> Completion {
+    let Ctor { name, doc, params, typ, .. } = ctor;
+    Completion {
+        label: name.id.clone(),
+        detail: signature(name, params, typ.print_to_string(None)),
+        doc: doc.clone().map(|doc| doc.docs),
+        kind: CompletionKind::Ctor,
+    }
+}
+
+fn dtor_completion(dtor: &Dtor) -> Completion {
+    let Dtor { name, doc, params, ret_typ, .. } = dtor;
+    Completion {
+        label: name.id.clone(),
+        detail: signature(name, params, ret_typ.print_to_string(None)),
+        doc: doc.clone().map(|doc| doc.docs),
+        kind: CompletionKind::Dtor,
+    }
+}
+
+fn def_completion(def: &Def) -> Completion {
+    let Def { name, doc, params, ret_typ, .. } = def;
+    Completion {
+        label: name.id.clone(),
+        detail: signature(name, params, ret_typ.print_to_string(None)),
+        doc: doc.clone().map(|doc| doc.docs),
+        kind: CompletionKind::Def,
+    }
+}
@@ -1,24 +1,33 @@
-use crate::result::DriverError;
+use crate::result::{CyclicImport, DriverError, ParseErrors};
 use crate::{cache::*, Error, FileSource};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeSet;
+use std::hash::{Hash, Hasher};
 use std::rc::Rc;
 use std::sync::Arc;
 
 use crate::dependency_graph::DependencyGraph;
 use ast::Exp;
 use ast::HashSet;
+use elaborator::normalizer::evaluator::{EvalConfig, Evaluator};
 use elaborator::normalizer::normalize::Normalize;
 use elaborator::{build_type_info_table, ModuleTypeInfoTable, TypeInfoTable};
-use lowering::{ModuleSymbolTable, SymbolTable};
+use lowering::{LoweringError, ModuleSymbolTable, SymbolTable};
 use parser::cst;
 use parser::cst::decls::UseDecl;
 use transformations::Rename;
 use url::Url;
 
 use crate::fs::*;
+use crate::incremental::CheckedDecls;
 use crate::info::*;
+use crate::qualify::Qualify;
 
 use rust_lapper::Lapper;
 
+/// The default number of evaluation steps each normalization may take, see [Database::eval_config]
+pub const DEFAULT_FUEL: u64 = 10_000_000;
+
 /// A database tracking a set of source files
 pub struct Database {
     /// The source provider of the files (file system or in-memory)
@@ -35,12 +44,31 @@ pub struct Database {
     pub ust: Cache<Result<Arc<ast::Module>, Error>>,
     /// The typechecked AST of a module
     pub ast: Cache<Result<Arc<ast::Module>, Error>>,
+    /// The most recent successfully typechecked version of a module
+    ///
+    /// In contrast to `ast`, this is kept if typechecking fails, such that unchanged declarations
+    /// can be reused once the module typechecks again.
+    pub checked_decls: Cache<CheckedDecls>,
     /// The type info table constructed during typechecking
     pub module_type_info_table: Cache<elaborator::ModuleTypeInfoTable>,
     /// Hover information for spans
     pub info_by_id: Cache<Lapper<u32, Info>>,
     /// Spans of top-level items
     pub item_by_id: Cache<Lapper<u32, Item>>,
+    /// The documents which are currently open in an editor
+    ///
+    /// This is tracked separately from the caches above, since closed modules may stay cached
+    /// as long as open modules depend on them.
+    pub open_documents: HashSet<Url>,
+    /// How terms are normalized during typechecking and evaluation
+    ///
+    /// By default, each normalization may take at most [DEFAULT_FUEL] evaluation steps.
+    /// Exceeding this limit is reported as an error, which prevents non-terminating
+    /// definitions from hanging the typechecker.
+    pub eval_config: EvalConfig,
+    /// Whether typechecked modules are cached in `.polc` files
+    #[cfg(feature = "polc")]
+    pub use_polc_cache: bool,
 }
 
 impl Database {
@@ -109,8 +137,12 @@ impl Database {
     async fn recompute_cst(&mut self, uri: &Url) -> Result<Arc<cst::decls::Module>, Error> {
         log::debug!("Recomputing cst for: {}", uri);
         let source = self.source(uri).await?;
-        let module =
-            parser::parse_module(uri.clone(), &source).map_err(Error::Parser).map(Arc::new);
+        let module = parser::parse_module(uri.clone(), &source)
+            .map_err(|errors| match ParseErrors::new(errors) {
+                Some(errors) => Error::Parser(errors),
+                None => DriverError::Impossible("Parser failed without errors".to_owned()).into(),
+            })
+            .map(Arc::new);
         self.cst.insert(uri.clone(), module.clone());
         module
     }
@@ -154,11 +186,21 @@ impl Database {
     pub async fn recompute_ust(&mut self, uri: &Url) -> Result<Arc<ast::Module>, Error> {
         log::debug!("Recomputing ust for: {}", uri);
         let cst = self.cst(uri).await?;
-        let deps = self.deps(uri).await?;
+        let symbol_table = self.symbol_table_with_deps(uri).await?;
 
-        // Compute the SymbolTable consisting of all the
-        // ModuleSymbolTables of all direct dependencies
-        // and the SymbolTable from the module itself.
+        let ust = match lowering::lower_module_with_symbol_table(&cst, &symbol_table) {
+            Ok(ust) => Ok(Arc::new(ust)),
+            Err(err) => Err(Error::Lowering(self.suggest_import(uri, err).await)),
+        };
+
+        self.ust.insert(uri.clone(), ust.clone());
+        ust
+    }
+
+    /// Compute the SymbolTable consisting of all the ModuleSymbolTables of all
+    /// dependencies and the SymbolTable from the module itself.
+    pub(crate) async fn symbol_table_with_deps(&mut self, uri: &Url) -> Result<SymbolTable, Error> {
+        let deps = self.deps(uri).await?;
         let mut symbol_table = SymbolTable::default();
         let module_symbol_table = self.symbol_table(uri).await?;
         symbol_table.insert(uri.clone(), module_symbol_table);
@@ -166,13 +208,39 @@ impl Database {
             let module_symbol_table = self.symbol_table(&dep).await?;
             symbol_table.insert(dep.clone(), module_symbol_table);
         }
+        Ok(symbol_table)
+    }
 
-        let ust = lowering::lower_module_with_symbol_table(&cst, &symbol_table)
-            .map_err(Error::Lowering)
-            .map(Arc::new);
-
-        self.ust.insert(uri.clone(), ust.clone());
-        ust
+    /// Point out the module from which an undefined identifier could be imported
+    ///
+    /// The candidates are the modules known to the database which are not imported by the module
+    /// at `uri`, such as modules which are only imported indirectly.
+    async fn suggest_import(&mut self, uri: &Url, err: LoweringError) -> LoweringError {
+        // An import of the exact name is a better suggestion than a similar name
+        let (name, span, help) = match err {
+            LoweringError::UndefinedIdent { name, span, help } => (name, span, help),
+            err => return err,
+        };
+        let deps = self.deps(uri).await.unwrap_or_default();
+        let mut candidates: Vec<Url> = self
+            .files
+            .keys()
+            .filter(|candidate| *candidate != uri && !deps.contains(candidate))
+            .cloned()
+            .collect();
+        candidates.sort();
+        for candidate in candidates {
+            let Ok(symbol_table) = self.symbol_table(&candidate).await else {
+                continue;
+            };
+            if symbol_table.contains_key(&name) {
+                let path = uri.make_relative(&candidate).unwrap_or_else(|| candidate.to_string());
+                let help =
+                    format!("{} is declared in {path}, try adding `use \"{path}\"`", name.id);
+                return LoweringError::UndefinedIdent { name, span, help: Some(help) };
+            }
+        }
+        LoweringError::UndefinedIdent { name, span, help }
     }
 
     // Core API: TypeInfoTable
@@ -235,15 +303,53 @@ impl Database {
     pub async fn recompute_ast(&mut self, uri: &Url) -> Result<Arc<ast::Module>, Error> {
         log::debug!("Recomputing ast for: {}", uri);
 
+        #[cfg(all(feature = "polc", not(target_arch = "wasm32")))]
+        if self.use_polc_cache {
+            if let Some(ast) = self.read_polc_file(uri).await? {
+                return Ok(ast);
+            }
+        }
+
         // Compute the type info table
         let info_table = self.type_info_table(uri).await?;
 
-        // Typecheck module
-        let ust = self.ust(uri).await.map(|x| (*x).clone())?;
-        let ast = elaborator::typechecker::check_with_lookup_table(Rc::new(ust), &info_table)
-            .map(Arc::new)
-            .map_err(|arg| Error::Type(Box::new(arg)));
+        // Typecheck module, reusing the unchanged declarations of its previous version
+        let ust = self.ust(uri).await?;
+        let (previous, reused, fingerprints) = self.reusable_decls(uri, &ust).await?;
+        let ust = Rc::new((*ust).clone());
+        let ast = match &previous {
+            Some(previous) => {
+                log::debug!("Reusing {} declarations of {}", reused.len(), uri);
+                elaborator::typechecker::check_incrementally(
+                    ust,
+                    &info_table,
+                    &self.eval_config,
+                    previous,
+                    &reused,
+                )
+            }
+            None => elaborator::typechecker::check_with_lookup_table(
+                ust,
+                &info_table,
+                &self.eval_config,
+            ),
+        }
+        .map(Arc::new)
+        .map_err(|arg| Error::Type(Box::new(arg)));
+        if let Ok(module) = &ast {
+            self.remember_checked_decls(uri, module.clone(), fingerprints);
+        }
         self.ast.insert(uri.clone(), ast.clone());
+
+        #[cfg(all(feature = "polc", not(target_arch = "wasm32")))]
+        if self.use_polc_cache {
+            if let Ok(module) = &ast {
+                if let Err(err) = self.write_polc_file(uri, module).await {
+                    log::warn!("Could not write module cache for {}: {}", uri, err);
+                }
+            }
+        }
+
         ast
     }
 
@@ -310,6 +416,27 @@ impl Database {
         self.deps(uri).await
     }
 
+    /// Hash of the sources of the modules `uris` and of all modules they transitively import
+    ///
+    /// The hash only needs to be stable between runs of the same compiler binary,
+    /// since it is only compared with hashes computed by the same version.
+    pub(crate) async fn sources_stamp(&mut self, uris: Vec<Url>) -> Result<u64, Error> {
+        let mut modules = BTreeSet::new();
+        let mut todo = uris;
+        while let Some(uri) = todo.pop() {
+            if modules.insert(uri.clone()) {
+                todo.extend(self.deps(&uri).await?);
+            }
+        }
+
+        let mut hasher = DefaultHasher::new();
+        for uri in modules {
+            uri.hash(&mut hasher);
+            self.source(&uri).await?.hash(&mut hasher);
+        }
+        Ok(hasher.finish())
+    }
+
     // Creation
     //
     // The following methods provide various means to construct a driver instance.
@@ -329,9 +456,14 @@ impl Database {
             symbol_table: Cache::default(),
             ust: Cache::default(),
             ast: Cache::default(),
+            checked_decls: Cache::default(),
             module_type_info_table: Cache::default(),
             info_by_id: Cache::default(),
             item_by_id: Cache::default(),
+            open_documents: HashSet::default(),
+            eval_config: EvalConfig { fuel: Some(DEFAULT_FUEL), ..Default::default() },
+            #[cfg(feature = "polc")]
+            use_polc_cache: false,
         }
     }
 
@@ -372,6 +504,7 @@ impl Database {
         self.symbol_table.invalidate(uri);
         self.ust.invalidate(uri);
         self.ast.invalidate(uri);
+        self.checked_decls.invalidate(uri);
         self.module_type_info_table.invalidate(uri);
         self.info_by_id.invalidate(uri);
         self.item_by_id.invalidate(uri);
@@ -385,14 +518,15 @@ impl Database {
 
         match main {
             Some(exp) => {
-                let nf = exp.normalize_in_empty_env(&Rc::new(info_table));
+                let ev = Evaluator::new(Rc::new(info_table), &self.eval_config);
+                let nf = ev.in_decl("main", || exp.normalize_in_empty_env(&ev));
                 nf.map(Some).map_err(|type_err| Error::Type(Box::new(type_err)))
             }
             None => Ok(None),
         }
     }
 
-    pub fn pretty_error(&self, uri: &Url, err: Error) -> miette::Report {
+    pub fn pretty_error(&self, uri: &Url, err: impl Into<miette::Error>) -> miette::Report {
         let miette_error: miette::Error = err.into();
         let source = &self.files.get_even_if_stale(uri).unwrap().source;
         miette_error.with_source_code(miette::NamedSource::new(uri, source.to_owned()))
@@ -403,11 +537,43 @@ impl Database {
         self.source.write_string(uri, source).await.map_err(|err| err.into())
     }
 
+    /// Register a document which has been opened in an editor with the given text
+    ///
+    /// The text of the editor takes precedence over the contents of the file on disk.
+    pub async fn did_open(&mut self, uri: &Url, text: &str) -> Result<(), Error> {
+        self.open_documents.insert(uri.clone());
+        self.source.manage(uri);
+        self.source.write_string(uri, text).await?;
+        self.invalidate(uri).await
+    }
+
+    /// Unregister a document which has been closed in an editor
+    ///
+    /// Returns `true` if the document was open.
+    pub fn did_close(&mut self, uri: &Url) -> bool {
+        self.open_documents.remove(uri)
+    }
+
+    /// Whether the document is currently open in an editor
+    pub fn is_open(&self, uri: &Url) -> bool {
+        self.open_documents.contains(uri)
+    }
+
     pub async fn print_to_string(&mut self, uri: &Url) -> Result<String, Error> {
+        let mut module = self.normalized_ust(uri).await?;
+        module.qualify(&self.qualifiers(uri).await?);
+        Ok(printer::Print::print_to_string(&module, None))
+    }
+
+    /// The lowered module at `uri` in the form which is printed by [Database::print_to_string]
+    ///
+    /// Variables are renamed such that they can be printed unambiguously.
+    /// Lowering the printed module again yields a module which is structurally equal to this one.
+    pub async fn normalized_ust(&mut self, uri: &Url) -> Result<ast::Module, Error> {
         let module = self.ust(uri).await?;
         let mut module = (*module).clone();
         module.rename();
-        Ok(printer::Print::print_to_string(&module, None))
+        Ok(module)
     }
 
     pub async fn load_imports(&mut self, module_uri: &Url) -> Result<(), Error> {
@@ -448,10 +614,13 @@ impl Database {
         stack: &mut Vec<Url>,
         graph: &mut DependencyGraph,
     ) -> Result<(), Error> {
-        if stack.contains(module_uri) {
+        if let Some(start) = stack.iter().position(|uri| uri == module_uri) {
             // Cycle detected
-            let cycle = stack.to_vec();
-            return Err(DriverError::ImportCycle(module_uri.clone(), cycle).into());
+            // Only the modules which are still being visited are on the stack, so modules
+            // which are imported along several paths (diamonds) are not mistaken for cycles.
+            let mut path = stack[start..].to_vec();
+            path.push(module_uri.clone());
+            return Err(self.import_cycle(path).await);
         }
 
         if visited.contains(module_uri) {
@@ -483,6 +652,29 @@ impl Database {
         Ok(())
     }
 
+    /// The error for the import cycle `path`, pointing at the `use` declarations of the cycle
+    async fn import_cycle(&mut self, path: Vec<Url>) -> Error {
+        let mut imports = Vec::new();
+        for step in path.windows(2) {
+            let [uri, imported] = step else { continue };
+            let (Ok(module), Ok(source)) = (self.cst(uri).await, self.source(uri).await) else {
+                continue;
+            };
+            let use_decl = module.use_decls.iter().find(|UseDecl { path, .. }| {
+                self.resolve_module_name(path, uri).is_ok_and(|url| url == *imported)
+            });
+            if let Some(UseDecl { span, .. }) = use_decl {
+                imports.push(CyclicImport {
+                    uri: uri.clone(),
+                    imported: imported.clone(),
+                    source,
+                    span: (span.start().to_usize(), (span.end() - span.start()).to_usize()).into(),
+                });
+            }
+        }
+        DriverError::ImportCycle { path, imports }.into()
+    }
+
     /// Resolves a module name to a `Url` relative to the current module.
     pub fn resolve_module_name(&self, name: &str, current_module: &Url) -> Result<Url, Error> {
         current_module.join(name).map_err(|err| DriverError::Url(err).into())
@@ -503,7 +695,10 @@ mod path_support {
             } else {
                 path.parent().expect("Could not get parent directory")
             };
-            Self::from_source(FileSystemSource::new(path))
+            let db = Self::from_source(FileSystemSource::new(path));
+            #[cfg(feature = "polc")]
+            let db = Self { use_polc_cache: true, ..db };
+            db
         }
 
         /// Create a new database tracking the current working directory
@@ -98,7 +98,7 @@ impl DependencyGraph {
 /// # Returns
 ///
 /// A `String` representing the file name or the full path if extraction fails.
-fn url_to_label(url: &Url) -> String {
+pub(crate) fn url_to_label(url: &Url) -> String {
     // Extract the file name from the path
     if let Some(path_segments) = url.path_segments() {
         if let Some(file_name) = path_segments.last() {
@@ -0,0 +1,83 @@
+//! An index of the codes of all diagnostics
+//!
+//! Every diagnostic has a unique code which consists of a prefix for the phase reporting it
+//! and a number, e.g. `T-002` for a type error. Codes are never reassigned once a diagnostic
+//! is removed, and the number `000` is reserved for internal errors.
+//! The index is assembled from the `CODES` which every error type declares next to its variants.
+
+use elaborator::result::TypeError;
+use lowering::LoweringError;
+use parser::ParseError;
+use transformations::result::XfuncError;
+
+use crate::result::Warning;
+
+/// The codes declared by the error types of all phases
+const SOURCES: &[&[(&str, &str)]] =
+    &[ParseError::CODES, LoweringError::CODES, TypeError::CODES, XfuncError::CODES, Warning::CODES];
+
+/// The code of a diagnostic together with the template of its message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorCode {
+    pub code: String,
+    /// The message of the diagnostic, with placeholders such as `{name}` for its fields
+    pub message: String,
+}
+
+/// The codes of all diagnostics, sorted by code
+pub fn error_index() -> Vec<ErrorCode> {
+    let mut index: Vec<ErrorCode> = SOURCES
+        .iter()
+        .flat_map(|codes| codes.iter())
+        .map(|(code, message)| ErrorCode { code: code.to_string(), message: message.to_string() })
+        .collect();
+    index.sort_by(|lhs, rhs| lhs.code.cmp(&rhs.code));
+    index
+}
+
+#[cfg(test)]
+mod error_index_tests {
+    use std::collections::HashSet;
+
+    use miette::Diagnostic;
+
+    use super::*;
+
+    #[test]
+    fn codes_are_unique() {
+        let mut seen = HashSet::new();
+        for ErrorCode { code, .. } in error_index() {
+            assert!(seen.insert(code.clone()), "Diagnostic code {code} is used more than once");
+        }
+    }
+
+    #[test]
+    fn codes_are_well_formed() {
+        for ErrorCode { code, .. } in error_index() {
+            let (prefix, number) = code.split_once('-').expect("Code without a number");
+            assert!(matches!(prefix, "P" | "L" | "T" | "X" | "W"), "Unknown prefix in {code}");
+            assert!(
+                number.len() == 3 && number.chars().all(|c| c.is_ascii_digit()),
+                "Invalid number in {code}"
+            );
+        }
+    }
+
+    #[test]
+    fn index_contains_templates() {
+        let index = error_index();
+        let code = index.iter().find(|code| code.code == "T-001").unwrap();
+        assert_eq!(
+            code.message,
+            "Wrong number of arguments to {name} provided: got {actual}, expected {expected}"
+        );
+    }
+
+    #[test]
+    fn index_contains_reported_codes() {
+        let index = error_index();
+        let warning = Warning::UnusedDecl { name: "f".to_owned(), span: None };
+        let code = warning.code().unwrap().to_string();
+        assert!(index.iter().any(|err| err.code == code), "{code} is missing from the index");
+    }
+}
@@ -0,0 +1,76 @@
+//! Standalone expressions in the context of a module, e.g. as entered in a REPL
+
+use std::rc::Rc;
+
+use url::Url;
+
+use ast::{Exp, HasType};
+use elaborator::normalizer::evaluator::Evaluator;
+use elaborator::normalizer::normalize::{eval_in_empty_env, Normalize};
+use elaborator::normalizer::val::Val;
+use elaborator::result::TypeError;
+use elaborator::Goal;
+
+use crate::database::Database;
+use crate::result::ParseErrors;
+use crate::Error;
+
+/// The name under which a standalone expression is traced and its goals are reported
+const EXPR: &str = "<expr>";
+
+impl Database {
+    /// Evaluate the expression `src` in the context of the module at `uri`
+    ///
+    /// The expression may reference any top-level name which is in scope in the module.
+    /// It is elaborated against the typechecked module and evaluated in an empty environment.
+    /// Spans in the returned errors refer to `src` rather than to the module.
+    pub async fn eval_expr(&mut self, uri: &Url, src: &str) -> Result<Rc<Val>, Error> {
+        let exp = self.elaborate_expr(uri, src).await?;
+        let ev = Evaluator::new(Rc::new(self.type_info_table(uri).await?), &self.eval_config);
+        let val = ev
+            .in_decl(EXPR, || eval_in_empty_env(&exp, &ev))
+            .map_err(|err| Error::Type(Box::new(err)))?;
+        Ok(Rc::from(val))
+    }
+
+    /// Infer the normalized type of the expression `src` in the context of the module at `uri`
+    ///
+    /// The expression is elaborated in the same way as the bodies of declarations, so the
+    /// inferred type is the same as that of the expression in a declaration of the module.
+    /// Typed holes `?` in the expression do not prevent its type from being inferred,
+    /// their goals are returned by [Database::expr_goals].
+    pub async fn infer_type(&mut self, uri: &Url, src: &str) -> Result<Rc<Exp>, Error> {
+        let exp = self.elaborate_expr(uri, src).await?;
+        let ev = Evaluator::new(Rc::new(self.type_info_table(uri).await?), &self.eval_config);
+        let typ = exp.typ().ok_or_else(|| TypeError::Impossible {
+            message: "Expected inferred type".to_owned(),
+            span: None,
+        });
+        let typ = typ?.normalize_in_empty_env(&ev).map_err(|err| Error::Type(Box::new(err)))?;
+        Ok(Rc::from(typ))
+    }
+
+    /// The goals of the named holes in the expression `src`, see [Database::infer_type]
+    pub async fn expr_goals(&mut self, uri: &Url, src: &str) -> Result<Vec<Goal>, Error> {
+        let exp = self.elaborate_expr(uri, src).await?;
+        Ok(elaborator::exp_goals(&exp, EXPR))
+    }
+
+    /// Parse, lower and elaborate the expression `src` in the context of the module at `uri`
+    async fn elaborate_expr(&mut self, uri: &Url, src: &str) -> Result<Exp, Error> {
+        let cst = parser::parse_exp(src)
+            .map_err(|err| ParseErrors::new(vec![err]).expect("a syntax error"))?;
+        let symbol_table = self.symbol_table_with_deps(uri).await?;
+        let (exp, meta_vars) = lowering::lower_exp_with_symbol_table(uri, &cst, &symbol_table)?;
+        let module = self.ast(uri).await?;
+        let info_table = self.type_info_table(uri).await?;
+        elaborator::typechecker::infer_exp_with_lookup_table(
+            &exp,
+            Rc::new((*module).clone()),
+            &info_table,
+            &self.eval_config,
+            meta_vars,
+        )
+        .map_err(|err| Error::Type(Box::new(err)))
+    }
+}
@@ -0,0 +1,55 @@
+use codespan::Span;
+use printer::Print;
+use transformations::ExtractResult;
+use url::Url;
+
+use crate::database::Database;
+
+use super::Edit;
+
+pub struct Extraction {
+    pub title: String,
+    pub edits: Vec<Edit>,
+}
+
+impl Database {
+    /// Extract the expression selected by `span` into a new top-level declaration
+    ///
+    /// Returns `None` if the selection does not correspond to a single complete expression.
+    pub async fn selection_to_def(
+        &mut self,
+        uri: &Url,
+        span: Span,
+    ) -> Result<Option<Extraction>, crate::Error> {
+        // The selection has to coincide with an expression whose type is known
+        let info_by_id = self.info_by_id(uri).await?;
+        let Some(typ) = info_by_id
+            .find(span.start().into(), span.end().into())
+            .find(|interval| {
+                interval.start == u32::from(span.start()) && interval.stop == u32::from(span.end())
+            })
+            .and_then(|interval| interval.val.typ.clone())
+        else {
+            return Ok(None);
+        };
+        let module = self.ast(uri).await?;
+
+        let Some(ExtractResult { decl_span: Some(decl_span), new_decl, call }) =
+            transformations::extract(&module, span, typ)
+        else {
+            return Ok(None);
+        };
+
+        let title = format!("Extract into {}", new_decl.name);
+
+        // Replace the selection by a call and insert the new declaration
+        // directly after the declaration which contained the selection
+        let decl_end = Span::new(decl_span.end(), decl_span.end());
+        let edits = vec![
+            Edit { span, text: call.print_to_string(None) },
+            Edit { span: decl_end, text: format!("\n\n{}", new_decl.print_to_string(None)) },
+        ];
+
+        Ok(Some(Extraction { title, edits }))
+    }
+}
@@ -0,0 +1,257 @@
+use codespan::{ByteIndex, Span};
+use url::Url;
+
+use ast::ctx::values::TypeCtx;
+use ast::*;
+use printer::tokens::UNDERSCORE;
+use printer::{Print, PrintCfg, DEFAULT_WIDTH};
+
+use crate::database::Database;
+use crate::info::{Info, InfoContent};
+
+use super::Edit;
+
+pub struct FillHole {
+    pub title: String,
+    pub edits: Vec<Edit>,
+}
+
+impl Database {
+    /// Suggest expressions which can be used to fill the hole at the given index
+    ///
+    /// If the goal of the hole is a data type, one suggestion is returned per constructor.
+    /// Each suggestion replaces the hole by the constructor applied to fresh holes `?`
+    /// for all of its explicit parameters. Nullary constructors are ranked first.
+    /// If the goal is a codata type, a `comatch` with one cocase per destructor is suggested.
+    /// In addition, a `match` is suggested for every variable in scope whose type is a data type.
+    /// The cases of these skeletons bind the parameters of the constructors and destructors
+    /// under their declared names and have fresh holes as bodies.
+    pub async fn fill_hole(
+        &mut self,
+        uri: &Url,
+        idx: ByteIndex,
+    ) -> Result<Vec<FillHole>, crate::Error> {
+        let Some(hole) = self.hole_at_index(uri, idx).await else {
+            return Ok(vec![]);
+        };
+        let (Some(span), Some(typ)) = (hole.span, hole.inferred_type.as_deref()) else {
+            return Ok(vec![]);
+        };
+        // Multi-line skeletons are aligned with the line on which the hole occurs
+        let indent = indentation(&self.source(uri).await?, span);
+
+        let mut fills = vec![];
+        if let Exp::TypCtor(TypCtor { name, .. }) = typ {
+            let ust = self.ust(&name.uri).await?;
+            match ust.decls.iter().find(|decl| decl.ident().id == name.id) {
+                Some(Decl::Data(data)) => fills.extend(ctor_fills(&ust, data, span)),
+                Some(Decl::Codata(codata)) => {
+                    let comatch = comatch_skeleton(&ust, codata, &hole);
+                    fills.push(FillHole {
+                        title: "Fill hole with comatch".to_owned(),
+                        edits: vec![Edit { span, text: print_skeleton(&comatch, &indent) }],
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        let ctx = hole.inferred_ctx.clone().unwrap_or_else(TypeCtx::empty);
+        for (lvl, name, typ) in scrutinees(&ctx) {
+            let ust = self.ust(&typ.name.uri).await?;
+            let Some(Decl::Data(data)) =
+                ust.decls.iter().find(|decl| decl.ident().id == typ.name.id)
+            else {
+                continue;
+            };
+            let on_exp = Variable {
+                span: None,
+                idx: ctx.lvl_to_idx(lvl),
+                name: VarBound::from_string(name),
+                inferred_type: None,
+            };
+            let local_match = match_skeleton(&ust, data, typ, on_exp, &hole);
+            fills.push(FillHole {
+                title: format!("Match on {name}"),
+                edits: vec![Edit { span, text: print_skeleton(&local_match, &indent) }],
+            });
+        }
+
+        Ok(fills)
+    }
+
+    /// The typed hole `?` at the given index
+    ///
+    /// The cursor may also be placed directly behind the hole.
+    async fn hole_at_index(&mut self, uri: &Url, idx: ByteIndex) -> Option<Hole> {
+        let mut candidates = vec![idx];
+        if let Some(before) = idx.to_usize().checked_sub(1) {
+            candidates.push(ByteIndex(before as u32));
+        }
+        for idx in candidates {
+            if let Some(Info { content: InfoContent::HoleInfo(info), .. }) =
+                self.hoverinfo_at_index(uri, idx).await
+            {
+                if info.hole.kind == MetaVarKind::CanSolve && info.hole.inferred_type.is_some() {
+                    return Some(info.hole);
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Suggest to fill a hole whose goal is the data type `data` with one of its constructors
+fn ctor_fills(ust: &Module, data: &Data, span: Span) -> Vec<FillHole> {
+    let mut ctors: Vec<_> = ust
+        .xtors_for_type(&data.name.id)
+        .into_iter()
+        .filter_map(|name| data.ctors.iter().find(|ctor| ctor.name.id == name.id))
+        .map(|ctor| (ctor, ctor.params.params.iter().filter(|param| !param.implicit).count()))
+        .collect();
+    // The sort is stable, so constructors with arguments stay in declaration order
+    ctors.sort_by_key(|(_, arity)| *arity != 0);
+
+    ctors
+        .into_iter()
+        .map(|(ctor, arity)| {
+            let text = if arity == 0 {
+                ctor.name.id.clone()
+            } else {
+                format!("{}({})", ctor.name.id, vec!["?"; arity].join(", "))
+            };
+            FillHole {
+                title: format!("Fill hole with {}", ctor.name.id),
+                edits: vec![Edit { span, text }],
+            }
+        })
+        .collect()
+}
+
+/// A `comatch` on the codata type `codata` with one cocase per destructor
+fn comatch_skeleton(ust: &Module, codata: &Codata, hole: &Hole) -> Exp {
+    let cases = ust
+        .xtors_for_type(&codata.name.id)
+        .into_iter()
+        .filter_map(|name| codata.dtors.iter().find(|dtor| dtor.name.id == name.id))
+        .map(|dtor| Case {
+            span: None,
+            pattern: pattern(&ust.uri, true, &dtor.name, &dtor.params),
+            body: Some(fresh_hole(hole)),
+        })
+        .collect();
+    Exp::LocalComatch(LocalComatch {
+        span: None,
+        ctx: None,
+        name: Label { id: 0, user_name: None },
+        is_lambda_sugar: false,
+        cases,
+        inferred_type: None,
+    })
+}
+
+/// A `match` on `on_exp` of type `typ` with one case per constructor of the data type `data`
+///
+/// Constructors whose type clashes with `typ` can never be the value of `on_exp`,
+/// so their cases are marked as `absurd`.
+fn match_skeleton(ust: &Module, data: &Data, typ: &TypCtor, on_exp: Variable, hole: &Hole) -> Exp {
+    let cases = ust
+        .xtors_for_type(&data.name.id)
+        .into_iter()
+        .filter_map(|name| data.ctors.iter().find(|ctor| ctor.name.id == name.id))
+        .map(|ctor| Case {
+            span: None,
+            pattern: pattern(&ust.uri, false, &ctor.name, &ctor.params),
+            body: if args_clash(&ctor.typ.args, &typ.args) { None } else { Some(fresh_hole(hole)) },
+        })
+        .collect();
+    Exp::LocalMatch(LocalMatch {
+        span: None,
+        ctx: None,
+        name: Label { id: 0, user_name: None },
+        on_exp: Box::new(on_exp.into()),
+        motive: None,
+        ret_typ: None,
+        cases,
+        inferred_type: None,
+    })
+}
+
+/// The pattern of a case which binds all parameters of an xtor under their declared names
+fn pattern(uri: &Url, is_copattern: bool, name: &IdBind, params: &Telescope) -> Pattern {
+    let params = params
+        .params
+        .iter()
+        .map(|param| ParamInst { span: None, info: None, name: param.name.clone(), typ: None })
+        .collect();
+    Pattern {
+        is_copattern,
+        name: IdBound { span: None, id: name.id.clone(), uri: uri.clone() },
+        params: TelescopeInst { params },
+    }
+}
+
+/// An unnamed hole `?` to be used as the body of a generated case
+fn fresh_hole(hole: &Hole) -> Box<Exp> {
+    Box::new(Hole { span: None, name: None, solution: None, ..hole.clone() }.into())
+}
+
+/// The variables in scope which may be matched on, together with their levels
+///
+/// Variables which are shadowed or which do not have a type constructor as their type are
+/// not included.
+fn scrutinees(ctx: &TypeCtx) -> Vec<(Lvl, &str, &TypCtor)> {
+    let binders: Vec<_> = ctx
+        .iter()
+        .enumerate()
+        .flat_map(|(fst, binders)| {
+            binders.iter().enumerate().map(move |(snd, binder)| (Lvl { fst, snd }, binder))
+        })
+        .collect();
+    binders
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &(lvl, binder))| {
+            let shadowed = binders[i + 1..].iter().any(|(_, other)| other.name == binder.name);
+            match &*binder.typ {
+                Exp::TypCtor(typ) if !shadowed && binder.name.id != UNDERSCORE => {
+                    Some((lvl, binder.name.id.as_str(), typ))
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Whether two lists of arguments can never be unified
+fn args_clash(lhs: &Args, rhs: &Args) -> bool {
+    lhs.args.iter().zip(rhs.args.iter()).any(|(lhs, rhs)| clash(&lhs.exp(), &rhs.exp()))
+}
+
+/// Whether two expressions can never be unified because they are built from different
+/// constructors at the same position
+fn clash(lhs: &Exp, rhs: &Exp) -> bool {
+    match (lhs, rhs) {
+        (Exp::Anno(Anno { exp, .. }), other) | (other, Exp::Anno(Anno { exp, .. })) => {
+            clash(exp, other)
+        }
+        (
+            Exp::Call(Call { kind: CallKind::Constructor, name: lhs_name, args: lhs_args, .. }),
+            Exp::Call(Call { kind: CallKind::Constructor, name: rhs_name, args: rhs_args, .. }),
+        ) => lhs_name != rhs_name || args_clash(lhs_args, rhs_args),
+        _ => false,
+    }
+}
+
+/// The leading whitespace of the line which contains the start of `span`
+pub(crate) fn indentation(source: &str, span: Span) -> String {
+    let start = span.start().to_usize();
+    let line_start = source[..start].rfind('\n').map(|idx| idx + 1).unwrap_or(0);
+    source[line_start..start].chars().take_while(|c| c.is_whitespace()).collect()
+}
+
+/// Print a generated expression such that all of its lines after the first are indented
+fn print_skeleton(exp: &Exp, indent: &str) -> String {
+    let cfg = PrintCfg { width: DEFAULT_WIDTH.saturating_sub(indent.len()), ..Default::default() };
+    exp.print_to_string(Some(&cfg)).replace('\n', &format!("\n{indent}"))
+}
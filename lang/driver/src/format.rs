@@ -0,0 +1,42 @@
+use url::Url;
+
+use printer::{Print, PrintCfg};
+
+use crate::database::Database;
+use crate::qualify::Qualify;
+use crate::result::Error;
+
+impl Database {
+    /// Format the module at `uri` using `cfg`, or the default configuration if `None`
+    ///
+    /// The LSP server and the `fmt` command of the CLI both use this method,
+    /// so that they agree on the printer configuration unless a client requests otherwise.
+    /// Like every source file, the result ends with a single newline.
+    pub async fn format(&mut self, uri: &Url, cfg: Option<&PrintCfg>) -> Result<String, Error> {
+        let mut module = (*self.ust(uri).await?).clone();
+        module.qualify(&self.qualifiers(uri).await?);
+        let mut formatted = module.print_to_string(cfg);
+        formatted.push('\n');
+        Ok(formatted)
+    }
+
+    /// Whether the source of the module at `uri` is already formatted
+    pub async fn is_formatted(&mut self, uri: &Url) -> Result<bool, Error> {
+        let source = self.source(uri).await?;
+        let formatted = self.format(uri, None).await?;
+        Ok(source == formatted)
+    }
+
+    /// Format the module at `uri` and write the result back to its source
+    ///
+    /// Returns whether the source has changed.
+    /// The source is only written if formatting changes it, so running this twice is a no-op.
+    pub async fn format_on_save(&mut self, uri: &Url) -> Result<bool, Error> {
+        if self.is_formatted(uri).await? {
+            return Ok(false);
+        }
+        let formatted = self.format(uri, None).await?;
+        self.write_source(uri, &formatted).await?;
+        Ok(true)
+    }
+}
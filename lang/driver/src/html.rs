@@ -0,0 +1,172 @@
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+use codespan::Span;
+use url::Url;
+
+use ast::Decl;
+
+use crate::database::Database;
+use crate::info::{Info, InfoContent};
+use crate::semantic_tokens::SemanticTokenKind;
+use crate::Error;
+
+const STYLE: &str = r#"
+pre.polarity { font-family: monospace; }
+pre.polarity a { color: inherit; text-decoration: none; }
+pre.polarity a[href]:hover { text-decoration: underline; }
+.keyword { color: #7928a1; font-weight: bold; }
+.type { color: #005cc5; }
+.ctor { color: #22863a; }
+.dtor { color: #b35900; }
+.variable { color: #24292e; }
+.hole { color: #d73a49; }
+"#;
+
+impl Database {
+    /// Render the module at `uri` as a standalone HTML page
+    ///
+    /// Identifiers are highlighted according to their semantic token kind and link to their
+    /// definition, annotated with their type as a `title` attribute.
+    /// Definitions in other modules are linked as `<module>.html`, relative to this page.
+    /// If the module does not typecheck, only the keywords are highlighted.
+    pub async fn render_html(&mut self, uri: &Url) -> Result<String, Error> {
+        let source = self.source(uri).await?;
+        let tokens = self.semantic_tokens(uri).await;
+        let infos = self.info_by_id(uri).await.ok();
+
+        // Every definition site in this module is an anchor which can be linked to
+        let mut anchors: BTreeSet<u32> = BTreeSet::new();
+        if let Ok(module) = self.ast(uri).await {
+            for decl in module.decls.iter() {
+                anchors.extend(decl.span().map(anchor_id));
+                match decl {
+                    Decl::Data(data) => anchors
+                        .extend(data.ctors.iter().filter_map(|ctor| ctor.span).map(anchor_id)),
+                    Decl::Codata(codata) => anchors
+                        .extend(codata.dtors.iter().filter_map(|dtor| dtor.span).map(anchor_id)),
+                    Decl::Def(_) | Decl::Codef(_) | Decl::Let(_) => {}
+                }
+            }
+        }
+        if let Some(infos) = &infos {
+            let sites = infos.iter().filter_map(|info| info.val.content.definition_site());
+            anchors.extend(sites.filter(|(site, _)| site == uri).map(|(_, span)| anchor_id(*span)));
+        }
+
+        let mut out = String::new();
+        let mut pos = 0;
+        let mut anchors = anchors.into_iter().peekable();
+        for token in tokens {
+            let (start, end) = (token.span.start().0, token.span.end().0);
+            while let Some(anchor) = anchors.next_if(|anchor| *anchor <= start) {
+                push_source(&source, &mut pos, anchor, &mut out);
+                write!(out, r#"<a id="{anchor}"></a>"#).unwrap();
+            }
+            push_source(&source, &mut pos, start, &mut out);
+
+            let info = infos.as_ref().and_then(|infos| {
+                infos
+                    .find(start, end)
+                    .filter(|interval| is_info_for(token.kind, &interval.val))
+                    .min_by_key(|interval| interval.stop - interval.start)
+                    .map(|interval| &interval.val)
+            });
+            out.push_str(r#"<a class=""#);
+            out.push_str(css_class(token.kind));
+            out.push('"');
+            if let Some((site, span)) = info.and_then(|info| info.content.definition_site()) {
+                let page = if site == uri { String::new() } else { page_name(uri, site) };
+                write!(out, r#" href="{}#{}""#, escape(&page), anchor_id(*span)).unwrap();
+            }
+            if let Some(typ) = info.and_then(|info| type_of(&info.content)) {
+                write!(out, r#" title="{}""#, escape(typ)).unwrap();
+            }
+            out.push('>');
+            push_source(&source, &mut pos, end, &mut out);
+            out.push_str("</a>");
+        }
+        for anchor in anchors {
+            push_source(&source, &mut pos, anchor, &mut out);
+            write!(out, r#"<a id="{anchor}"></a>"#).unwrap();
+        }
+        push_source(&source, &mut pos, source.len() as u32, &mut out);
+
+        let title = uri.path_segments().and_then(|mut segments| segments.next_back()).unwrap_or("");
+        Ok(format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+             <style>{STYLE}</style>\n</head>\n<body>\n<pre class=\"polarity\">{out}</pre>\n\
+             </body>\n</html>\n",
+            escape(title)
+        ))
+    }
+}
+
+/// The id of the anchor of a definition site
+fn anchor_id(span: Span) -> u32 {
+    span.start().0
+}
+
+/// Append the source text up to `end` to `out`, escaping it
+fn push_source(source: &str, pos: &mut u32, end: u32, out: &mut String) {
+    if end > *pos {
+        out.push_str(&escape(&source[*pos as usize..end as usize]));
+        *pos = end;
+    }
+}
+
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// The relative link to the rendered page of the module `target`
+fn page_name(uri: &Url, target: &Url) -> String {
+    let path = uri.make_relative(target).unwrap_or_else(|| target.to_string());
+    match path.strip_suffix(".pol") {
+        Some(stem) => format!("{stem}.html"),
+        None => path,
+    }
+}
+
+fn css_class(kind: SemanticTokenKind) -> &'static str {
+    match kind {
+        SemanticTokenKind::Keyword => "keyword",
+        SemanticTokenKind::TypeCtor => "type",
+        SemanticTokenKind::Ctor => "ctor",
+        SemanticTokenKind::Dtor => "dtor",
+        SemanticTokenKind::Variable => "variable",
+        SemanticTokenKind::Hole => "hole",
+    }
+}
+
+/// Whether `info` describes a token of the given kind rather than an enclosing expression
+fn is_info_for(kind: SemanticTokenKind, info: &Info) -> bool {
+    matches!(
+        (kind, &info.content),
+        (SemanticTokenKind::TypeCtor, InfoContent::TypeCtorInfo(_))
+            | (SemanticTokenKind::Ctor, InfoContent::CallInfo(_))
+            | (SemanticTokenKind::Dtor, InfoContent::DotCallInfo(_))
+            | (SemanticTokenKind::Variable, InfoContent::VariableInfo(_))
+            | (SemanticTokenKind::Hole, InfoContent::HoleInfo(_))
+    )
+}
+
+fn type_of(content: &InfoContent) -> Option<&str> {
+    match content {
+        InfoContent::VariableInfo(info) => Some(&info.typ),
+        InfoContent::CallInfo(info) => Some(&info.typ),
+        InfoContent::DotCallInfo(info) => Some(&info.typ),
+        InfoContent::HoleInfo(info) => Some(&info.goal),
+        _ => None,
+    }
+}
@@ -0,0 +1,213 @@
+//! Incremental typechecking of modules
+//!
+//! Every declaration of a module is assigned a fingerprint, which is a hash of
+//!
+//! - the source text of the declaration itself,
+//! - the source text of all declarations it transitively depends on,
+//! - the sources of all modules the module transitively imports.
+//!
+//! When a module is typechecked again, declarations whose fingerprint did not change are taken
+//! from the previous run instead of being checked again.
+//! A declaration depends on the declarations whose names it references. Since constructors and
+//! destructors are declared by their type, a definition which matches on a data type depends on
+//! the data type as soon as it mentions one of its constructors or the type itself.
+//! An edit thus only invalidates the edited declaration and the declarations which depend on it.
+//! Reused declarations are moved to their location in the new version of the module, see
+//! [relocate].
+
+mod relocate;
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeSet;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use url::Url;
+
+use ast::*;
+
+use crate::database::Database;
+use crate::references::Occurrences;
+use crate::Error;
+
+/// The typechecked version of a module together with the fingerprints of its declarations
+pub struct CheckedDecls {
+    fingerprints: HashMap<String, u64>,
+    module: Arc<Module>,
+}
+
+impl Database {
+    /// The declarations of the previously typechecked version of the module at `uri`
+    /// which can be reused for the lowered module `ust`
+    ///
+    /// Returns the reusable declarations moved to their location in `ust`, their names and
+    /// the fingerprints of all declarations of `ust`.
+    pub(crate) async fn reusable_decls(
+        &mut self,
+        uri: &Url,
+        ust: &Module,
+    ) -> Result<(Option<Module>, HashSet<String>, HashMap<String, u64>), Error> {
+        let fingerprints = self.fingerprints(uri, ust).await?;
+        let Some(previous) = self.checked_decls.get_even_if_stale(uri) else {
+            return Ok((None, HashSet::default(), fingerprints));
+        };
+        let reusable = ust.decls.iter().filter(|decl| {
+            let name = &decl.ident().id;
+            fingerprints.get(name).is_some_and(|fp| previous.fingerprints.get(name) == Some(fp))
+        });
+        let module = relocate::relocate_decls(&previous.module, ust, reusable);
+        let reused = module.decls.iter().map(|decl| decl.ident().id.clone()).collect();
+        Ok((Some(module), reused, fingerprints))
+    }
+
+    /// Remember the typechecked module at `uri` such that its declarations can be reused
+    pub(crate) fn remember_checked_decls(
+        &mut self,
+        uri: &Url,
+        module: Arc<Module>,
+        fingerprints: HashMap<String, u64>,
+    ) {
+        self.checked_decls.insert(uri.clone(), CheckedDecls { fingerprints, module });
+    }
+
+    async fn fingerprints(
+        &mut self,
+        uri: &Url,
+        ust: &Module,
+    ) -> Result<HashMap<String, u64>, Error> {
+        let source = self.source(uri).await?;
+        let imports = self.deps(uri).await?;
+        let imports = self.sources_stamp(imports).await?;
+
+        // The index of the declaration which declares each top-level name of the module
+        let mut declared_by: HashMap<&str, usize> = HashMap::default();
+        for (idx, decl) in ust.decls.iter().enumerate() {
+            declared_by.insert(&decl.ident().id, idx);
+            match decl {
+                Decl::Data(data) => {
+                    for ctor in data.ctors.iter() {
+                        declared_by.insert(&ctor.name.id, idx);
+                    }
+                }
+                Decl::Codata(codata) => {
+                    for dtor in codata.dtors.iter() {
+                        declared_by.insert(&dtor.name.id, idx);
+                    }
+                }
+                Decl::Def(_) | Decl::Codef(_) | Decl::Let(_) => {}
+            }
+        }
+
+        let texts: Vec<Option<&str>> = ust
+            .decls
+            .iter()
+            .map(|decl| {
+                let span = decl.span()?;
+                source.get(span.start().to_usize()..span.end().to_usize())
+            })
+            .collect();
+        let direct_deps: Vec<BTreeSet<usize>> = ust
+            .decls
+            .iter()
+            .map(|decl| {
+                let mut occurrences = vec![];
+                decl.occurrences(uri, &mut occurrences);
+                occurrences
+                    .into_iter()
+                    .filter(|occ| !occ.is_decl && occ.name.uri == *uri)
+                    .filter_map(|occ| declared_by.get(occ.name.id.as_str()).copied())
+                    .collect()
+            })
+            .collect();
+
+        let mut fingerprints = HashMap::default();
+        for (idx, decl) in ust.decls.iter().enumerate() {
+            let deps = transitive_deps(idx, &direct_deps);
+            // Declarations without a location are never reused
+            if deps.iter().any(|dep| texts[*dep].is_none()) {
+                continue;
+            }
+            let mut hasher = DefaultHasher::new();
+            imports.hash(&mut hasher);
+            for dep in deps {
+                texts[dep].hash(&mut hasher);
+            }
+            fingerprints.insert(decl.ident().id.clone(), hasher.finish());
+        }
+        Ok(fingerprints)
+    }
+}
+
+/// The declarations which the declaration `idx` transitively depends on, including itself
+fn transitive_deps(idx: usize, direct_deps: &[BTreeSet<usize>]) -> BTreeSet<usize> {
+    let mut deps = BTreeSet::new();
+    let mut todo = vec![idx];
+    while let Some(idx) = todo.pop() {
+        if deps.insert(idx) {
+            todo.extend(direct_deps[idx].iter().copied());
+        }
+    }
+    deps
+}
+
+#[cfg(test)]
+mod incremental_tests {
+    use futures::executor::block_on;
+
+    use crate::test_support::{database, uri};
+
+    use super::*;
+
+    const SOURCE: &str = "data Bool { T, F }
+
+codata Fun(a: Type, b: Type) {
+    Fun(a, b).ap(a: Type, b: Type, x: a) : b
+}
+
+def Bool.not: Bool { T => F, F => T }
+
+let notFun: Fun(Bool, Bool) { \\x. x.not }
+
+let hole: Bool { ?hole }
+";
+
+    /// Typecheck `source`, edit it to `edited` and return the names of the reused declarations
+    ///
+    /// Also asserts that the edited module is the same as if it had been typechecked from scratch.
+    fn reused_after_edit(source: &str, edited: &str) -> HashSet<String> {
+        let mut db = database(source);
+        block_on(db.ast(&uri())).unwrap();
+        block_on(db.write_source(&uri(), edited)).unwrap();
+        let ust = block_on(db.ust(&uri())).unwrap();
+        let (_, reused, _) = block_on(db.reusable_decls(&uri(), &ust)).unwrap();
+
+        let module = block_on(db.ast(&uri())).unwrap();
+        let expected = block_on(database(edited).ast(&uri())).unwrap();
+        assert_eq!(*module, *expected);
+        let spans =
+            |module: &Module| module.decls.iter().map(|decl| decl.span()).collect::<Vec<_>>();
+        assert_eq!(spans(&module), spans(&expected));
+        let meta_vars =
+            |module: &Module| module.meta_vars.keys().copied().collect::<HashSet<MetaVar>>();
+        assert_eq!(meta_vars(&module), meta_vars(&expected));
+        reused
+    }
+
+    fn names(names: &[&str]) -> HashSet<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn edit_in_front_reuses_later_decls() {
+        let edited = format!("let unit: Bool {{ ?unit }}\n\n{SOURCE}");
+        let reused = reused_after_edit(SOURCE, &edited);
+        assert_eq!(reused, names(&["Bool", "Fun", "not", "notFun", "hole"]));
+    }
+
+    #[test]
+    fn edit_invalidates_dependent_decls() {
+        let edited = SOURCE.replace("T => F, F => T", "T => T, F => F");
+        let reused = reused_after_edit(SOURCE, &edited);
+        assert_eq!(reused, names(&["Bool", "Fun", "hole"]));
+    }
+}
@@ -0,0 +1,450 @@
+//! Moving typechecked declarations into a new version of their module
+//!
+//! A reused declaration is taken from the previous version of the module, but it has to look as
+//! if it had been typechecked as part of the new version:
+//!
+//! - Its source text may have moved, since declarations in front of it may have changed.
+//! - Metavariables and labels of local (co)matches are numbered consecutively during lowering,
+//!   so they may have different numbers in the new version.
+//!
+//! Lowering the same source text yields the same metavariables and labels in the same order.
+//! The metavariables and labels of a reused declaration are therefore renamed to their
+//! counterparts in the lowered declaration of the new version, ordered by their numbers.
+//! Spans and labels in annotations added during elaboration, such as inferred types,
+//! may belong to other declarations or modules and are left unchanged.
+
+use codespan::{ByteIndex, Span};
+
+use ast::ctx::values::{Binder, TypeCtx};
+use ast::*;
+
+/// Move the declarations `reusable` of the lowered module `ust` from the typechecked module
+/// `previous` to their location in `ust`
+///
+/// Returns a module which contains the moved declarations and their metavariables.
+/// Declarations which cannot be moved, for example because they do not have a location,
+/// are left out.
+pub(super) fn relocate_decls<'a>(
+    previous: &Module,
+    ust: &Module,
+    reusable: impl Iterator<Item = &'a Decl>,
+) -> Module {
+    let mut decls = vec![];
+    let mut meta_vars = HashMap::default();
+    for decl in reusable {
+        let name = &decl.ident().id;
+        let Some(prev) = previous.decls.iter().find(|prev| prev.ident().id == *name) else {
+            continue;
+        };
+        let Some(mut relocation) = Relocation::new(prev, decl) else {
+            continue;
+        };
+        let mut prev = prev.clone();
+        prev.relocate(&mut relocation);
+        decls.push(prev);
+        let mvs: Vec<(MetaVar, MetaVar)> = relocation.meta_vars.clone().into_iter().collect();
+        for (mv, new_mv) in mvs {
+            if let Some(state) = previous.meta_vars.get(&mv) {
+                let mut state = state.clone();
+                relocation.annotation(&mut state);
+                meta_vars.insert(new_mv, state);
+            }
+        }
+    }
+    Module { uri: ust.uri.clone(), use_decls: vec![], decls, meta_vars, comments: vec![] }
+}
+
+/// How to move a declaration into the new version of its module
+#[derive(Default)]
+struct Relocation {
+    /// The offset by which the source text of the declaration has moved
+    offset: i64,
+    /// The metavariables of the declaration and their counterparts in the new version
+    meta_vars: HashMap<MetaVar, MetaVar>,
+    /// The labels of the declaration and their counterparts in the new version
+    labels: HashMap<usize, Label>,
+    /// Whether an annotation added during elaboration is being moved
+    in_annotation: bool,
+    /// The metavariables which have been visited outside of annotations
+    visited_meta_vars: Vec<MetaVar>,
+    /// The labels which have been visited outside of annotations
+    visited_labels: Vec<Label>,
+}
+
+impl Relocation {
+    /// The relocation of the declaration `prev` to the declaration `new` with the same source text
+    fn new(prev: &Decl, new: &Decl) -> Option<Self> {
+        let (prev_span, new_span) = (prev.span()?, new.span()?);
+        let (prev_meta_vars, prev_labels) = Relocation::visit(prev);
+        let (new_meta_vars, new_labels) = Relocation::visit(new);
+        if prev_meta_vars.len() != new_meta_vars.len() || prev_labels.len() != new_labels.len() {
+            return None;
+        }
+        Some(Relocation {
+            offset: i64::from(new_span.start().0) - i64::from(prev_span.start().0),
+            meta_vars: prev_meta_vars.into_iter().zip(new_meta_vars).collect(),
+            labels: prev_labels.into_iter().map(|label| label.id).zip(new_labels).collect(),
+            ..Default::default()
+        })
+    }
+
+    /// The metavariables and labels of `decl`, ordered by their numbers
+    fn visit(decl: &Decl) -> (Vec<MetaVar>, Vec<Label>) {
+        let mut relocation = Relocation::default();
+        decl.clone().relocate(&mut relocation);
+        let Relocation { mut visited_meta_vars, mut visited_labels, .. } = relocation;
+        visited_meta_vars.sort_by_key(|mv| mv.id);
+        visited_meta_vars.dedup();
+        visited_labels.sort_by_key(|label| label.id);
+        visited_labels.dedup();
+        (visited_meta_vars, visited_labels)
+    }
+
+    /// Move an annotation added during elaboration
+    fn annotation<T: Relocate>(&mut self, x: &mut T) {
+        let in_annotation = std::mem::replace(&mut self.in_annotation, true);
+        x.relocate(self);
+        self.in_annotation = in_annotation;
+    }
+
+    fn span(&self, span: &mut Option<Span>) {
+        if self.in_annotation {
+            return;
+        }
+        if let Some(span) = span {
+            let shift = |idx: ByteIndex| ByteIndex((i64::from(idx.0) + self.offset) as u32);
+            *span = Span::new(shift(span.start()), shift(span.end()));
+        }
+    }
+
+    fn meta_var(&mut self, mv: &mut MetaVar) {
+        if !self.in_annotation {
+            self.visited_meta_vars.push(*mv);
+        }
+        if let Some(new_mv) = self.meta_vars.get(mv) {
+            *mv = *new_mv;
+        }
+    }
+
+    fn label(&mut self, label: &mut Label) {
+        if self.in_annotation {
+            return;
+        }
+        self.visited_labels.push(label.clone());
+        if let Some(new_label) = self.labels.get(&label.id) {
+            *label = new_label.clone();
+        }
+    }
+}
+
+/// Move a syntax tree into the new version of its module, see [Relocation]
+trait Relocate {
+    fn relocate(&mut self, r: &mut Relocation);
+}
+
+impl<T: Relocate> Relocate for Box<T> {
+    fn relocate(&mut self, r: &mut Relocation) {
+        (**self).relocate(r)
+    }
+}
+
+impl<T: Relocate> Relocate for Option<T> {
+    fn relocate(&mut self, r: &mut Relocation) {
+        if let Some(x) = self {
+            x.relocate(r)
+        }
+    }
+}
+
+impl<T: Relocate> Relocate for Vec<T> {
+    fn relocate(&mut self, r: &mut Relocation) {
+        for x in self {
+            x.relocate(r)
+        }
+    }
+}
+
+impl Relocate for Decl {
+    fn relocate(&mut self, r: &mut Relocation) {
+        match self {
+            Decl::Data(Data { span, doc: _, name, attr: _, typ, ctors }) => {
+                r.span(span);
+                name.relocate(r);
+                typ.relocate(r);
+                ctors.relocate(r);
+            }
+            Decl::Codata(Codata { span, doc: _, name, attr: _, typ, dtors }) => {
+                r.span(span);
+                name.relocate(r);
+                typ.relocate(r);
+                dtors.relocate(r);
+            }
+            Decl::Def(Def { span, doc: _, name, attr: _, params, self_param, ret_typ, cases }) => {
+                r.span(span);
+                name.relocate(r);
+                params.relocate(r);
+                self_param.relocate(r);
+                ret_typ.relocate(r);
+                cases.relocate(r);
+            }
+            Decl::Codef(Codef { span, doc: _, name, attr: _, params, typ, cases }) => {
+                r.span(span);
+                name.relocate(r);
+                params.relocate(r);
+                typ.relocate(r);
+                cases.relocate(r);
+            }
+            Decl::Let(Let { span, doc: _, name, attr: _, params, typ, body }) => {
+                r.span(span);
+                name.relocate(r);
+                params.relocate(r);
+                typ.relocate(r);
+                body.relocate(r);
+            }
+        }
+    }
+}
+
+impl Relocate for Ctor {
+    fn relocate(&mut self, r: &mut Relocation) {
+        let Ctor { span, doc: _, name, params, typ } = self;
+        r.span(span);
+        name.relocate(r);
+        params.relocate(r);
+        typ.relocate(r);
+    }
+}
+
+impl Relocate for Dtor {
+    fn relocate(&mut self, r: &mut Relocation) {
+        let Dtor { span, doc: _, name, params, self_param, ret_typ, default } = self;
+        r.span(span);
+        name.relocate(r);
+        params.relocate(r);
+        self_param.relocate(r);
+        ret_typ.relocate(r);
+        default.relocate(r);
+    }
+}
+
+impl Relocate for SelfParam {
+    fn relocate(&mut self, r: &mut Relocation) {
+        let SelfParam { info, name, typ } = self;
+        r.span(info);
+        name.relocate(r);
+        typ.relocate(r);
+    }
+}
+
+impl Relocate for Telescope {
+    fn relocate(&mut self, r: &mut Relocation) {
+        self.params.relocate(r);
+    }
+}
+
+impl Relocate for Param {
+    fn relocate(&mut self, r: &mut Relocation) {
+        let Param { implicit: _, lazy: _, name, typ, default } = self;
+        name.relocate(r);
+        typ.relocate(r);
+        default.relocate(r);
+    }
+}
+
+impl Relocate for TelescopeInst {
+    fn relocate(&mut self, r: &mut Relocation) {
+        self.params.relocate(r);
+    }
+}
+
+impl Relocate for ParamInst {
+    fn relocate(&mut self, r: &mut Relocation) {
+        let ParamInst { span, info, name, typ } = self;
+        r.span(span);
+        r.annotation(info);
+        name.relocate(r);
+        r.annotation(typ);
+    }
+}
+
+impl Relocate for Case {
+    fn relocate(&mut self, r: &mut Relocation) {
+        let Case { span, pattern: Pattern { is_copattern: _, name, params }, body } = self;
+        r.span(span);
+        name.relocate(r);
+        params.relocate(r);
+        body.relocate(r);
+    }
+}
+
+impl Relocate for Motive {
+    fn relocate(&mut self, r: &mut Relocation) {
+        let Motive { span, param, ret_typ } = self;
+        r.span(span);
+        param.relocate(r);
+        ret_typ.relocate(r);
+    }
+}
+
+impl Relocate for Args {
+    fn relocate(&mut self, r: &mut Relocation) {
+        for arg in self.args.iter_mut() {
+            match arg {
+                Arg::UnnamedArg(exp) => exp.relocate(r),
+                Arg::NamedArg(name, exp) => {
+                    name.relocate(r);
+                    exp.relocate(r);
+                }
+                Arg::InsertedImplicitArg(hole) => hole.relocate(r),
+            }
+        }
+    }
+}
+
+impl Relocate for TypCtor {
+    fn relocate(&mut self, r: &mut Relocation) {
+        let TypCtor { span, name, args } = self;
+        r.span(span);
+        name.relocate(r);
+        args.relocate(r);
+    }
+}
+
+impl Relocate for Hole {
+    fn relocate(&mut self, r: &mut Relocation) {
+        let Hole { span, kind: _, name: _, metavar, inferred_type, inferred_ctx, args, solution } =
+            self;
+        r.span(span);
+        r.meta_var(metavar);
+        r.annotation(inferred_type);
+        r.annotation(inferred_ctx);
+        args.relocate(r);
+        r.annotation(solution);
+    }
+}
+
+impl Relocate for Exp {
+    fn relocate(&mut self, r: &mut Relocation) {
+        match self {
+            Exp::Variable(Variable { span, idx: _, name, inferred_type }) => {
+                r.span(span);
+                name.relocate(r);
+                r.annotation(inferred_type);
+            }
+            Exp::TypCtor(typ) => typ.relocate(r),
+            Exp::Call(Call { span, kind: _, name, args, inferred_type }) => {
+                r.span(span);
+                name.relocate(r);
+                args.relocate(r);
+                r.annotation(inferred_type);
+            }
+            Exp::DotCall(DotCall { span, kind: _, exp, name, args, inferred_type }) => {
+                r.span(span);
+                exp.relocate(r);
+                name.relocate(r);
+                args.relocate(r);
+                r.annotation(inferred_type);
+            }
+            Exp::Anno(Anno { span, exp, typ, normalized_type }) => {
+                r.span(span);
+                exp.relocate(r);
+                typ.relocate(r);
+                r.annotation(normalized_type);
+            }
+            Exp::TypeUniv(TypeUniv { span }) => r.span(span),
+            Exp::LocalMatch(LocalMatch {
+                span,
+                ctx,
+                name,
+                on_exp,
+                motive,
+                ret_typ,
+                cases,
+                inferred_type,
+            }) => {
+                r.span(span);
+                r.annotation(ctx);
+                r.label(name);
+                on_exp.relocate(r);
+                motive.relocate(r);
+                r.annotation(ret_typ);
+                cases.relocate(r);
+                r.annotation(inferred_type);
+            }
+            Exp::LocalComatch(LocalComatch {
+                span,
+                ctx,
+                name,
+                is_lambda_sugar: _,
+                cases,
+                inferred_type,
+            }) => {
+                r.span(span);
+                r.annotation(ctx);
+                r.label(name);
+                cases.relocate(r);
+                r.annotation(inferred_type);
+            }
+            Exp::Hole(hole) => hole.relocate(r),
+            Exp::LocalLet(LocalLet { span, name, typ, bound, body, bound_type, inferred_type }) => {
+                r.span(span);
+                name.relocate(r);
+                typ.relocate(r);
+                bound.relocate(r);
+                body.relocate(r);
+                r.annotation(bound_type);
+                r.annotation(inferred_type);
+            }
+        }
+    }
+}
+
+impl Relocate for TypeCtx {
+    fn relocate(&mut self, r: &mut Relocation) {
+        for binder in self.bound.iter_mut().flatten() {
+            binder.relocate(r);
+        }
+    }
+}
+
+impl Relocate for Binder {
+    fn relocate(&mut self, r: &mut Relocation) {
+        let Binder { name, typ, value } = self;
+        name.relocate(r);
+        typ.relocate(r);
+        value.relocate(r);
+    }
+}
+
+impl Relocate for MetaVarState {
+    fn relocate(&mut self, r: &mut Relocation) {
+        match self {
+            MetaVarState::Solved { ctx: _, solution } => solution.relocate(r),
+            MetaVarState::Unsolved { ctx: _ } => {}
+        }
+    }
+}
+
+impl Relocate for IdBind {
+    fn relocate(&mut self, r: &mut Relocation) {
+        r.span(&mut self.span);
+    }
+}
+
+impl Relocate for IdBound {
+    fn relocate(&mut self, r: &mut Relocation) {
+        r.span(&mut self.span);
+    }
+}
+
+impl Relocate for VarBind {
+    fn relocate(&mut self, r: &mut Relocation) {
+        r.span(&mut self.span);
+    }
+}
+
+impl Relocate for VarBound {
+    fn relocate(&mut self, r: &mut Relocation) {
+        r.span(&mut self.span);
+    }
+}
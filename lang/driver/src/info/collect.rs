@@ -2,6 +2,7 @@ use codespan::Span;
 use rust_lapper::{Interval, Lapper};
 
 use ast::*;
+use elaborator::typechecker::termination::{recursive_calls, RecursiveCall};
 use printer::{Print, PrintCfg};
 use url::Url;
 
@@ -11,8 +12,8 @@ use crate::{
 };
 
 use super::data::{
-    AnnoInfo, CallInfo, DotCallInfo, HoleInfo, Info, InfoContent, TypeCtorInfo, TypeUnivInfo,
-    UseInfo, VariableInfo,
+    AnnoInfo, CallInfo, CasesInfo, DotCallInfo, HoleInfo, Info, InfoContent, TypeCtorInfo,
+    TypeUnivInfo, UseInfo, VariableInfo,
 };
 use super::item::Item;
 use super::lookup::{lookup_codef, lookup_ctor, lookup_decl, lookup_def, lookup_dtor, lookup_let};
@@ -24,13 +25,15 @@ pub async fn collect_info(
     uri: &Url,
 ) -> Result<(Lapper<u32, Info>, Lapper<u32, Item>), Error> {
     let module = db.ast(uri).await?;
-    let mut collector = InfoCollector::new(module.meta_vars.clone());
+    let info_table = db.type_info_table(uri).await?;
+    let mut collector = InfoCollector::new(uri.clone(), module.meta_vars.clone());
 
     for use_decl in module.use_decls.iter() {
         let dep_uri = db.resolve_module_name(&use_decl.path, uri)?;
         let info = Info {
             span: use_decl.span,
             content: InfoContent::UseInfo(UseInfo { uri: dep_uri, path: use_decl.path.clone() }),
+            typ: None,
         };
         collector.info_spans.push(Interval {
             start: use_decl.span.start().into(),
@@ -40,6 +43,7 @@ pub async fn collect_info(
     }
 
     for decl in module.decls.iter() {
+        collector.recursive_calls = recursive_calls(decl, &info_table);
         decl.collect_info(db, &mut collector)
     }
 
@@ -49,21 +53,78 @@ pub async fn collect_info(
 }
 
 struct InfoCollector {
+    uri: Url,
     meta_vars: HashMap<MetaVar, MetaVarState>,
     info_spans: Vec<Interval<u32, Info>>,
     item_spans: Vec<Interval<u32, Item>>,
+    /// The binding sites of the variables in scope, one vector per telescope
+    binders: Vec<Vec<Option<Span>>>,
+    /// The recursive calls of the current declaration found by the termination check
+    recursive_calls: Vec<RecursiveCall>,
 }
 
 impl InfoCollector {
-    fn new(meta_vars: HashMap<MetaVar, MetaVarState>) -> Self {
-        InfoCollector { meta_vars, info_spans: vec![], item_spans: vec![] }
+    fn new(uri: Url, meta_vars: HashMap<MetaVar, MetaVarState>) -> Self {
+        InfoCollector {
+            uri,
+            meta_vars,
+            info_spans: vec![],
+            item_spans: vec![],
+            binders: vec![],
+            recursive_calls: vec![],
+        }
+    }
+
+    /// Collect the information of the telescope `params` and of `f`,
+    /// where `f` is in the scope of the parameters
+    fn telescope<F: FnOnce(&mut Self)>(&mut self, db: &Database, params: &Telescope, f: F) {
+        self.binders.push(vec![]);
+        for Param { name, typ, default, .. } in params.params.iter() {
+            // The type and default value of a parameter are in the scope of the previous parameters
+            typ.collect_info(db, self);
+            if let Some(default) = default {
+                default.collect_info(db, self);
+            }
+            self.binders.last_mut().unwrap().push(name.span);
+        }
+        f(self);
+        self.binders.pop();
+    }
+
+    /// Collect the information of `f` in the scope of a telescope with the given binding sites
+    fn bind<F: FnOnce(&mut Self)>(&mut self, binders: Vec<Option<Span>>, f: F) {
+        self.binders.push(binders);
+        f(self);
+        self.binders.pop();
+    }
+
+    /// The binding site of the variable `idx`
+    fn binding_site(&self, idx: Idx) -> Option<(Url, Span)> {
+        let telescope = self.binders.len().checked_sub(idx.fst + 1)?;
+        let span = (*self.binders.get(telescope)?.get(idx.snd)?)?;
+        Some((self.uri.clone(), span))
+    }
+
+    /// The recursive call of the current declaration at `span`, if any
+    fn recursive_call(&self, span: Span) -> Option<RecursiveCall> {
+        self.recursive_calls.iter().find(|call| call.span == Some(span)).cloned()
     }
 
     fn add_info<T: Into<InfoContent>>(&mut self, span: Span, info: T) {
         let info = Interval {
             start: span.start().into(),
             stop: span.end().into(),
-            val: Info { span, content: info.into() },
+            val: Info { span, content: info.into(), typ: None },
+        };
+        self.info_spans.push(info)
+    }
+
+    /// Add the information of an expression together with its elaborated type
+    fn add_exp_info<T: Into<InfoContent>>(&mut self, span: Span, info: T, typ: Option<Box<Exp>>) {
+        let info = Interval {
+            start: span.start().into(),
+            stop: span.end().into(),
+            val: Info { span, content: info.into(), typ },
         };
         self.info_spans.push(info)
     }
@@ -141,7 +202,7 @@ impl CollectInfo for Data {
             ctor.collect_info(db, collector)
         }
 
-        typ.collect_info(db, collector)
+        collector.telescope(db, typ, |_| ())
     }
 }
 
@@ -163,44 +224,56 @@ impl CollectInfo for Codata {
             dtor.collect_info(db, collector)
         }
 
-        typ.collect_info(db, collector)
+        collector.telescope(db, typ, |_| ())
     }
 }
 
 impl CollectInfo for Def {
     fn collect_info(&self, db: &Database, collector: &mut InfoCollector) {
-        let Def { name, span, self_param, cases, params, ret_typ, .. } = self;
+        let Def { name, span, doc, self_param, cases, params, ret_typ, .. } = self;
         if let Some(span) = span {
             // Add Item
             let item =
                 Item::Def { name: name.clone().id, type_name: self_param.typ.name.clone().id };
             collector.add_item(*span, item);
             // Add Info
-            let info = DefInfo {};
+            let doc = doc.clone().map(|doc| doc.docs);
+            let cases = ret_typ.span().map(|ret_typ_span| CasesInfo {
+                scrutinee: self_param.typ.clone(),
+                covered: covered(cases),
+                start: ret_typ_span.end(),
+                case_spans: case_spans(cases),
+            });
+            let info = DefInfo { name: name.clone().id, doc, cases };
             collector.add_info(*span, info);
         };
 
-        self_param.collect_info(db, collector);
-        cases.collect_info(db, collector);
-        params.collect_info(db, collector);
-        ret_typ.collect_info(db, collector);
+        collector.telescope(db, params, |collector| {
+            self_param.collect_info(db, collector);
+            collector.bind(vec![self_binding_site(self_param)], |collector| {
+                ret_typ.collect_info(db, collector)
+            });
+            cases.collect_info(db, collector);
+        });
     }
 }
 
 impl CollectInfo for Codef {
     fn collect_info(&self, db: &Database, collector: &mut InfoCollector) {
-        let Codef { name, span, typ, cases, params, .. } = self;
+        let Codef { name, span, doc, typ, cases, params, .. } = self;
         if let Some(span) = span {
             // Add item
             let item = Item::Codef { name: name.clone().id, type_name: typ.name.clone().id };
             collector.add_item(*span, item);
             // Add info
-            let info = CodefInfo {};
+            let doc = doc.clone().map(|doc| doc.docs);
+            let info = CodefInfo { name: name.clone().id, doc };
             collector.add_info(*span, info);
         }
-        typ.collect_info(db, collector);
-        cases.collect_info(db, collector);
-        params.collect_info(db, collector)
+        collector.telescope(db, params, |collector| {
+            typ.collect_info(db, collector);
+            cases.collect_info(db, collector)
+        })
     }
 }
 
@@ -213,37 +286,42 @@ impl CollectInfo for Ctor {
             let info = CtorInfo { name: name.clone().id, doc };
             collector.add_info(*span, info);
         }
-        params.collect_info(db, collector);
-        typ.collect_info(db, collector);
+        collector.telescope(db, params, |collector| typ.collect_info(db, collector));
     }
 }
 
 impl CollectInfo for Dtor {
     fn collect_info(&self, db: &Database, collector: &mut InfoCollector) {
-        let Dtor { span, name, doc, self_param, params, ret_typ } = self;
+        let Dtor { span, name, doc, self_param, params, ret_typ, default } = self;
         if let Some(span) = span {
             // Add info
             let doc = doc.clone().map(|doc| doc.docs);
             let info = DtorInfo { name: name.clone().id, doc };
             collector.add_info(*span, info);
         }
-        self_param.collect_info(db, collector);
-        params.collect_info(db, collector);
-        ret_typ.collect_info(db, collector);
+        collector.telescope(db, params, |collector| {
+            self_param.collect_info(db, collector);
+            collector.bind(vec![self_binding_site(self_param)], |collector| {
+                ret_typ.collect_info(db, collector)
+            });
+            default.collect_info(db, collector);
+        });
     }
 }
 
 impl CollectInfo for Let {
     fn collect_info(&self, db: &Database, collector: &mut InfoCollector) {
-        let Let { span, typ, body, params, .. } = self;
+        let Let { span, name, doc, typ, body, params, .. } = self;
         if let Some(span) = span {
             // Add info
-            let info = LetInfo {};
+            let doc = doc.clone().map(|doc| doc.docs);
+            let info = LetInfo { name: name.clone().id, doc, typ: typ.print_to_string(None) };
             collector.add_info(*span, info);
         }
-        typ.collect_info(db, collector);
-        body.collect_info(db, collector);
-        params.collect_info(db, collector)
+        collector.telescope(db, params, |collector| {
+            typ.collect_info(db, collector);
+            body.collect_info(db, collector)
+        })
     }
 }
 
@@ -263,16 +341,21 @@ impl CollectInfo for Exp {
             Exp::Anno(e) => e.collect_info(db, collector),
             Exp::LocalMatch(e) => e.collect_info(db, collector),
             Exp::LocalComatch(e) => e.collect_info(db, collector),
+            Exp::LocalLet(e) => e.collect_info(db, collector),
         }
     }
 }
 
 impl CollectInfo for Variable {
     fn collect_info(&self, _db: &Database, collector: &mut InfoCollector) {
-        let Variable { span, inferred_type, name, .. } = self;
+        let Variable { span, idx, inferred_type, name } = self;
         if let (Some(span), Some(typ)) = (span, inferred_type) {
-            let info = VariableInfo { typ: typ.print_to_string(None), name: name.clone().id };
-            collector.add_info(*span, info)
+            let info = VariableInfo {
+                definition_site: collector.binding_site(*idx),
+                typ: typ.print_to_string(None),
+                name: name.clone().id,
+            };
+            collector.add_exp_info(*span, info, self.typ())
         }
     }
 }
@@ -296,7 +379,7 @@ impl CollectInfo for TypCtor {
                 _ => (None, None),
             };
             let info = TypeCtorInfo { name: name.clone().id, definition_site, doc };
-            collector.add_info(*span, info)
+            collector.add_exp_info(*span, info, self.typ())
         }
         args.collect_info(db, collector)
     }
@@ -340,7 +423,7 @@ impl CollectInfo for Call {
                 name: name.clone().id,
                 definition_site,
             };
-            collector.add_info(*span, info)
+            collector.add_exp_info(*span, info, self.typ())
         }
         args.collect_info(db, collector)
     }
@@ -374,8 +457,9 @@ impl CollectInfo for DotCall {
                 name: name.clone().id,
                 typ: typ.print_to_string(None),
                 definition_site,
+                recursive_call: collector.recursive_call(*span),
             };
-            collector.add_info(*span, info)
+            collector.add_exp_info(*span, info, self.typ())
         }
         exp.collect_info(db, collector);
         args.collect_info(db, collector)
@@ -384,7 +468,7 @@ impl CollectInfo for DotCall {
 
 impl CollectInfo for Hole {
     fn collect_info(&self, _db: &Database, collector: &mut InfoCollector) {
-        let Hole { span, kind: _, metavar, inferred_type, inferred_ctx, args, solution: _ } = self;
+        let Hole { span, metavar, inferred_type, inferred_ctx, args, .. } = self;
         if let Some(span) = span {
             let metavar_state = collector
                 .meta_vars
@@ -404,8 +488,9 @@ impl CollectInfo for Hole {
                     .map(|subst| subst.iter().map(|exp| exp.print_to_string(None)).collect())
                     .collect(),
                 metavar_state: metavar_str,
+                hole: self.clone(),
             };
-            collector.add_info(*span, info)
+            collector.add_exp_info(*span, info, self.typ())
         }
     }
 }
@@ -415,7 +500,7 @@ impl CollectInfo for TypeUniv {
         let TypeUniv { span } = self;
         if let Some(span) = span {
             let info = TypeUnivInfo {};
-            collector.add_info(*span, info)
+            collector.add_exp_info(*span, info, self.typ())
         }
     }
 }
@@ -425,7 +510,7 @@ impl CollectInfo for Anno {
         let Anno { span, exp, typ, normalized_type } = self;
         if let (Some(span), Some(typ)) = (span, normalized_type) {
             let info = AnnoInfo { typ: typ.print_to_string(None) };
-            collector.add_info(*span, info)
+            collector.add_exp_info(*span, info, self.typ())
         }
         exp.collect_info(db, collector);
         typ.collect_info(db, collector)
@@ -434,13 +519,24 @@ impl CollectInfo for Anno {
 
 impl CollectInfo for LocalMatch {
     fn collect_info(&self, db: &Database, collector: &mut InfoCollector) {
-        let LocalMatch { span, on_exp, ret_typ, cases, inferred_type, .. } = self;
+        let LocalMatch { span, on_exp, motive, ret_typ, cases, inferred_type, .. } = self;
         if let (Some(span), Some(typ)) = (span, inferred_type) {
             // Add info
-            let info = LocalMatchInfo { typ: typ.print_to_string(None) };
-            collector.add_info(*span, info)
+            let cases = on_exp.typ().and_then(|typ| typ.to_typctor()).zip(on_exp.span()).map(
+                |(scrutinee, on_exp_span)| CasesInfo {
+                    scrutinee,
+                    covered: covered(cases),
+                    start: on_exp_span.end(),
+                    case_spans: case_spans(cases),
+                },
+            );
+            let info = LocalMatchInfo { typ: typ.print_to_string(None), cases };
+            collector.add_exp_info(*span, info, self.typ())
         }
         on_exp.collect_info(db, collector);
+        if let Some(Motive { param, ret_typ, .. }) = motive {
+            collector.bind(vec![param.name.span], |collector| ret_typ.collect_info(db, collector));
+        }
         ret_typ.collect_info(db, collector);
         cases.collect_info(db, collector)
     }
@@ -452,16 +548,35 @@ impl CollectInfo for LocalComatch {
         if let (Some(span), Some(typ)) = (span, inferred_type) {
             // Add info
             let info = LocalComatchInfo { typ: typ.print_to_string(None) };
-            collector.add_info(*span, info)
+            collector.add_exp_info(*span, info, self.typ())
         }
         cases.collect_info(db, collector)
     }
 }
 
+impl CollectInfo for LocalLet {
+    fn collect_info(&self, db: &Database, collector: &mut InfoCollector) {
+        let LocalLet { name, typ, bound, body, .. } = self;
+        typ.collect_info(db, collector);
+        bound.collect_info(db, collector);
+        collector.bind(vec![name.span], |collector| body.collect_info(db, collector))
+    }
+}
+
+/// The names of the constructors which are covered by `cases`
+fn covered(cases: &[Case]) -> Vec<String> {
+    cases.iter().map(|case| case.pattern.name.id.clone()).collect()
+}
+
+fn case_spans(cases: &[Case]) -> Vec<Span> {
+    cases.iter().filter_map(|case| case.span).collect()
+}
+
 impl CollectInfo for Case {
     fn collect_info(&self, db: &Database, collector: &mut InfoCollector) {
-        let Case { body, .. } = self;
-        body.collect_info(db, collector)
+        let Case { pattern, body, .. } = self;
+        let binders = pattern.params.params.iter().map(|param| param.name.span).collect();
+        collector.bind(binders, |collector| body.collect_info(db, collector))
     }
 }
 
@@ -484,22 +599,13 @@ impl CollectInfo for Arg {
     }
 }
 
-impl CollectInfo for Telescope {
-    fn collect_info(&self, db: &Database, collector: &mut InfoCollector) {
-        self.params.collect_info(db, collector)
-    }
-}
-
-impl CollectInfo for Param {
-    fn collect_info(&self, db: &Database, collector: &mut InfoCollector) {
-        let Param { typ, .. } = self;
-        typ.collect_info(db, collector)
-    }
-}
-
 impl CollectInfo for SelfParam {
     fn collect_info(&self, db: &Database, collector: &mut InfoCollector) {
         let SelfParam { typ, .. } = self;
         typ.collect_info(db, collector);
     }
 }
+
+fn self_binding_site(self_param: &SelfParam) -> Option<Span> {
+    self_param.name.as_ref().and_then(|name| name.span)
+}
@@ -1,10 +1,11 @@
-use codespan::Span;
+use codespan::{ByteIndex, Span};
 use printer::Print;
 
 use ast::{
     ctx::values::{Binder as TypeCtxBinder, TypeCtx},
-    CallKind, DotCallKind,
+    CallKind, DotCallKind, Exp, Hole, Lvl, TypCtor,
 };
+use elaborator::typechecker::termination::RecursiveCall;
 use url::Url;
 
 // Info
@@ -19,6 +20,8 @@ pub struct Info {
     pub span: Span,
     /// The information that is available for that span
     pub content: InfoContent,
+    /// The elaborated type, if the span belongs to an expression whose type is known
+    pub typ: Option<Box<Exp>>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -45,6 +48,21 @@ pub enum InfoContent {
     UseInfo(UseInfo),
 }
 
+impl InfoContent {
+    /// The declaration which defines the symbol this information is about, if any
+    pub fn definition_site(&self) -> Option<&(Url, Span)> {
+        match self {
+            InfoContent::TypeCtorInfo(TypeCtorInfo { definition_site, .. })
+            | InfoContent::CallInfo(CallInfo { definition_site, .. })
+            | InfoContent::DotCallInfo(DotCallInfo { definition_site, .. })
+            | InfoContent::VariableInfo(VariableInfo { definition_site, .. }) => {
+                definition_site.as_ref()
+            }
+            _ => None,
+        }
+    }
+}
+
 // Info structs for modules
 //
 //
@@ -124,7 +142,14 @@ impl From<DtorInfo> for InfoContent {
 
 /// Information for toplevel definitions
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct DefInfo {}
+pub struct DefInfo {
+    /// Name of the definition
+    pub name: String,
+    /// Doc comments for the definition
+    pub doc: Option<Vec<String>>,
+    /// The cases of the definition
+    pub cases: Option<CasesInfo>,
+}
 
 impl From<DefInfo> for InfoContent {
     fn from(value: DefInfo) -> Self {
@@ -134,7 +159,12 @@ impl From<DefInfo> for InfoContent {
 
 /// Information for toplevel codefinitions
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct CodefInfo {}
+pub struct CodefInfo {
+    /// Name of the codefinition
+    pub name: String,
+    /// Doc comments for the codefinition
+    pub doc: Option<Vec<String>>,
+}
 
 impl From<CodefInfo> for InfoContent {
     fn from(value: CodefInfo) -> Self {
@@ -144,7 +174,14 @@ impl From<CodefInfo> for InfoContent {
 
 /// Information for toplevel let bindings
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct LetInfo {}
+pub struct LetInfo {
+    /// Name of the let binding
+    pub name: String,
+    /// Doc comments for the let binding
+    pub doc: Option<Vec<String>>,
+    /// The type of the let binding
+    pub typ: String,
+}
 
 impl From<LetInfo> for InfoContent {
     fn from(value: LetInfo) -> Self {
@@ -159,6 +196,9 @@ impl From<LetInfo> for InfoContent {
 /// Information for bound variables
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct VariableInfo {
+    /// The binding site of the variable.
+    /// This is used for the jump-to-definition feature.
+    pub definition_site: Option<(Url, Span)>,
     pub name: String,
     pub typ: String,
 }
@@ -216,6 +256,8 @@ pub struct DotCallInfo {
     pub kind: DotCallKind,
     pub name: String,
     pub typ: String,
+    /// The evidence of the termination check if this is a recursive call
+    pub recursive_call: Option<RecursiveCall>,
 }
 
 impl From<DotCallInfo> for InfoContent {
@@ -250,6 +292,8 @@ impl From<AnnoInfo> for InfoContent {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LocalMatchInfo {
     pub typ: String,
+    /// The cases of the match
+    pub cases: Option<CasesInfo>,
 }
 
 impl From<LocalMatchInfo> for InfoContent {
@@ -258,6 +302,27 @@ impl From<LocalMatchInfo> for InfoContent {
     }
 }
 
+/// The cases of a definition or local match, which are used to offer the missing cases
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CasesInfo {
+    /// The type of the scrutinee
+    pub scrutinee: TypCtor,
+    /// The constructors which are already covered by a case
+    pub covered: Vec<String>,
+    /// The end of the scrutinee or of the return type, after which the cases start
+    pub start: ByteIndex,
+    /// The spans of the cases
+    pub case_spans: Vec<Span>,
+}
+
+impl CasesInfo {
+    /// Whether the index lies after `start` but outside of all cases
+    pub fn is_between_cases(&self, idx: ByteIndex) -> bool {
+        let contains = |span: &Span| span.start() <= idx && idx <= span.end();
+        self.start < idx && !self.case_spans.iter().any(contains)
+    }
+}
+
 /// Information for local comatches
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LocalComatchInfo {
@@ -279,6 +344,8 @@ pub struct HoleInfo {
     pub args: Vec<Vec<String>>,
     /// `Some(e)` if the solution`e` has been found for the metavariable.
     pub metavar_state: Option<String>,
+    /// The elaborated hole, which code actions use to fill it
+    pub hole: Hole,
 }
 
 impl From<HoleInfo> for InfoContent {
@@ -295,19 +362,30 @@ pub struct Ctx {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Binder {
     pub name: String,
+    /// The de Bruijn level of the bound variable
+    pub lvl: Lvl,
     pub typ: String,
 }
 
 impl From<TypeCtx> for Ctx {
     fn from(ctx: TypeCtx) -> Self {
-        let bound =
-            ctx.bound.into_iter().map(|tel| tel.into_iter().map(Into::into).collect()).collect();
+        let bound = ctx
+            .bound
+            .into_iter()
+            .enumerate()
+            .map(|(fst, tel)| {
+                tel.into_iter()
+                    .enumerate()
+                    .map(|(snd, binder)| Binder::new(binder, Lvl { fst, snd }))
+                    .collect()
+            })
+            .collect();
         Ctx { bound }
     }
 }
 
-impl From<TypeCtxBinder> for Binder {
-    fn from(binder: TypeCtxBinder) -> Self {
-        Binder { name: binder.name.id, typ: binder.typ.print_to_string(None) }
+impl Binder {
+    fn new(binder: TypeCtxBinder, lvl: Lvl) -> Self {
+        Binder { name: binder.name.id, lvl, typ: binder.typ.print_to_string(None) }
     }
 }
@@ -0,0 +1,202 @@
+use codespan::{ByteIndex, Span};
+use url::Url;
+
+use ast::*;
+use printer::Print;
+
+use crate::database::Database;
+
+/// The inferred type of a binder, displayed after its name
+pub struct InlayHint {
+    /// The location after the name of the binder
+    pub idx: ByteIndex,
+    /// The printed inferred type
+    pub label: String,
+}
+
+impl Database {
+    /// Compute the inlay hints of the module at `uri` located within `range`
+    ///
+    /// Only the declarations which overlap `range` are traversed,
+    /// such that clients can request the hints for the visible part of large modules.
+    pub async fn inlay_hints(&mut self, uri: &Url, range: Span) -> Vec<InlayHint> {
+        let Ok(module) = self.ast(uri).await else {
+            return vec![];
+        };
+        let mut hints = vec![];
+        for decl in module.decls.iter() {
+            if decl.span().is_some_and(|span| overlaps(span, range)) {
+                decl.hints(range, &mut hints);
+            }
+        }
+        hints
+    }
+}
+
+fn overlaps(span: Span, range: Span) -> bool {
+    span.start() <= range.end() && range.start() <= span.end()
+}
+
+/// Collect the inlay hints of binders whose types are not annotated in the source
+///
+/// Patterns, lambdas, motives and unannotated local lets bind variables without type annotations.
+/// Inferred types are not traversed, since their spans may point to other modules.
+trait Hints {
+    fn hints(&self, range: Span, out: &mut Vec<InlayHint>);
+}
+
+impl<T: Hints> Hints for Box<T> {
+    fn hints(&self, range: Span, out: &mut Vec<InlayHint>) {
+        (**self).hints(range, out)
+    }
+}
+
+impl<T: Hints> Hints for Option<T> {
+    fn hints(&self, range: Span, out: &mut Vec<InlayHint>) {
+        if let Some(x) = self {
+            x.hints(range, out)
+        }
+    }
+}
+
+impl<T: Hints> Hints for Vec<T> {
+    fn hints(&self, range: Span, out: &mut Vec<InlayHint>) {
+        for x in self {
+            x.hints(range, out)
+        }
+    }
+}
+
+impl Hints for Decl {
+    fn hints(&self, range: Span, out: &mut Vec<InlayHint>) {
+        match self {
+            Decl::Data(Data { ctors, .. }) => {
+                for ctor in ctors {
+                    ctor.params.hints(range, out);
+                }
+            }
+            Decl::Codata(Codata { dtors, .. }) => {
+                for dtor in dtors {
+                    dtor.params.hints(range, out);
+                    dtor.ret_typ.hints(range, out);
+                    dtor.default.hints(range, out);
+                }
+            }
+            Decl::Def(Def { params, ret_typ, cases, .. }) => {
+                params.hints(range, out);
+                ret_typ.hints(range, out);
+                cases.hints(range, out);
+            }
+            Decl::Codef(Codef { params, cases, .. }) => {
+                params.hints(range, out);
+                cases.hints(range, out);
+            }
+            Decl::Let(Let { params, typ, body, .. }) => {
+                params.hints(range, out);
+                typ.hints(range, out);
+                body.hints(range, out);
+            }
+        }
+    }
+}
+
+impl Hints for Telescope {
+    fn hints(&self, range: Span, out: &mut Vec<InlayHint>) {
+        for param in self.params.iter() {
+            param.typ.hints(range, out);
+            if let Some(default) = &param.default {
+                default.hints(range, out);
+            }
+        }
+    }
+}
+
+impl Hints for ParamInst {
+    fn hints(&self, range: Span, out: &mut Vec<InlayHint>) {
+        let ParamInst { name, info, .. } = self;
+        let (Some(span), Some(typ)) = (name.span, info) else {
+            return;
+        };
+        if overlaps(span, range) {
+            out.push(InlayHint { idx: span.end(), label: typ.print_to_string(None) });
+        }
+    }
+}
+
+impl Hints for TelescopeInst {
+    fn hints(&self, range: Span, out: &mut Vec<InlayHint>) {
+        for param in self.params.iter() {
+            param.hints(range, out);
+        }
+    }
+}
+
+impl Hints for Case {
+    fn hints(&self, range: Span, out: &mut Vec<InlayHint>) {
+        let Case { pattern, body, .. } = self;
+        pattern.params.hints(range, out);
+        body.hints(range, out);
+    }
+}
+
+impl Hints for Args {
+    fn hints(&self, range: Span, out: &mut Vec<InlayHint>) {
+        for arg in self.args.iter() {
+            match arg {
+                Arg::UnnamedArg(exp) | Arg::NamedArg(_, exp) => exp.hints(range, out),
+                Arg::InsertedImplicitArg(_) => {}
+            }
+        }
+    }
+}
+
+impl Hints for Exp {
+    fn hints(&self, range: Span, out: &mut Vec<InlayHint>) {
+        if self.span().is_some_and(|span| !overlaps(span, range)) {
+            return;
+        }
+        match self {
+            Exp::Variable(_) | Exp::TypeUniv(_) | Exp::Hole(_) => {}
+            Exp::TypCtor(TypCtor { args, .. }) | Exp::Call(Call { args, .. }) => {
+                args.hints(range, out)
+            }
+            Exp::DotCall(DotCall { exp, args, .. }) => {
+                exp.hints(range, out);
+                args.hints(range, out);
+            }
+            Exp::Anno(Anno { exp, typ, .. }) => {
+                match &**exp {
+                    // The types of the binders of an annotated comatch are evident from the
+                    // annotation, so we only show the hints within the bodies of its cocases
+                    Exp::LocalComatch(LocalComatch { cases, .. }) => {
+                        for case in cases {
+                            case.body.hints(range, out);
+                        }
+                    }
+                    exp => exp.hints(range, out),
+                }
+                typ.hints(range, out);
+            }
+            Exp::LocalMatch(LocalMatch { on_exp, motive, cases, .. }) => {
+                on_exp.hints(range, out);
+                if let Some(Motive { param, ret_typ, .. }) = motive {
+                    param.hints(range, out);
+                    ret_typ.hints(range, out);
+                }
+                cases.hints(range, out);
+            }
+            Exp::LocalComatch(LocalComatch { cases, .. }) => cases.hints(range, out),
+            Exp::LocalLet(LocalLet { name, typ, bound, body, bound_type, .. }) => {
+                if let (Some(span), None, Some(bound_type)) = (name.span, typ, bound_type) {
+                    if overlaps(span, range) {
+                        let label = bound_type.print_to_string(None);
+                        out.push(InlayHint { idx: span.end(), label });
+                    }
+                }
+                typ.hints(range, out);
+                bound.hints(range, out);
+                body.hints(range, out);
+            }
+        }
+    }
+}
@@ -0,0 +1,151 @@
+//! JSON representation of modules
+//!
+//! The JSON representation is a faithful image of the syntax tree, including the spans and the
+//! de Bruijn indices of all variables. It is intended to be consumed by external tooling.
+//!
+//! A module can be loaded back from its JSON representation. Since a dump may have been produced
+//! or modified by other tools, all references to top-level declarations are validated on load.
+
+use std::rc::Rc;
+
+use url::Url;
+
+use ast::*;
+use elaborator::{build_type_info_table, TypeInfoTable};
+
+use crate::database::Database;
+use crate::references::{Occurrence, OccurrenceKind, Occurrences};
+use crate::result::DriverError;
+use crate::Error;
+
+impl Database {
+    /// Serialize the typechecked module at `uri` to JSON
+    pub async fn dump_ast_json(&mut self, uri: &Url) -> Result<String, Error> {
+        let module = self.ast(uri).await?;
+        to_json(&module)
+    }
+
+    /// Serialize the lowered module at `uri` to JSON
+    ///
+    /// In contrast to the typechecked module, the lowered module is not annotated with
+    /// inferred types and its metavariables are unsolved.
+    pub async fn dump_ust_json(&mut self, uri: &Url) -> Result<String, Error> {
+        let module = self.ust(uri).await?;
+        to_json(&module)
+    }
+
+    /// Load a module from its JSON representation
+    ///
+    /// The dump may either contain a lowered or a typechecked module.
+    /// References to declarations of other modules are resolved against the current sources
+    /// of these modules. An error is returned if any reference cannot be resolved.
+    pub async fn load_json(&mut self, json: &str) -> Result<Module, Error> {
+        let module = from_json(json)?;
+        self.json_type_info_table(&module).await?;
+        Ok(module)
+    }
+
+    /// Load a module from its JSON representation and typecheck it
+    pub async fn check_json(&mut self, json: &str) -> Result<Module, Error> {
+        let module = from_json(json)?;
+        let info_table = self.json_type_info_table(&module).await?;
+        elaborator::typechecker::check_with_lookup_table(
+            Rc::new(module),
+            &info_table,
+            &self.eval_config,
+        )
+        .map_err(|err| Box::new(err).into())
+    }
+
+    /// Build the type info table for a module which has been loaded from JSON
+    ///
+    /// The table of the module itself is reconstructed from its declarations.
+    /// Every reference to a declaration is checked to resolve in the table.
+    async fn json_type_info_table(&mut self, module: &Module) -> Result<TypeInfoTable, Error> {
+        let mut occurrences = vec![];
+        module.decls.occurrences(&module.uri, &mut occurrences);
+        let references: Vec<Occurrence> =
+            occurrences.into_iter().filter(|occ| !occ.is_decl).collect();
+
+        let mut info_table = TypeInfoTable::default();
+        info_table.insert(module.uri.clone(), build_type_info_table(module));
+        let deps: HashSet<&Url> = references
+            .iter()
+            .map(|reference| &reference.name.uri)
+            .filter(|uri| **uri != module.uri)
+            .collect();
+        for dep in deps {
+            info_table.insert(dep.clone(), self.module_type_info_table(dep).await?);
+        }
+
+        for Occurrence { kind, name, .. } in references.iter() {
+            let resolves = match kind {
+                OccurrenceKind::TypCtor => info_table.lookup_tyctor(name).is_ok(),
+                OccurrenceKind::Ctor => info_table.lookup_ctor(name).is_ok(),
+                OccurrenceKind::Dtor => info_table.lookup_dtor(name).is_ok(),
+                OccurrenceKind::Def => info_table.lookup_def(name).is_ok(),
+                OccurrenceKind::Codef => info_table.lookup_codef(name).is_ok(),
+                OccurrenceKind::Let => info_table.lookup_let(name).is_ok(),
+            };
+            if !resolves {
+                return Err(DriverError::Json(format!(
+                    "The {} {} of module {} does not exist",
+                    kind, name.id, name.uri
+                ))
+                .into());
+            }
+        }
+        Ok(info_table)
+    }
+}
+
+fn to_json(module: &Module) -> Result<String, Error> {
+    serde_json::to_string_pretty(module).map_err(|err| DriverError::Json(err.to_string()).into())
+}
+
+fn from_json(json: &str) -> Result<Module, Error> {
+    serde_json::from_str(json).map_err(|err| DriverError::Json(err.to_string()).into())
+}
+
+#[cfg(test)]
+mod json_tests {
+    use futures::executor::block_on;
+
+    use crate::test_support::{database_with, uri};
+
+    use super::*;
+
+    fn bool_uri() -> Url {
+        Url::parse("inmemory:///bool.pol").unwrap()
+    }
+
+    fn database(bool_source: &str) -> Database {
+        database_with(vec![
+            (bool_uri(), bool_source),
+            (uri(), "use \"bool.pol\"\n\nlet not(b: Bool): Bool { b.match { T => F, F => T } }\n"),
+        ])
+    }
+
+    #[test]
+    fn load_dumped_module() {
+        let mut db = database("data Bool { T, F }\n");
+        let expected = block_on(db.ast(&uri())).unwrap();
+        let json = block_on(db.dump_ast_json(&uri())).unwrap();
+        assert_eq!(block_on(db.load_json(&json)).unwrap(), *expected);
+        assert_eq!(block_on(db.check_json(&json)).unwrap(), *expected);
+
+        let json = block_on(db.dump_ust_json(&uri())).unwrap();
+        assert_eq!(block_on(db.check_json(&json)).unwrap(), *expected);
+    }
+
+    #[test]
+    fn load_rejects_missing_declaration() {
+        let json = block_on(database("data Bool { T, F }\n").dump_ast_json(&uri())).unwrap();
+
+        let mut db = database("data Bool { T }\n");
+        let Err(Error::Driver(DriverError::Json(msg))) = block_on(db.load_json(&json)) else {
+            panic!("Expected the reference to the missing constructor F to be rejected");
+        };
+        assert_eq!(msg, "The constructor F of module inmemory:///bool.pol does not exist");
+    }
+}
@@ -2,22 +2,60 @@ pub use result::Error;
 
 mod asserts;
 mod cache;
+mod canonical;
+mod check;
+mod complete_match;
+mod completion;
 mod database;
 mod dependency_graph;
 mod edit;
+mod error_index;
+mod expr;
+mod extract;
+mod fill_hole;
+mod format;
 mod fs;
+mod html;
+mod incremental;
 mod info;
+mod inlay_hints;
+#[cfg(feature = "json")]
+mod json;
 mod lift;
 pub mod paths;
+mod peek;
+#[cfg(feature = "polc")]
+mod polc;
+mod qualify;
+mod references;
+mod rename;
 mod result;
+mod semantic_tokens;
+mod signature_help;
 mod spans;
+mod symbols;
+#[cfg(test)]
+mod test_support;
+mod warnings;
 mod xfunc;
 
-pub use database::Database;
+pub use database::{Database, DEFAULT_FUEL};
 
+pub use complete_match::*;
+pub use completion::*;
 pub use edit::*;
+pub use error_index::*;
+pub use extract::*;
+pub use fill_hole::*;
 pub use fs::*;
 pub use info::*;
+pub use inlay_hints::*;
 pub use paths::*;
-pub use result::DriverError;
+pub use peek::*;
+#[cfg(feature = "polc")]
+pub use polc::*;
+pub use result::{CyclicImport, DriverError, ModuleDiagnostic, ParseErrors, Warning};
+pub use semantic_tokens::*;
+pub use signature_help::*;
+pub use symbols::*;
 pub use xfunc::*;
@@ -2,6 +2,8 @@ pub const TARGET_PATH: &str = "target_pol/";
 
 pub const DOCS_PATH: &str = "target_pol/docs/";
 
+pub const POLC_PATH: &str = "target_pol/polc/";
+
 pub const CSS_PATH: &str = "target_pol/docs/style.css";
 
 pub const CSS_TEMPLATE_PATH: &str = include_str!("../../docs/templates/style.css");
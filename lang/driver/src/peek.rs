@@ -0,0 +1,27 @@
+use codespan::{ByteIndex, Span};
+use url::Url;
+
+use crate::database::Database;
+
+/// The definition of a symbol, as shown in an inline preview of an editor
+pub struct Peek {
+    /// The module which contains the definition
+    pub uri: Url,
+    /// The span of the definition within that module
+    pub span: Span,
+    /// The source text of the definition
+    pub text: String,
+}
+
+impl Database {
+    /// Find the definition of the symbol at the given index together with its source text
+    ///
+    /// If the definition is located in an imported module, the source of that module is loaded.
+    pub async fn peek(&mut self, uri: &Url, idx: ByteIndex) -> Option<Peek> {
+        let info = self.hoverinfo_at_index(uri, idx).await?;
+        let (uri, span) = info.content.definition_site()?.clone();
+        let source = self.source(&uri).await.ok()?;
+        let text = source.get(span.start().to_usize()..span.end().to_usize())?.to_owned();
+        Some(Peek { uri, span, text })
+    }
+}
@@ -0,0 +1,161 @@
+//! Binary cache of typechecked modules
+//!
+//! A `.polc` file contains a typechecked module together with a stamp.
+//! The stamp is a hash of the source code of the module and of all of its transitive dependencies.
+//! A cached module is only used if its stamp matches the current sources,
+//! otherwise the module is compiled from source as usual.
+
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use elaborator::build_type_info_table;
+
+use crate::database::Database;
+use crate::result::DriverError;
+use crate::Error;
+
+/// The file extension of cached modules
+pub const POLC_EXTENSION: &str = "polc";
+
+#[derive(Serialize, Deserialize)]
+struct Polc<M> {
+    /// The version of the compiler which wrote the cache
+    version: String,
+    /// Hash of the sources the module was compiled from
+    stamp: u64,
+    module: M,
+}
+
+impl Database {
+    /// Serialize the typechecked module at `uri` into the `.polc` format
+    pub async fn export_polc(&mut self, uri: &Url) -> Result<Vec<u8>, Error> {
+        let module = self.ast(uri).await?;
+        self.encode_polc(uri, &module).await
+    }
+
+    /// Load the typechecked module at `uri` from bytes in the `.polc` format
+    ///
+    /// Returns `None` if the bytes are not a valid cache or if the stamp does not match the
+    /// current sources. In that case, the module is compiled from source when it is requested.
+    pub async fn import_polc(
+        &mut self,
+        uri: &Url,
+        bytes: &[u8],
+    ) -> Result<Option<Arc<ast::Module>>, Error> {
+        let polc: Polc<ast::Module> = match bincode::deserialize(bytes) {
+            Ok(polc) => polc,
+            Err(err) => {
+                log::debug!("Ignoring invalid module cache for {}: {}", uri, err);
+                return Ok(None);
+            }
+        };
+        let stamp = self.sources_stamp(vec![uri.clone()]).await?;
+        if polc.version != env!("CARGO_PKG_VERSION") || polc.stamp != stamp {
+            log::debug!("Ignoring outdated module cache for {}", uri);
+            return Ok(None);
+        }
+
+        log::debug!("Loaded module from cache: {}", uri);
+        let module = Arc::new(polc.module);
+        // Modules depending on this module only need its type info table, not its UST
+        self.module_type_info_table.insert(uri.clone(), build_type_info_table(&module));
+        self.ast.insert(uri.clone(), Ok(module.clone()));
+        Ok(Some(module))
+    }
+
+    async fn encode_polc(&mut self, uri: &Url, module: &ast::Module) -> Result<Vec<u8>, Error> {
+        let stamp = self.sources_stamp(vec![uri.clone()]).await?;
+        let polc = Polc { version: env!("CARGO_PKG_VERSION").to_owned(), stamp, module };
+        bincode::serialize(&polc).map_err(|err| DriverError::Polc(err.to_string()).into())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod file_system {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::paths::POLC_PATH;
+
+    impl Database {
+        /// The path of the `.polc` file caching the module at `uri`
+        ///
+        /// Returns `None` if the module is not located on the file system.
+        pub fn polc_path(uri: &Url) -> Option<PathBuf> {
+            let path = uri.to_file_path().ok()?;
+            let stem = path.file_stem()?.to_string_lossy();
+            // Different modules may have the same file name, so we also include a hash of the URI
+            let mut hasher = DefaultHasher::new();
+            uri.hash(&mut hasher);
+            let name = format!("{}-{:016x}.{}", stem, hasher.finish(), POLC_EXTENSION);
+            Some(PathBuf::from(POLC_PATH).join(name))
+        }
+
+        /// Load the typechecked module at `uri` from its `.polc` file, if it is up to date
+        pub async fn read_polc_file(
+            &mut self,
+            uri: &Url,
+        ) -> Result<Option<Arc<ast::Module>>, Error> {
+            let Some(bytes) = Self::polc_path(uri).and_then(|path| std::fs::read(path).ok()) else {
+                return Ok(None);
+            };
+            self.import_polc(uri, &bytes).await
+        }
+
+        /// Write the typechecked module at `uri` to its `.polc` file
+        pub async fn write_polc_file(
+            &mut self,
+            uri: &Url,
+            module: &ast::Module,
+        ) -> Result<(), Error> {
+            let Some(path) = Self::polc_path(uri) else {
+                return Ok(());
+            };
+            let bytes = self.encode_polc(uri, module).await?;
+            std::fs::create_dir_all(POLC_PATH).map_err(Arc::new).map_err(DriverError::Io)?;
+            std::fs::write(path, bytes).map_err(Arc::new).map_err(DriverError::Io)?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod polc_tests {
+    use futures::executor::block_on;
+
+    use crate::test_support::{database, uri};
+
+    use super::*;
+
+    #[test]
+    fn import_exported_module() {
+        let source = "data Bool { T, F }\n";
+        let bytes = block_on(database(source).export_polc(&uri())).unwrap();
+
+        let mut db = database(source);
+        let module = block_on(db.import_polc(&uri(), &bytes)).unwrap().unwrap();
+        let expected = block_on(database(source).ast(&uri())).unwrap();
+        assert_eq!(*module, *expected);
+        assert!(Arc::ptr_eq(&block_on(db.ast(&uri())).unwrap(), &module));
+    }
+
+    #[test]
+    fn stamp_mismatch_falls_back_to_source() {
+        let bytes = block_on(database("data Bool { T, F }\n").export_polc(&uri())).unwrap();
+
+        let mut db = database("data Unit { Unit }\n");
+        assert!(block_on(db.import_polc(&uri(), &bytes)).unwrap().is_none());
+        let module = block_on(db.ast(&uri())).unwrap();
+        assert_eq!(module.decls.len(), 1);
+        assert_eq!(module.decls[0].ident().id, "Unit");
+    }
+
+    #[test]
+    fn invalid_cache_falls_back_to_source() {
+        let mut db = database("data Bool { T, F }\n");
+        assert!(block_on(db.import_polc(&uri(), b"not a module cache")).unwrap().is_none());
+        assert!(block_on(db.ast(&uri())).is_ok());
+    }
+}
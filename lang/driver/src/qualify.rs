@@ -0,0 +1,198 @@
+//! Qualify names of imported declarations when printing a module
+//!
+//! A name which is declared by more than one of the modules in scope cannot be printed as is,
+//! since it would either be ambiguous or refer to the declaration of the current module.
+//! Such names are printed as qualified names `module.name` instead.
+
+use url::Url;
+
+use ast::*;
+
+use crate::database::Database;
+use crate::Error;
+
+/// The names which need to be qualified when printing the module `uri`
+pub(crate) struct Qualifiers {
+    uri: Url,
+    /// The number of modules in scope which declare each top-level name
+    declared_in: HashMap<String, usize>,
+}
+
+impl Database {
+    /// Compute the names which need to be qualified when printing the module at `uri`
+    pub(crate) async fn qualifiers(&mut self, uri: &Url) -> Result<Qualifiers, Error> {
+        let mut declared_in: HashMap<String, usize> = HashMap::default();
+        let mut modules = vec![uri.clone()];
+        modules.extend(self.deps(uri).await?);
+        for module in modules {
+            for name in self.symbol_table(&module).await?.keys() {
+                *declared_in.entry(name.id.clone()).or_default() += 1;
+            }
+        }
+        Ok(Qualifiers { uri: uri.clone(), declared_in })
+    }
+}
+
+impl Qualifiers {
+    fn qualify_name(&self, name: &mut IdBound) {
+        if name.uri == self.uri || self.declared_in.get(&name.id).copied().unwrap_or_default() < 2 {
+            return;
+        }
+        if let Some(module) = lowering::module_name(&name.uri) {
+            name.id = format!("{module}.{}", name.id);
+        }
+    }
+}
+
+/// Qualify the names of imported declarations which would be ambiguous otherwise
+///
+/// Only the names of calls and type constructors are qualified, since only these can be
+/// written as qualified names.
+pub(crate) trait Qualify {
+    fn qualify(&mut self, qualifiers: &Qualifiers);
+}
+
+impl<T: Qualify> Qualify for Box<T> {
+    fn qualify(&mut self, qualifiers: &Qualifiers) {
+        (**self).qualify(qualifiers)
+    }
+}
+
+impl<T: Qualify> Qualify for Option<T> {
+    fn qualify(&mut self, qualifiers: &Qualifiers) {
+        if let Some(x) = self {
+            x.qualify(qualifiers)
+        }
+    }
+}
+
+impl<T: Qualify> Qualify for Vec<T> {
+    fn qualify(&mut self, qualifiers: &Qualifiers) {
+        for x in self {
+            x.qualify(qualifiers)
+        }
+    }
+}
+
+impl Qualify for Module {
+    fn qualify(&mut self, qualifiers: &Qualifiers) {
+        self.decls.qualify(qualifiers)
+    }
+}
+
+impl Qualify for Decl {
+    fn qualify(&mut self, qualifiers: &Qualifiers) {
+        match self {
+            Decl::Data(Data { typ, ctors, .. }) => {
+                typ.qualify(qualifiers);
+                ctors.qualify(qualifiers);
+            }
+            Decl::Codata(Codata { typ, dtors, .. }) => {
+                typ.qualify(qualifiers);
+                dtors.qualify(qualifiers);
+            }
+            Decl::Def(Def { params, self_param, ret_typ, cases, .. }) => {
+                params.qualify(qualifiers);
+                self_param.typ.qualify(qualifiers);
+                ret_typ.qualify(qualifiers);
+                cases.qualify(qualifiers);
+            }
+            Decl::Codef(Codef { params, typ, cases, .. }) => {
+                params.qualify(qualifiers);
+                typ.qualify(qualifiers);
+                cases.qualify(qualifiers);
+            }
+            Decl::Let(Let { params, typ, body, .. }) => {
+                params.qualify(qualifiers);
+                typ.qualify(qualifiers);
+                body.qualify(qualifiers);
+            }
+        }
+    }
+}
+
+impl Qualify for Ctor {
+    fn qualify(&mut self, qualifiers: &Qualifiers) {
+        self.params.qualify(qualifiers);
+        self.typ.qualify(qualifiers);
+    }
+}
+
+impl Qualify for Dtor {
+    fn qualify(&mut self, qualifiers: &Qualifiers) {
+        let Dtor { params, self_param, ret_typ, default, .. } = self;
+        params.qualify(qualifiers);
+        self_param.typ.qualify(qualifiers);
+        ret_typ.qualify(qualifiers);
+        default.qualify(qualifiers);
+    }
+}
+
+impl Qualify for Telescope {
+    fn qualify(&mut self, qualifiers: &Qualifiers) {
+        for param in self.params.iter_mut() {
+            param.typ.qualify(qualifiers);
+            if let Some(default) = &mut param.default {
+                default.qualify(qualifiers);
+            }
+        }
+    }
+}
+
+impl Qualify for Case {
+    fn qualify(&mut self, qualifiers: &Qualifiers) {
+        self.body.qualify(qualifiers);
+    }
+}
+
+impl Qualify for Args {
+    fn qualify(&mut self, qualifiers: &Qualifiers) {
+        for arg in self.args.iter_mut() {
+            match arg {
+                Arg::UnnamedArg(exp) | Arg::NamedArg(_, exp) => exp.qualify(qualifiers),
+                Arg::InsertedImplicitArg(_) => {}
+            }
+        }
+    }
+}
+
+impl Qualify for TypCtor {
+    fn qualify(&mut self, qualifiers: &Qualifiers) {
+        qualifiers.qualify_name(&mut self.name);
+        self.args.qualify(qualifiers);
+    }
+}
+
+impl Qualify for Exp {
+    fn qualify(&mut self, qualifiers: &Qualifiers) {
+        match self {
+            Exp::Variable(_) | Exp::TypeUniv(_) | Exp::Hole(_) => {}
+            Exp::TypCtor(typ) => typ.qualify(qualifiers),
+            Exp::Call(Call { name, args, .. }) => {
+                qualifiers.qualify_name(name);
+                args.qualify(qualifiers);
+            }
+            Exp::DotCall(DotCall { exp, args, .. }) => {
+                exp.qualify(qualifiers);
+                args.qualify(qualifiers);
+            }
+            Exp::Anno(Anno { exp, typ, .. }) => {
+                exp.qualify(qualifiers);
+                typ.qualify(qualifiers);
+            }
+            Exp::LocalMatch(LocalMatch { on_exp, motive, cases, .. }) => {
+                on_exp.qualify(qualifiers);
+                if let Some(Motive { ret_typ, .. }) = motive {
+                    ret_typ.qualify(qualifiers);
+                }
+                cases.qualify(qualifiers);
+            }
+            Exp::LocalComatch(LocalComatch { cases, .. }) => cases.qualify(qualifiers),
+            Exp::LocalLet(LocalLet { typ, bound, body, .. }) => {
+                typ.qualify(qualifiers);
+                bound.qualify(qualifiers);
+                body.qualify(qualifiers);
+            }
+        }
+    }
+}
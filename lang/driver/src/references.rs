@@ -0,0 +1,276 @@
+use codespan::{ByteIndex, Span};
+use url::Url;
+
+use ast::*;
+
+use crate::database::Database;
+
+impl Database {
+    /// Find all references to the top-level symbol at the given index in the module at `uri`
+    ///
+    /// Symbols are identified by their name together with the module which declares them.
+    /// Hence, local variables and symbols of other modules with the same name are not included.
+    /// If `include_declaration` is set, the name in the declaration of the symbol is included
+    /// as well, provided that the symbol is declared in the module at `uri`.
+    pub async fn references(
+        &mut self,
+        uri: &Url,
+        idx: ByteIndex,
+        include_declaration: bool,
+    ) -> Vec<Span> {
+        let Ok(module) = self.ust(uri).await else {
+            return vec![];
+        };
+        let mut occurrences = vec![];
+        module.decls.occurrences(uri, &mut occurrences);
+
+        // Syntactic sugar such as `a -> b` spans its arguments, so we pick the innermost name
+        let Some((name, kind)) = occurrences
+            .iter()
+            .filter_map(|occ| Some((occ, occ.span?)))
+            .filter(|(_, span)| span.start() <= idx && idx <= span.end())
+            .min_by_key(|(_, span)| span.end().to_usize() - span.start().to_usize())
+            .map(|(occ, _)| (occ.name.clone(), occ.kind))
+        else {
+            return vec![];
+        };
+
+        // A data type and its constructor may have the same name, so we compare kinds as well
+        let (declarations, references): (Vec<_>, Vec<_>) = occurrences
+            .into_iter()
+            .filter(|occ| occ.name == name && occ.kind == kind)
+            .partition(|occ| occ.is_decl);
+        let declarations: Vec<Span> = declarations.into_iter().filter_map(|occ| occ.span).collect();
+        let mut spans: Vec<Span> = references
+            .into_iter()
+            .filter_map(|occ| occ.span)
+            // Elided type constructors of constructors point to the name of the data type
+            .filter(|span| !declarations.contains(span))
+            .collect();
+        if include_declaration {
+            spans.extend(declarations);
+        }
+        spans.sort_by_key(|span| (span.start(), span.end()));
+        // Literals desugar to nested calls which all have the same span
+        spans.dedup();
+        spans
+    }
+}
+
+/// An occurrence of the name of a top-level symbol
+pub(crate) struct Occurrence {
+    /// The name of the symbol together with the module which declares it
+    pub name: IdBound,
+    /// The kind of the symbol
+    pub kind: OccurrenceKind,
+    /// The span of the name, if the name occurs in the source code
+    pub span: Option<Span>,
+    /// Whether this occurrence declares the symbol
+    pub is_decl: bool,
+}
+
+/// The kind of a top-level symbol which occurs in a module
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum OccurrenceKind {
+    TypCtor,
+    Ctor,
+    Dtor,
+    Def,
+    Codef,
+    Let,
+}
+
+impl std::fmt::Display for OccurrenceKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OccurrenceKind::TypCtor => write!(f, "type constructor"),
+            OccurrenceKind::Ctor => write!(f, "constructor"),
+            OccurrenceKind::Dtor => write!(f, "destructor"),
+            OccurrenceKind::Def => write!(f, "definition"),
+            OccurrenceKind::Codef => write!(f, "codefinition"),
+            OccurrenceKind::Let => write!(f, "let-bound definition"),
+        }
+    }
+}
+
+impl Occurrence {
+    fn declaration(uri: &Url, kind: OccurrenceKind, name: &IdBind, out: &mut Vec<Occurrence>) {
+        let span = name.span;
+        let name = IdBound { span, id: name.id.clone(), uri: uri.clone() };
+        out.push(Occurrence { name, kind, span, is_decl: true });
+    }
+
+    fn reference(kind: OccurrenceKind, name: &IdBound, out: &mut Vec<Occurrence>) {
+        out.push(Occurrence { name: name.clone(), kind, span: name.span, is_decl: false });
+    }
+}
+
+/// Collect the occurrences of top-level names in a module
+///
+/// Inferred types are not traversed, since their spans may point to other modules.
+/// Names which do not occur in the source code, such as the names of desugared expressions
+/// without spans, are included as well.
+pub(crate) trait Occurrences {
+    fn occurrences(&self, uri: &Url, out: &mut Vec<Occurrence>);
+}
+
+impl<T: Occurrences> Occurrences for Box<T> {
+    fn occurrences(&self, uri: &Url, out: &mut Vec<Occurrence>) {
+        (**self).occurrences(uri, out)
+    }
+}
+
+impl<T: Occurrences> Occurrences for Option<T> {
+    fn occurrences(&self, uri: &Url, out: &mut Vec<Occurrence>) {
+        if let Some(x) = self {
+            x.occurrences(uri, out)
+        }
+    }
+}
+
+impl<T: Occurrences> Occurrences for Vec<T> {
+    fn occurrences(&self, uri: &Url, out: &mut Vec<Occurrence>) {
+        for x in self {
+            x.occurrences(uri, out)
+        }
+    }
+}
+
+impl Occurrences for Decl {
+    fn occurrences(&self, uri: &Url, out: &mut Vec<Occurrence>) {
+        match self {
+            Decl::Data(Data { name, typ, ctors, .. }) => {
+                Occurrence::declaration(uri, OccurrenceKind::TypCtor, name, out);
+                typ.occurrences(uri, out);
+                ctors.occurrences(uri, out);
+            }
+            Decl::Codata(Codata { name, typ, dtors, .. }) => {
+                Occurrence::declaration(uri, OccurrenceKind::TypCtor, name, out);
+                typ.occurrences(uri, out);
+                dtors.occurrences(uri, out);
+            }
+            Decl::Def(Def { name, params, self_param, ret_typ, cases, .. }) => {
+                Occurrence::declaration(uri, OccurrenceKind::Def, name, out);
+                params.occurrences(uri, out);
+                self_param.typ.occurrences(uri, out);
+                ret_typ.occurrences(uri, out);
+                cases.occurrences(uri, out);
+            }
+            Decl::Codef(Codef { name, params, typ, cases, .. }) => {
+                Occurrence::declaration(uri, OccurrenceKind::Codef, name, out);
+                params.occurrences(uri, out);
+                typ.occurrences(uri, out);
+                cases.occurrences(uri, out);
+            }
+            Decl::Let(Let { name, params, typ, body, .. }) => {
+                Occurrence::declaration(uri, OccurrenceKind::Let, name, out);
+                params.occurrences(uri, out);
+                typ.occurrences(uri, out);
+                body.occurrences(uri, out);
+            }
+        }
+    }
+}
+
+impl Occurrences for Ctor {
+    fn occurrences(&self, uri: &Url, out: &mut Vec<Occurrence>) {
+        let Ctor { name, params, typ, .. } = self;
+        Occurrence::declaration(uri, OccurrenceKind::Ctor, name, out);
+        params.occurrences(uri, out);
+        typ.occurrences(uri, out);
+    }
+}
+
+impl Occurrences for Dtor {
+    fn occurrences(&self, uri: &Url, out: &mut Vec<Occurrence>) {
+        let Dtor { name, params, self_param, ret_typ, default, .. } = self;
+        Occurrence::declaration(uri, OccurrenceKind::Dtor, name, out);
+        params.occurrences(uri, out);
+        self_param.typ.occurrences(uri, out);
+        ret_typ.occurrences(uri, out);
+        default.occurrences(uri, out);
+    }
+}
+
+impl Occurrences for Telescope {
+    fn occurrences(&self, uri: &Url, out: &mut Vec<Occurrence>) {
+        for param in self.params.iter() {
+            param.typ.occurrences(uri, out);
+            if let Some(default) = &param.default {
+                default.occurrences(uri, out);
+            }
+        }
+    }
+}
+
+impl Occurrences for Case {
+    fn occurrences(&self, uri: &Url, out: &mut Vec<Occurrence>) {
+        let Case { pattern: Pattern { is_copattern, name, .. }, body, .. } = self;
+        let kind = if *is_copattern { OccurrenceKind::Dtor } else { OccurrenceKind::Ctor };
+        Occurrence::reference(kind, name, out);
+        body.occurrences(uri, out);
+    }
+}
+
+impl Occurrences for Args {
+    fn occurrences(&self, uri: &Url, out: &mut Vec<Occurrence>) {
+        for arg in self.args.iter() {
+            match arg {
+                Arg::UnnamedArg(exp) | Arg::NamedArg(_, exp) => exp.occurrences(uri, out),
+                Arg::InsertedImplicitArg(_) => {}
+            }
+        }
+    }
+}
+
+impl Occurrences for TypCtor {
+    fn occurrences(&self, uri: &Url, out: &mut Vec<Occurrence>) {
+        let TypCtor { name, args, .. } = self;
+        Occurrence::reference(OccurrenceKind::TypCtor, name, out);
+        args.occurrences(uri, out);
+    }
+}
+
+impl Occurrences for Exp {
+    fn occurrences(&self, uri: &Url, out: &mut Vec<Occurrence>) {
+        match self {
+            Exp::Variable(_) | Exp::TypeUniv(_) | Exp::Hole(_) => {}
+            Exp::TypCtor(typ) => typ.occurrences(uri, out),
+            Exp::Call(Call { kind, name, args, .. }) => {
+                let kind = match kind {
+                    CallKind::Constructor => OccurrenceKind::Ctor,
+                    CallKind::Codefinition => OccurrenceKind::Codef,
+                    CallKind::LetBound => OccurrenceKind::Let,
+                };
+                Occurrence::reference(kind, name, out);
+                args.occurrences(uri, out);
+            }
+            Exp::DotCall(DotCall { kind, exp, name, args, .. }) => {
+                let kind = match kind {
+                    DotCallKind::Destructor => OccurrenceKind::Dtor,
+                    DotCallKind::Definition => OccurrenceKind::Def,
+                };
+                exp.occurrences(uri, out);
+                Occurrence::reference(kind, name, out);
+                args.occurrences(uri, out);
+            }
+            Exp::Anno(Anno { exp, typ, .. }) => {
+                exp.occurrences(uri, out);
+                typ.occurrences(uri, out);
+            }
+            Exp::LocalMatch(LocalMatch { on_exp, motive, cases, .. }) => {
+                on_exp.occurrences(uri, out);
+                if let Some(Motive { ret_typ, .. }) = motive {
+                    ret_typ.occurrences(uri, out);
+                }
+                cases.occurrences(uri, out);
+            }
+            Exp::LocalComatch(LocalComatch { cases, .. }) => cases.occurrences(uri, out),
+            Exp::LocalLet(LocalLet { typ, bound, body, .. }) => {
+                typ.occurrences(uri, out);
+                bound.occurrences(uri, out);
+                body.occurrences(uri, out);
+            }
+        }
+    }
+}
@@ -0,0 +1,355 @@
+use std::collections::BTreeMap;
+
+use codespan::{ByteIndex, Span};
+use miette_util::ToMiette;
+use url::Url;
+
+use ast::*;
+
+use crate::database::Database;
+use crate::references::{Occurrence, Occurrences};
+use crate::result::DriverError;
+use crate::{Edit, Error};
+
+impl Database {
+    /// Compute the edits which rename the symbol at the given index in the module at `uri`
+    ///
+    /// Top-level symbols are renamed in the module which declares them as well as in all
+    /// loaded modules which import it. Local variables are renamed within their scope only.
+    /// Returns `None` if there is no symbol at the given index.
+    pub async fn rename(
+        &mut self,
+        uri: &Url,
+        idx: ByteIndex,
+        new_name: &str,
+    ) -> Result<Option<BTreeMap<Url, Vec<Edit>>>, Error> {
+        if !parser::is_identifier(new_name) {
+            return Err(DriverError::InvalidIdentifier { name: new_name.to_owned() }.into());
+        }
+        let module = self.ust(uri).await?;
+        let source = self.source(uri).await?;
+
+        // Local variables
+        for decl in module.decls.iter() {
+            let mut vars = vec![];
+            decl.local_vars(&mut vec![], &mut vars);
+            let Some(var) = vars.iter().find(|var| var.spans().any(|span| contains(span, idx)))
+            else {
+                continue;
+            };
+            // Renaming the variable must not capture other names in its declaration
+            let mut occurrences = vec![];
+            decl.occurrences(uri, &mut occurrences);
+            if vars.iter().any(|other| other.name == new_name)
+                || occurrences.iter().any(|occ| occ.name.local_name() == new_name)
+            {
+                let name = var.name.clone();
+                return Err(
+                    DriverError::RenameCollision { name, new_name: new_name.to_owned() }.into()
+                );
+            }
+            let edits = rename_spans(&source, &var.name, var.spans(), new_name)?;
+            return Ok(Some(BTreeMap::from_iter([(uri.clone(), edits)])));
+        }
+
+        // Top-level symbols
+        let mut occurrences = vec![];
+        module.decls.occurrences(uri, &mut occurrences);
+        // Syntactic sugar such as `a -> b` spans its arguments, so we pick the innermost name
+        let Some(target) = occurrences
+            .into_iter()
+            .filter_map(|occ| Some((occ.span?, occ.name)))
+            .filter(|(span, _)| contains(*span, idx))
+            .min_by_key(|(span, _)| span.end().to_usize() - span.start().to_usize())
+            .map(|(_, name)| name)
+        else {
+            return Ok(None);
+        };
+
+        let mut uris: Vec<Url> =
+            self.deps.reverse_dependencies(&target.uri).into_iter().cloned().collect();
+        // Modules which are not part of the dependency graph have no reverse dependencies
+        if !uris.contains(&target.uri) {
+            uris.insert(0, target.uri.clone());
+        }
+        let mut out = BTreeMap::new();
+        for uri in uris {
+            let module = self.ust(&uri).await?;
+            let source = self.source(&uri).await?;
+            if declares(&module, new_name) {
+                let name = target.local_name().to_owned();
+                return Err(
+                    DriverError::RenameCollision { name, new_name: new_name.to_owned() }.into()
+                );
+            }
+            let mut occurrences: Vec<Occurrence> = vec![];
+            module.decls.occurrences(&uri, &mut occurrences);
+            let spans =
+                occurrences.into_iter().filter(|occ| occ.name == target).filter_map(|occ| occ.span);
+            let edits = rename_spans(&source, target.local_name(), spans, new_name)?;
+            if !edits.is_empty() {
+                out.insert(uri, edits);
+            }
+        }
+        Ok(Some(out))
+    }
+}
+
+fn contains(span: Span, idx: ByteIndex) -> bool {
+    span.start() <= idx && idx <= span.end()
+}
+
+/// Whether `name` is declared at the top level of `module`, including constructors and
+/// destructors
+fn declares(module: &Module, name: &str) -> bool {
+    module.symbols().any(|symbol| {
+        symbol.name.local_name() == name || symbol.xtors.iter().any(|xtor| xtor.id == name)
+    })
+}
+
+/// The edits which replace the occurrences of `name` at `spans` by `new_name`
+///
+/// Fails if one of the spans does not consist of `name` only, since it then stems from
+/// syntactic sugar which cannot be renamed.
+fn rename_spans(
+    source: &str,
+    name: &str,
+    spans: impl Iterator<Item = Span>,
+    new_name: &str,
+) -> Result<Vec<Edit>, Error> {
+    let mut spans: Vec<Span> = spans.collect();
+    spans.sort_by_key(|span| (span.start(), span.end()));
+    // Literals desugar to nested calls which all have the same span
+    spans.dedup();
+    spans
+        .into_iter()
+        .map(|span| {
+            if source.get(span.start().to_usize()..span.end().to_usize()) != Some(name) {
+                return Err(DriverError::RenameSugar {
+                    name: name.to_owned(),
+                    span: Some(span.to_miette()),
+                }
+                .into());
+            }
+            Ok(Edit { span, text: new_name.to_owned() })
+        })
+        .collect()
+}
+
+/// A local variable together with the references to it
+struct LocalVar {
+    name: String,
+    binder: Span,
+    references: Vec<Span>,
+}
+
+impl LocalVar {
+    fn spans(&self) -> impl Iterator<Item = Span> + '_ {
+        std::iter::once(self.binder).chain(self.references.iter().cloned())
+    }
+}
+
+/// The binders in scope, one vector per telescope, with the innermost telescope last
+///
+/// Each binder refers to its entry in the list of local variables, or is `None` for binders
+/// without a source location.
+type Scope = Vec<Vec<Option<usize>>>;
+
+/// Bind `name` in the innermost telescope of `scope`
+fn bind(name: Option<&VarBind>, scope: &mut Scope, out: &mut Vec<LocalVar>) {
+    let var = name.and_then(|name| {
+        let binder = name.span?;
+        out.push(LocalVar { name: name.id.clone(), binder, references: vec![] });
+        Some(out.len() - 1)
+    });
+    scope.last_mut().expect("Binder outside of a telescope").push(var);
+}
+
+/// Run `f` with a new telescope added to `scope`
+fn with_telescope(scope: &mut Scope, f: impl FnOnce(&mut Scope)) {
+    scope.push(vec![]);
+    f(scope);
+    scope.pop();
+}
+
+/// Collect the local variables and the references to them
+///
+/// This follows the binding structure of the lowering, such that the de Bruijn indices
+/// of variables can be resolved to their binders.
+trait LocalVars {
+    fn local_vars(&self, scope: &mut Scope, out: &mut Vec<LocalVar>);
+}
+
+impl<T: LocalVars> LocalVars for Box<T> {
+    fn local_vars(&self, scope: &mut Scope, out: &mut Vec<LocalVar>) {
+        (**self).local_vars(scope, out)
+    }
+}
+
+impl<T: LocalVars> LocalVars for Option<T> {
+    fn local_vars(&self, scope: &mut Scope, out: &mut Vec<LocalVar>) {
+        if let Some(x) = self {
+            x.local_vars(scope, out)
+        }
+    }
+}
+
+impl<T: LocalVars> LocalVars for Vec<T> {
+    fn local_vars(&self, scope: &mut Scope, out: &mut Vec<LocalVar>) {
+        for x in self {
+            x.local_vars(scope, out)
+        }
+    }
+}
+
+/// Bind the parameters of `telescope` in the innermost telescope of `scope`
+fn bind_telescope(telescope: &Telescope, scope: &mut Scope, out: &mut Vec<LocalVar>) {
+    for param in telescope.params.iter() {
+        param.typ.local_vars(scope, out);
+        param.default.local_vars(scope, out);
+        bind(Some(&param.name), scope, out);
+    }
+}
+
+impl LocalVars for Decl {
+    fn local_vars(&self, scope: &mut Scope, out: &mut Vec<LocalVar>) {
+        match self {
+            Decl::Data(Data { typ, ctors, .. }) => {
+                with_telescope(scope, |scope| bind_telescope(typ, scope, out));
+                ctors.local_vars(scope, out);
+            }
+            Decl::Codata(Codata { typ, dtors, .. }) => {
+                with_telescope(scope, |scope| bind_telescope(typ, scope, out));
+                dtors.local_vars(scope, out);
+            }
+            Decl::Def(Def { params, self_param, ret_typ, cases, .. }) => {
+                with_telescope(scope, |scope| {
+                    bind_telescope(params, scope, out);
+                    cases.local_vars(scope, out);
+                    self_param.typ.local_vars(scope, out);
+                    with_telescope(scope, |scope| {
+                        bind(self_param.name.as_ref(), scope, out);
+                        ret_typ.local_vars(scope, out);
+                    });
+                });
+            }
+            Decl::Codef(Codef { params, typ, cases, .. }) => {
+                with_telescope(scope, |scope| {
+                    bind_telescope(params, scope, out);
+                    typ.local_vars(scope, out);
+                    cases.local_vars(scope, out);
+                });
+            }
+            Decl::Let(Let { params, typ, body, .. }) => {
+                with_telescope(scope, |scope| {
+                    bind_telescope(params, scope, out);
+                    typ.local_vars(scope, out);
+                    body.local_vars(scope, out);
+                });
+            }
+        }
+    }
+}
+
+impl LocalVars for Ctor {
+    fn local_vars(&self, scope: &mut Scope, out: &mut Vec<LocalVar>) {
+        let Ctor { params, typ, .. } = self;
+        with_telescope(scope, |scope| {
+            bind_telescope(params, scope, out);
+            typ.local_vars(scope, out);
+        });
+    }
+}
+
+impl LocalVars for Dtor {
+    fn local_vars(&self, scope: &mut Scope, out: &mut Vec<LocalVar>) {
+        let Dtor { params, self_param, ret_typ, default, .. } = self;
+        with_telescope(scope, |scope| {
+            bind_telescope(params, scope, out);
+            // The self parameter is not in scope in the default implementation
+            default.local_vars(scope, out);
+            self_param.typ.local_vars(scope, out);
+            with_telescope(scope, |scope| {
+                bind(self_param.name.as_ref(), scope, out);
+                ret_typ.local_vars(scope, out);
+            });
+        });
+    }
+}
+
+impl LocalVars for Case {
+    fn local_vars(&self, scope: &mut Scope, out: &mut Vec<LocalVar>) {
+        let Case { pattern, body, .. } = self;
+        with_telescope(scope, |scope| {
+            for param in pattern.params.params.iter() {
+                bind(Some(&param.name), scope, out);
+            }
+            body.local_vars(scope, out);
+        });
+    }
+}
+
+impl LocalVars for Args {
+    fn local_vars(&self, scope: &mut Scope, out: &mut Vec<LocalVar>) {
+        for arg in self.args.iter() {
+            match arg {
+                Arg::UnnamedArg(exp) | Arg::NamedArg(_, exp) => exp.local_vars(scope, out),
+                Arg::InsertedImplicitArg(_) => {}
+            }
+        }
+    }
+}
+
+impl LocalVars for TypCtor {
+    fn local_vars(&self, scope: &mut Scope, out: &mut Vec<LocalVar>) {
+        self.args.local_vars(scope, out)
+    }
+}
+
+impl LocalVars for Exp {
+    fn local_vars(&self, scope: &mut Scope, out: &mut Vec<LocalVar>) {
+        match self {
+            Exp::Variable(Variable { span, idx, .. }) => {
+                let var = scope
+                    .len()
+                    .checked_sub(idx.fst + 1)
+                    .and_then(|fst| scope[fst].len().checked_sub(idx.snd + 1).map(|snd| (fst, snd)))
+                    .and_then(|(fst, snd)| scope[fst][snd]);
+                if let (Some(var), Some(span)) = (var, *span) {
+                    out[var].references.push(span);
+                }
+            }
+            Exp::TypeUniv(_) | Exp::Hole(_) => {}
+            Exp::TypCtor(TypCtor { args, .. }) | Exp::Call(Call { args, .. }) => {
+                args.local_vars(scope, out)
+            }
+            Exp::DotCall(DotCall { exp, args, .. }) => {
+                exp.local_vars(scope, out);
+                args.local_vars(scope, out);
+            }
+            Exp::Anno(Anno { exp, typ, .. }) => {
+                exp.local_vars(scope, out);
+                typ.local_vars(scope, out);
+            }
+            Exp::LocalMatch(LocalMatch { on_exp, motive, cases, .. }) => {
+                on_exp.local_vars(scope, out);
+                if let Some(Motive { param, ret_typ, .. }) = motive {
+                    with_telescope(scope, |scope| {
+                        bind(Some(&param.name), scope, out);
+                        ret_typ.local_vars(scope, out);
+                    });
+                }
+                cases.local_vars(scope, out);
+            }
+            Exp::LocalComatch(LocalComatch { cases, .. }) => cases.local_vars(scope, out),
+            Exp::LocalLet(LocalLet { name, typ, bound, body, .. }) => {
+                typ.local_vars(scope, out);
+                bound.local_vars(scope, out);
+                with_telescope(scope, |scope| {
+                    bind(Some(name), scope, out);
+                    body.local_vars(scope, out);
+                });
+            }
+        }
+    }
+}
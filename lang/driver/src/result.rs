@@ -1,24 +1,70 @@
+use std::fmt::Display;
 use std::sync::Arc;
 
-use miette::Diagnostic;
+use miette::{Diagnostic, LabeledSpan, Severity, SourceSpan};
 use thiserror::Error;
 use url::Url;
 
+use crate::dependency_graph::url_to_label;
+
 #[derive(Error, Diagnostic, Debug, Clone)]
 #[diagnostic(transparent)]
 #[error(transparent)]
 pub enum Error {
-    Parser(#[from] parser::ParseError),
+    Parser(#[from] ParseErrors),
     Lowering(#[from] lowering::LoweringError),
     Type(#[from] Box<elaborator::result::TypeError>),
     Xfunc(#[from] transformations::result::XfuncError),
     Driver(#[from] DriverError),
 }
 
+/// The syntax errors of a module
+///
+/// The first syntax error is the main diagnostic, the remaining ones are related diagnostics.
+#[derive(Error, Debug, Clone)]
+#[error("{first}")]
+pub struct ParseErrors {
+    pub first: parser::ParseError,
+    pub rest: Vec<parser::ParseError>,
+}
+
+impl ParseErrors {
+    pub fn new(mut errors: Vec<parser::ParseError>) -> Option<Self> {
+        if errors.is_empty() {
+            return None;
+        }
+        let first = errors.remove(0);
+        Some(ParseErrors { first, rest: errors })
+    }
+}
+
+impl Diagnostic for ParseErrors {
+    fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.first.code()
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        self.first.labels()
+    }
+
+    fn related<'a>(&'a self) -> Option<Box<dyn Iterator<Item = &'a dyn Diagnostic> + 'a>> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        Some(Box::new(self.rest.iter().map(|err| err as &dyn Diagnostic)))
+    }
+}
+
 #[derive(Error, Debug, Diagnostic, Clone)]
 pub enum DriverError {
-    #[error("Import cycle detected for module {0:?}: {1:?}")]
-    ImportCycle(Url, Vec<Url>),
+    #[error("Import cycle detected: {}", cycle_to_string(path))]
+    ImportCycle {
+        /// The modules of the cycle, starting and ending with the same module
+        path: Vec<Url>,
+        /// The `use` declarations which import the next module of the cycle
+        #[related]
+        imports: Vec<CyclicImport>,
+    },
     #[error("Invalid URI: {0}")]
     InvalidUri(Url),
     #[error("File not found: {0}")]
@@ -27,6 +73,117 @@ pub enum DriverError {
     Io(#[from] Arc<std::io::Error>),
     #[error("URL error: {0}")]
     Url(#[from] url::ParseError),
+    #[cfg(feature = "polc")]
+    #[error("Failed to write module cache: {0}")]
+    Polc(String),
+    #[cfg(feature = "json")]
+    #[error("JSON error: {0}")]
+    Json(String),
+    #[error("{name} is not a valid identifier")]
+    InvalidIdentifier { name: String },
+    #[error("Cannot rename {name} to {new_name}, since {new_name} is already in use")]
+    RenameCollision { name: String, new_name: String },
+    #[error("{name} cannot be renamed, since it is used by syntactic sugar")]
+    RenameSugar {
+        name: String,
+        #[label]
+        span: Option<SourceSpan>,
+    },
+    /// A secondary diagnostic for a declaration which refers to a declaration with errors
+    #[error("{name} depends on {dependency}, which failed to check")]
+    #[diagnostic(
+        severity(Advice),
+        help("This may be resolved by fixing the errors in {dependency}")
+    )]
+    DependsOnFailedDecl {
+        name: String,
+        dependency: String,
+        #[label]
+        span: Option<SourceSpan>,
+    },
     #[error("Impossible: {0}")]
     Impossible(String),
 }
+
+/// An error or a warning reported for a module
+#[derive(Error, Diagnostic, Debug, Clone)]
+#[diagnostic(transparent)]
+#[error(transparent)]
+pub enum ModuleDiagnostic {
+    Error(#[from] Error),
+    Warning(#[from] Warning),
+}
+
+impl ModuleDiagnostic {
+    /// Whether this is an error, as opposed to a warning or a secondary diagnostic
+    pub fn is_error(&self) -> bool {
+        matches!(self.severity(), None | Some(Severity::Error))
+    }
+}
+
+/// A problem in a module which does not prevent it from being checked
+#[derive(Error, Debug, Diagnostic, Clone)]
+pub enum Warning {
+    #[error("{name} is never used")]
+    #[diagnostic(
+        code("W-001"),
+        severity(Warning),
+        help("Mark it as #[entry] if it is used from outside the program")
+    )]
+    UnusedDecl {
+        name: String,
+        #[label]
+        span: Option<SourceSpan>,
+    },
+    #[error("{name} shadows a variable of the same name")]
+    #[diagnostic(
+        code("W-002"),
+        severity(Warning),
+        help("Rename the variable to _{name} if the shadowing is intended")
+    )]
+    ShadowedVariable {
+        name: String,
+        #[label]
+        span: Option<SourceSpan>,
+        #[related]
+        shadowed: Vec<ShadowedBinding>,
+    },
+}
+
+impl Warning {
+    /// The codes of all variants, together with the templates of their messages
+    ///
+    /// This list must be extended whenever a variant is added, see `driver::error_index`.
+    pub const CODES: &'static [(&'static str, &'static str)] = &[
+        ("W-001", "{name} is never used"),
+        ("W-002", "{name} shadows a variable of the same name"),
+    ];
+}
+
+/// The binding of a variable which is shadowed by a pattern variable
+#[derive(Error, Debug, Diagnostic, Clone)]
+#[error("{name} is bound here")]
+#[diagnostic(severity(Advice))]
+pub struct ShadowedBinding {
+    pub name: String,
+    #[label]
+    pub span: Option<SourceSpan>,
+}
+
+/// A `use` declaration which is part of an import cycle
+#[derive(Error, Debug, Diagnostic, Clone)]
+#[error("{} imports {}", url_to_label(uri), url_to_label(imported))]
+pub struct CyclicImport {
+    /// The module containing the `use` declaration
+    pub uri: Url,
+    /// The module imported by the `use` declaration
+    pub imported: Url,
+    #[source_code]
+    pub source: String,
+    #[label]
+    pub span: SourceSpan,
+}
+
+fn cycle_to_string(path: &[Url]) -> String {
+    path.iter().map(url_to_label).collect::<Vec<_>>().join(" -> ")
+}
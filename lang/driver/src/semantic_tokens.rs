@@ -0,0 +1,252 @@
+use codespan::{ByteIndex, Span};
+use url::Url;
+
+use ast::*;
+
+use crate::database::Database;
+
+/// A highlighted range of the source code
+pub struct SemanticToken {
+    pub span: Span,
+    pub kind: SemanticTokenKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SemanticTokenKind {
+    Keyword,
+    TypeCtor,
+    Ctor,
+    Dtor,
+    Variable,
+    Hole,
+}
+
+impl Database {
+    /// Compute the semantic tokens of the module at `uri`
+    ///
+    /// Names are classified by their resolved role in the typechecked module.
+    /// If the module does not typecheck, only the keywords are highlighted.
+    /// The tokens are ordered by their location and do not overlap.
+    pub async fn semantic_tokens(&mut self, uri: &Url) -> Vec<SemanticToken> {
+        let Ok(source) = self.source(uri).await else {
+            return vec![];
+        };
+        let mut tokens: Vec<SemanticToken> = parser::keyword_spans(&source)
+            .into_iter()
+            .map(|range| SemanticToken {
+                span: Span::new(ByteIndex(range.start as u32), ByteIndex(range.end as u32)),
+                kind: SemanticTokenKind::Keyword,
+            })
+            .collect();
+        if let Ok(module) = self.ast(uri).await {
+            module.decls.tokens(&mut tokens);
+        }
+
+        // Outer tokens come first, such that they are dropped if they contain other tokens
+        tokens.sort_by_key(|token| (token.span.start(), std::cmp::Reverse(token.span.end())));
+        // Literals desugar to nested calls which all have the same span
+        tokens.dedup_by_key(|token| token.span);
+        // Syntactic sugar such as `a -> b` spans its arguments, so we only keep innermost tokens
+        let mut innermost = Vec::with_capacity(tokens.len());
+        let mut tokens = tokens.into_iter().peekable();
+        while let Some(token) = tokens.next() {
+            match tokens.peek() {
+                Some(next) if next.span.start() < token.span.end() => {}
+                _ => innermost.push(token),
+            }
+        }
+        innermost
+    }
+}
+
+fn push(span: Option<Span>, kind: SemanticTokenKind, out: &mut Vec<SemanticToken>) {
+    if let Some(span) = span {
+        out.push(SemanticToken { span, kind });
+    }
+}
+
+/// Collect the semantic tokens of names in the typechecked module
+///
+/// Inferred types are not traversed, since their spans may point to other modules.
+trait Tokens {
+    fn tokens(&self, out: &mut Vec<SemanticToken>);
+}
+
+impl<T: Tokens> Tokens for Box<T> {
+    fn tokens(&self, out: &mut Vec<SemanticToken>) {
+        (**self).tokens(out)
+    }
+}
+
+impl<T: Tokens> Tokens for Option<T> {
+    fn tokens(&self, out: &mut Vec<SemanticToken>) {
+        if let Some(x) = self {
+            x.tokens(out)
+        }
+    }
+}
+
+impl<T: Tokens> Tokens for Vec<T> {
+    fn tokens(&self, out: &mut Vec<SemanticToken>) {
+        for x in self {
+            x.tokens(out)
+        }
+    }
+}
+
+impl Tokens for Decl {
+    fn tokens(&self, out: &mut Vec<SemanticToken>) {
+        match self {
+            Decl::Data(Data { name, typ, ctors, .. }) => {
+                push(name.span, SemanticTokenKind::TypeCtor, out);
+                typ.tokens(out);
+                ctors.tokens(out);
+            }
+            Decl::Codata(Codata { name, typ, dtors, .. }) => {
+                push(name.span, SemanticTokenKind::TypeCtor, out);
+                typ.tokens(out);
+                dtors.tokens(out);
+            }
+            Decl::Def(Def { name, params, self_param, ret_typ, cases, .. }) => {
+                push(name.span, SemanticTokenKind::Dtor, out);
+                params.tokens(out);
+                self_param.tokens(out);
+                ret_typ.tokens(out);
+                cases.tokens(out);
+            }
+            Decl::Codef(Codef { name, params, typ, cases, .. }) => {
+                push(name.span, SemanticTokenKind::Ctor, out);
+                params.tokens(out);
+                typ.tokens(out);
+                cases.tokens(out);
+            }
+            Decl::Let(Let { params, typ, body, .. }) => {
+                params.tokens(out);
+                typ.tokens(out);
+                body.tokens(out);
+            }
+        }
+    }
+}
+
+impl Tokens for Ctor {
+    fn tokens(&self, out: &mut Vec<SemanticToken>) {
+        let Ctor { name, params, typ, .. } = self;
+        push(name.span, SemanticTokenKind::Ctor, out);
+        params.tokens(out);
+        typ.tokens(out);
+    }
+}
+
+impl Tokens for Dtor {
+    fn tokens(&self, out: &mut Vec<SemanticToken>) {
+        let Dtor { name, params, self_param, ret_typ, default, .. } = self;
+        push(name.span, SemanticTokenKind::Dtor, out);
+        params.tokens(out);
+        self_param.tokens(out);
+        ret_typ.tokens(out);
+        default.tokens(out);
+    }
+}
+
+impl Tokens for SelfParam {
+    fn tokens(&self, out: &mut Vec<SemanticToken>) {
+        let SelfParam { name, typ, .. } = self;
+        push(name.as_ref().and_then(|name| name.span), SemanticTokenKind::Variable, out);
+        typ.tokens(out);
+    }
+}
+
+impl Tokens for Telescope {
+    fn tokens(&self, out: &mut Vec<SemanticToken>) {
+        for Param { name, typ, default, .. } in self.params.iter() {
+            push(name.span, SemanticTokenKind::Variable, out);
+            typ.tokens(out);
+            if let Some(default) = default {
+                default.tokens(out);
+            }
+        }
+    }
+}
+
+impl Tokens for TelescopeInst {
+    fn tokens(&self, out: &mut Vec<SemanticToken>) {
+        for ParamInst { name, .. } in self.params.iter() {
+            push(name.span, SemanticTokenKind::Variable, out);
+        }
+    }
+}
+
+impl Tokens for Case {
+    fn tokens(&self, out: &mut Vec<SemanticToken>) {
+        let Case { pattern: Pattern { is_copattern, name, params }, body, .. } = self;
+        let kind = if *is_copattern { SemanticTokenKind::Dtor } else { SemanticTokenKind::Ctor };
+        push(name.span, kind, out);
+        params.tokens(out);
+        body.tokens(out);
+    }
+}
+
+impl Tokens for Args {
+    fn tokens(&self, out: &mut Vec<SemanticToken>) {
+        for arg in self.args.iter() {
+            match arg {
+                Arg::UnnamedArg(exp) | Arg::NamedArg(_, exp) => exp.tokens(out),
+                Arg::InsertedImplicitArg(_) => {}
+            }
+        }
+    }
+}
+
+impl Tokens for TypCtor {
+    fn tokens(&self, out: &mut Vec<SemanticToken>) {
+        let TypCtor { name, args, .. } = self;
+        push(name.span, SemanticTokenKind::TypeCtor, out);
+        args.tokens(out);
+    }
+}
+
+impl Tokens for Exp {
+    fn tokens(&self, out: &mut Vec<SemanticToken>) {
+        match self {
+            Exp::Variable(Variable { span, .. }) => push(*span, SemanticTokenKind::Variable, out),
+            Exp::Hole(Hole { span, .. }) => push(*span, SemanticTokenKind::Hole, out),
+            // The universe is highlighted as the keyword `Type`
+            Exp::TypeUniv(_) => {}
+            Exp::TypCtor(typ) => typ.tokens(out),
+            Exp::Call(Call { kind, name, args, .. }) => {
+                match kind {
+                    CallKind::Constructor | CallKind::Codefinition => {
+                        push(name.span, SemanticTokenKind::Ctor, out)
+                    }
+                    CallKind::LetBound => {}
+                }
+                args.tokens(out);
+            }
+            Exp::DotCall(DotCall { exp, name, args, .. }) => {
+                exp.tokens(out);
+                push(name.span, SemanticTokenKind::Dtor, out);
+                args.tokens(out);
+            }
+            Exp::Anno(Anno { exp, typ, .. }) => {
+                exp.tokens(out);
+                typ.tokens(out);
+            }
+            Exp::LocalMatch(LocalMatch { on_exp, motive, cases, .. }) => {
+                on_exp.tokens(out);
+                if let Some(Motive { param, ret_typ, .. }) = motive {
+                    push(param.name.span, SemanticTokenKind::Variable, out);
+                    ret_typ.tokens(out);
+                }
+                cases.tokens(out);
+            }
+            Exp::LocalComatch(LocalComatch { cases, .. }) => cases.tokens(out),
+            Exp::LocalLet(LocalLet { name, typ, bound, body, .. }) => {
+                push(name.span, SemanticTokenKind::Variable, out);
+                typ.tokens(out);
+                bound.tokens(out);
+                body.tokens(out);
+            }
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use codespan::ByteIndex;
+use url::Url;
+
+use ast::*;
+use printer::tokens::TYPE;
+use printer::Print;
+
+use crate::database::Database;
+
+/// The signature of the callee whose argument list contains the cursor
+pub struct Signature {
+    /// The signature of the callee, e.g. `Cons(x: Nat, xs: List): List`
+    pub label: String,
+    /// The parameters of the callee, exactly as they occur in `label`
+    pub params: Vec<String>,
+    /// Doc comments of the callee
+    pub doc: Option<Vec<String>>,
+    /// The parameter for the argument at the cursor, if any
+    pub active_param: Option<usize>,
+}
+
+impl Database {
+    /// Compute the signature of the call whose argument list contains the given index
+    ///
+    /// The callee is the name in front of the innermost unclosed `(` before the index.
+    /// If the name follows a `.`, it is resolved as a destructor or definition on the type of
+    /// the expression in front of the `.`. Otherwise, it is resolved among the top-level names
+    /// in scope.
+    ///
+    /// Since the call is usually still being typed, the argument list is read from the source
+    /// text rather than from the lowered module.
+    pub async fn signature_help(&mut self, uri: &Url, idx: ByteIndex) -> Option<Signature> {
+        let source = self.source(uri).await.ok()?;
+        let call = OpenCall::before(source.get(..idx.to_usize())?)?;
+        let (name, params, ret_typ, doc) = match call.dot {
+            Some(dot) => self.dot_callee(uri, ByteIndex(dot as u32), &call.name).await?,
+            None => self.callee(uri, &call.name).await?,
+        };
+
+        let printed: Vec<String> =
+            params.params.iter().map(|param| param.print_to_string(None)).collect();
+        Some(Signature {
+            label: format!("{}({}): {}", name.id, printed.join(", "), ret_typ),
+            params: printed,
+            doc: doc.map(|doc| doc.docs),
+            active_param: call.active_param(&params),
+        })
+    }
+
+    /// Resolve the top-level name `name` which is in scope in the module at `uri`
+    async fn callee(&mut self, uri: &Url, name: &str) -> Option<Callee> {
+        for ust in self.usts_in_scope(uri).await? {
+            for decl in ust.decls.iter() {
+                let callee = match decl {
+                    Decl::Data(Data { name: n, doc, typ, .. })
+                    | Decl::Codata(Codata { name: n, doc, typ, .. })
+                        if n.id == name =>
+                    {
+                        (n.clone(), (**typ).clone(), TYPE.to_owned(), doc.clone())
+                    }
+                    Decl::Codef(Codef { name: n, doc, params, typ, .. }) if n.id == name => {
+                        (n.clone(), params.clone(), typ.print_to_string(None), doc.clone())
+                    }
+                    Decl::Let(Let { name: n, doc, params, typ, .. }) if n.id == name => {
+                        (n.clone(), params.clone(), typ.print_to_string(None), doc.clone())
+                    }
+                    Decl::Data(Data { ctors, .. }) => {
+                        let Some(ctor) = ctors.iter().find(|ctor| ctor.name.id == name) else {
+                            continue;
+                        };
+                        let Ctor { name, doc, params, typ, .. } = ctor;
+                        (name.clone(), params.clone(), typ.print_to_string(None), doc.clone())
+                    }
+                    _ => continue,
+                };
+                return Some(callee);
+            }
+        }
+        None
+    }
+
+    /// Resolve the destructor or definition `name` on the type of the expression ending at `dot`
+    async fn dot_callee(&mut self, uri: &Url, dot: ByteIndex, name: &str) -> Option<Callee> {
+        let typ = self.typ_ending_at(uri, dot).await?;
+        let Exp::TypCtor(TypCtor { name: typ_name, .. }) = *typ else {
+            return None;
+        };
+
+        if let Some(Ok(ust)) = self.ust.get_even_if_stale(&typ_name.uri) {
+            let dtor = ust.decls.iter().find_map(|decl| match decl {
+                Decl::Codata(codata) if codata.name == typ_name => {
+                    codata.dtors.iter().find(|dtor| dtor.name.id == name)
+                }
+                _ => None,
+            });
+            if let Some(Dtor { name, doc, params, ret_typ, .. }) = dtor {
+                return Some((
+                    name.clone(),
+                    params.clone(),
+                    ret_typ.print_to_string(None),
+                    doc.clone(),
+                ));
+            }
+        }
+        // Definitions on the type may be located in any module in scope
+        for ust in self.usts_in_scope(uri).await? {
+            let def = ust.decls.iter().find_map(|decl| match decl {
+                Decl::Def(def) if def.self_param.typ.name == typ_name && def.name.id == name => {
+                    Some(def)
+                }
+                _ => None,
+            });
+            if let Some(Def { name, doc, params, ret_typ, .. }) = def {
+                return Some((
+                    name.clone(),
+                    params.clone(),
+                    ret_typ.print_to_string(None),
+                    doc.clone(),
+                ));
+            }
+        }
+        None
+    }
+}
+
+/// The name, parameters, printed return type and doc comments of a callee
+type Callee = (IdBind, Telescope, String, Option<DocComment>);
+
+/// A call whose argument list is not closed yet
+struct OpenCall {
+    /// The name of the callee
+    name: String,
+    /// The position of the `.` in front of the name, if the call is a dotcall
+    dot: Option<usize>,
+    /// For each argument up to and including the one at the cursor, the name it is given for
+    args: Vec<Option<String>>,
+}
+
+impl OpenCall {
+    /// Find the innermost call in `text` whose argument list is not closed at its end
+    fn before(text: &str) -> Option<OpenCall> {
+        let mut depth = 0usize;
+        let mut commas = vec![];
+        let mut paren = None;
+        for (i, c) in text.char_indices().rev() {
+            match c {
+                ')' | ']' | '}' => depth += 1,
+                '(' | '[' | '{' if depth > 0 => depth -= 1,
+                '(' => {
+                    paren = Some(i);
+                    break;
+                }
+                // The text at the end is inside of a block rather than an argument list
+                '[' | '{' => return None,
+                ',' if depth == 0 => commas.push(i),
+                _ => {}
+            }
+        }
+        let paren = paren?;
+
+        let head = &text[..paren];
+        let start = head
+            .char_indices()
+            .rev()
+            .take_while(|(_, c)| c.is_alphanumeric() || *c == '_' || *c == '\'')
+            .last()
+            .map(|(i, _)| i)?;
+        let name = &head[start..];
+        if !parser::is_identifier(name) {
+            return None;
+        }
+        let dot = start.checked_sub(1).filter(|dot| head.as_bytes()[*dot] == b'.');
+
+        let mut bounds: Vec<usize> =
+            std::iter::once(paren).chain(commas.into_iter().rev()).collect();
+        bounds.push(text.len());
+        let args = bounds.windows(2).map(|w| arg_name(&text[w[0] + 1..w[1]])).collect();
+        Some(OpenCall { name: name.to_owned(), dot, args })
+    }
+
+    /// The index of the parameter in `params` for the argument at the cursor
+    ///
+    /// This mirrors how the lowering matches arguments with parameters: Implicit parameters
+    /// only consume an argument if it is given by name, while all other parameters consume
+    /// the next argument.
+    fn active_param(&self, params: &Telescope) -> Option<usize> {
+        if let Some(Some(name)) = self.args.last() {
+            return params.params.iter().position(|param| param.name.id == *name);
+        }
+        let mut args = self.args.iter().peekable();
+        for (i, param) in params.params.iter().enumerate() {
+            if param.implicit && !matches!(args.peek(), Some(Some(name)) if *name == param.name.id)
+            {
+                continue;
+            }
+            args.next()?;
+            if args.peek().is_none() {
+                return Some(i);
+            }
+        }
+        None
+    }
+}
+
+/// The name of the argument `arg` if it is given by name, as in `x := e` or `@x := e`
+fn arg_name(arg: &str) -> Option<String> {
+    let (name, _) = arg.split_once(":=")?;
+    let name = name.trim();
+    let name = name.strip_prefix('@').unwrap_or(name);
+    parser::is_identifier(name).then(|| name.to_owned())
+}
@@ -3,7 +3,7 @@ use url::Url;
 
 use crate::database::Database;
 
-use super::info::{Info, Item};
+use super::info::{Info, InfoContent, Item};
 
 impl Database {
     pub fn location_to_index(&self, uri: &Url, location: Location) -> Option<ByteIndex> {
@@ -43,4 +43,31 @@ impl Database {
             intervals.max_by(|i1, i2| (i1.stop - i1.start).cmp(&(i2.stop - i1.start)));
         largest_interval.map(|interval| interval.val.clone())
     }
+
+    /// The spans of the declarations and local (co)matches of the module at `uri`,
+    /// which can be folded in an editor
+    pub async fn folding_ranges(&mut self, uri: &Url) -> Vec<Span> {
+        let Ok(lapper) = self.info_by_id(uri).await else {
+            return vec![];
+        };
+        let mut spans: Vec<Span> = lapper
+            .iter()
+            .filter(|interval| {
+                matches!(
+                    interval.val.content,
+                    InfoContent::DataInfo(_)
+                        | InfoContent::CodataInfo(_)
+                        | InfoContent::DefInfo(_)
+                        | InfoContent::CodefInfo(_)
+                        | InfoContent::LetInfo(_)
+                        | InfoContent::LocalMatchInfo(_)
+                        | InfoContent::LocalComatchInfo(_)
+                )
+            })
+            .map(|interval| Span::new(ByteIndex(interval.start), ByteIndex(interval.stop)))
+            .collect();
+        spans.sort_by_key(|span| (span.start(), span.end()));
+        spans.dedup();
+        spans
+    }
 }
@@ -0,0 +1,76 @@
+use codespan::Span;
+use url::Url;
+
+use ast::DeclKind;
+
+use crate::database::Database;
+
+/// A declaration found by [`Database::workspace_symbols`]
+pub struct WorkspaceSymbol {
+    pub name: String,
+    pub kind: DeclKind,
+    pub uri: Url,
+    pub span: Span,
+}
+
+impl Database {
+    /// The URIs of all modules which have been loaded into the database
+    pub fn loaded_modules(&self) -> Vec<Url> {
+        let mut uris: Vec<Url> = self.files.keys().cloned().collect();
+        uris.sort();
+        uris
+    }
+
+    /// Search the declarations of all loaded modules whose name matches `query`
+    ///
+    /// The characters of `query` have to occur in the name in the same order, but not
+    /// necessarily consecutively. The results are sorted by how well they match.
+    /// Declarations annotated with `#[omit_print]` are only found if `query` is their exact name.
+    /// Modules which cannot be lowered are skipped.
+    pub async fn workspace_symbols(&mut self, query: &str) -> Vec<WorkspaceSymbol> {
+        let mut results = vec![];
+        for uri in self.loaded_modules() {
+            let Ok(module) = self.ust(&uri).await else {
+                continue;
+            };
+            for symbol in module.symbols() {
+                if symbol.hidden && symbol.name.id != query {
+                    continue;
+                }
+                let Some(span) = symbol.span.or(symbol.name.span) else {
+                    continue;
+                };
+                if let Some(score) = subsequence_score(query, &symbol.name.id) {
+                    let name = symbol.name.id.clone();
+                    let uri = uri.clone();
+                    results.push((score, WorkspaceSymbol { name, kind: symbol.kind, uri, span }));
+                }
+            }
+        }
+        results.sort_by(|(lhs_score, lhs), (rhs_score, rhs)| {
+            rhs_score.cmp(lhs_score).then_with(|| lhs.name.cmp(&rhs.name))
+        });
+        results.into_iter().map(|(_, symbol)| symbol).collect()
+    }
+}
+
+/// How well `name` matches `query`, or `None` if the characters of `query` do not occur in
+/// `name` in the same order
+///
+/// Characters are compared case-insensitively. Characters matched at the start of `name` or
+/// directly after the previous match score higher, and shorter names are preferred.
+fn subsequence_score(query: &str, name: &str) -> Option<i64> {
+    let name: Vec<char> = name.chars().collect();
+    let mut score = 0;
+    let mut pos = 0;
+    for c in query.chars() {
+        let idx = (pos..name.len()).find(|idx| name[*idx].eq_ignore_ascii_case(&c))?;
+        score += match idx {
+            0 => 3,
+            _ if idx == pos => 2,
+            _ => 1,
+        };
+        pos = idx + 1;
+    }
+    Some(score * 100 - name.len() as i64)
+}
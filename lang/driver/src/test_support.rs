@@ -0,0 +1,24 @@
+//! Databases for unit tests
+//!
+//! The module under test is located at [uri], the same URI which `ast::test_support` uses.
+
+use url::Url;
+
+use crate::fs::InMemorySource;
+use crate::Database;
+
+pub use ast::test_support::uri;
+
+/// A database which contains the module `source` at [uri]
+pub fn database(source: &str) -> Database {
+    database_with(vec![(uri(), source)])
+}
+
+/// A database which contains each of `files` at its URI
+pub fn database_with(files: Vec<(Url, &str)>) -> Database {
+    let mut source = InMemorySource::new();
+    for (uri, contents) in files {
+        source.insert(uri, contents.to_owned());
+    }
+    Database::from_source(source)
+}
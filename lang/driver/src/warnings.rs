@@ -0,0 +1,223 @@
+//! Warnings about problems which do not prevent a module from being checked
+
+use miette_util::ToMiette;
+use url::Url;
+
+use ast::*;
+
+use crate::database::Database;
+use crate::references::{Occurrence, Occurrences};
+use crate::result::{ShadowedBinding, Warning};
+use crate::Error;
+
+impl Database {
+    /// Compute the warnings for the module at `uri`
+    pub async fn warnings(&mut self, uri: &Url) -> Result<Vec<Warning>, Error> {
+        let mut warnings = self.unused_decls(uri).await?;
+        // Pattern variables are only checked in modules which typecheck
+        if let Ok(module) = self.ast(uri).await {
+            module.decls.shadowing(&mut vec![], &mut warnings);
+        }
+        Ok(warnings)
+    }
+
+    /// Report the (co)definitions of the module at `uri` which are never used
+    ///
+    /// A (co)definition is used if it is reachable from an entry point, that is from
+    /// any other declaration of the module, a declaration annotated with `#[entry]`,
+    /// or a module which imports this module.
+    /// Declarations annotated with `#[omit_print]` are hidden and never reported.
+    async fn unused_decls(&mut self, uri: &Url) -> Result<Vec<Warning>, Error> {
+        let module = self.ust(uri).await?;
+
+        // The names declared in this module which are referenced by each declaration
+        let mut references: HashMap<&str, Vec<String>> = HashMap::default();
+        for decl in module.decls.iter() {
+            let mut occurrences = vec![];
+            decl.occurrences(uri, &mut occurrences);
+            references.insert(&decl.ident().id, referenced_names(uri, occurrences));
+        }
+
+        let mut todo: Vec<String> = module
+            .decls
+            .iter()
+            .filter(|decl| !may_be_unused(decl))
+            .map(|decl| decl.ident().id.clone())
+            .collect();
+        let rev_deps: Vec<Url> = self.deps.reverse_dependencies(uri).into_iter().cloned().collect();
+        for rev_dep in rev_deps {
+            if let Ok(importer) = self.ust(&rev_dep).await {
+                let mut occurrences = vec![];
+                importer.decls.occurrences(&rev_dep, &mut occurrences);
+                todo.extend(referenced_names(uri, occurrences));
+            }
+        }
+
+        let mut reachable: HashSet<String> = HashSet::default();
+        while let Some(name) = todo.pop() {
+            if let Some(names) = references.get(name.as_str()) {
+                if reachable.insert(name.clone()) {
+                    todo.extend(names.iter().cloned());
+                }
+            }
+        }
+
+        Ok(module
+            .decls
+            .iter()
+            .filter(|decl| may_be_unused(decl) && !reachable.contains(&decl.ident().id))
+            .map(|decl| Warning::UnusedDecl {
+                name: decl.ident().id.clone(),
+                span: decl.ident().span.to_miette(),
+            })
+            .collect())
+    }
+}
+
+/// Whether `decl` is reported if it is not reachable from an entry point
+fn may_be_unused(decl: &Decl) -> bool {
+    let attrs = &decl.attributes().attrs;
+    matches!(decl, Decl::Def(_) | Decl::Codef(_))
+        && !attrs.contains(&Attribute::Entry)
+        && !attrs.contains(&Attribute::OmitPrint)
+}
+
+/// The names declared in the module `uri` which are referenced by `occurrences`
+fn referenced_names(uri: &Url, occurrences: Vec<Occurrence>) -> Vec<String> {
+    occurrences
+        .into_iter()
+        .filter(|occ| !occ.is_decl && occ.name.uri == *uri)
+        .map(|occ| occ.name.id)
+        .collect()
+}
+
+/// Report the pattern variables which shadow a variable in scope
+///
+/// `scope` contains the variables which are bound at the current position, innermost last.
+/// Wildcards and variables whose name starts with `_` are never reported.
+trait Shadowing {
+    fn shadowing(&self, scope: &mut Vec<VarBind>, out: &mut Vec<Warning>);
+}
+
+/// Run `f` with the variables `names` bound in addition to `scope`
+fn with_bound<'a>(
+    scope: &mut Vec<VarBind>,
+    names: impl IntoIterator<Item = &'a VarBind>,
+    f: impl FnOnce(&mut Vec<VarBind>),
+) {
+    let len = scope.len();
+    scope.extend(names.into_iter().cloned());
+    f(scope);
+    scope.truncate(len);
+}
+
+impl<T: Shadowing> Shadowing for Box<T> {
+    fn shadowing(&self, scope: &mut Vec<VarBind>, out: &mut Vec<Warning>) {
+        (**self).shadowing(scope, out)
+    }
+}
+
+impl<T: Shadowing> Shadowing for Option<T> {
+    fn shadowing(&self, scope: &mut Vec<VarBind>, out: &mut Vec<Warning>) {
+        if let Some(x) = self {
+            x.shadowing(scope, out)
+        }
+    }
+}
+
+impl<T: Shadowing> Shadowing for Vec<T> {
+    fn shadowing(&self, scope: &mut Vec<VarBind>, out: &mut Vec<Warning>) {
+        for x in self {
+            x.shadowing(scope, out)
+        }
+    }
+}
+
+impl Shadowing for Decl {
+    fn shadowing(&self, scope: &mut Vec<VarBind>, out: &mut Vec<Warning>) {
+        let params = |telescope: &Telescope| -> Vec<VarBind> {
+            telescope.params.iter().map(|param| param.name.clone()).collect()
+        };
+        match self {
+            Decl::Data(_) => {}
+            Decl::Codata(Codata { dtors, .. }) => {
+                for Dtor { params: tel, self_param, default, .. } in dtors {
+                    let names: Vec<VarBind> =
+                        params(tel).into_iter().chain(self_param.name.clone()).collect();
+                    with_bound(scope, &names, |scope| default.shadowing(scope, out));
+                }
+            }
+            Decl::Def(Def { params: tel, cases, .. })
+            | Decl::Codef(Codef { params: tel, cases, .. }) => {
+                with_bound(scope, &params(tel), |scope| cases.shadowing(scope, out))
+            }
+            Decl::Let(Let { params: tel, body, .. }) => {
+                with_bound(scope, &params(tel), |scope| body.shadowing(scope, out))
+            }
+        }
+    }
+}
+
+impl Shadowing for Case {
+    fn shadowing(&self, scope: &mut Vec<VarBind>, out: &mut Vec<Warning>) {
+        let Case { pattern, body, .. } = self;
+        let names: Vec<VarBind> =
+            pattern.params.params.iter().map(|param| param.name.clone()).collect();
+        for name in names.iter().filter(|name| !name.id.starts_with('_')) {
+            if let Some(shadowed) = scope.iter().rev().find(|bound| bound.id == name.id) {
+                out.push(Warning::ShadowedVariable {
+                    name: name.id.clone(),
+                    span: name.span.to_miette(),
+                    shadowed: vec![ShadowedBinding {
+                        name: shadowed.id.clone(),
+                        span: shadowed.span.to_miette(),
+                    }],
+                });
+            }
+        }
+        with_bound(scope, &names, |scope| body.shadowing(scope, out))
+    }
+}
+
+impl Shadowing for Args {
+    fn shadowing(&self, scope: &mut Vec<VarBind>, out: &mut Vec<Warning>) {
+        for arg in self.args.iter() {
+            match arg {
+                Arg::UnnamedArg(exp) | Arg::NamedArg(_, exp) => exp.shadowing(scope, out),
+                Arg::InsertedImplicitArg(_) => {}
+            }
+        }
+    }
+}
+
+impl Shadowing for Exp {
+    fn shadowing(&self, scope: &mut Vec<VarBind>, out: &mut Vec<Warning>) {
+        match self {
+            Exp::Variable(_) | Exp::TypeUniv(_) | Exp::Hole(_) => {}
+            Exp::TypCtor(TypCtor { args, .. }) | Exp::Call(Call { args, .. }) => {
+                args.shadowing(scope, out)
+            }
+            Exp::DotCall(DotCall { exp, args, .. }) => {
+                exp.shadowing(scope, out);
+                args.shadowing(scope, out);
+            }
+            Exp::Anno(Anno { exp, typ, .. }) => {
+                exp.shadowing(scope, out);
+                typ.shadowing(scope, out);
+            }
+            Exp::LocalMatch(LocalMatch { on_exp, motive, cases, .. }) => {
+                on_exp.shadowing(scope, out);
+                if let Some(Motive { param, ret_typ, .. }) = motive {
+                    with_bound(scope, [&param.name], |scope| ret_typ.shadowing(scope, out));
+                }
+                cases.shadowing(scope, out);
+            }
+            Exp::LocalComatch(LocalComatch { cases, .. }) => cases.shadowing(scope, out),
+            Exp::LocalLet(LocalLet { name, typ, bound, body, .. }) => {
+                typ.shadowing(scope, out);
+                bound.shadowing(scope, out);
+                with_bound(scope, [name], |scope| body.shadowing(scope, out));
+            }
+        }
+    }
+}
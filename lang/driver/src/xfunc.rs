@@ -1,5 +1,8 @@
-use codespan::Span;
-use printer::Print;
+use std::fmt::Write;
+
+use codespan::{ByteIndex, Span};
+use miette_util::ToMiette;
+use printer::{Print, PrintCfg};
 use transformations::LiftResult;
 use transformations::Rename;
 
@@ -10,6 +13,7 @@ use transformations::result::XfuncError;
 use url::Url;
 
 use crate::database::Database;
+use crate::qualify::{Qualifiers, Qualify};
 
 use super::Edit;
 
@@ -35,8 +39,17 @@ impl Database {
             .collect())
     }
 
-    pub async fn xfunc(&mut self, uri: &Url, type_name: &str) -> Result<Xfunc, crate::Error> {
+    /// Compute the edits which xfunctionalize the type `type_name` in the module at `uri`
+    ///
+    /// The rewritten declarations are printed using `cfg`, or the default configuration if `None`.
+    pub async fn xfunc(
+        &mut self,
+        uri: &Url,
+        type_name: &str,
+        cfg: Option<&PrintCfg>,
+    ) -> Result<Xfunc, crate::Error> {
         let module = self.ast(uri).await?;
+        check_xfunc_target(&module, type_name)?;
 
         let decl_spans =
             module.decls.iter().map(|decl| (decl.ident().clone(), decl.span().unwrap())).collect();
@@ -45,10 +58,30 @@ impl Database {
         let xdefs = module.xdefs_for_type(type_name);
         let xtors = module.xtors_for_type(type_name);
 
+        // The declarations in the `where` clauses of the xdefs are removed together with the xdefs.
+        // Those which are not xdefs themselves are moved to the top level.
+        let nested = where_decls(&module, &xdefs);
+        let moved: Vec<IdBind> =
+            nested.iter().filter(|name| !xdefs.contains(name)).cloned().collect();
+        check_where_decls_movable(&module, &xdefs, &moved, &decl_spans)?;
+        let source = self.source(uri).await?;
+        let removed_spans = xdefs
+            .iter()
+            .filter(|name| !nested.contains(name))
+            .map(|name| {
+                let span = decl_spans[name];
+                match where_clause_end(&source, &module, name, &decl_spans) {
+                    Some(end) => Span::new(span.start(), end),
+                    None => span,
+                }
+            })
+            .collect();
+
         // Filter out dirty declarations of the type being xfunctionalized which are handled separately
         let mut filter_out = HashSet::default();
         filter_out.extend(xdefs.clone());
         filter_out.extend(xtors);
+        filter_out.extend(moved.clone());
 
         let LiftResult { module, modified_decls: mut dirty_decls, .. } =
             transformations::lift(module, type_name);
@@ -62,7 +95,7 @@ impl Database {
                 span: None,
             })?;
 
-        let original = Original { type_span, decl_spans, xdefs };
+        let original = Original { type_span, decl_spans, removed_spans, moved };
 
         let repr = transformations::repr(&mat, type_name)?;
 
@@ -71,14 +104,150 @@ impl Database {
             transformations::matrix::Repr::Codata => defunctionalize(&mat, type_name),
         }?;
 
-        Ok(generate_edits(&module, original, dirty_decls, result))
+        let qualifiers = self.qualifiers(uri).await?;
+        Ok(generate_edits(&module, original, dirty_decls, result, &qualifiers, cfg))
+    }
+
+    /// Render the changes which xfunctionalizing the type `type_name` in the module at `uri`
+    /// would make as a unified diff
+    ///
+    /// The diff is empty if xfunc does not change the module.
+    pub async fn xfunc_preview(
+        &mut self,
+        uri: &Url,
+        type_name: &str,
+    ) -> Result<String, crate::Error> {
+        let Xfunc { edits, .. } = self.xfunc(uri, type_name, None).await?;
+        let old = self.source(uri).await?;
+        let new = self.edited(uri, edits).to_string();
+        let name = uri.path_segments().and_then(|mut segments| segments.next_back()).unwrap_or("");
+        Ok(unified_diff(name, &old, &new))
+    }
+
+    /// Compute the edits which xfunctionalize only the member `member_name` of the type
+    /// `type_name` in the module at `uri`
+    ///
+    /// A constructor of a data type becomes a codefinition and a destructor of a codata type
+    /// becomes a definition, into which the cases for the member are moved out of the
+    /// (co)definitions of the type.
+    /// This is an incremental step: the module typechecks again once all members of the type
+    /// have been xfunctionalized.
+    pub async fn xfunc_member(
+        &mut self,
+        uri: &Url,
+        type_name: &str,
+        member_name: &str,
+        cfg: Option<&PrintCfg>,
+    ) -> Result<Xfunc, crate::Error> {
+        let module = self.ast(uri).await?;
+        check_xfunc_target(&module, type_name)?;
+        if module.xtors_for_type(type_name).is_empty() {
+            let span = module
+                .decls
+                .iter()
+                .find(|decl| decl.ident().id == type_name)
+                .and_then(|decl| decl.ident().span);
+            return Err(XfuncError::NoMembers {
+                name: type_name.to_owned(),
+                span: span.to_miette(),
+            }
+            .into());
+        }
+
+        let decl_spans =
+            module.decls.iter().map(|decl| (decl.ident().clone(), decl.span().unwrap())).collect();
+
+        let LiftResult { mut module, new_decls: lifted, modified_decls: mut dirty_decls } =
+            transformations::lift(module, type_name);
+
+        let mat = transformations::as_matrix(&module)?;
+        let xdata = mat.map.get(type_name).ok_or(XfuncError::Impossible {
+            message: format!("Could not resolve {type_name}"),
+            span: None,
+        })?;
+        let type_span = xdata.span.ok_or(XfuncError::Impossible {
+            message: format!("Could not resolve {type_name}"),
+            span: None,
+        })?;
+
+        let is_xtor = match xdata.repr {
+            matrix::Repr::Data => xdata.ctors.contains_key(member_name),
+            matrix::Repr::Codata => xdata.dtors.contains_key(member_name),
+        };
+        let slice = xdata.slice(member_name).filter(|_| is_xtor).ok_or_else(|| {
+            let span = match xdata.ctors.get(member_name) {
+                Some(ctor) => ctor.span,
+                None => xdata.dtors.get(member_name).and_then(|dtor| dtor.span),
+            };
+            XfuncError::NotAnXtor {
+                name: member_name.to_owned(),
+                type_name: type_name.to_owned(),
+                span: span.to_miette(),
+            }
+        })?;
+
+        let (title, mut member_decls) = match xdata.repr {
+            matrix::Repr::Data => {
+                let (_, codefs) = slice.as_codata(&mat.uri);
+                let decls: Vec<Decl> = codefs.into_iter().map(Decl::Codef).collect();
+                (format!("Refunctionalize {member_name}"), decls)
+            }
+            matrix::Repr::Codata => {
+                let (_, defs) = slice.as_data(&mat.uri);
+                let decls: Vec<Decl> = defs.into_iter().map(Decl::Def).collect();
+                (format!("Defunctionalize {member_name}"), decls)
+            }
+        };
+
+        // Remove the member from the type and its cases from the (co)definitions of the type
+        let mut type_decl = None;
+        for decl in module.decls.iter_mut() {
+            let name = decl.ident().clone();
+            let cases = match decl {
+                Decl::Data(data) if data.name.id == type_name => {
+                    data.ctors.retain(|ctor| ctor.name.id != member_name);
+                    type_decl = Some(decl.clone());
+                    continue;
+                }
+                Decl::Codata(codata) if codata.name.id == type_name => {
+                    codata.dtors.retain(|dtor| dtor.name.id != member_name);
+                    type_decl = Some(decl.clone());
+                    continue;
+                }
+                Decl::Def(def) if def.self_param.typ.name.id == type_name => &mut def.cases,
+                Decl::Codef(codef) if codef.typ.name.id == type_name => &mut codef.cases,
+                _ => continue,
+            };
+            let len = cases.len();
+            cases.retain(|case| case.pattern.name.id != member_name);
+            if cases.len() < len {
+                dirty_decls.insert(name);
+            }
+        }
+        // The lifted definitions are new and printed together with the type
+        dirty_decls.retain(|name| !lifted.contains(name));
+
+        let mut new_decls: Vec<Decl> = type_decl.into_iter().collect();
+        new_decls.extend(module.decls.iter().filter(|decl| lifted.contains(decl.ident())).cloned());
+        new_decls.append(&mut member_decls);
+        new_decls.iter_mut().for_each(|decl| decl.rename());
+
+        let original = Original { type_span, decl_spans, removed_spans: vec![], moved: vec![] };
+        let result = XfuncResult { title, new_decls };
+
+        let qualifiers = self.qualifiers(uri).await?;
+        Ok(generate_edits(&module, original, dirty_decls, result, &qualifiers, cfg))
     }
 }
 
 struct Original {
-    xdefs: Vec<IdBind>,
     type_span: Span,
     decl_spans: HashMap<IdBind, Span>,
+    /// The spans of the top-level definitions of the previous decomposition,
+    /// including their `where` clauses
+    removed_spans: Vec<Span>,
+    /// The declarations which are moved out of the `where` clauses of the removed definitions
+    moved: Vec<IdBind>,
 }
 
 struct XfuncResult {
@@ -92,19 +261,32 @@ fn generate_edits(
     original: Original,
     dirty_decls: HashSet<IdBind>,
     result: XfuncResult,
+    qualifiers: &Qualifiers,
+    cfg: Option<&PrintCfg>,
 ) -> Xfunc {
-    let XfuncResult { title, new_decls } = result;
+    let XfuncResult { title, mut new_decls } = result;
+
+    for name in original.moved.iter() {
+        let decl = module
+            .lookup_decl(&IdBound { span: None, id: name.id.clone(), uri: module.uri.clone() })
+            .unwrap();
+        let mut decl = decl.clone();
+        decl.rename();
+        new_decls.push(decl);
+    }
 
     // Edits for the type that has been xfunctionalized
     // Here we rewrite the entire (co)data declaration and its associated (co)definitions
-    let new_items = Module {
+    let mut new_items = Module {
         uri: module.uri.clone(),
         // Use declarations don't change, and we are only printing an excerpt of the module
         use_decls: vec![],
         decls: new_decls,
         meta_vars: module.meta_vars.clone(),
+        comments: vec![],
     };
-    let type_text = new_items.print_to_string(None);
+    new_items.qualify(qualifiers);
+    let type_text = new_items.print_to_string(cfg);
 
     let mut edits = vec![Edit { span: original.type_span, text: type_text }];
 
@@ -116,14 +298,14 @@ fn generate_edits(
             .unwrap();
         let mut decl = decl.clone();
         decl.rename();
+        decl.qualify(qualifiers);
         let span = original.decl_spans[&name];
-        let text = decl.print_to_string(None);
+        let text = decl.print_to_string(cfg);
         edits.push(Edit { span, text });
     }
 
     // Remove all top-level definitions of the previous decomposition
-    for name in original.xdefs {
-        let span = original.decl_spans[&name];
+    for span in original.removed_spans {
         edits.push(Edit { span, text: "".to_owned() });
     }
 
@@ -153,3 +335,166 @@ fn defunctionalize(mat: &matrix::Prg, type_name: &str) -> Result<XfuncResult, cr
 
     Ok(XfuncResult { title: format!("Defunctionalize {type_name}"), new_decls })
 }
+
+/// The number of unchanged lines shown around each change of a unified diff
+const DIFF_CONTEXT: usize = 3;
+
+/// A line-based diff between `old` and `new` in the unified format, with `name` as the file name
+fn unified_diff(name: &str, old: &str, new: &str) -> String {
+    let lines = diff::lines(old, new);
+
+    // The line numbers in the old and new text at which each line of the diff starts
+    let mut positions = Vec::with_capacity(lines.len());
+    let (mut old_line, mut new_line) = (0, 0);
+    for line in lines.iter() {
+        positions.push((old_line, new_line));
+        match line {
+            diff::Result::Left(_) => old_line += 1,
+            diff::Result::Right(_) => new_line += 1,
+            diff::Result::Both(_, _) => {
+                old_line += 1;
+                new_line += 1;
+            }
+        }
+    }
+
+    let changes: Vec<usize> =
+        (0..lines.len()).filter(|idx| !matches!(lines[*idx], diff::Result::Both(_, _))).collect();
+    let mut out = String::new();
+    if changes.is_empty() {
+        return out;
+    }
+    writeln!(out, "--- a/{name}\n+++ b/{name}").unwrap();
+
+    let mut changes = changes.into_iter().peekable();
+    while let Some(first) = changes.next() {
+        // Changes whose context overlaps are shown in the same hunk
+        let mut last = first;
+        while let Some(next) = changes.next_if(|next| *next <= last + 2 * DIFF_CONTEXT + 1) {
+            last = next;
+        }
+        let hunk_start = first.saturating_sub(DIFF_CONTEXT);
+        let hunk_end = (last + DIFF_CONTEXT + 1).min(lines.len());
+        let hunk = &lines[hunk_start..hunk_end];
+        let (old_start, new_start) = positions[hunk_start];
+        let old_len = hunk.iter().filter(|line| !matches!(line, diff::Result::Right(_))).count();
+        let new_len = hunk.iter().filter(|line| !matches!(line, diff::Result::Left(_))).count();
+        // Empty ranges start at the line before them
+        let start = |start: usize, len: usize| if len == 0 { start } else { start + 1 };
+        writeln!(
+            out,
+            "@@ -{},{old_len} +{},{new_len} @@",
+            start(old_start, old_len),
+            start(new_start, new_len)
+        )
+        .unwrap();
+        for line in hunk {
+            match line {
+                diff::Result::Left(line) => writeln!(out, "-{line}"),
+                diff::Result::Right(line) => writeln!(out, "+{line}"),
+                diff::Result::Both(line, _) => writeln!(out, " {line}"),
+            }
+            .unwrap();
+        }
+    }
+    out
+}
+
+/// Check that `type_name` is a data or codata type of `module` which can be xfunctionalized
+fn check_xfunc_target(module: &Module, type_name: &str) -> Result<(), XfuncError> {
+    let decl = module
+        .decls
+        .iter()
+        .find(|decl| decl.ident().id == type_name)
+        .ok_or_else(|| XfuncError::UndefinedType { name: type_name.to_owned() })?;
+    match decl {
+        Decl::Data(_) | Decl::Codata(_) => Ok(()),
+        _ => Err(XfuncError::NotAType {
+            name: type_name.to_owned(),
+            span: decl.ident().span.to_miette(),
+        }),
+    }
+}
+
+/// The declarations which are declared in the `where` clauses of the `parents`,
+/// including those declared in the `where` clauses of these declarations
+fn where_decls(module: &Module, parents: &[IdBind]) -> Vec<IdBind> {
+    let mut out: Vec<IdBind> = vec![];
+    let mut todo: Vec<&str> = parents.iter().map(|parent| parent.id.as_str()).collect();
+    while let Some(parent) = todo.pop() {
+        for decl in module.decls.iter() {
+            if decl.ident().where_parent() == Some(parent) {
+                out.push(decl.ident().clone());
+                todo.push(&decl.ident().id);
+            }
+        }
+    }
+    out
+}
+
+/// Check that the declarations which are moved out of their `where` clause by xfunc
+/// do not clash with other top-level names under their local names
+///
+/// These are the `moved` declarations as well as the xdefs declared in a `where` clause,
+/// which become destructors or constructors.
+fn check_where_decls_movable(
+    module: &Module,
+    xdefs: &[IdBind],
+    moved: &[IdBind],
+    decl_spans: &HashMap<IdBind, Span>,
+) -> Result<(), XfuncError> {
+    let mut top_level: HashSet<&str> = HashSet::default();
+    for decl in module.decls.iter().filter(|decl| decl.ident().where_parent().is_none()) {
+        top_level.insert(&decl.ident().id);
+        match decl {
+            Decl::Data(data) => {
+                top_level.extend(data.ctors.iter().map(|ctor| ctor.name.id.as_str()))
+            }
+            Decl::Codata(codata) => {
+                top_level.extend(codata.dtors.iter().map(|dtor| dtor.name.id.as_str()))
+            }
+            Decl::Def(_) | Decl::Codef(_) | Decl::Let(_) => {}
+        }
+    }
+
+    // Declarations whose parent is moved as well stay in the `where` clause of their parent
+    let is_lifted = |name: &&IdBind| {
+        name.where_parent().is_some_and(|parent| !moved.iter().any(|other| other.id == parent))
+    };
+    for name in xdefs.iter().chain(moved.iter()).filter(is_lifted) {
+        if !top_level.insert(name.local_name()) {
+            let span = decl_spans.get(name).map(|span| {
+                (span.start().to_usize(), (span.end() - span.start()).to_usize()).into()
+            });
+            return Err(XfuncError::WhereDeclClash { name: name.local_name().to_owned(), span });
+        }
+    }
+    Ok(())
+}
+
+/// The end of the `where` clause of the declaration `name`, if it has one
+///
+/// The `where` clause ends with the first closing brace after its last declaration.
+fn where_clause_end(
+    source: &str,
+    module: &Module,
+    name: &IdBind,
+    decl_spans: &HashMap<IdBind, Span>,
+) -> Option<ByteIndex> {
+    let last = where_decls(module, std::slice::from_ref(name))
+        .iter()
+        .filter_map(|decl| decl_spans.get(decl))
+        .map(|span| span.end())
+        .max()?;
+    let mut rest = &source[last.to_usize()..];
+    loop {
+        rest = rest.trim_start();
+        if rest.starts_with("--") {
+            rest = rest.split_once('\n').map(|(_, rest)| rest).unwrap_or_default();
+        } else {
+            break;
+        }
+    }
+    let offset = source.len() - rest.len();
+    rest.starts_with('}').then(|| ByteIndex(offset as u32 + 1))
+}
@@ -0,0 +1,82 @@
+//! Checking whether two terms are convertible
+
+use ast::ctx::LevelCtx;
+use ast::{ContainsMetaVars, Exp, HashMap};
+
+use crate::normalizer::env::ToEnv;
+use crate::normalizer::evaluator::Evaluator;
+use crate::normalizer::normalize::Normalize;
+use crate::result::TypeError;
+use crate::unifier::constraints::Constraint;
+use crate::unifier::dec::Dec;
+use crate::unifier::unify::unify;
+
+/// Check whether `lhs` and `rhs` are convertible, i.e. whether they are equal up to alpha
+/// equivalence after normalization
+///
+/// Both terms are interpreted in the context `ctx`, whose variables are treated as rigid.
+/// Terms which are syntactically identical, ignoring names and spans, are convertible without
+/// being normalized. Terms containing holes cannot be compared.
+pub fn convertible(
+    ev: &Evaluator,
+    ctx: &LevelCtx,
+    lhs: &Exp,
+    rhs: &Exp,
+) -> Result<bool, TypeError> {
+    if lhs == rhs {
+        return Ok(true);
+    }
+    if lhs.contains_metavars() || rhs.contains_metavars() {
+        return Err(TypeError::cannot_decide(lhs, rhs, &None));
+    }
+    let lhs = lhs.normalize(ev, &mut ctx.env())?;
+    let rhs = rhs.normalize(ev, &mut ctx.env())?;
+    if lhs == rhs {
+        return Ok(true);
+    }
+    let constraint = Constraint::Equality { lhs, rhs };
+    match unify(ctx.clone(), &mut HashMap::default(), constraint, true, &None)? {
+        Dec::Yes(_) => Ok(true),
+        Dec::No(_) => Ok(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use ast::test_support::{type_univ, var};
+    use ast::*;
+
+    use crate::normalizer::evaluator::EvalConfig;
+    use crate::TypeInfoTable;
+
+    use super::*;
+
+    fn check(lhs: &Exp, rhs: &Exp) -> bool {
+        let ev = Evaluator::new(Rc::new(TypeInfoTable::default()), &EvalConfig::default());
+        convertible(&ev, &LevelCtx::from(vec![2]), lhs, rhs).unwrap()
+    }
+
+    #[test]
+    fn names_are_ignored() {
+        assert!(check(&var("x", 0, 0), &var("y", 0, 0)));
+    }
+
+    #[test]
+    fn distinct_variables_are_not_convertible() {
+        assert!(!check(&var("x", 0, 0), &var("x", 0, 1)));
+    }
+
+    #[test]
+    fn annotations_are_ignored() {
+        let anno = Exp::Anno(Anno {
+            span: None,
+            exp: var("x", 0, 1),
+            typ: type_univ(),
+            normalized_type: None,
+        });
+        assert!(check(&anno, &var("x", 0, 1)));
+        assert!(!check(&anno, &var("x", 0, 0)));
+    }
+}
@@ -1,8 +1,11 @@
+pub mod conversion;
 pub mod normalizer;
 pub mod result;
 pub mod typechecker;
 pub mod unifier;
 
+pub use conversion::convertible;
+pub use typechecker::goals::{exp_goals, goals, Goal};
 pub use typechecker::type_info_table::build::build_type_info_table;
 pub use typechecker::type_info_table::ModuleTypeInfoTable;
 pub use typechecker::type_info_table::TypeInfoTable;
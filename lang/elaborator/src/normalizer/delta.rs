@@ -0,0 +1,23 @@
+//! Whether toplevel definitions and codefinitions are unfolded during normalization
+//!
+//! A definition `d` annotated with `#[opaque]` is never unfolded, so that `C(args).d(args')`
+//! is a neutral term which is only convertible to syntactically equal terms.
+//! Likewise, the cocases of an opaque codefinition `C` are never selected.
+//! (Co)definitions without such an annotation are unfolded unless they are declared opaque
+//! by default, in which case `#[transparent]` unfolds them again.
+//! Let-bound definitions are not affected by the default, since they are always opaque
+//! unless they are annotated with `#[transparent]`.
+
+use ast::{Attribute, Attributes};
+
+/// Whether the toplevel (co)definition with the attributes `attr` is not unfolded, given whether
+/// (co)definitions without an annotation are `opaque_by_default`
+pub(crate) fn is_opaque(attr: &Attributes, opaque_by_default: bool) -> bool {
+    if attr.attrs.contains(&Attribute::Opaque) {
+        true
+    } else if attr.attrs.contains(&Attribute::Transparent) {
+        false
+    } else {
+        opaque_by_default
+    }
+}
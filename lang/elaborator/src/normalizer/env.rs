@@ -115,7 +115,7 @@ impl ToEnv for LevelCtx {
 
 impl ToEnv for TypeCtx {
     fn env(&self) -> Env {
-        let bound = self
+        let mut bound = self
             .bound
             .map_idx(|idx, binder| {
                 Box::new(Val::Neu(Neu::Variable(Variable {
@@ -127,6 +127,17 @@ impl ToEnv for TypeCtx {
             })
             .collect();
 
+        // Variables bound by a local let unfold to their bound expression.
+        // The bound expression may only refer to earlier variables, whose entries are already set.
+        for (fst, stack) in self.bound.iter().enumerate() {
+            for (snd, binder) in stack.iter().enumerate() {
+                if let Some(value) = &binder.value {
+                    let thunk = Thunk { env: Env::from_vec(bound.clone()), exp: value.clone() };
+                    bound[fst][snd] = Box::new(thunk.into());
+                }
+            }
+        }
+
         Env::from_vec(bound)
     }
 }
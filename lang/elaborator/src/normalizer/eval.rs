@@ -1,5 +1,4 @@
-use std::rc::Rc;
-
+use codespan::Span;
 use log::trace;
 
 use ast::ctx::{BindContext, Context};
@@ -7,48 +6,79 @@ use ast::*;
 use miette_util::ToMiette;
 use printer::types::Print;
 
+use crate::normalizer::delta;
 use crate::normalizer::env::*;
+use crate::normalizer::evaluator::Evaluator;
+use crate::normalizer::memo::Key;
+use crate::normalizer::order::EvaluationOrder;
 use crate::normalizer::val::{self, Closure, Val};
 
-use crate::{result::*, TypeInfoTable};
+use crate::result::*;
+use crate::typechecker::type_info_table::{CtorMeta, DtorMeta};
 
 use super::val::AnnoNeu;
 
 pub trait Eval {
     type Val;
 
-    fn eval(&self, info_table: &Rc<TypeInfoTable>, env: &mut Env) -> Result<Self::Val, TypeError>;
+    fn eval(&self, ev: &Evaluator, env: &mut Env) -> Result<Self::Val, TypeError>;
 }
 
 pub trait Apply {
+    fn apply(self, ev: &Evaluator, args: &[Box<Val>]) -> Result<Box<Val>, TypeError>;
+}
+
+/// The strategy used to evaluate the body of a closure after a beta reduction
+#[derive(Debug, Clone, Copy)]
+enum Strategy {
+    /// Evaluate the body to a value, see [Eval::eval]
+    Full,
+    /// Evaluate the body to weak head normal form, see [eval_whnf]
+    Whnf,
+}
+
+impl Strategy {
     fn apply(
         self,
-        info_table: &Rc<TypeInfoTable>,
+        closure: Closure,
+        ev: &Evaluator,
         args: &[Box<Val>],
-    ) -> Result<Box<Val>, TypeError>;
+    ) -> Result<Box<Val>, TypeError> {
+        match self {
+            Strategy::Full => closure.apply(ev, args),
+            // Weak head normal forms are not memoized, since they contain unevaluated thunks
+            Strategy::Whnf => {
+                let Closure { mut env, body, .. } = closure;
+                env.bind_iter(args.iter(), |env| eval_whnf(&body, ev, env))
+            }
+        }
+    }
 }
 
 impl Eval for Exp {
     type Val = Box<Val>;
 
-    fn eval(&self, info_table: &Rc<TypeInfoTable>, env: &mut Env) -> Result<Self::Val, TypeError> {
+    fn eval(&self, ev: &Evaluator, env: &mut Env) -> Result<Self::Val, TypeError> {
         let e = match self {
-            Exp::Variable(e) => e.eval(info_table, env),
-            Exp::TypCtor(e) => e.eval(info_table, env),
-            Exp::Call(e) => e.eval(info_table, env),
-            Exp::DotCall(e) => e.eval(info_table, env),
-            Exp::Anno(e) => e.eval(info_table, env),
-            Exp::TypeUniv(e) => e.eval(info_table, env),
-            Exp::LocalMatch(e) => e.eval(info_table, env),
-            Exp::LocalComatch(e) => e.eval(info_table, env),
-            Exp::Hole(e) => e.eval(info_table, env),
+            Exp::Variable(e) => e.eval(ev, env),
+            Exp::TypCtor(e) => e.eval(ev, env),
+            Exp::Call(e) => e.eval(ev, env),
+            Exp::DotCall(e) => e.eval(ev, env),
+            Exp::Anno(e) => e.eval(ev, env),
+            Exp::TypeUniv(e) => e.eval(ev, env),
+            Exp::LocalMatch(e) => e.eval(ev, env),
+            Exp::LocalComatch(e) => e.eval(ev, env),
+            Exp::LocalLet(e) => e.eval(ev, env),
+            Exp::Hole(e) => e.eval(ev, env),
         };
-        trace!(
-            "{} |- {} ▷ {}",
-            env.print_to_colored_string(None),
-            self.print_to_colored_string(None),
-            e.print_to_colored_string(None)
-        );
+        if ev.trace.is_enabled() {
+            trace!(
+                "{} |- {} ▷ {}",
+                env.print_to_colored_string(None),
+                self.print_to_colored_string(None),
+                e.print_to_colored_string(None)
+            );
+        }
         e
     }
 }
@@ -56,20 +86,24 @@ impl Eval for Exp {
 impl Eval for Variable {
     type Val = Box<Val>;
 
-    fn eval(&self, _info_table: &Rc<TypeInfoTable>, env: &mut Env) -> Result<Self::Val, TypeError> {
+    fn eval(&self, ev: &Evaluator, env: &mut Env) -> Result<Self::Val, TypeError> {
         let Variable { idx, .. } = self;
-        Ok(env.lookup(*idx))
+        let val = env.lookup(*idx);
+        // Variables bound to delayed arguments are forced on access, see [super::order].
+        match &*val {
+            Val::Thunk(thunk) => thunk.force(ev),
+            _ => Ok(val),
+        }
     }
 }
 
 impl Eval for TypCtor {
     type Val = Box<Val>;
 
-    fn eval(&self, info_table: &Rc<TypeInfoTable>, env: &mut Env) -> Result<Self::Val, TypeError> {
+    fn eval(&self, ev: &Evaluator, env: &mut Env) -> Result<Self::Val, TypeError> {
         let TypCtor { span, name, args } = self;
         Ok(Box::new(
-            val::TypCtor { span: *span, name: name.clone(), args: args.eval(info_table, env)? }
-                .into(),
+            val::TypCtor { span: *span, name: name.clone(), args: args.eval(ev, env)? }.into(),
         ))
     }
 }
@@ -77,35 +111,52 @@ impl Eval for TypCtor {
 impl Eval for Call {
     type Val = Box<Val>;
 
-    fn eval(&self, info_table: &Rc<TypeInfoTable>, env: &mut Env) -> Result<Self::Val, TypeError> {
+    fn eval(&self, ev: &Evaluator, env: &mut Env) -> Result<Self::Val, TypeError> {
         let Call { span, name, kind, args, .. } = self;
         match kind {
             CallKind::LetBound => {
-                let Let { attr, body, .. } = info_table.lookup_let(name)?;
+                let Let { attr, body, .. } = ev.info_table.lookup_let(name)?;
                 // We now have to distinguish two cases:
                 // If the let-bound definition is transparent, then we substitute the
                 // arguments for the body of the definition. If it is opaque, then
                 // the further computation is blocked so we return a neutral value.
                 if attr.attrs.contains(&Attribute::Transparent) {
-                    let args = args.eval(info_table, env)?;
-                    env.bind_iter(args.to_vals().iter(), |env| body.eval(info_table, env))
+                    ev.fuel.consume(*span)?;
+                    let args = eval_args(args, ev, env)?;
+                    ev.trace.in_decl(&name.id, || {
+                        env.bind_iter(args.to_vals().iter(), |env| body.eval(ev, env))
+                    })
                 } else {
                     Ok(Box::new(Val::Neu(
                         val::OpaqueCall {
                             span: *span,
                             name: name.clone(),
-                            args: args.eval(info_table, env)?,
+                            args: args.eval(ev, env)?,
                         }
                         .into(),
                     )))
                 }
             }
-            CallKind::Constructor | CallKind::Codefinition => Ok(Box::new(
+            CallKind::Constructor => {
+                let CtorMeta { params, typ } = ev.info_table.lookup_ctor(name)?;
+                Ok(Box::new(
+                    val::Call {
+                        span: *span,
+                        kind: *kind,
+                        name: name.clone(),
+                        args: eval_ctor_args(args, params, ev, env)?,
+                        data: Some(typ.name.clone()),
+                    }
+                    .into(),
+                ))
+            }
+            CallKind::Codefinition => Ok(Box::new(
                 val::Call {
                     span: *span,
                     kind: *kind,
                     name: name.clone(),
-                    args: args.eval(info_table, env)?,
+                    args: eval_args(args, ev, env)?,
+                    data: None,
                 }
                 .into(),
             )),
@@ -113,6 +164,42 @@ impl Eval for Call {
     }
 }
 
+/// Evaluate the arguments of a constructor call.
+///
+/// Arguments for lazy parameters are not evaluated but wrapped in a thunk
+/// which is only forced when the argument is accessed by a pattern match.
+fn eval_ctor_args(
+    args: &Args,
+    params: &Telescope,
+    ev: &Evaluator,
+    env: &mut Env,
+) -> Result<val::Args, TypeError> {
+    if ev.order == EvaluationOrder::CallByName {
+        return delay_args(args, ev, env);
+    }
+    let args = args
+        .args
+        .iter()
+        .zip(params.params.iter())
+        .map(|(arg, Param { lazy, .. })| {
+            if !lazy {
+                return arg.eval(ev, env);
+            }
+            let thunk = |exp: &Box<Exp>| {
+                Box::new(Val::from(val::Thunk { env: env.clone(), exp: exp.clone() }))
+            };
+            Ok(match arg {
+                Arg::UnnamedArg(exp) => val::Arg::UnnamedArg(thunk(exp)),
+                Arg::NamedArg(name, exp) => val::Arg::NamedArg(name.clone(), thunk(exp)),
+                Arg::InsertedImplicitArg(hole) => {
+                    val::Arg::InsertedImplicitArg(hole.eval(ev, env)?)
+                }
+            })
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(val::Args(args))
+}
+
 impl Eval for DotCall {
     type Val = Box<Val>;
 
@@ -125,151 +212,192 @@ impl Eval for DotCall {
     /// ┃ ┗━━━━━━━━━━━━ name
     /// ┗━━━━━━━━━━━━━━ exp
     /// ```
-    fn eval(&self, info_table: &Rc<TypeInfoTable>, env: &mut Env) -> Result<Self::Val, TypeError> {
+    fn eval(&self, ev: &Evaluator, env: &mut Env) -> Result<Self::Val, TypeError> {
         let DotCall { span, kind, exp, name, args, .. } = self;
 
         // We first evaluate `exp` and then the arguments `args` to `d` from left to right.
-        let exp = exp.eval(info_table, env)?;
-        let args = args.eval(info_table, env)?;
-
-        // If possible, strip away all annotations from the expression.
-        // For example, we need to strip away the annotation around `T` in  `(T : Bool).match { T => F, F => T }` before we can evaluate further.
-        let exp = strip_annotations(&exp);
-
-        match exp {
-            Val::Call(val::Call { name: call_name, kind, args: call_args, .. }) => {
-                match kind {
-                    CallKind::Constructor => {
-                        // The specific instance of the DotCall we are evaluating is:
-                        //
-                        // ```text
-                        //  C(t_1,..).d(e_1,...)
-                        //  ┳ ━━┳━━━  ┳ ━━━┳━━━
-                        //  ┃   ┃     ┃    ┗━━━━ args
-                        //  ┃   ┃     ┗━━━━━━━━━ name
-                        //  ┃   ┗━━━━━━━━━━━━━━━ call_args
-                        //  ┗━━━━━━━━━━━━━━━━━━━ call_name
-                        // ```
-                        //
-                        // where `C` is the name of a constructor declared in a
-                        // data type, and `d` is the name of a toplevel definition.
-
-                        // First, we have to find the corresponding case in the toplevel definition `d`.
-                        let Def { cases, .. } = info_table.lookup_def(&name.clone())?;
-                        let mut env = Env::empty();
-                        let cases = env
-                            .bind_iter(args.to_vals().iter(), |env| cases.eval(info_table, env))?;
-                        let val::Case { body, .. } = cases
-                            .clone()
-                            .into_iter()
-                            .find(|case| case.name == call_name)
-                            .ok_or_else(|| TypeError::MissingCase { name: call_name.id.clone() })?;
-
-                        // Then we apply the body to the `call_args`.
-                        body.clone().unwrap().apply(info_table, &call_args.to_vals())
-                    }
-                    CallKind::Codefinition => {
-                        // The specific instance of the DotCall we are evaluating is:
-                        //
-                        // ```text
-                        //  C(t_1,..).d(e_1,...)
-                        //  ┳ ━━┳━━━  ┳ ━━━┳━━━
-                        //  ┃   ┃     ┃    ┗━━━━ args
-                        //  ┃   ┃     ┗━━━━━━━━━ name
-                        //  ┃   ┗━━━━━━━━━━━━━━━ call_args
-                        //  ┗━━━━━━━━━━━━━━━━━━━ call_name
-                        // ```
-                        //
-                        // where `d` is the name of a destructor declared in a
-                        // data type, and `C` is the name of a toplevel codefinition.
-
-                        // First, we have to find the corresponding cocase in the toplevel
-                        // codefinition `C`.
-                        let Codef { cases, .. } = info_table.lookup_codef(&call_name.clone())?;
-                        let mut env = Env::empty();
-                        let cases = env.bind_iter(call_args.to_vals().iter(), |env| {
-                            cases.eval(info_table, env)
-                        })?;
-                        let val::Case { body, .. } = cases
-                            .clone()
-                            .into_iter()
-                            .find(|cocase| cocase.name == *name)
-                            .ok_or_else(|| TypeError::MissingCocase { name: name.id.clone() })?;
-
-                        // Then we apply the body to the `args`.
-                        body.clone().unwrap().apply(info_table, &args.to_vals())
-                    }
-                    CallKind::LetBound => {
-                        // This case is unreachable because all let-bound calls have either already
-                        // been replaced by their body (if they are transparent), or they have been
-                        // turned into a neutral `OpaqueCall` if they are opaque.
-                        unreachable!()
-                    }
-                }
-            }
-            Val::LocalComatch(val::LocalComatch { cases, .. }) => {
-                // The specific instance of the DotCall we are evaluating is:
-                //
-                // ```text
-                //  comatch { ... }.d(e_1,...)
-                //            ━┳━   ┳ ━━━┳━━━
-                //             ┃    ┃    ┗━━━━ args
-                //             ┃    ┗━━━━━━━━━ name
-                //             ┗━━━━━━━━━━━━━━ cases
-                // ```
-                //
-                // where `d` is the name of a destructor declared in a
-                // codata type.
-
-                // First, we have to select the correct case from the comatch.
-                let val::Case { body, .. } = cases
-                    .clone()
-                    .into_iter()
-                    .find(|cocase| cocase.name == *name)
-                    .ok_or_else(|| TypeError::MissingCocase { name: name.id.clone() })?;
-
-                // Then we apply the body to the `args`.
-                body.clone().unwrap().apply(info_table, &args.to_vals())
-            }
+        let exp = exp.eval(ev, env)?;
+        let args = eval_args(args, ev, env)?;
 
-            Val::Neu(exp) => {
-                // The specific instance of the DotCall we are evaluating is:
-                //
-                // ```text
-                // n.d(e_1,...)
-                // ┳ ┳ ━━━┳━━━
-                // ┃ ┃    ┗━━━━━━━ args
-                // ┃ ┗━━━━━━━━━━━━ name
-                // ┗━━━━━━━━━━━━━━ exp (Neutral value)
-                // ```
-                // Evaluation is blocked by the neutral value `n`.
-                Ok(Box::new(Val::Neu(
-                    val::DotCall {
-                        span: *span,
-                        kind: *kind,
-                        exp: Box::new(exp),
-                        name: name.to_owned(),
-                        args,
-                    }
+        beta_dot_call(*span, *kind, exp, name, args, ev, Strategy::Full)
+    }
+}
+
+/// Reduce the DotCall `exp.name(args)`, where `exp` and `args` have already been evaluated
+///
+/// The bodies of the selected cases are evaluated using the given `strategy`.
+/// Reductions of toplevel definitions and codefinitions are memoized, see [super::memo].
+fn beta_dot_call(
+    span: Option<Span>,
+    kind: DotCallKind,
+    exp: Box<Val>,
+    name: &IdBound,
+    args: val::Args,
+    ev: &Evaluator,
+    strategy: Strategy,
+) -> Result<Box<Val>, TypeError> {
+    // Weak head normal forms are not memoized, since they contain unevaluated thunks
+    let key = match (&*exp, strategy) {
+        (Val::Call(val::Call { name: call_name, args: call_args, .. }), Strategy::Full) => {
+            Key::new(call_name, call_args, name, &args, ev)
+        }
+        _ => None,
+    };
+    ev.memo.memoized(key, || beta_dot_call_uncached(span, kind, exp, name, args, ev, strategy))
+}
+
+fn beta_dot_call_uncached(
+    span: Option<Span>,
+    kind: DotCallKind,
+    exp: Box<Val>,
+    name: &IdBound,
+    args: val::Args,
+    ev: &Evaluator,
+    strategy: Strategy,
+) -> Result<Box<Val>, TypeError> {
+    // If possible, strip away all annotations from the expression.
+    // For example, we need to strip away the annotation around `T` in  `(T : Bool).match { T => F, F => T }` before we can evaluate further.
+    let exp = strip_annotations(&exp);
+
+    // Opaque toplevel (co)definitions block the computation, see [delta].
+    if let Val::Call(call) = &exp {
+        if is_opaque_reduction(call, name, ev)? {
+            return Ok(Box::new(Val::Neu(
+                val::OpaqueDotCall { span, kind, exp: Box::new(exp), name: name.clone(), args }
                     .into(),
-                )))
+            )));
+        }
+    }
+
+    match exp {
+        Val::Call(val::Call { name: call_name, kind, args: call_args, .. }) => {
+            match kind {
+                CallKind::Constructor => {
+                    // The specific instance of the DotCall we are evaluating is:
+                    //
+                    // ```text
+                    //  C(t_1,..).d(e_1,...)
+                    //  ┳ ━━┳━━━  ┳ ━━━┳━━━
+                    //  ┃   ┃     ┃    ┗━━━━ args
+                    //  ┃   ┃     ┗━━━━━━━━━ name
+                    //  ┃   ┗━━━━━━━━━━━━━━━ call_args
+                    //  ┗━━━━━━━━━━━━━━━━━━━ call_name
+                    // ```
+                    //
+                    // where `C` is the name of a constructor declared in a
+                    // data type, and `d` is the name of a toplevel definition.
+
+                    // First, we evaluate the cases of the toplevel definition `d`.
+                    let Def { cases, .. } = ev.info_table.lookup_def(&name.clone())?;
+                    let mut env = Env::empty();
+                    let cases = env.bind_iter(args.to_vals().iter(), |env| cases.eval(ev, env))?;
+
+                    // Then we apply the body of the case to the `call_args`.
+                    ev.trace.in_decl(&name.id, || {
+                        beta_match(span, &cases, &call_name, &call_args, ev, strategy)
+                    })
+                }
+                CallKind::Codefinition => {
+                    // The specific instance of the DotCall we are evaluating is:
+                    //
+                    // ```text
+                    //  C(t_1,..).d(e_1,...)
+                    //  ┳ ━━┳━━━  ┳ ━━━┳━━━
+                    //  ┃   ┃     ┃    ┗━━━━ args
+                    //  ┃   ┃     ┗━━━━━━━━━ name
+                    //  ┃   ┗━━━━━━━━━━━━━━━ call_args
+                    //  ┗━━━━━━━━━━━━━━━━━━━ call_name
+                    // ```
+                    //
+                    // where `d` is the name of a destructor declared in a
+                    // data type, and `C` is the name of a toplevel codefinition.
+
+                    // First, we evaluate the cocases of the toplevel codefinition `C`.
+                    let Codef { cases, .. } = ev.info_table.lookup_codef(&call_name.clone())?;
+                    let mut env = Env::empty();
+                    let cases =
+                        env.bind_iter(call_args.to_vals().iter(), |env| cases.eval(ev, env))?;
+
+                    // Then we apply the body of the cocase to the `args`.
+                    ev.trace.in_decl(&call_name.id, || {
+                        beta_comatch(span, &cases, name, &args, ev, strategy)
+                    })
+                }
+                CallKind::LetBound => {
+                    // This case is unreachable because all let-bound calls have either already
+                    // been replaced by their body (if they are transparent), or they have been
+                    // turned into a neutral `OpaqueCall` if they are opaque.
+                    unreachable!()
+                }
             }
-            Val::Anno(_) => Err(TypeError::Impossible {
-                message: "Type annotation was not stripped when evaluating DotCall".to_owned(),
-                span: span.to_miette(),
-            }),
-            Val::TypCtor(_) => Err(TypeError::Impossible {
-                message: "Cannot apply DotCall to type constructor".to_owned(),
-                span: span.to_miette(),
-            }),
-            Val::TypeUniv(_) => Err(TypeError::Impossible {
-                message: "Cannot apply DotCall to type universe".to_owned(),
-                span: span.to_miette(),
-            }),
         }
+        Val::LocalComatch(val::LocalComatch { cases, .. }) => {
+            // The specific instance of the DotCall we are evaluating is:
+            //
+            // ```text
+            //  comatch { ... }.d(e_1,...)
+            //            ━┳━   ┳ ━━━┳━━━
+            //             ┃    ┃    ┗━━━━ args
+            //             ┃    ┗━━━━━━━━━ name
+            //             ┗━━━━━━━━━━━━━━ cases
+            // ```
+            //
+            // where `d` is the name of a destructor declared in a
+            // codata type.
+
+            // We select the correct case from the comatch and apply its body to the `args`.
+            beta_comatch(span, &cases, name, &args, ev, strategy)
+        }
+
+        Val::Neu(exp) => {
+            // The specific instance of the DotCall we are evaluating is:
+            //
+            // ```text
+            // n.d(e_1,...)
+            // ┳ ┳ ━━━┳━━━
+            // ┃ ┃    ┗━━━━━━━ args
+            // ┃ ┗━━━━━━━━━━━━ name
+            // ┗━━━━━━━━━━━━━━ exp (Neutral value)
+            // ```
+            // Evaluation is blocked by the neutral value `n`.
+            Ok(Box::new(Val::Neu(
+                val::DotCall { span, kind, exp: Box::new(exp), name: name.to_owned(), args }.into(),
+            )))
+        }
+        Val::Anno(_) => Err(TypeError::Impossible {
+            message: "Type annotation was not stripped when evaluating DotCall".to_owned(),
+            span: span.to_miette(),
+        }),
+        Val::TypCtor(_) => Err(TypeError::Impossible {
+            message: "Cannot apply DotCall to type constructor".to_owned(),
+            span: span.to_miette(),
+        }),
+        Val::TypeUniv(_) => Err(TypeError::Impossible {
+            message: "Cannot apply DotCall to type universe".to_owned(),
+            span: span.to_miette(),
+        }),
+        Val::Thunk(_) => Err(TypeError::Impossible {
+            message: "Thunk was not forced when evaluating DotCall".to_owned(),
+            span: span.to_miette(),
+        }),
     }
 }
 
+/// Whether the reduction of `call.name(..)` is blocked by an opaque toplevel definition `name`
+/// or an opaque toplevel codefinition `call`
+fn is_opaque_reduction(
+    call: &val::Call,
+    name: &IdBound,
+    ev: &Evaluator,
+) -> Result<bool, TypeError> {
+    let attr = match call.kind {
+        CallKind::Constructor => &ev.info_table.lookup_def(name)?.attr,
+        CallKind::Codefinition => &ev.info_table.lookup_codef(&call.name)?.attr,
+        CallKind::LetBound => return Ok(false),
+    };
+    Ok(delta::is_opaque(attr, ev.opaque_by_default))
+}
+
 /// Given a value, strip away all the annotations and return the inner value.
 /// Unless the inner value is neutral, in which case all annotations become neutral.
 /// For example, stripping the annotations from `((T : Bool): Bool)` would yield `T` because `T` is not neutral.
@@ -289,10 +417,10 @@ fn strip_annotations(val: &Val) -> Val {
 impl Eval for Anno {
     type Val = Box<Val>;
 
-    fn eval(&self, info_table: &Rc<TypeInfoTable>, env: &mut Env) -> Result<Self::Val, TypeError> {
+    fn eval(&self, ev: &Evaluator, env: &mut Env) -> Result<Self::Val, TypeError> {
         let Anno { span, exp, typ, normalized_type: _ } = self;
-        let exp = exp.eval(info_table, env)?;
-        let typ = typ.eval(info_table, env)?;
+        let exp = exp.eval(ev, env)?;
+        let typ = typ.eval(ev, env)?;
         Ok(Box::new(val::AnnoVal { span: *span, exp, typ }.into()))
     }
 }
@@ -300,11 +428,7 @@ impl Eval for Anno {
 impl Eval for TypeUniv {
     type Val = Box<Val>;
 
-    fn eval(
-        &self,
-        _info_table: &Rc<TypeInfoTable>,
-        _env: &mut Env,
-    ) -> Result<Self::Val, TypeError> {
+    fn eval(&self, _ev: &Evaluator, _env: &mut Env) -> Result<Self::Val, TypeError> {
         let TypeUniv { span } = self;
         Ok(Box::new(val::TypeUniv { span: *span }.into()))
     }
@@ -321,118 +445,196 @@ impl Eval for LocalMatch {
     /// ┃          ┗━━━━ cases
     /// ┗━━━━━━━━━━━━━━━ on_exp
     /// ```
-    fn eval(&self, info_table: &Rc<TypeInfoTable>, env: &mut Env) -> Result<Self::Val, TypeError> {
-        let LocalMatch { name: match_name, on_exp, cases, .. } = self;
+    fn eval(&self, ev: &Evaluator, env: &mut Env) -> Result<Self::Val, TypeError> {
+        let LocalMatch { span, name: match_name, on_exp, cases, .. } = self;
         // We first evaluate `on_exp` and `cases`
-        let on_exp = on_exp.eval(info_table, env)?;
-        let cases = cases.eval(info_table, env)?;
-
-        let on_exp = strip_annotations(&on_exp);
-
-        match on_exp {
-            Val::Call(val::Call { name: ctor_name, args, .. }) => {
-                // The specific instance of the LocalMatch we are evaluating is:
-                //
-                // ```text
-                // C(e_1,...).match { ... }
-                // ┳ ━━━┳━━━         ━━┳━━
-                // ┃    ┃              ┗━━━━━ cases
-                // ┃    ┗━━━━━━━━━━━━━━━━━━━━ args
-                // ┗━━━━━━━━━━━━━━━━━━━━━━━━━ ctor__name
-                // ```
-                // where `C` is the name of a constructor declared in a data
-                // type declaration.
-
-                // We first look up the correct case.
-                let val::Case { body, .. } = cases
-                    .clone()
-                    .into_iter()
-                    .find(|case| case.name == ctor_name)
-                    .ok_or_else(|| TypeError::MissingCase { name: ctor_name.id.clone() })?;
-
-                // Then we substitute the `args` in the body.
-                body.clone().unwrap().apply(info_table, &args.to_vals())
-            }
-            Val::Neu(exp) => {
-                // The specific instance of the LocalMatch we are evaluating is:
-                //
-                // ```text
-                // n.match { ... }
-                // ┳        ━━┳━━
-                // ┃          ┗━━━━━ cases
-                // ┗━━━━━━━━━━━━━━━━ exp (Neutral value)
-                // ```
-                // Evaluation is blocked by the neutral value `n`.
-                Ok(Box::new(Val::Neu(
-                    val::LocalMatch {
-                        span: None,
-                        name: match_name.to_owned(),
-                        on_exp: Box::new(exp),
-                        cases,
-                    }
-                    .into(),
-                )))
-            }
-            Val::TypCtor(typ_ctor) => Err(TypeError::Impossible {
-                message: "Cannot match on a type constructor".to_owned(),
-                span: typ_ctor.span.to_miette(),
-            }),
-            Val::TypeUniv(type_univ) => Err(TypeError::Impossible {
-                message: "Cannot match on a type universe".to_owned(),
-                span: type_univ.span.to_miette(),
-            }),
-            Val::LocalComatch(local_comatch) => Err(TypeError::Impossible {
-                message: "Cannot match on a local comatch".to_owned(),
-                span: local_comatch.span.to_miette(),
-            }),
-            Val::Anno(anno_val) => Err(TypeError::Impossible {
-                message: "Type annotation was not stripped when evaluating local match".to_owned(),
-                span: anno_val.span.to_miette(),
-            }),
+        let on_exp = on_exp.eval(ev, env)?;
+        let cases = cases.eval(ev, env)?;
+
+        beta_local_match(*span, match_name, on_exp, cases, ev, Strategy::Full)
+    }
+}
+
+/// Reduce the LocalMatch `on_exp.match { cases }`, where `on_exp` and `cases` have already
+/// been evaluated
+///
+/// The body of the selected case is evaluated using the given `strategy`.
+fn beta_local_match(
+    span: Option<Span>,
+    match_name: &Label,
+    on_exp: Box<Val>,
+    cases: Vec<val::Case>,
+    ev: &Evaluator,
+    strategy: Strategy,
+) -> Result<Box<Val>, TypeError> {
+    let on_exp = strip_annotations(&on_exp);
+
+    match on_exp {
+        Val::Call(val::Call { name: ctor_name, args, .. }) => {
+            // The specific instance of the LocalMatch we are evaluating is:
+            //
+            // ```text
+            // C(e_1,...).match { ... }
+            // ┳ ━━━┳━━━         ━━┳━━
+            // ┃    ┃              ┗━━━━━ cases
+            // ┃    ┗━━━━━━━━━━━━━━━━━━━━ args
+            // ┗━━━━━━━━━━━━━━━━━━━━━━━━━ ctor__name
+            // ```
+            // where `C` is the name of a constructor declared in a data
+            // type declaration.
+
+            // We look up the correct case and substitute the `args` in its body.
+            beta_match(span, &cases, &ctor_name, &args, ev, strategy)
+        }
+        Val::Neu(exp) => {
+            // The specific instance of the LocalMatch we are evaluating is:
+            //
+            // ```text
+            // n.match { ... }
+            // ┳        ━━┳━━
+            // ┃          ┗━━━━━ cases
+            // ┗━━━━━━━━━━━━━━━━ exp (Neutral value)
+            // ```
+            // Evaluation is blocked by the neutral value `n`.
+            Ok(Box::new(Val::Neu(
+                val::LocalMatch {
+                    span: None,
+                    name: match_name.to_owned(),
+                    on_exp: Box::new(exp),
+                    cases,
+                }
+                .into(),
+            )))
         }
+        Val::TypCtor(typ_ctor) => Err(TypeError::Impossible {
+            message: "Cannot match on a type constructor".to_owned(),
+            span: typ_ctor.span.to_miette(),
+        }),
+        Val::TypeUniv(type_univ) => Err(TypeError::Impossible {
+            message: "Cannot match on a type universe".to_owned(),
+            span: type_univ.span.to_miette(),
+        }),
+        Val::LocalComatch(local_comatch) => Err(TypeError::Impossible {
+            message: "Cannot match on a local comatch".to_owned(),
+            span: local_comatch.span.to_miette(),
+        }),
+        Val::Anno(anno_val) => Err(TypeError::Impossible {
+            message: "Type annotation was not stripped when evaluating local match".to_owned(),
+            span: anno_val.span.to_miette(),
+        }),
+        Val::Thunk(thunk) => Err(TypeError::Impossible {
+            message: "Thunk was not forced when evaluating local match".to_owned(),
+            span: thunk.exp.span().to_miette(),
+        }),
     }
 }
 
 impl Eval for LocalComatch {
     type Val = Box<Val>;
 
-    fn eval(&self, info_table: &Rc<TypeInfoTable>, env: &mut Env) -> Result<Self::Val, TypeError> {
+    fn eval(&self, ev: &Evaluator, env: &mut Env) -> Result<Self::Val, TypeError> {
         let LocalComatch { span, name, is_lambda_sugar, cases, .. } = self;
         Ok(Box::new(
             val::LocalComatch {
                 span: *span,
                 name: name.clone(),
                 is_lambda_sugar: *is_lambda_sugar,
-                cases: cases.eval(info_table, env)?,
+                cases: cases.eval(ev, env)?,
             }
             .into(),
         ))
     }
 }
 
+impl Eval for LocalLet {
+    type Val = Box<Val>;
+
+    /// The bound expression is evaluated according to the evaluation order, see [super::order]
+    fn eval(&self, ev: &Evaluator, env: &mut Env) -> Result<Self::Val, TypeError> {
+        let LocalLet { bound, body, .. } = self;
+        let bound = if ev.order == EvaluationOrder::CallByName {
+            Box::new(Val::from(val::Thunk { env: env.clone(), exp: bound.clone() }))
+        } else {
+            bound.eval(ev, env)?
+        };
+        env.bind_single(&bound, |env| body.eval(ev, env))
+    }
+}
+
 impl Eval for Hole {
     type Val = Box<Val>;
 
-    fn eval(&self, info_table: &Rc<TypeInfoTable>, env: &mut Env) -> Result<Self::Val, TypeError> {
-        let Hole { span, kind, metavar, args, .. } = self;
-        let args = args.eval(info_table, env)?;
+    fn eval(&self, ev: &Evaluator, env: &mut Env) -> Result<Self::Val, TypeError> {
+        let Hole { span, kind, name, metavar, args, .. } = self;
+        let args = args.eval(ev, env)?;
         Ok(Box::new(Val::Neu(
-            val::Hole { span: *span, kind: *kind, metavar: *metavar, args }.into(),
+            val::Hole { span: *span, kind: *kind, name: name.clone(), metavar: *metavar, args }
+                .into(),
         )))
     }
 }
 
+/// Select the case for the constructor `ctor_name` and apply its body to `args`
+fn beta_match(
+    span: Option<Span>,
+    cases: &[val::Case],
+    ctor_name: &IdBound,
+    args: &val::Args,
+    ev: &Evaluator,
+    strategy: Strategy,
+) -> Result<Box<Val>, TypeError> {
+    ev.fuel.consume(span)?;
+    let val::Case { body, .. } =
+        cases.iter().find(|case| case.name == *ctor_name).ok_or_else(|| {
+            TypeError::MissingCase { name: ctor_name.id.clone(), while_elaborating_span: None }
+        })?;
+    strategy.apply(body.clone().unwrap(), ev, &args.to_vals())
+}
+
+/// Select the cocase for the destructor `name` and apply its body to `args`
+fn beta_comatch(
+    span: Option<Span>,
+    cases: &[val::Case],
+    name: &IdBound,
+    args: &val::Args,
+    ev: &Evaluator,
+    strategy: Strategy,
+) -> Result<Box<Val>, TypeError> {
+    ev.fuel.consume(span)?;
+    let body = select_cocase(cases, name, ev)?;
+    strategy.apply(body.unwrap(), ev, &args.to_vals())
+}
+
+/// Select the body of the cocase for the destructor `name`
+///
+/// If there is no such cocase, the default implementation of the destructor is used instead.
+/// Since it only refers to the parameters of the destructor, it is evaluated in an empty
+/// environment.
+fn select_cocase(
+    cases: &[val::Case],
+    name: &IdBound,
+    ev: &Evaluator,
+) -> Result<Option<Closure>, TypeError> {
+    if let Some(val::Case { body, .. }) = cases.iter().find(|cocase| cocase.name == *name) {
+        return Ok(body.clone());
+    }
+    let DtorMeta { params, default, .. } = ev.info_table.lookup_dtor(name)?;
+    match default {
+        Some(default) => Ok(Some(Closure::new(Env::empty(), params.len(), default.clone()))),
+        None => {
+            Err(TypeError::MissingCocase { name: name.id.clone(), while_elaborating_span: None })
+        }
+    }
+}
+
 impl Eval for Case {
     type Val = val::Case;
 
-    fn eval(&self, _info_table: &Rc<TypeInfoTable>, env: &mut Env) -> Result<Self::Val, TypeError> {
+    fn eval(&self, _ev: &Evaluator, env: &mut Env) -> Result<Self::Val, TypeError> {
         let Case { span, pattern, body } = self;
 
-        let body = body.as_ref().map(|body| Closure {
-            body: body.clone(),
-            n_args: pattern.params.len(),
-            env: env.clone(),
-        });
+        let body =
+            body.as_ref().map(|body| Closure::new(env.clone(), pattern.params.len(), body.clone()));
 
         Ok(val::Case {
             span: *span,
@@ -444,52 +646,392 @@ impl Eval for Case {
     }
 }
 
+/// Evaluate the arguments of a call or dotcall according to the evaluation order, see [super::order]
+fn eval_args(args: &Args, ev: &Evaluator, env: &mut Env) -> Result<val::Args, TypeError> {
+    if ev.order == EvaluationOrder::CallByName {
+        delay_args(args, ev, env)
+    } else {
+        args.eval(ev, env)
+    }
+}
+
 impl Eval for Args {
     type Val = val::Args;
 
-    fn eval(&self, info_table: &Rc<TypeInfoTable>, env: &mut Env) -> Result<Self::Val, TypeError> {
-        Ok(val::Args(self.args.eval(info_table, env)?))
+    fn eval(&self, ev: &Evaluator, env: &mut Env) -> Result<Self::Val, TypeError> {
+        Ok(val::Args(self.args.eval(ev, env)?))
     }
 }
 
 impl Eval for Arg {
     type Val = val::Arg;
 
-    fn eval(&self, info_table: &Rc<TypeInfoTable>, env: &mut Env) -> Result<Self::Val, TypeError> {
+    fn eval(&self, ev: &Evaluator, env: &mut Env) -> Result<Self::Val, TypeError> {
         match self {
-            Arg::UnnamedArg(exp) => Ok(val::Arg::UnnamedArg(exp.eval(info_table, env)?)),
-            Arg::NamedArg(name, exp) => {
-                Ok(val::Arg::NamedArg(name.clone(), exp.eval(info_table, env)?))
-            }
+            Arg::UnnamedArg(exp) => Ok(val::Arg::UnnamedArg(exp.eval(ev, env)?)),
+            Arg::NamedArg(name, exp) => Ok(val::Arg::NamedArg(name.clone(), exp.eval(ev, env)?)),
             Arg::InsertedImplicitArg(hole) => {
-                Ok(val::Arg::InsertedImplicitArg(hole.eval(info_table, env)?))
+                Ok(val::Arg::InsertedImplicitArg(hole.eval(ev, env)?))
             }
         }
     }
 }
 
 impl Apply for Closure {
-    fn apply(
-        mut self,
-        info_table: &Rc<TypeInfoTable>,
-        args: &[Box<Val>],
-    ) -> Result<Box<Val>, TypeError> {
-        self.env.bind_iter(args.iter(), |env| self.body.eval(info_table, env))
+    fn apply(mut self, ev: &Evaluator, args: &[Box<Val>]) -> Result<Box<Val>, TypeError> {
+        let key = ev.memo.is_enabled().then(|| Closure::cache_key(args, ev)).flatten();
+        if let Some(res) = key.as_ref().and_then(|key| self.cache.borrow().get(key).cloned()) {
+            return Ok(res);
+        }
+        let res = self.env.bind_iter(args.iter(), |env| self.body.eval(ev, env))?;
+        if let Some(key) = key {
+            self.cache.borrow_mut().insert(key, res.clone());
+        }
+        Ok(res)
     }
 }
 
 impl<T: Eval> Eval for Vec<T> {
     type Val = Vec<T::Val>;
 
-    fn eval(&self, info_table: &Rc<TypeInfoTable>, env: &mut Env) -> Result<Self::Val, TypeError> {
-        self.iter().map(|x| x.eval(info_table, env)).collect()
+    fn eval(&self, ev: &Evaluator, env: &mut Env) -> Result<Self::Val, TypeError> {
+        self.iter().map(|x| x.eval(ev, env)).collect()
     }
 }
 
 impl Eval for Box<Exp> {
     type Val = Box<Val>;
 
-    fn eval(&self, info_table: &Rc<TypeInfoTable>, env: &mut Env) -> Result<Self::Val, TypeError> {
-        (**self).eval(info_table, env)
+    fn eval(&self, ev: &Evaluator, env: &mut Env) -> Result<Self::Val, TypeError> {
+        (**self).eval(ev, env)
+    }
+}
+
+/// Evaluate `exp` to weak head normal form
+///
+/// In contrast to [Eval::eval], only the head of `exp` is reduced.
+/// The arguments of constructors, codefinitions, type constructors and destructors are not
+/// evaluated, but wrapped in thunks which are only forced once they end up in head position.
+/// For example, the weak head normal form of `S(n.add(m))` is `S(...)`.
+pub fn eval_whnf(exp: &Exp, ev: &Evaluator, env: &mut Env) -> Result<Box<Val>, TypeError> {
+    match exp {
+        Exp::Variable(Variable { idx, .. }) => {
+            let val = env.lookup(*idx);
+            match &*val {
+                Val::Thunk(val::Thunk { env, exp }) => eval_whnf(exp, ev, &mut env.clone()),
+                _ => Ok(val),
+            }
+        }
+        Exp::TypCtor(TypCtor { span, name, args }) => {
+            let args = delay_args(args, ev, env)?;
+            Ok(Box::new(val::TypCtor { span: *span, name: name.clone(), args }.into()))
+        }
+        Exp::Call(Call { span, name, kind: CallKind::LetBound, args, .. }) => {
+            let Let { attr, body, .. } = ev.info_table.lookup_let(name)?;
+            let args = delay_args(args, ev, env)?;
+            if attr.attrs.contains(&Attribute::Transparent) {
+                ev.fuel.consume(*span)?;
+                ev.trace.in_decl(&name.id, || {
+                    env.bind_iter(args.to_vals().iter(), |env| eval_whnf(body, ev, env))
+                })
+            } else {
+                let call = val::OpaqueCall { span: *span, name: name.clone(), args };
+                Ok(Box::new(Val::Neu(call.into())))
+            }
+        }
+        Exp::Call(Call { span, name, kind, args, .. }) => {
+            let args = delay_args(args, ev, env)?;
+            let data = match kind {
+                CallKind::Constructor => {
+                    ev.info_table.lookup_ctor(name).ok().map(|meta| meta.typ.name.clone())
+                }
+                CallKind::Codefinition | CallKind::LetBound => None,
+            };
+            let call = val::Call { span: *span, kind: *kind, name: name.clone(), args, data };
+            Ok(Box::new(call.into()))
+        }
+        Exp::DotCall(DotCall { span, kind, exp, name, args, .. }) => {
+            let exp = eval_whnf(exp, ev, env)?;
+            let args = delay_args(args, ev, env)?;
+            beta_dot_call(*span, *kind, exp, name, args, ev, Strategy::Whnf)
+        }
+        Exp::Anno(Anno { span, exp, typ, .. }) => {
+            let exp = eval_whnf(exp, ev, env)?;
+            let typ = Box::new(val::Thunk { env: env.clone(), exp: typ.clone() }.into());
+            Ok(Box::new(val::AnnoVal { span: *span, exp, typ }.into()))
+        }
+        Exp::LocalMatch(LocalMatch { span, name, on_exp, cases, .. }) => {
+            let on_exp = eval_whnf(on_exp, ev, env)?;
+            let cases = cases.eval(ev, env)?;
+            beta_local_match(*span, name, on_exp, cases, ev, Strategy::Whnf)
+        }
+        Exp::LocalLet(LocalLet { bound, body, .. }) => {
+            let bound = Box::new(Val::from(val::Thunk { env: env.clone(), exp: bound.clone() }));
+            env.bind_single(&bound, |env| eval_whnf(body, ev, env))
+        }
+        Exp::TypeUniv(_) | Exp::LocalComatch(_) | Exp::Hole(_) => exp.eval(ev, env),
+    }
+}
+
+/// Wrap the arguments `args` in thunks without evaluating them
+///
+/// Inserted implicit arguments are holes, which are already in normal form.
+fn delay_args(args: &Args, ev: &Evaluator, env: &Env) -> Result<val::Args, TypeError> {
+    let thunk =
+        |exp: &Box<Exp>| Box::new(Val::from(val::Thunk { env: env.clone(), exp: exp.clone() }));
+    let args = args
+        .args
+        .iter()
+        .map(|arg| {
+            Ok(match arg {
+                Arg::UnnamedArg(exp) => val::Arg::UnnamedArg(thunk(exp)),
+                Arg::NamedArg(name, exp) => val::Arg::NamedArg(name.clone(), thunk(exp)),
+                Arg::InsertedImplicitArg(hole) => {
+                    val::Arg::InsertedImplicitArg(hole.eval(ev, &mut env.clone())?)
+                }
+            })
+        })
+        .collect::<Result<_, TypeError>>()?;
+    Ok(val::Args(args))
+}
+
+#[cfg(test)]
+mod apply_cache_tests {
+    use std::rc::Rc;
+
+    use ast::test_support::var;
+
+    use crate::normalizer::evaluator::EvalConfig;
+    use crate::normalizer::val::ReadBack;
+    use crate::TypeInfoTable;
+
+    use super::*;
+
+    fn neutral(snd: usize) -> Box<Val> {
+        Box::new(Val::Neu(
+            val::Variable { span: None, name: VarBound::from_string(""), idx: Idx { fst: 0, snd } }
+                .into(),
+        ))
+    }
+
+    #[test]
+    fn cached_application_agrees_with_uncached() {
+        let ev = Evaluator::new(Rc::new(TypeInfoTable::default()), &EvalConfig::default());
+        // The closure returns its first argument
+        let body = var("x", 0, 1);
+        let closure = Closure::new(Env::empty(), 2, body.clone());
+
+        for args in [[neutral(0), neutral(1)], [neutral(1), neutral(0)], [neutral(0), neutral(1)]] {
+            let cached = closure.clone().apply(&ev, &args).unwrap();
+            let uncached = Closure::new(Env::empty(), 2, body.clone()).apply(&ev, &args);
+            assert_eq!(cached.read_back(&ev).unwrap(), uncached.unwrap().read_back(&ev).unwrap());
+        }
+        assert_eq!(closure.cache.borrow().len(), 2);
+    }
+
+    #[test]
+    fn thunks_are_not_used_as_keys() {
+        let ev = Evaluator::new(Rc::new(TypeInfoTable::default()), &EvalConfig::default());
+        let thunk = Box::new(Val::Thunk(val::Thunk {
+            env: Env::empty(),
+            exp: Box::new(TypeUniv::new().into()),
+        }));
+        assert!(Closure::cache_key(&[thunk], &ev).is_none());
+        assert!(Closure::cache_key(&[neutral(0)], &ev).is_some());
+    }
+}
+
+#[cfg(test)]
+mod whnf_tests {
+    use std::rc::Rc;
+
+    use ast::test_support::{call, case, ctor, pattern};
+
+    use crate::normalizer::evaluator::EvalConfig;
+    use crate::TypeInfoTable;
+
+    use super::*;
+
+    /// `S(undefined)`, where evaluating `undefined` fails because it is not declared
+    fn succ_of_undefined() -> Box<Exp> {
+        let undefined = call(CallKind::LetBound, "undefined", vec![]);
+        ctor("S", vec![undefined])
+    }
+
+    fn assert_head(val: &Val, head: &str) {
+        let Val::Call(val::Call { name, args, .. }) = val else {
+            panic!("Expected a constructor, got {}", val.print_to_string(None));
+        };
+        assert_eq!(name.id, head);
+        assert!(args.to_vals().iter().all(|arg| matches!(**arg, Val::Thunk(_))));
+    }
+
+    #[test]
+    fn whnf_does_not_evaluate_ctor_args() {
+        let ev = Evaluator::new(Rc::new(TypeInfoTable::default()), &EvalConfig::default());
+        let exp = succ_of_undefined();
+        assert!(exp.eval(&ev, &mut Env::empty()).is_err());
+        let val = eval_whnf(&exp, &ev, &mut Env::empty()).unwrap();
+        assert_head(&val, "S");
+        assert_eq!(val.print_to_string(None), "S(...)");
+    }
+
+    /// `Z.match { Z => body }`
+    fn match_on_zero(body: Box<Exp>) -> Exp {
+        Exp::LocalMatch(LocalMatch {
+            span: None,
+            ctx: None,
+            name: Label { id: 0, user_name: None },
+            on_exp: ctor("Z", vec![]),
+            motive: None,
+            ret_typ: None,
+            cases: vec![case(pattern(false, "Z", &[]), body)],
+            inferred_type: None,
+        })
+    }
+
+    #[test]
+    fn whnf_reduces_match() {
+        let ev = Evaluator::new(Rc::new(TypeInfoTable::default()), &EvalConfig::default());
+        let exp = match_on_zero(succ_of_undefined());
+        let val = eval_whnf(&exp, &ev, &mut Env::empty()).unwrap();
+        assert_head(&val, "S");
+    }
+
+    #[test]
+    fn normalization_fails_without_fuel() {
+        use crate::normalizer::normalize::Normalize;
+
+        let evaluator = |fuel| {
+            let config = EvalConfig { fuel: Some(fuel), ..Default::default() };
+            Evaluator::new(Rc::new(TypeInfoTable::default()), &config)
+        };
+        let exp = match_on_zero(ctor("Z", vec![]));
+        let res = exp.normalize_in_empty_env(&evaluator(0));
+        assert!(matches!(res, Err(TypeError::FuelExhausted { limit: 0, .. })));
+        let res = exp.normalize_in_empty_env(&evaluator(1));
+        assert!(res.is_ok());
+    }
+}
+
+#[cfg(test)]
+mod call_by_name_tests {
+    use std::rc::Rc;
+
+    use ast::test_support::{call, case, dot_call, module, pattern, type_univ, uri};
+
+    use crate::normalizer::evaluator::EvalConfig;
+    use crate::normalizer::normalize::Normalize;
+    use crate::typechecker::type_info_table::build::build_type_info_table;
+    use crate::TypeInfoTable;
+
+    use super::*;
+
+    /// The call of the transparent let-bound definition `#[transparent] let loop: Type { loop }`
+    fn call_loop() -> Box<Exp> {
+        call(CallKind::LetBound, "loop", vec![])
+    }
+
+    fn config(order: EvaluationOrder) -> EvalConfig {
+        EvalConfig { fuel: Some(100), order, ..Default::default() }
+    }
+
+    fn info_table() -> Rc<TypeInfoTable> {
+        let tl_let = Let {
+            span: None,
+            doc: None,
+            name: IdBind::from_string("loop"),
+            attr: Attributes { attrs: vec![Attribute::Transparent] },
+            params: Telescope { params: vec![] },
+            typ: type_univ(),
+            body: call_loop(),
+        };
+        let mut info_table = TypeInfoTable::default();
+        info_table.insert(uri(), build_type_info_table(&module(vec![tl_let.into()])));
+        Rc::new(info_table)
+    }
+
+    /// `(\x. Type).ap(loop)`, whose argument diverges but is never used
+    fn const_type_of_loop() -> Box<Exp> {
+        let lambda = Box::new(Exp::LocalComatch(LocalComatch {
+            span: None,
+            ctx: None,
+            name: Label { id: 0, user_name: None },
+            is_lambda_sugar: true,
+            cases: vec![case(pattern(true, "ap", &["x"]), type_univ())],
+            inferred_type: None,
+        }));
+        dot_call(DotCallKind::Destructor, lambda, "ap", vec![call_loop()])
+    }
+
+    #[test]
+    fn unused_argument_loops_under_call_by_value() {
+        let ev = Evaluator::new(info_table(), &config(EvaluationOrder::CallByValue));
+        let exp = const_type_of_loop();
+        let res = exp.normalize_in_empty_env(&ev);
+        assert!(matches!(res, Err(TypeError::FuelExhausted { limit: 100, .. })));
+    }
+
+    #[test]
+    fn unused_argument_is_not_evaluated_under_call_by_name() {
+        let ev = Evaluator::new(info_table(), &config(EvaluationOrder::CallByName));
+        let exp = const_type_of_loop();
+        let res = exp.normalize_in_empty_env(&ev);
+        assert_eq!(res.unwrap().print_to_string(None), "Type");
+    }
+}
+
+#[cfg(test)]
+mod print_nat_lit_tests {
+    use std::rc::Rc;
+
+    use ast::test_support::{ctor, id, module, param, uri};
+
+    use crate::normalizer::evaluator::EvalConfig;
+    use crate::normalizer::val::ReadBack;
+    use crate::typechecker::type_info_table::build::build_type_info_table;
+    use crate::TypeInfoTable;
+
+    use super::*;
+
+    /// An evaluator for the module `data name { Z, S(n: name) }`
+    fn evaluator(name: &str) -> Evaluator {
+        let typ = TypCtor { span: None, name: id(name), args: Args { args: vec![] } };
+        let ctor = |ctor: &str, params: Vec<Param>| Ctor {
+            span: None,
+            doc: None,
+            name: IdBind::from_string(ctor),
+            params: Telescope { params },
+            typ: typ.clone(),
+        };
+        let data = Data {
+            span: None,
+            doc: None,
+            name: IdBind::from_string(name),
+            attr: Attributes { attrs: vec![] },
+            typ: Box::new(Telescope { params: vec![] }),
+            ctors: vec![ctor("Z", vec![]), ctor("S", vec![param("n", Box::new(typ.to_exp()))])],
+        };
+        let mut info_table = TypeInfoTable::default();
+        info_table.insert(uri(), build_type_info_table(&module(vec![data.into()])));
+        Evaluator::new(Rc::new(info_table), &EvalConfig::default())
+    }
+
+    /// Print `S(S(Z))` both as a value and as a normal form
+    fn print_two(ev: &Evaluator) -> (String, String) {
+        let val = ctor("S", vec![ctor("S", vec![ctor("Z", vec![])])]).eval(ev, &mut Env::empty());
+        let val = val.unwrap();
+        (val.print_to_string(None), val.read_back(ev).unwrap().print_to_string(None))
+    }
+
+    #[test]
+    fn print_nat() {
+        assert_eq!(print_two(&evaluator("Nat")), ("2".to_owned(), "2".to_owned()));
+    }
+
+    #[test]
+    fn print_other_type_with_nat_ctors() {
+        let expected = "S(S(Z))".to_owned();
+        assert_eq!(print_two(&evaluator("Peano")), (expected.clone(), expected));
     }
 }
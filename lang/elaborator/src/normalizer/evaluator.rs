@@ -0,0 +1,76 @@
+//! The configuration and state of a normalization
+//!
+//! Every normalization is performed by an [Evaluator], which is threaded through the evaluation
+//! and read back of all terms and values.
+//! Its state only lives as long as the evaluator itself, so it does not leak into later
+//! normalizations even if a normalization is aborted.
+
+use std::rc::Rc;
+
+use ast::HashSet;
+
+use crate::TypeInfoTable;
+
+use super::fuel::Fuel;
+use super::memo::Memo;
+use super::order::EvaluationOrder;
+use super::trace::Trace;
+
+/// How terms are normalized
+#[derive(Debug, Clone)]
+pub struct EvalConfig {
+    /// Names of the declarations whose evaluation is traced, or `None` if everything is traced,
+    /// see [super::trace]
+    pub trace_filter: Option<HashSet<String>>,
+    /// Number of evaluation steps each normalization may take, or `None` if it is unbounded,
+    /// see [super::fuel]
+    pub fuel: Option<u64>,
+    /// Whether evaluation results are memoized, see [super::memo]
+    pub memoize: bool,
+    /// Whether toplevel (co)definitions without an annotation are opaque, see [super::delta]
+    pub opaque_by_default: bool,
+    /// The order in which the arguments of calls are evaluated, see [super::order]
+    pub order: EvaluationOrder,
+}
+
+impl Default for EvalConfig {
+    fn default() -> Self {
+        Self {
+            trace_filter: None,
+            fuel: None,
+            memoize: true,
+            opaque_by_default: false,
+            order: EvaluationOrder::default(),
+        }
+    }
+}
+
+/// Performs one normalization, possibly consisting of several nested ones
+#[derive(Debug)]
+pub struct Evaluator {
+    /// Global lookup table for declarations
+    pub info_table: Rc<TypeInfoTable>,
+    pub(crate) trace: Trace,
+    pub(crate) fuel: Fuel,
+    pub(crate) memo: Memo,
+    pub(crate) opaque_by_default: bool,
+    pub(crate) order: EvaluationOrder,
+}
+
+impl Evaluator {
+    pub fn new(info_table: Rc<TypeInfoTable>, config: &EvalConfig) -> Self {
+        Self {
+            info_table,
+            trace: Trace::new(config.trace_filter.clone()),
+            fuel: Fuel::new(config.fuel),
+            memo: Memo::new(config.memoize),
+            opaque_by_default: config.opaque_by_default,
+            order: config.order,
+        }
+    }
+
+    /// Run `f`, which evaluates the body of the declaration `name`, see [super::trace]
+    pub fn in_decl<T>(&self, name: &str, f: impl FnOnce() -> T) -> T {
+        self.trace.in_decl(name, f)
+    }
+}
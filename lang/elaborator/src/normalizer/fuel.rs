@@ -0,0 +1,48 @@
+//! Bounding the number of evaluation steps
+//!
+//! The termination check does not cover let-bound definitions, mutual recursion or
+//! declarations marked `#[no_termination_check]`, so normalizing a term may not terminate.
+//! Every normalization is therefore given a budget of evaluation steps, its fuel.
+//! Each beta reduction of a match or comatch and each unfolding of a let-bound definition
+//! consumes one step. Once the fuel is exhausted, evaluation fails with
+//! [TypeError::FuelExhausted].
+
+use std::cell::Cell;
+
+use codespan::Span;
+use miette_util::ToMiette;
+
+use crate::result::TypeError;
+
+/// The evaluation steps which a normalization may still take
+#[derive(Debug)]
+pub(crate) struct Fuel {
+    /// Number of evaluation steps of the whole normalization, or `None` if it is unbounded
+    limit: Option<u64>,
+    /// Remaining evaluation steps, or `None` if they are unbounded
+    remaining: Cell<Option<u64>>,
+}
+
+impl Fuel {
+    pub(crate) fn new(limit: Option<u64>) -> Self {
+        Self { limit, remaining: Cell::new(limit) }
+    }
+
+    /// Consume one evaluation step of the expression at `span`
+    ///
+    /// Nested normalizations by the same evaluator share its budget.
+    pub(crate) fn consume(&self, span: Option<Span>) -> Result<(), TypeError> {
+        match self.remaining.get() {
+            None => Ok(()),
+            Some(0) => Err(TypeError::FuelExhausted {
+                limit: self.limit.unwrap_or_default(),
+                span: span.to_miette(),
+                while_elaborating_span: None,
+            }),
+            Some(n) => {
+                self.remaining.set(Some(n - 1));
+                Ok(())
+            }
+        }
+    }
+}
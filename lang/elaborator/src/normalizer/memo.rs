@@ -0,0 +1,128 @@
+//! Memoization of the reductions of toplevel definitions and codefinitions
+//!
+//! While evaluating a term, the results of reducing `C(args).d(args')` are memoized, so that
+//! structurally identical reductions, for example the overlapping recursive calls of a
+//! naive Fibonacci function, are only evaluated once.
+//! A reduction is keyed on the names of `C` and `d` and its read back arguments, which are only
+//! used as keys if they can be read back without further evaluation.
+//! The memoized results belong to the evaluator and are discarded together with it.
+//!
+//! Memoization is enabled by default, but can be disabled to compare the performance.
+//! This also disables the memoization of closure applications, see [super::val::Closure].
+
+use std::cell::RefCell;
+
+use ast::{Exp, HashMap, IdBound};
+
+use crate::result::TypeError;
+
+use super::evaluator::Evaluator;
+use super::val::{Args, IsFirstOrder, ReadBack, Val};
+
+/// The key of the reduction of `C(args).d(args')`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct Key {
+    /// The constructor or codefinition `C`
+    call: IdBound,
+    /// The definition or destructor `d`
+    dot_call: IdBound,
+    /// The read back arguments `args` followed by `args'`
+    args: Vec<Box<Exp>>,
+}
+
+impl Key {
+    /// The key of the reduction of `call(call_args).dot_call(args)`, if its arguments are
+    /// first-order
+    pub(crate) fn new(
+        call: &IdBound,
+        call_args: &Args,
+        dot_call: &IdBound,
+        args: &Args,
+        ev: &Evaluator,
+    ) -> Option<Key> {
+        if !ev.memo.is_enabled() {
+            return None;
+        }
+        if !call_args.is_first_order() || !args.is_first_order() {
+            return None;
+        }
+        let args = call_args
+            .to_vals()
+            .iter()
+            .chain(args.to_vals().iter())
+            .map(|arg| arg.read_back(ev).ok())
+            .collect::<Option<_>>()?;
+        Some(Key { call: call.clone(), dot_call: dot_call.clone(), args })
+    }
+}
+
+/// The memoized reductions of an evaluator
+#[derive(Debug)]
+pub(crate) struct Memo {
+    /// The memoized reductions, or `None` if memoization is disabled
+    table: Option<RefCell<HashMap<Key, Box<Val>>>>,
+}
+
+impl Memo {
+    pub(crate) fn new(enabled: bool) -> Self {
+        Self { table: enabled.then(RefCell::default) }
+    }
+
+    /// Whether evaluation results are memoized
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.table.is_some()
+    }
+
+    /// Look up the result of the reduction `key`, computing it with `f` if it is not memoized
+    /// yet
+    pub(crate) fn memoized(
+        &self,
+        key: Option<Key>,
+        f: impl FnOnce() -> Result<Box<Val>, TypeError>,
+    ) -> Result<Box<Val>, TypeError> {
+        let (Some(key), Some(table)) = (key, &self.table) else {
+            return f();
+        };
+        // The table must not be borrowed while `f` runs, since `f` may memoize reductions itself
+        let memoized = table.borrow().get(&key).cloned();
+        if let Some(res) = memoized {
+            return Ok(res);
+        }
+        let res = f()?;
+        table.borrow_mut().insert(key, res.clone());
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use ast::test_support;
+    use url::Url;
+
+    use crate::normalizer::evaluator::EvalConfig;
+    use crate::TypeInfoTable;
+
+    use super::*;
+
+    fn id(name: &str, uri: &str) -> IdBound {
+        IdBound { uri: Url::parse(uri).unwrap(), ..test_support::id(name) }
+    }
+
+    #[test]
+    fn keys_distinguish_modules() {
+        let ev = Evaluator::new(Rc::new(TypeInfoTable::default()), &EvalConfig::default());
+        let key = |uri| Key::new(&id("C", uri), &Args(vec![]), &id("d", uri), &Args(vec![]), &ev);
+        assert_eq!(key("inmemory:///a.pol"), key("inmemory:///a.pol"));
+        assert_ne!(key("inmemory:///a.pol"), key("inmemory:///b.pol"));
+    }
+
+    #[test]
+    fn disabled_memoization_has_no_keys() {
+        let config = EvalConfig { memoize: false, ..Default::default() };
+        let ev = Evaluator::new(Rc::new(TypeInfoTable::default()), &config);
+        let uri = "inmemory:///a.pol";
+        assert!(Key::new(&id("C", uri), &Args(vec![]), &id("d", uri), &Args(vec![]), &ev).is_none());
+    }
+}
@@ -1,4 +1,11 @@
+mod delta;
 pub mod env;
 mod eval;
+pub mod evaluator;
+mod fuel;
+mod memo;
 pub mod normalize;
+pub mod occurs;
+pub mod order;
+mod trace;
 pub mod val;
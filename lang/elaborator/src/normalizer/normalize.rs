@@ -1,25 +1,21 @@
-use std::rc::Rc;
+use ast::Exp;
 
-use crate::normalizer::val::ReadBack;
-use crate::{result::*, TypeInfoTable};
+use crate::normalizer::val::{ReadBack, Val};
+use crate::result::*;
 
 use super::env::Env;
 use super::eval::*;
+use super::evaluator::Evaluator;
+
+pub use super::eval::eval_whnf;
 
 pub trait Normalize {
     type Nf;
 
-    fn normalize(
-        &self,
-        info_table: &Rc<TypeInfoTable>,
-        env: &mut Env,
-    ) -> Result<Self::Nf, TypeError>;
-
-    fn normalize_in_empty_env(
-        &self,
-        info_table: &Rc<TypeInfoTable>,
-    ) -> Result<Self::Nf, TypeError> {
-        self.normalize(info_table, &mut Env::empty())
+    fn normalize(&self, ev: &Evaluator, env: &mut Env) -> Result<Self::Nf, TypeError>;
+
+    fn normalize_in_empty_env(&self, ev: &Evaluator) -> Result<Self::Nf, TypeError> {
+        self.normalize(ev, &mut Env::empty())
     }
 }
 
@@ -30,12 +26,13 @@ where
 {
     type Nf = <<T as Eval>::Val as ReadBack>::Nf;
 
-    fn normalize(
-        &self,
-        info_table: &Rc<TypeInfoTable>,
-        env: &mut Env,
-    ) -> Result<Self::Nf, TypeError> {
-        let val = self.eval(info_table, env)?;
-        val.read_back(info_table)
+    fn normalize(&self, ev: &Evaluator, env: &mut Env) -> Result<Self::Nf, TypeError> {
+        let val = self.eval(ev, env)?;
+        val.read_back(ev)
     }
 }
+
+/// Evaluate the closed expression `exp` to a value without reading it back
+pub fn eval_in_empty_env(exp: &Exp, ev: &Evaluator) -> Result<Box<Val>, TypeError> {
+    exp.eval(ev, &mut Env::empty())
+}
@@ -0,0 +1,218 @@
+//! Occurs checks for values and expressions
+//!
+//! Unification, the typechecker and the refactoring tools use these functions to decide whether
+//! a variable occurs in a term, so that they agree with the errors reported by the typechecker.
+
+use ast::ctx::{Context, LevelCtx};
+use ast::{Exp, FreeVariables, Idx, Lvl};
+
+use super::env::Env;
+use super::val::*;
+
+/// Whether the variable `idx` occurs in the value `val`
+///
+/// The closures and thunks contained in `val` are not evaluated.
+/// Instead, the variable occurs in a closure if it occurs in an entry of the environment
+/// which is referenced by the body of the closure.
+pub fn occurs(idx: Idx, val: &Val) -> bool {
+    val.occurs(idx)
+}
+
+/// Whether the variable `idx` occurs in the expression `exp`
+pub fn occurs_exp(idx: Idx, exp: &Exp) -> bool {
+    exp.free_vars().contains(&idx)
+}
+
+/// Whether the variable `lvl` occurs in the expression `exp`, which is well-scoped in `ctx`
+pub fn occurs_lvl(ctx: &LevelCtx, lvl: Lvl, exp: &Exp) -> bool {
+    exp.free_vars().into_iter().any(|idx| ctx.idx_to_lvl(idx) == lvl)
+}
+
+trait OccursVal {
+    fn occurs(&self, idx: Idx) -> bool;
+}
+
+impl<T: OccursVal> OccursVal for Box<T> {
+    fn occurs(&self, idx: Idx) -> bool {
+        (**self).occurs(idx)
+    }
+}
+
+impl<T: OccursVal> OccursVal for Vec<T> {
+    fn occurs(&self, idx: Idx) -> bool {
+        self.iter().any(|x| x.occurs(idx))
+    }
+}
+
+impl<T: OccursVal> OccursVal for Option<T> {
+    fn occurs(&self, idx: Idx) -> bool {
+        self.as_ref().is_some_and(|x| x.occurs(idx))
+    }
+}
+
+impl OccursVal for Val {
+    fn occurs(&self, idx: Idx) -> bool {
+        match self {
+            Val::TypCtor(TypCtor { args, .. }) | Val::Call(Call { args, .. }) => args.occurs(idx),
+            Val::TypeUniv(_) => false,
+            Val::LocalComatch(LocalComatch { cases, .. }) => cases.occurs(idx),
+            Val::Anno(AnnoVal { exp, typ, .. }) => exp.occurs(idx) || typ.occurs(idx),
+            Val::Neu(neu) => neu.occurs(idx),
+            Val::Thunk(Thunk { env, exp }) => occurs_in_env(idx, env, exp, 0),
+        }
+    }
+}
+
+impl OccursVal for Neu {
+    fn occurs(&self, idx: Idx) -> bool {
+        match self {
+            Neu::Variable(Variable { idx: other, .. }) => *other == idx,
+            Neu::DotCall(DotCall { exp, args, .. }) => exp.occurs(idx) || args.occurs(idx),
+            Neu::LocalMatch(LocalMatch { on_exp, cases, .. }) => {
+                on_exp.occurs(idx) || cases.occurs(idx)
+            }
+            Neu::Hole(Hole { args, .. }) => args.occurs(idx),
+            Neu::OpaqueCall(OpaqueCall { args, .. }) => args.occurs(idx),
+            Neu::OpaqueDotCall(OpaqueDotCall { exp, args, .. }) => {
+                exp.occurs(idx) || args.occurs(idx)
+            }
+            Neu::AnnoNeu(AnnoNeu { exp, typ, .. }) => exp.occurs(idx) || typ.occurs(idx),
+        }
+    }
+}
+
+impl OccursVal for Args {
+    fn occurs(&self, idx: Idx) -> bool {
+        self.0.occurs(idx)
+    }
+}
+
+impl OccursVal for Arg {
+    fn occurs(&self, idx: Idx) -> bool {
+        match self {
+            Arg::UnnamedArg(val) | Arg::NamedArg(_, val) | Arg::InsertedImplicitArg(val) => {
+                val.occurs(idx)
+            }
+        }
+    }
+}
+
+impl OccursVal for Case {
+    fn occurs(&self, idx: Idx) -> bool {
+        self.body.occurs(idx)
+    }
+}
+
+impl OccursVal for Closure {
+    fn occurs(&self, idx: Idx) -> bool {
+        // The body of the closure binds one telescope for the arguments of the closure
+        occurs_in_env(idx, &self.env, &self.body, 1)
+    }
+}
+
+/// Whether `idx` occurs in the entries of `env` which are referenced by `exp`
+///
+/// `exp` is evaluated in `env` extended by `binders` telescopes.
+fn occurs_in_env(idx: Idx, env: &Env, exp: &Exp, binders: usize) -> bool {
+    exp.free_vars()
+        .into_iter()
+        .filter(|var| var.fst >= binders)
+        .any(|var| env.lookup(Idx { fst: var.fst - binders, snd: var.snd }).occurs(idx))
+}
+
+#[cfg(test)]
+mod occurs_tests {
+    use ast::test_support::{case, id, pattern, var as exp_var};
+    use ast::VarBound;
+
+    use super::*;
+
+    fn var(fst: usize, snd: usize) -> Box<Val> {
+        Box::new(Val::Neu(
+            Variable { span: None, name: VarBound::from_string(""), idx: Idx { fst, snd } }.into(),
+        ))
+    }
+
+    /// A stuck match on `on_exp` with a single case, whose body is evaluated in `env`
+    fn local_match(on_exp: Neu, env: Env, body: Box<Exp>) -> Val {
+        Val::Neu(Neu::LocalMatch(LocalMatch {
+            span: None,
+            name: ast::Label { id: 0, user_name: None },
+            on_exp: Box::new(on_exp),
+            cases: vec![Case {
+                span: None,
+                is_copattern: false,
+                name: id("C"),
+                params: ast::TelescopeInst { params: vec![] },
+                body: Some(Closure::new(env, 1, body)),
+            }],
+        }))
+    }
+
+    fn scrutinee() -> Neu {
+        Variable { span: None, name: VarBound::from_string(""), idx: Idx { fst: 2, snd: 0 } }.into()
+    }
+
+    #[test]
+    fn occurs_variable() {
+        assert!(occurs(Idx { fst: 0, snd: 1 }, &var(0, 1)));
+        assert!(!occurs(Idx { fst: 0, snd: 0 }, &var(0, 1)));
+    }
+
+    #[test]
+    fn occurs_in_case_body() {
+        // The environment maps the variable 0.0 to the value of the variable 1.0.
+        // Within the case body, the binder of the case shifts the environment entry to 1.0.
+        let env = Env::from_vec(vec![vec![var(1, 0)]]);
+        let val = local_match(scrutinee(), env, exp_var("x", 1, 0));
+        assert!(occurs(Idx { fst: 1, snd: 0 }, &val));
+        assert!(!occurs(Idx { fst: 0, snd: 0 }, &val));
+    }
+
+    #[test]
+    fn occurs_not_in_case_binder() {
+        // The case body only refers to the argument of the case, which is bound by the closure
+        let env = Env::from_vec(vec![vec![var(1, 0)]]);
+        let val = local_match(scrutinee(), env, exp_var("x", 0, 0));
+        assert!(!occurs(Idx { fst: 1, snd: 0 }, &val));
+        assert!(!occurs(Idx { fst: 0, snd: 0 }, &val));
+    }
+
+    #[test]
+    fn occurs_in_scrutinee() {
+        let val = local_match(scrutinee(), Env::empty(), exp_var("x", 0, 0));
+        assert!(occurs(Idx { fst: 2, snd: 0 }, &val));
+    }
+
+    #[test]
+    fn occurs_exp_variable() {
+        assert!(occurs_exp(Idx { fst: 1, snd: 0 }, &exp_var("x", 1, 0)));
+        assert!(!occurs_exp(Idx { fst: 0, snd: 0 }, &exp_var("x", 1, 0)));
+    }
+
+    /// `a.match { C(y) => body }` in the context `a, b`
+    fn exp_local_match(body: Box<Exp>) -> Exp {
+        Exp::LocalMatch(ast::LocalMatch {
+            span: None,
+            ctx: None,
+            name: ast::Label { id: 0, user_name: None },
+            on_exp: exp_var("a", 0, 1),
+            motive: None,
+            ret_typ: None,
+            cases: vec![case(pattern(false, "C", &["y"]), body)],
+            inferred_type: None,
+        })
+    }
+
+    #[test]
+    fn occurs_lvl_in_case_body() {
+        let ctx = LevelCtx::from(vec![2]);
+        // Within the case body, the binder of the case shifts `b` from 0.0 to 1.0
+        let exp = exp_local_match(exp_var("b", 1, 0));
+        assert!(occurs_lvl(&ctx, Lvl { fst: 0, snd: 0 }, &exp));
+        assert!(occurs_lvl(&ctx, Lvl { fst: 0, snd: 1 }, &exp));
+        let exp = exp_local_match(exp_var("y", 0, 0));
+        assert!(occurs_lvl(&ctx, Lvl { fst: 0, snd: 0 }, &exp));
+        assert!(!occurs_lvl(&ctx, Lvl { fst: 0, snd: 1 }, &exp));
+    }
+}
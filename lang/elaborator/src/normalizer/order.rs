@@ -0,0 +1,18 @@
+//! The order in which the arguments of calls are evaluated
+//!
+//! Under call-by-value, which is the default, the arguments of calls and dotcalls are
+//! evaluated before the call is reduced.
+//! Under call-by-name, they are wrapped in thunks instead, which are forced whenever the
+//! variable bound to the argument is looked up. Arguments which are never used are thus never
+//! evaluated, so a term with a diverging but unused argument still has a normal form.
+//! Thunks are not shared, so an argument which is used twice is also evaluated twice.
+
+/// The order in which the arguments of calls are evaluated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EvaluationOrder {
+    /// Evaluate arguments before the call is reduced
+    #[default]
+    CallByValue,
+    /// Evaluate arguments only when they are used
+    CallByName,
+}
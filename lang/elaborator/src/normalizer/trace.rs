@@ -0,0 +1,40 @@
+//! Scoping of evaluation traces to individual declarations
+//!
+//! By default, every evaluation step is traced. If a filter is set, only those evaluation steps
+//! are traced which happen while evaluating the body of one of the declarations in the filter.
+
+use std::cell::Cell;
+
+use ast::HashSet;
+
+/// Which evaluation steps of a normalization are traced
+#[derive(Debug)]
+pub(crate) struct Trace {
+    /// Names of the declarations whose evaluation is traced, or `None` if everything is traced
+    filter: Option<HashSet<String>>,
+    /// Number of evaluations of traced declarations which are currently in progress
+    depth: Cell<usize>,
+}
+
+impl Trace {
+    pub(crate) fn new(filter: Option<HashSet<String>>) -> Self {
+        Self { filter, depth: Cell::new(0) }
+    }
+
+    /// Whether evaluation steps should currently be traced
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.filter.is_none() || self.depth.get() > 0
+    }
+
+    /// Run `f`, which evaluates the body of the declaration `name`
+    pub(crate) fn in_decl<T>(&self, name: &str, f: impl FnOnce() -> T) -> T {
+        let is_traced = self.filter.as_ref().is_some_and(|filter| filter.contains(name));
+        if !is_traced {
+            return f();
+        }
+        self.depth.set(self.depth.get() + 1);
+        let res = f();
+        self.depth.set(self.depth.get() - 1);
+        res
+    }
+}
@@ -1,13 +1,16 @@
+use std::cell::RefCell;
 use std::rc::Rc;
 
 use ast;
 use ast::ctx::BindContext;
 use ast::shift_and_clone;
+use ast::HashMap;
 use ast::Idx;
 use ast::MetaVar;
 use ast::Shift;
 use ast::ShiftRange;
 use ast::ShiftRangeExt;
+use ast::SubstUnderCtx;
 use ast::VarBound;
 use codespan::Span;
 use log::trace;
@@ -19,7 +22,8 @@ use printer::types::*;
 use printer::util::*;
 
 use crate::normalizer::env::*;
-use crate::TypeInfoTable;
+use crate::normalizer::evaluator::Evaluator;
+use crate::typechecker::type_info_table::CtorMeta;
 
 use super::eval::Eval;
 use crate::result::*;
@@ -44,30 +48,30 @@ fn print_cases<'a>(cases: &'a [Case], cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> B
 pub trait ReadBack {
     type Nf;
 
-    fn read_back(&self, info_table: &Rc<TypeInfoTable>) -> Result<Self::Nf, TypeError>;
+    fn read_back(&self, ev: &Evaluator) -> Result<Self::Nf, TypeError>;
 }
 
 impl<T: ReadBack> ReadBack for Vec<T> {
     type Nf = Vec<T::Nf>;
 
-    fn read_back(&self, info_table: &Rc<TypeInfoTable>) -> Result<Self::Nf, TypeError> {
-        self.iter().map(|x| x.read_back(info_table)).collect()
+    fn read_back(&self, ev: &Evaluator) -> Result<Self::Nf, TypeError> {
+        self.iter().map(|x| x.read_back(ev)).collect()
     }
 }
 
 impl<T: ReadBack> ReadBack for Box<T> {
     type Nf = Box<T::Nf>;
 
-    fn read_back(&self, info_table: &Rc<TypeInfoTable>) -> Result<Self::Nf, TypeError> {
-        (**self).read_back(info_table).map(Box::new)
+    fn read_back(&self, ev: &Evaluator) -> Result<Self::Nf, TypeError> {
+        (**self).read_back(ev).map(Box::new)
     }
 }
 
 impl<T: ReadBack> ReadBack for Option<T> {
     type Nf = Option<T::Nf>;
 
-    fn read_back(&self, info_table: &Rc<TypeInfoTable>) -> Result<Self::Nf, TypeError> {
-        self.as_ref().map(|x| x.read_back(info_table)).transpose()
+    fn read_back(&self, ev: &Evaluator) -> Result<Self::Nf, TypeError> {
+        self.as_ref().map(|x| x.read_back(ev)).transpose()
     }
 }
 
@@ -85,6 +89,8 @@ pub enum Val {
     LocalComatch(LocalComatch),
     Anno(AnnoVal),
     Neu(Neu),
+    // A thunk only occurs as an argument which has not been evaluated yet.
+    Thunk(Thunk),
 }
 
 impl Shift for Val {
@@ -96,6 +102,7 @@ impl Shift for Val {
             Val::LocalComatch(e) => e.shift_in_range(range, by),
             Val::Anno(e) => e.shift_in_range(range, by),
             Val::Neu(exp) => exp.shift_in_range(range, by),
+            Val::Thunk(e) => e.shift_in_range(range, by),
         }
     }
 }
@@ -109,6 +116,7 @@ impl Print for Val {
             Val::LocalComatch(e) => e.print(cfg, alloc),
             Val::Anno(e) => e.print(cfg, alloc),
             Val::Neu(exp) => exp.print(cfg, alloc),
+            Val::Thunk(e) => e.print(cfg, alloc),
         }
     }
 }
@@ -116,16 +124,19 @@ impl Print for Val {
 impl ReadBack for Val {
     type Nf = ast::Exp;
 
-    fn read_back(&self, info_table: &Rc<TypeInfoTable>) -> Result<Self::Nf, TypeError> {
+    fn read_back(&self, ev: &Evaluator) -> Result<Self::Nf, TypeError> {
         let res = match self {
-            Val::TypCtor(e) => e.read_back(info_table)?.into(),
-            Val::Call(e) => e.read_back(info_table)?.into(),
-            Val::TypeUniv(e) => e.read_back(info_table)?.into(),
-            Val::LocalComatch(e) => e.read_back(info_table)?.into(),
-            Val::Anno(e) => e.read_back(info_table)?.into(),
-            Val::Neu(exp) => exp.read_back(info_table)?,
+            Val::TypCtor(e) => e.read_back(ev)?.into(),
+            Val::Call(e) => e.read_back(ev)?.into(),
+            Val::TypeUniv(e) => e.read_back(ev)?.into(),
+            Val::LocalComatch(e) => e.read_back(ev)?.into(),
+            Val::Anno(e) => e.read_back(ev)?.into(),
+            Val::Neu(exp) => exp.read_back(ev)?,
+            Val::Thunk(e) => e.read_back(ev)?,
         };
-        trace!("↓{} ~> {}", self.print_trace(), res.print_trace());
+        if ev.trace.is_enabled() {
+            trace!("↓{} ~> {}", self.print_trace(), res.print_trace());
+        }
         Ok(res)
     }
 }
@@ -164,12 +175,12 @@ impl From<TypCtor> for Val {
 impl ReadBack for TypCtor {
     type Nf = ast::TypCtor;
 
-    fn read_back(&self, info_table: &Rc<TypeInfoTable>) -> Result<Self::Nf, TypeError> {
+    fn read_back(&self, ev: &Evaluator) -> Result<Self::Nf, TypeError> {
         let TypCtor { span, name, args } = self;
         Ok(ast::TypCtor {
             span: *span,
             name: name.clone(),
-            args: ast::Args { args: args.read_back(info_table)? },
+            args: ast::Args { args: args.read_back(ev)? },
         })
     }
 }
@@ -184,6 +195,8 @@ pub struct Call {
     pub kind: ast::CallKind,
     pub name: ast::IdBound,
     pub args: Args,
+    /// The data type which declares the constructor, if it is known
+    pub data: Option<ast::IdBound>,
 }
 
 impl Shift for Call {
@@ -192,9 +205,42 @@ impl Shift for Call {
     }
 }
 
+impl Call {
+    /// The natural number denoted by `self`, if it is of the form `S(...S(Z))`
+    ///
+    /// `S` and `Z` have to be constructors of the data type `Nat` declared in the same module.
+    fn as_nat_lit(&self) -> Option<u64> {
+        let mut n: u64 = 0;
+        let mut call = self;
+        loop {
+            let is_nat = call
+                .data
+                .as_ref()
+                .is_some_and(|data| data.id == "Nat" && data.uri == self.name.uri);
+            if call.kind != ast::CallKind::Constructor || !is_nat {
+                return None;
+            }
+            match (call.name.id.as_str(), call.args.0.as_slice()) {
+                ("Z", []) => return Some(n),
+                ("S", [Arg::UnnamedArg(pred)]) => {
+                    let Val::Call(pred) = &**pred else {
+                        return None;
+                    };
+                    n = n.checked_add(1)?;
+                    call = pred;
+                }
+                _ => return None,
+            }
+        }
+    }
+}
+
 impl Print for Call {
     fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
-        let Call { span: _, kind: _, name, args } = self;
+        if let Some(n) = self.as_nat_lit().filter(|_| cfg.print_nat_sugar) {
+            return alloc.text(n.to_string());
+        }
+        let Call { span: _, kind: _, name, args, data: _ } = self;
         let psubst = if args.is_empty() { alloc.nil() } else { args.print(cfg, alloc).parens() };
         alloc.ctor(&name.id).append(psubst)
     }
@@ -209,15 +255,22 @@ impl From<Call> for Val {
 impl ReadBack for Call {
     type Nf = ast::Call;
 
-    fn read_back(&self, info_table: &Rc<TypeInfoTable>) -> Result<Self::Nf, TypeError> {
-        let Call { span, kind, name, args } = self;
-        Ok(ast::Call {
-            span: *span,
-            kind: *kind,
-            name: name.clone(),
-            args: ast::Args { args: args.read_back(info_table)? },
-            inferred_type: None,
-        })
+    fn read_back(&self, ev: &Evaluator) -> Result<Self::Nf, TypeError> {
+        let Call { span, kind, name, args, data: _ } = self;
+        let args = ast::Args { args: args.read_back(ev)? };
+        // The type of a constructor is its declared type, instantiated with its arguments
+        let inferred_type = match kind {
+            ast::CallKind::Constructor => {
+                ev.info_table.lookup_ctor(name).ok().map(|CtorMeta { params, typ }| {
+                    Box::new(
+                        typ.subst_under_ctx(vec![params.len()].into(), &vec![args.args.clone()])
+                            .to_exp(),
+                    )
+                })
+            }
+            ast::CallKind::Codefinition | ast::CallKind::LetBound => None,
+        };
+        Ok(ast::Call { span: *span, kind: *kind, name: name.clone(), args, inferred_type })
     }
 }
 
@@ -249,7 +302,7 @@ impl From<TypeUniv> for Val {
 impl ReadBack for TypeUniv {
     type Nf = ast::TypeUniv;
 
-    fn read_back(&self, _info_table: &Rc<TypeInfoTable>) -> Result<Self::Nf, TypeError> {
+    fn read_back(&self, _ev: &Evaluator) -> Result<Self::Nf, TypeError> {
         let TypeUniv { span } = self;
         Ok(ast::TypeUniv { span: *span })
     }
@@ -293,14 +346,14 @@ impl From<LocalComatch> for Val {
 
 impl ReadBack for LocalComatch {
     type Nf = ast::LocalComatch;
-    fn read_back(&self, info_table: &Rc<TypeInfoTable>) -> Result<Self::Nf, TypeError> {
+    fn read_back(&self, ev: &Evaluator) -> Result<Self::Nf, TypeError> {
         let LocalComatch { span, name, is_lambda_sugar, cases } = self;
         Ok(ast::LocalComatch {
             span: *span,
             ctx: None,
             name: name.clone(),
             is_lambda_sugar: *is_lambda_sugar,
-            cases: cases.read_back(info_table)?,
+            cases: cases.read_back(ev)?,
             inferred_type: None,
         })
     }
@@ -340,12 +393,12 @@ impl From<AnnoVal> for Val {
 impl ReadBack for AnnoVal {
     type Nf = ast::Anno;
 
-    fn read_back(&self, info_table: &Rc<TypeInfoTable>) -> Result<Self::Nf, TypeError> {
+    fn read_back(&self, ev: &Evaluator) -> Result<Self::Nf, TypeError> {
         let AnnoVal { span, exp, typ } = self;
-        let typ_nf = typ.read_back(info_table)?;
+        let typ_nf = typ.read_back(ev)?;
         Ok(ast::Anno {
             span: *span,
-            exp: exp.read_back(info_table)?,
+            exp: exp.read_back(ev)?,
             typ: typ_nf.clone(),
             normalized_type: Some(typ_nf),
         })
@@ -366,6 +419,9 @@ pub enum Neu {
     /// A call which corresponds to an opaque let-bound definition on the toplevel
     /// cannot be inlined and must therefore block computation.
     OpaqueCall(OpaqueCall),
+    /// A DotCall which corresponds to an opaque toplevel definition or codefinition
+    /// cannot be unfolded and must therefore block computation.
+    OpaqueDotCall(OpaqueDotCall),
     AnnoNeu(AnnoNeu),
 }
 
@@ -377,6 +433,7 @@ impl Shift for Neu {
             Neu::LocalMatch(e) => e.shift_in_range(range, by),
             Neu::Hole(e) => e.shift_in_range(range, by),
             Neu::OpaqueCall(e) => e.shift_in_range(range, by),
+            Neu::OpaqueDotCall(e) => e.shift_in_range(range, by),
             Neu::AnnoNeu(e) => e.shift_in_range(range, by),
         }
     }
@@ -390,6 +447,7 @@ impl Print for Neu {
             Neu::LocalMatch(e) => e.print(cfg, alloc),
             Neu::Hole(e) => e.print(cfg, alloc),
             Neu::OpaqueCall(e) => e.print(cfg, alloc),
+            Neu::OpaqueDotCall(e) => e.print(cfg, alloc),
             Neu::AnnoNeu(e) => e.print(cfg, alloc),
         }
     }
@@ -404,14 +462,15 @@ impl From<Neu> for Val {
 impl ReadBack for Neu {
     type Nf = ast::Exp;
 
-    fn read_back(&self, info_table: &Rc<TypeInfoTable>) -> Result<Self::Nf, TypeError> {
+    fn read_back(&self, ev: &Evaluator) -> Result<Self::Nf, TypeError> {
         let res = match self {
-            Neu::Variable(e) => e.read_back(info_table)?.into(),
-            Neu::DotCall(e) => e.read_back(info_table)?.into(),
-            Neu::LocalMatch(e) => e.read_back(info_table)?.into(),
-            Neu::Hole(e) => e.read_back(info_table)?.into(),
-            Neu::OpaqueCall(e) => e.read_back(info_table)?.into(),
-            Neu::AnnoNeu(e) => e.read_back(info_table)?.into(),
+            Neu::Variable(e) => e.read_back(ev)?.into(),
+            Neu::DotCall(e) => e.read_back(ev)?.into(),
+            Neu::LocalMatch(e) => e.read_back(ev)?.into(),
+            Neu::Hole(e) => e.read_back(ev)?.into(),
+            Neu::OpaqueCall(e) => e.read_back(ev)?.into(),
+            Neu::OpaqueDotCall(e) => e.read_back(ev)?.into(),
+            Neu::AnnoNeu(e) => e.read_back(ev)?.into(),
         };
         Ok(res)
     }
@@ -450,7 +509,7 @@ impl From<Variable> for Neu {
 impl ReadBack for Variable {
     type Nf = ast::Variable;
 
-    fn read_back(&self, _info_table: &Rc<TypeInfoTable>) -> Result<Self::Nf, TypeError> {
+    fn read_back(&self, _ev: &Evaluator) -> Result<Self::Nf, TypeError> {
         let Variable { span, name, idx } = self;
         Ok(ast::Variable { span: *span, idx: *idx, name: name.clone(), inferred_type: None })
     }
@@ -493,14 +552,14 @@ impl From<DotCall> for Neu {
 impl ReadBack for DotCall {
     type Nf = ast::DotCall;
 
-    fn read_back(&self, info_table: &Rc<TypeInfoTable>) -> Result<Self::Nf, TypeError> {
+    fn read_back(&self, ev: &Evaluator) -> Result<Self::Nf, TypeError> {
         let DotCall { span, kind, exp, name, args } = self;
         Ok(ast::DotCall {
             span: *span,
             kind: *kind,
-            exp: exp.read_back(info_table)?,
+            exp: exp.read_back(ev)?,
             name: name.clone(),
-            args: ast::Args { args: args.read_back(info_table)? },
+            args: ast::Args { args: args.read_back(ev)? },
             inferred_type: None,
         })
     }
@@ -548,7 +607,7 @@ impl From<LocalMatch> for Neu {
 impl ReadBack for LocalMatch {
     type Nf = ast::LocalMatch;
 
-    fn read_back(&self, info_table: &Rc<TypeInfoTable>) -> Result<Self::Nf, TypeError> {
+    fn read_back(&self, ev: &Evaluator) -> Result<Self::Nf, TypeError> {
         let LocalMatch { span, name, on_exp, cases } = self;
         Ok(ast::LocalMatch {
             span: *span,
@@ -556,8 +615,8 @@ impl ReadBack for LocalMatch {
             motive: None,
             ret_typ: None,
             name: name.clone(),
-            on_exp: on_exp.read_back(info_table)?,
-            cases: cases.read_back(info_table)?,
+            on_exp: on_exp.read_back(ev)?,
+            cases: cases.read_back(ev)?,
             inferred_type: None,
         })
     }
@@ -571,6 +630,8 @@ impl ReadBack for LocalMatch {
 pub struct Hole {
     pub span: Option<Span>,
     pub kind: ast::MetaVarKind,
+    /// The name of a named hole `?name`
+    pub name: Option<String>,
     pub metavar: MetaVar,
     /// Explicit substitution of the context, compare documentation of ast::Hole
     pub args: Vec<Vec<Box<Val>>>,
@@ -584,10 +645,10 @@ impl Shift for Hole {
 
 impl Print for Hole {
     fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
-        if cfg.print_metavar_ids {
-            alloc.text(format!("?{}", self.metavar.id))
-        } else {
-            alloc.keyword(QUESTION_MARK)
+        match &self.name {
+            _ if cfg.print_metavar_ids => alloc.text(format!("?{}", self.metavar.id)),
+            Some(name) => alloc.keyword(QUESTION_MARK).append(alloc.keyword(name)),
+            None => alloc.keyword(QUESTION_MARK),
         }
     }
 }
@@ -601,12 +662,13 @@ impl From<Hole> for Neu {
 impl ReadBack for Hole {
     type Nf = ast::Hole;
 
-    fn read_back(&self, info_table: &Rc<TypeInfoTable>) -> Result<Self::Nf, TypeError> {
-        let Hole { span, kind, metavar, args } = self;
-        let args = args.read_back(info_table)?;
+    fn read_back(&self, ev: &Evaluator) -> Result<Self::Nf, TypeError> {
+        let Hole { span, kind, name, metavar, args } = self;
+        let args = args.read_back(ev)?;
         Ok(ast::Hole {
             span: *span,
             kind: *kind,
+            name: name.clone(),
             metavar: *metavar,
             inferred_type: None,
             inferred_ctx: None,
@@ -662,7 +724,7 @@ impl Print for Case {
 impl ReadBack for Case {
     type Nf = ast::Case;
 
-    fn read_back(&self, info_table: &Rc<TypeInfoTable>) -> Result<Self::Nf, TypeError> {
+    fn read_back(&self, ev: &Evaluator) -> Result<Self::Nf, TypeError> {
         let Case { span, is_copattern, name, params, body } = self;
 
         Ok(ast::Case {
@@ -672,7 +734,7 @@ impl ReadBack for Case {
                 name: name.clone(),
                 params: params.clone(),
             },
-            body: body.read_back(info_table)?,
+            body: body.read_back(ev)?,
         })
     }
 }
@@ -711,13 +773,63 @@ impl From<OpaqueCall> for Neu {
 impl ReadBack for OpaqueCall {
     type Nf = ast::Call;
 
-    fn read_back(&self, info_table: &Rc<TypeInfoTable>) -> Result<Self::Nf, TypeError> {
+    fn read_back(&self, ev: &Evaluator) -> Result<Self::Nf, TypeError> {
         let OpaqueCall { span, name, args } = self;
         Ok(ast::Call {
             span: *span,
             kind: ast::CallKind::LetBound,
             name: name.clone(),
-            args: ast::Args { args: args.read_back(info_table)? },
+            args: ast::Args { args: args.read_back(ev)? },
+            inferred_type: None,
+        })
+    }
+}
+
+// OpaqueDotCall
+//
+//
+
+#[derive(Debug, Clone)]
+pub struct OpaqueDotCall {
+    pub span: Option<Span>,
+    pub kind: ast::DotCallKind,
+    pub exp: Box<Val>,
+    pub name: ast::IdBound,
+    pub args: Args,
+}
+
+impl Shift for OpaqueDotCall {
+    fn shift_in_range<R: ShiftRange>(&mut self, range: &R, by: (isize, isize)) {
+        self.exp.shift_in_range(range, by);
+        self.args.shift_in_range(range, by);
+    }
+}
+
+impl Print for OpaqueDotCall {
+    fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
+        let OpaqueDotCall { span: _, kind: _, exp, name, args } = self;
+        let psubst = if args.is_empty() { alloc.nil() } else { args.print(cfg, alloc).parens() };
+        exp.print(cfg, alloc).append(DOT).append(alloc.dtor(&name.id)).append(psubst)
+    }
+}
+
+impl From<OpaqueDotCall> for Neu {
+    fn from(value: OpaqueDotCall) -> Self {
+        Neu::OpaqueDotCall(value)
+    }
+}
+
+impl ReadBack for OpaqueDotCall {
+    type Nf = ast::DotCall;
+
+    fn read_back(&self, ev: &Evaluator) -> Result<Self::Nf, TypeError> {
+        let OpaqueDotCall { span, kind, exp, name, args } = self;
+        Ok(ast::DotCall {
+            span: *span,
+            kind: *kind,
+            exp: exp.read_back(ev)?,
+            name: name.clone(),
+            args: ast::Args { args: args.read_back(ev)? },
             inferred_type: None,
         })
     }
@@ -755,12 +867,12 @@ impl From<AnnoNeu> for Neu {
 impl ReadBack for AnnoNeu {
     type Nf = ast::Anno;
 
-    fn read_back(&self, info_table: &Rc<TypeInfoTable>) -> Result<Self::Nf, TypeError> {
+    fn read_back(&self, ev: &Evaluator) -> Result<Self::Nf, TypeError> {
         let AnnoNeu { span, exp, typ } = self;
-        let typ_nf = typ.read_back(info_table)?;
+        let typ_nf = typ.read_back(ev)?;
         Ok(ast::Anno {
             span: *span,
-            exp: exp.read_back(info_table)?,
+            exp: exp.read_back(ev)?,
             typ: typ_nf.clone(),
             normalized_type: Some(typ_nf),
         })
@@ -797,8 +909,8 @@ impl Shift for Args {
 impl ReadBack for Args {
     type Nf = Vec<ast::Arg>;
 
-    fn read_back(&self, info_table: &Rc<TypeInfoTable>) -> Result<Self::Nf, TypeError> {
-        self.0.read_back(info_table)
+    fn read_back(&self, ev: &Evaluator) -> Result<Self::Nf, TypeError> {
+        self.0.read_back(ev)
     }
 }
 
@@ -817,7 +929,7 @@ impl Print for Args {
             }
         }
 
-        doc.align().parens().group()
+        alloc.line_().append(doc).nest(cfg.indent).append(alloc.line_()).parens().group()
     }
 }
 
@@ -847,13 +959,11 @@ impl Shift for Arg {
 impl ReadBack for Arg {
     type Nf = ast::Arg;
 
-    fn read_back(&self, info_table: &Rc<TypeInfoTable>) -> Result<Self::Nf, TypeError> {
+    fn read_back(&self, ev: &Evaluator) -> Result<Self::Nf, TypeError> {
         match self {
-            Arg::UnnamedArg(val) => Ok(ast::Arg::UnnamedArg(val.read_back(info_table)?)),
-            Arg::NamedArg(name, val) => {
-                Ok(ast::Arg::NamedArg(name.clone(), val.read_back(info_table)?))
-            }
-            Arg::InsertedImplicitArg(val) => Ok(ast::Arg::UnnamedArg(val.read_back(info_table)?)),
+            Arg::UnnamedArg(val) => Ok(ast::Arg::UnnamedArg(val.read_back(ev)?)),
+            Arg::NamedArg(name, val) => Ok(ast::Arg::NamedArg(name.clone(), val.read_back(ev)?)),
+            Arg::InsertedImplicitArg(val) => Ok(ast::Arg::UnnamedArg(val.read_back(ev)?)),
         }
     }
 }
@@ -889,16 +999,40 @@ impl Arg {
 //
 //
 
+/// Results of applying a closure, keyed on the read back arguments
+pub type ApplyCache = Rc<RefCell<HashMap<Vec<Box<ast::Exp>>, Box<Val>>>>;
+
 #[derive(Debug, Clone)]
 pub struct Closure {
     pub env: Env,
     pub n_args: usize,
     pub body: Box<ast::Exp>,
+    /// Memoized applications of this closure, shared between all of its clones
+    pub cache: ApplyCache,
+}
+
+impl Closure {
+    pub fn new(env: Env, n_args: usize, body: Box<ast::Exp>) -> Self {
+        Self { env, n_args, body, cache: Default::default() }
+    }
+
+    /// The key under which the application of this closure to `args` is memoized
+    ///
+    /// Arguments containing closures or thunks are not used as keys,
+    /// because reading them back would require evaluation, which might not terminate.
+    pub fn cache_key(args: &[Box<Val>], ev: &Evaluator) -> Option<Vec<Box<ast::Exp>>> {
+        if !args.iter().all(|arg| arg.is_first_order()) {
+            return None;
+        }
+        args.iter().map(|arg| arg.read_back(ev).ok()).collect()
+    }
 }
 
 impl Shift for Closure {
     fn shift_in_range<R: ShiftRange>(&mut self, range: &R, by: (isize, isize)) {
         self.env.shift_in_range(range, by);
+        // The memoized results are only valid for the unshifted environment
+        self.cache = Default::default();
     }
 }
 
@@ -911,7 +1045,7 @@ impl Print for Closure {
 impl ReadBack for Closure {
     type Nf = Box<ast::Exp>;
 
-    fn read_back(&self, info_table: &Rc<TypeInfoTable>) -> Result<Self::Nf, TypeError> {
+    fn read_back(&self, ev: &Evaluator) -> Result<Self::Nf, TypeError> {
         let args: Vec<Box<Val>> = (0..self.n_args)
             .rev()
             .map(|snd| {
@@ -924,8 +1058,121 @@ impl ReadBack for Closure {
             .map(Box::new)
             .collect();
         let mut shifted_env = shift_and_clone(&self.env, (1, 0));
-        shifted_env
-            .bind_iter(args.iter(), |env| self.body.eval(info_table, env))?
-            .read_back(info_table)
+        shifted_env.bind_iter(args.iter(), |env| self.body.eval(ev, env))?.read_back(ev)
+    }
+}
+
+// First-order values
+//
+//
+
+/// Whether a value can be read back without evaluating any closures or thunks
+pub trait IsFirstOrder {
+    fn is_first_order(&self) -> bool;
+}
+
+impl<T: IsFirstOrder> IsFirstOrder for Box<T> {
+    fn is_first_order(&self) -> bool {
+        (**self).is_first_order()
+    }
+}
+
+impl<T: IsFirstOrder> IsFirstOrder for Vec<T> {
+    fn is_first_order(&self) -> bool {
+        self.iter().all(IsFirstOrder::is_first_order)
+    }
+}
+
+impl IsFirstOrder for Val {
+    fn is_first_order(&self) -> bool {
+        match self {
+            Val::TypCtor(TypCtor { args, .. }) | Val::Call(Call { args, .. }) => {
+                args.is_first_order()
+            }
+            Val::TypeUniv(_) => true,
+            Val::Anno(AnnoVal { exp, typ, .. }) => exp.is_first_order() && typ.is_first_order(),
+            Val::Neu(neu) => neu.is_first_order(),
+            Val::LocalComatch(_) | Val::Thunk(_) => false,
+        }
+    }
+}
+
+impl IsFirstOrder for Neu {
+    fn is_first_order(&self) -> bool {
+        match self {
+            Neu::Variable(_) => true,
+            Neu::DotCall(DotCall { exp, args, .. }) => {
+                exp.is_first_order() && args.is_first_order()
+            }
+            Neu::Hole(Hole { args, .. }) => args.is_first_order(),
+            Neu::OpaqueCall(OpaqueCall { args, .. }) => args.is_first_order(),
+            Neu::OpaqueDotCall(OpaqueDotCall { exp, args, .. }) => {
+                exp.is_first_order() && args.is_first_order()
+            }
+            Neu::AnnoNeu(AnnoNeu { exp, typ, .. }) => exp.is_first_order() && typ.is_first_order(),
+            Neu::LocalMatch(_) => false,
+        }
+    }
+}
+
+impl IsFirstOrder for Args {
+    fn is_first_order(&self) -> bool {
+        self.0.is_first_order()
+    }
+}
+
+impl IsFirstOrder for Arg {
+    fn is_first_order(&self) -> bool {
+        match self {
+            Arg::UnnamedArg(val) | Arg::NamedArg(_, val) | Arg::InsertedImplicitArg(val) => {
+                val.is_first_order()
+            }
+        }
+    }
+}
+
+// Thunk
+//
+//
+
+/// The delayed evaluation of an expression in an environment.
+/// Thunks are created for the arguments of lazy constructor parameters, as well as for all
+/// arguments under call-by-name evaluation, and are forced whenever the bound variable is
+/// looked up.
+#[derive(Debug, Clone)]
+pub struct Thunk {
+    pub env: Env,
+    pub exp: Box<ast::Exp>,
+}
+
+impl Thunk {
+    pub fn force(&self, ev: &Evaluator) -> Result<Box<Val>, TypeError> {
+        self.exp.eval(ev, &mut self.env.clone())
+    }
+}
+
+impl Shift for Thunk {
+    fn shift_in_range<R: ShiftRange>(&mut self, range: &R, by: (isize, isize)) {
+        self.env.shift_in_range(range, by);
+    }
+}
+
+impl Print for Thunk {
+    fn print<'a>(&'a self, _cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
+        alloc.text("...")
+    }
+}
+
+impl From<Thunk> for Val {
+    fn from(value: Thunk) -> Self {
+        Val::Thunk(value)
+    }
+}
+
+impl ReadBack for Thunk {
+    type Nf = ast::Exp;
+
+    fn read_back(&self, ev: &Evaluator) -> Result<Self::Nf, TypeError> {
+        (*self.force(ev)?).read_back(ev)
     }
 }
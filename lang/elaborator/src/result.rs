@@ -4,6 +4,7 @@ use miette_util::ToMiette;
 use thiserror::Error;
 
 use ast::*;
+use printer::tokens::{FAT_ARROW, QUESTION_MARK};
 use printer::types::Print;
 
 fn comma_separated<I: IntoIterator<Item = String>>(iter: I) -> String {
@@ -15,10 +16,37 @@ fn separated<I: IntoIterator<Item = String>>(s: &str, iter: I) -> String {
     vec.join(s)
 }
 
+/// Whether `exp` contains a call of a let-bound definition
+///
+/// When two normal forms are compared, such a call has not been unfolded because it is opaque.
+fn contains_let_call(exp: &Exp) -> bool {
+    let in_args = |args: &Args| args.args.iter().any(|arg| contains_let_call(&arg.exp()));
+    let in_cases = |cases: &[Case]| {
+        cases.iter().any(|case| case.body.as_deref().is_some_and(contains_let_call))
+    };
+    match exp {
+        Exp::Call(Call { kind: CallKind::LetBound, .. }) => true,
+        Exp::Call(Call { args, .. }) | Exp::TypCtor(TypCtor { args, .. }) => in_args(args),
+        Exp::DotCall(DotCall { exp, args, .. }) => contains_let_call(exp) || in_args(args),
+        Exp::Anno(Anno { exp, typ, .. }) => contains_let_call(exp) || contains_let_call(typ),
+        Exp::LocalMatch(LocalMatch { on_exp, cases, .. }) => {
+            contains_let_call(on_exp) || in_cases(cases)
+        }
+        Exp::LocalComatch(LocalComatch { cases, .. }) => in_cases(cases),
+        Exp::LocalLet(LocalLet { typ, bound, body, .. }) => {
+            typ.as_deref().is_some_and(contains_let_call)
+                || contains_let_call(bound)
+                || contains_let_call(body)
+        }
+        Exp::Hole(Hole { args, .. }) => args.iter().flatten().any(|arg| contains_let_call(arg)),
+        Exp::Variable(_) | Exp::TypeUniv(_) => false,
+    }
+}
+
 #[derive(Error, Diagnostic, Debug, Clone)]
 pub enum TypeError {
     #[error("Wrong number of arguments to {name} provided: got {actual}, expected {expected}")]
-    #[diagnostic(code("T-001"))]
+    #[diagnostic(code("T-001"), help("Give one argument for every explicit parameter of {name}"))]
     ArgLenMismatch {
         name: String,
         expected: usize,
@@ -37,30 +65,46 @@ pub enum TypeError {
         rhs_span: Option<SourceSpan>,
         #[label("While elaborating")]
         while_elaborating_span: Option<SourceSpan>,
+        #[help]
+        help: Option<String>,
     },
     #[error("Cannot match on codata type {name}")]
-    #[diagnostic(code("T-003"))]
+    #[diagnostic(
+        code("T-003"),
+        help("Elements of codata types are observed by calling their destructors instead")
+    )]
     MatchOnCodata {
         name: Box<IdBound>,
         #[label]
         span: Option<SourceSpan>,
     },
     #[error("Cannot comatch on data type {name}")]
-    #[diagnostic(code("T-004"))]
+    #[diagnostic(
+        code("T-004"),
+        help("Elements of data types are constructed by calling their constructors instead")
+    )]
     ComatchOnData {
         name: Box<IdBound>,
         #[label]
         span: Option<SourceSpan>,
     },
     #[error("Invalid pattern match: {msg}")]
-    #[diagnostic(code("T-005"))]
+    #[diagnostic(
+        code("T-005"),
+        help("Give exactly one case for every constructor or destructor of the type")
+    )]
     InvalidMatch {
         msg: String,
         #[label]
         span: Option<SourceSpan>,
+        #[related]
+        missing_cases: Option<MissingCases>,
     },
     #[error("Got {actual}, which is not in type {expected}")]
-    #[diagnostic(code("T-006"))]
+    #[diagnostic(
+        code("T-006"),
+        help("Only the constructors or destructors declared by {expected} can be used here")
+    )]
     NotInType {
         expected: Box<IdBind>,
         actual: Box<IdBound>,
@@ -68,39 +112,45 @@ pub enum TypeError {
         span: Option<SourceSpan>,
     },
     #[error("Pattern for {name} is marked as absurd but that could not be proven")]
-    #[diagnostic(code("T-007"))]
+    #[diagnostic(
+        code("T-007"),
+        help("Give the case a body, since its constructor might match the type indices")
+    )]
     PatternIsNotAbsurd {
         name: Box<IdBound>,
         #[label]
         span: Option<SourceSpan>,
     },
     #[error("Pattern for {name} is absurd and must be marked accordingly")]
-    #[diagnostic(code("T-008"))]
+    #[diagnostic(code("T-008"), help("Replace the body of the case by `absurd`"))]
     PatternIsAbsurd {
         name: Box<IdBound>,
         #[label]
         span: Option<SourceSpan>,
     },
     #[error("Type annotation required for match expression")]
-    #[diagnostic(code("T-009"))]
+    #[diagnostic(
+        code("T-009"),
+        help("Annotate the match with its type or give it a motive `as x => T`")
+    )]
     CannotInferMatch {
         #[label]
         span: Option<SourceSpan>,
     },
     #[error("Type annotation required for comatch expression")]
-    #[diagnostic(code("T-010"))]
+    #[diagnostic(code("T-010"), help("Annotate the comatch with its type"))]
     CannotInferComatch {
         #[label]
         span: Option<SourceSpan>,
     },
     #[error("Type annotation required for typed hole")]
-    #[diagnostic(code("T-011"))]
+    #[diagnostic(code("T-011"), help("Annotate the hole with its type, e.g. `(? : T)`"))]
     CannotInferHole {
         #[label]
         span: Option<SourceSpan>,
     },
     #[error("Expected type constructor application, got {got}")]
-    #[diagnostic(code("T-012"))]
+    #[diagnostic(code("T-012"), help("Only data and codata types can be used here"))]
     ExpectedTypApp {
         got: String,
         #[label]
@@ -114,7 +164,7 @@ pub enum TypeError {
         span: Option<SourceSpan>,
     },
     #[error("{idx} occurs in {exp}")]
-    #[diagnostic(code("T-014"))]
+    #[diagnostic(code("T-014"), help("Unifying these terms would require an infinite term"))]
     OccursCheckFailed {
         idx: Idx,
         exp: String,
@@ -122,7 +172,10 @@ pub enum TypeError {
         span: Option<SourceSpan>,
     },
     #[error("Cannot automatically decide whether {lhs} and {rhs} unify")]
-    #[diagnostic(code("T-016"))]
+    #[diagnostic(
+        code("T-016"),
+        help("Unification only solves equations between constructors and variables")
+    )]
     CannotDecide {
         lhs: String,
         rhs: String,
@@ -132,22 +185,114 @@ pub enum TypeError {
         rhs_span: Option<SourceSpan>,
         #[label("While elaborating")]
         while_elaborating_span: Option<SourceSpan>,
+        /// The subgoals which led to this constraint, only recorded if tracing is enabled
+        #[related]
+        trace: Vec<UnificationStep>,
     },
     #[error("The metavariable {message} could not be solved")]
-    #[diagnostic(code("T-017"))]
+    #[diagnostic(
+        code("T-017"),
+        help("Give the missing argument explicitly or add a type annotation")
+    )]
     UnresolvedMeta {
         #[label]
         span: Option<SourceSpan>,
         message: String,
     },
     #[error("A case for constructor {name} was missing during evaluation.")]
-    #[diagnostic(code("T-018"))]
-    MissingCase { name: String },
+    #[diagnostic(code("T-018"), help("Add a case for {name} to the match"))]
+    MissingCase {
+        name: String,
+        #[label("While elaborating")]
+        while_elaborating_span: Option<SourceSpan>,
+    },
     #[error("A case for destructor {name} was missing during evaluation.")]
-    #[diagnostic(code("T-019"))]
-    MissingCocase { name: String },
+    #[diagnostic(code("T-019"), help("Add a case for {name} to the comatch"))]
+    MissingCocase {
+        name: String,
+        #[label("While elaborating")]
+        while_elaborating_span: Option<SourceSpan>,
+    },
+    #[error("The type of parameter {name} references {references}, which is not bound by an earlier parameter")]
+    #[diagnostic(
+        code("T-020"),
+        help("Reorder the parameters such that {references} is bound before {name}")
+    )]
+    IllScopedParameter {
+        name: String,
+        references: String,
+        #[label]
+        span: Option<SourceSpan>,
+    },
+    #[error("Possibly non-terminating recursive call of {name}")]
+    #[diagnostic(
+        code("T-021"),
+        help("Recursive calls have to be on a structurally smaller argument")
+    )]
+    PossiblyNonTerminating {
+        name: String,
+        #[label]
+        span: Option<SourceSpan>,
+    },
+    #[error("{data_name} occurs in a non-strictly positive position in constructor {ctor_name}")]
+    #[diagnostic(
+        code("T-022"),
+        help("A data type must not occur on the left of a function arrow in its own constructors")
+    )]
+    NonStrictlyPositive {
+        data_name: String,
+        ctor_name: String,
+        #[label]
+        span: Option<SourceSpan>,
+    },
+    #[error("Evaluation did not terminate within {limit} steps")]
+    #[diagnostic(code("T-023"), help("The evaluated expression may not terminate"))]
+    FuelExhausted {
+        limit: u64,
+        #[label]
+        span: Option<SourceSpan>,
+        #[label("While elaborating")]
+        while_elaborating_span: Option<SourceSpan>,
+    },
+    #[error("There is no variable of an empty type in the context")]
+    #[diagnostic(
+        code("T-024"),
+        help("`absurd` requires a variable whose type is a data type without constructors")
+    )]
+    NoAbsurdHypothesis {
+        #[label]
+        span: Option<SourceSpan>,
+    },
+    #[error("The motive does not match the expected type:\n  1: {motive}\n  2: {expected}\n")]
+    #[diagnostic(
+        code("T-025"),
+        help("The return type of the motive has to match the type expected of the match")
+    )]
+    MotiveMismatch {
+        /// The return type of the motive, instantiated with the scrutinee
+        motive: String,
+        expected: String,
+        #[label("Motive")]
+        motive_span: Option<SourceSpan>,
+        #[label("Bound to this scrutinee")]
+        scrutinee_span: Option<SourceSpan>,
+    },
+    #[error("The case does not match the motive:\n  1: {typ}\n  2: {expected}\n")]
+    #[diagnostic(
+        code("T-026"),
+        help("Every case has to have the return type of the motive for its pattern")
+    )]
+    CaseMotiveMismatch {
+        typ: String,
+        /// The return type of the motive, instantiated with the pattern of the case
+        expected: String,
+        #[label("Motive")]
+        motive_span: Option<SourceSpan>,
+        #[label("This case")]
+        case_span: Option<SourceSpan>,
+    },
     #[error("An unexpected internal error occurred: {message}")]
-    #[diagnostic(code("T-XXX"))]
+    #[diagnostic(code("T-000"), help("This is a bug in the typechecker, please report it"))]
     /// This error should not occur.
     /// Some internal invariant has been violated.
     Impossible {
@@ -157,6 +302,63 @@ pub enum TypeError {
     },
 }
 
+impl TypeError {
+    /// The codes of all variants, together with the templates of their messages
+    ///
+    /// This list must be extended whenever a variant is added, see `driver::error_index`.
+    pub const CODES: &'static [(&'static str, &'static str)] = &[
+        ("T-001", "Wrong number of arguments to {name} provided: got {actual}, expected {expected}"),
+        ("T-002", "The following terms are not equal:"),
+        ("T-003", "Cannot match on codata type {name}"),
+        ("T-004", "Cannot comatch on data type {name}"),
+        ("T-005", "Invalid pattern match: {msg}"),
+        ("T-006", "Got {actual}, which is not in type {expected}"),
+        ("T-007", "Pattern for {name} is marked as absurd but that could not be proven"),
+        ("T-008", "Pattern for {name} is absurd and must be marked accordingly"),
+        ("T-009", "Type annotation required for match expression"),
+        ("T-010", "Type annotation required for comatch expression"),
+        ("T-011", "Type annotation required for typed hole"),
+        ("T-012", "Expected type constructor application, got {got}"),
+        ("T-013", "Local comatch not supported for type {type_name} because {type_name} contains destructors with self parameters"),
+        ("T-014", "{idx} occurs in {exp}"),
+        ("T-016", "Cannot automatically decide whether {lhs} and {rhs} unify"),
+        ("T-017", "The metavariable {message} could not be solved"),
+        ("T-018", "A case for constructor {name} was missing during evaluation."),
+        ("T-019", "A case for destructor {name} was missing during evaluation."),
+        ("T-020", "The type of parameter {name} references {references}, which is not bound by an earlier parameter"),
+        ("T-021", "Possibly non-terminating recursive call of {name}"),
+        ("T-022", "{data_name} occurs in a non-strictly positive position in constructor {ctor_name}"),
+        ("T-023", "Evaluation did not terminate within {limit} steps"),
+        ("T-024", "There is no variable of an empty type in the context"),
+        ("T-025", "The motive does not match the expected type:"),
+        ("T-026", "The case does not match the motive:"),
+        ("T-000", "An unexpected internal error occurred: {message}"),
+    ];
+}
+
+/// The cases which are missing from a pattern or copattern match
+#[derive(Error, Diagnostic, Debug, Clone)]
+#[error("The missing cases can be added as follows:\n{cases}")]
+#[diagnostic(severity(Advice))]
+pub struct MissingCases {
+    cases: String,
+}
+
+/// A subgoal of the unification which led to an undecidable constraint
+#[derive(Error, Diagnostic, Debug, Clone)]
+#[error("While unifying {subgoal}: {lhs} = {rhs}")]
+#[diagnostic(severity(Advice))]
+pub struct UnificationStep {
+    /// Which part of its parent constraint this subgoal is
+    pub subgoal: String,
+    pub lhs: String,
+    pub rhs: String,
+    #[label]
+    pub lhs_span: Option<SourceSpan>,
+    #[label]
+    pub rhs_span: Option<SourceSpan>,
+}
+
 impl TypeError {
     pub fn not_eq(lhs: &Exp, rhs: &Exp, while_elaborating_span: &Option<Span>) -> Self {
         Self::NotEq {
@@ -165,11 +367,17 @@ impl TypeError {
             lhs_span: lhs.span().to_miette(),
             rhs_span: rhs.span().to_miette(),
             while_elaborating_span: while_elaborating_span.to_miette(),
+            help: (contains_let_call(lhs) || contains_let_call(rhs)).then(|| {
+                "Let definitions are only unfolded during typechecking if marked #[transparent]"
+                    .to_owned()
+            }),
         }
     }
 
+    /// The patterns in `missing` are listed in the order in which their xtors are declared.
+    /// They are also printed as cases with holes as bodies, which can be pasted into the match.
     pub fn invalid_match(
-        missing: HashSet<String>,
+        missing: Vec<Pattern>,
         undeclared: HashSet<String>,
         duplicate: HashSet<String>,
         info: &Option<Span>,
@@ -177,7 +385,10 @@ impl TypeError {
         let mut msgs = Vec::new();
 
         if !missing.is_empty() {
-            msgs.push(format!("missing {}", comma_separated(missing.iter().cloned())));
+            msgs.push(format!(
+                "missing {}",
+                comma_separated(missing.iter().map(|pattern| pattern.name.id.clone()))
+            ));
         }
         if !undeclared.is_empty() {
             msgs.push(format!("undeclared {}", comma_separated(undeclared.iter().cloned())));
@@ -186,7 +397,16 @@ impl TypeError {
             msgs.push(format!("duplicate {}", comma_separated(duplicate.iter().cloned())));
         }
 
-        Self::InvalidMatch { msg: separated("; ", msgs), span: info.to_miette() }
+        let missing_cases = (!missing.is_empty()).then(|| MissingCases {
+            cases: separated(
+                ",\n",
+                missing.iter().map(|pattern| {
+                    format!("{} {FAT_ARROW} {QUESTION_MARK}", pattern.print_to_string(None))
+                }),
+            ),
+        });
+
+        Self::InvalidMatch { msg: separated("; ", msgs), span: info.to_miette(), missing_cases }
     }
 
     pub fn expected_typ_app(got: &Exp) -> Self {
@@ -201,6 +421,77 @@ impl TypeError {
         }
     }
 
+    pub fn ill_scoped_parameter(name: &VarBind, var: &Variable) -> Self {
+        let references =
+            if var.name.id.is_empty() { format!("@{}", var.idx) } else { var.name.id.clone() };
+        Self::IllScopedParameter {
+            name: name.id.clone(),
+            references,
+            span: var.span.or(name.span).to_miette(),
+        }
+    }
+
+    /// Errors raised during evaluation do not know which expression the typechecker was
+    /// elaborating when it triggered the evaluation. This adds the span of that expression.
+    /// Internal errors only get it if they have no span of their own.
+    pub fn while_elaborating(self, span: Option<Span>) -> Self {
+        match self {
+            Self::MissingCase { name, while_elaborating_span: None } => {
+                Self::MissingCase { name, while_elaborating_span: span.to_miette() }
+            }
+            Self::MissingCocase { name, while_elaborating_span: None } => {
+                Self::MissingCocase { name, while_elaborating_span: span.to_miette() }
+            }
+            Self::FuelExhausted { limit, span: fuel_span, while_elaborating_span: None } => {
+                Self::FuelExhausted {
+                    limit,
+                    span: fuel_span,
+                    while_elaborating_span: span.to_miette(),
+                }
+            }
+            Self::Impossible { message, span: None } => {
+                Self::Impossible { message, span: span.to_miette() }
+            }
+            err => err,
+        }
+    }
+
+    /// Report a mismatch between the motive of a match and its expected type as such
+    pub fn motive_mismatch(self, motive_span: Option<Span>, scrutinee_span: Option<Span>) -> Self {
+        match self {
+            Self::NotEq { lhs, rhs, .. } => Self::MotiveMismatch {
+                motive: lhs,
+                expected: rhs,
+                motive_span: motive_span.to_miette(),
+                scrutinee_span: scrutinee_span.to_miette(),
+            },
+            err => err,
+        }
+    }
+
+    /// Report a mismatch between the body of a case and its expected type `t`, which is given
+    /// by the motive of the match, as such
+    ///
+    /// Mismatches within the body which do not concern its expected type are not affected.
+    pub fn case_motive_mismatch(
+        self,
+        t: &Exp,
+        motive_span: Option<Span>,
+        case_span: Option<Span>,
+    ) -> Self {
+        match self {
+            Self::NotEq { lhs, rhs, .. } if rhs == t.print_to_string(None) => {
+                Self::CaseMotiveMismatch {
+                    typ: lhs,
+                    expected: rhs,
+                    motive_span: motive_span.to_miette(),
+                    case_span: case_span.to_miette(),
+                }
+            }
+            err => err,
+        }
+    }
+
     pub fn cannot_decide(lhs: &Exp, rhs: &Exp, while_elaborating_span: &Option<Span>) -> Self {
         Self::CannotDecide {
             lhs: lhs.print_to_string(None),
@@ -208,6 +499,42 @@ impl TypeError {
             lhs_span: lhs.span().to_miette(),
             rhs_span: rhs.span().to_miette(),
             while_elaborating_span: while_elaborating_span.to_miette(),
+            trace: vec![],
         }
     }
+
+    /// Record the subgoals of the unification which led to an undecidable constraint
+    pub fn with_unification_trace(self, steps: &[UnificationStep]) -> Self {
+        match self {
+            Self::CannotDecide { lhs, rhs, lhs_span, rhs_span, while_elaborating_span, .. } => {
+                Self::CannotDecide {
+                    lhs,
+                    rhs,
+                    lhs_span,
+                    rhs_span,
+                    while_elaborating_span,
+                    trace: steps.to_vec(),
+                }
+            }
+            err => err,
+        }
+    }
+}
+
+#[cfg(test)]
+mod invalid_match_tests {
+    use ast::test_support::pattern;
+
+    use super::*;
+
+    #[test]
+    fn missing_cases_are_printed() {
+        let missing = vec![pattern(false, "Z", &[]), pattern(false, "S", &["n"])];
+        let err = TypeError::invalid_match(missing, HashSet::default(), HashSet::default(), &None);
+        let TypeError::InvalidMatch { msg, missing_cases: Some(missing_cases), .. } = err else {
+            panic!("Expected missing cases");
+        };
+        assert_eq!(msg, "missing Z, S");
+        assert_eq!(missing_cases.cases, "Z => ?,\nS(n) => ?");
+    }
 }
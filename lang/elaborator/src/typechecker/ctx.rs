@@ -5,6 +5,7 @@
 use std::rc::Rc;
 
 use crate::normalizer::env::{Env, ToEnv};
+use crate::normalizer::evaluator::{EvalConfig, Evaluator};
 use crate::normalizer::normalize::Normalize;
 use ast::ctx::values::TypeCtx;
 use ast::ctx::{BindContext, Context, LevelCtx};
@@ -25,6 +26,8 @@ pub struct Ctx {
     pub type_info_table: Rc<TypeInfoTable>,
     /// The program for looking up the expressions when evaluating
     pub module: Rc<Module>,
+    /// How terms are normalized during typechecking
+    pub eval_config: Rc<EvalConfig>,
 }
 
 impl Ctx {
@@ -32,34 +35,37 @@ impl Ctx {
         meta_vars: HashMap<MetaVar, MetaVarState>,
         type_info_table: TypeInfoTable,
         module: Rc<Module>,
+        eval_config: &EvalConfig,
     ) -> Self {
         Self {
             vars: TypeCtx::empty(),
             meta_vars,
             type_info_table: Rc::new(type_info_table),
             module,
+            eval_config: Rc::new(eval_config.clone()),
         }
     }
+
+    /// A fresh evaluator for normalizing a term during typechecking
+    pub fn evaluator(&self) -> Evaluator {
+        Evaluator::new(self.type_info_table.clone(), &self.eval_config)
+    }
 }
 pub trait ContextSubstExt: Sized {
-    fn subst<S: Substitution>(
-        &mut self,
-        type_info_table: &Rc<TypeInfoTable>,
-        s: &S,
-    ) -> Result<(), TypeError>;
+    fn subst<S: Substitution>(&mut self, s: &S) -> Result<(), TypeError>;
 }
 
 impl ContextSubstExt for Ctx {
-    fn subst<S: Substitution>(
-        &mut self,
-        type_info_table: &Rc<TypeInfoTable>,
-        s: &S,
-    ) -> Result<(), TypeError> {
+    fn subst<S: Substitution>(&mut self, s: &S) -> Result<(), TypeError> {
+        let type_info_table = self.type_info_table.clone();
+        let eval_config = self.eval_config.clone();
         let env = self.vars.env();
         let levels = self.vars.levels();
         self.map_failable(|nf| {
             let exp = nf.subst(&mut levels.clone(), s);
-            let nf = exp.normalize(type_info_table, &mut env.clone())?;
+            // Each type is normalized with its own budget of evaluation steps
+            let ev = Evaluator::new(type_info_table.clone(), &eval_config);
+            let nf = exp.normalize(&ev, &mut env.clone())?;
             Ok(nf)
         })
     }
@@ -111,6 +117,7 @@ impl Ctx {
             meta_vars,
             type_info_table: self.type_info_table.clone(),
             module: self.module.clone(),
+            eval_config: self.eval_config.clone(),
         };
         let res = f(&mut inner_ctx);
         self.meta_vars = inner_ctx.meta_vars;
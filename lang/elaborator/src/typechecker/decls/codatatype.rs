@@ -4,6 +4,8 @@ use miette_util::ToMiette;
 
 use ast::*;
 
+use crate::normalizer::env::ToEnv;
+use crate::normalizer::normalize::Normalize;
 use crate::typechecker::{
     ctx::Ctx,
     exprs::{CheckInfer, InferTelescope},
@@ -39,7 +41,7 @@ impl CheckToplevel for Codata {
 fn check_dtor_wf(codata_name: &IdBind, dtor: &Dtor, ctx: &mut Ctx) -> Result<Dtor, TypeError> {
     trace!("Checking well-formedness of destructor: {}", dtor.name);
 
-    let Dtor { span, doc, name, params, self_param, ret_typ } = dtor;
+    let Dtor { span, doc, name, params, self_param, ret_typ, default } = dtor;
 
     // Check that the destructor lies in the codata type it is defined in
     if &self_param.typ.name != codata_name {
@@ -54,6 +56,17 @@ fn check_dtor_wf(codata_name: &IdBind, dtor: &Dtor, ctx: &mut Ctx) -> Result<Dto
         self_param.infer_telescope(ctx, |ctx, self_param_out| {
             let ret_typ_out = ret_typ.infer(ctx)?;
 
+            // The default implementation only refers to the parameters of the destructor,
+            // so it has to be shifted over the self parameter to check it against the return type.
+            if let Some(default) = default {
+                let ret_typ_nf = ret_typ
+                    .normalize(&ctx.evaluator(), &mut ctx.env())
+                    .map_err(|err| err.while_elaborating(ret_typ.span()))?;
+                let mut default = default.clone();
+                default.shift((1, 0));
+                default.check(ctx, &ret_typ_nf)?;
+            }
+
             Ok(Dtor {
                 span: *span,
                 doc: doc.clone(),
@@ -61,6 +74,8 @@ fn check_dtor_wf(codata_name: &IdBind, dtor: &Dtor, ctx: &mut Ctx) -> Result<Dto
                 params: params_out,
                 self_param: self_param_out,
                 ret_typ: ret_typ_out,
+                // The default implementation is elaborated wherever it is used in place of a cocase
+                default: default.clone(),
             })
         })
     })
@@ -7,7 +7,7 @@ use ast::*;
 use crate::normalizer::env::ToEnv;
 use crate::normalizer::normalize::Normalize;
 
-use crate::typechecker::exprs::local_comatch::WithExpectedType;
+use crate::typechecker::exprs::local_comatch::{fill_in_defaults, WithExpectedType};
 use crate::typechecker::{
     ctx::Ctx,
     exprs::{CheckInfer, InferTelescope},
@@ -28,11 +28,15 @@ impl CheckToplevel for Codef {
 
         params.infer_telescope(ctx, |ctx, params_out| {
             let typ_out = typ.check(ctx, &Box::new(TypeUniv::new().into()))?;
-            let typ_nf = typ.normalize(&ctx.type_info_table, &mut ctx.env())?;
+            let typ_nf = typ
+                .normalize(&ctx.evaluator(), &mut ctx.env())
+                .map_err(|err| err.while_elaborating(typ.span))?;
+            let expected_type = typ_nf.expect_typ_app()?;
+            let cases = fill_in_defaults(cases, &expected_type, ctx)?;
             let with_expected_type = WithExpectedType {
-                cases,
+                cases: &cases,
                 label: Some((label, params.len())),
-                expected_type: typ_nf.expect_typ_app()?,
+                expected_type,
             };
 
             with_expected_type.check_exhaustiveness(ctx)?;
@@ -7,6 +7,7 @@ use ast::*;
 use crate::typechecker::{
     ctx::Ctx,
     exprs::{CheckInfer, InferTelescope},
+    positivity::check_positivity,
     TypeError,
 };
 
@@ -50,6 +51,8 @@ fn check_ctor_wf(data_type_name: &IdBind, ctor: &Ctor, ctx: &mut Ctx) -> Result<
         });
     }
 
+    check_positivity(data_type_name, ctor)?;
+
     params.infer_telescope(ctx, |ctx, params_out| {
         let typ_out = typ.infer(ctx)?;
 
@@ -22,17 +22,25 @@ impl CheckToplevel for Def {
         let Def { span, doc, name, attr, params, self_param, ret_typ, cases } = self;
 
         params.infer_telescope(ctx, |ctx, params_out| {
-            let self_param_nf = self_param.typ.normalize(&ctx.type_info_table, &mut ctx.env())?;
+            let self_param_nf = self_param
+                .typ
+                .normalize(&ctx.evaluator(), &mut ctx.env())
+                .map_err(|err| err.while_elaborating(self_param.typ.span))?;
 
             let (ret_typ_out, ret_typ_nf, self_param_out) =
                 self_param.infer_telescope(ctx, |ctx, self_param_out| {
                     let ret_typ_out = ret_typ.infer(ctx)?;
-                    let ret_typ_nf = ret_typ.normalize(&ctx.type_info_table, &mut ctx.env())?;
+                    let ret_typ_nf = ret_typ
+                        .normalize(&ctx.evaluator(), &mut ctx.env())
+                        .map_err(|err| err.while_elaborating(ret_typ.span()))?;
                     Ok((ret_typ_out, ret_typ_nf, self_param_out))
                 })?;
 
-            let with_scrutinee_type =
-                WithScrutineeType { cases, scrutinee_type: self_param_nf.expect_typ_app()? };
+            let with_scrutinee_type = WithScrutineeType {
+                cases,
+                scrutinee_type: self_param_nf.expect_typ_app()?,
+                motive_span: None,
+            };
             with_scrutinee_type.check_exhaustiveness(ctx)?;
             let cases = with_scrutinee_type.check_type(ctx, &ret_typ_nf)?;
 
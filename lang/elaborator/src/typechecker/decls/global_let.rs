@@ -21,7 +21,9 @@ impl CheckToplevel for Let {
 
         params.infer_telescope(ctx, |ctx, params_out| {
             let typ_out = typ.infer(ctx)?;
-            let typ_nf = typ.normalize(&ctx.type_info_table, &mut ctx.env())?;
+            let typ_nf = typ
+                .normalize(&ctx.evaluator(), &mut ctx.env())
+                .map_err(|err| err.while_elaborating(typ.span()))?;
             let body_out = body.check(ctx, &typ_nf)?;
 
             Ok(Let {
@@ -6,10 +6,16 @@ mod datatype;
 mod definition;
 mod global_let;
 
+use ast::ctx::values::TypeCtx;
 use ast::*;
+use codespan::Span;
 use miette_util::ToMiette;
 use printer::Print;
 
+use crate::normalizer::evaluator::EvalConfig;
+
+use super::exprs::CheckInfer;
+use super::termination::check_termination;
 use super::{ctx::Ctx, type_info_table::TypeInfoTable, TypeError};
 
 /// Check a module
@@ -18,16 +24,154 @@ use super::{ctx::Ctx, type_info_table::TypeInfoTable, TypeError};
 pub fn check_with_lookup_table(
     prg: Rc<Module>,
     info_table: &TypeInfoTable,
+    eval_config: &EvalConfig,
 ) -> Result<Module, TypeError> {
+    check_module(prg, info_table, eval_config, None).map_err(first_error)
+}
+
+/// Check a module, reporting the errors of all declarations instead of only the first one
+///
+/// Declarations are checked independently of each other against the signatures in the info
+/// table, so an error in one declaration does not prevent the others from being checked.
+/// The signature of a declaration which fails to check remains in the info table as a
+/// placeholder, such that the declarations depending on it are still checked against it.
+pub fn check_all_with_lookup_table(
+    prg: Rc<Module>,
+    info_table: &TypeInfoTable,
+    eval_config: &EvalConfig,
+) -> Result<Module, ModuleErrors> {
+    check_module(prg, info_table, eval_config, None)
+}
+
+/// Check a module, reusing declarations which have been checked in a previous version of it
+///
+/// The declarations named in `reused` are taken from the typechecked module `previous` instead of
+/// being checked again. The caller has to ensure that these declarations and all declarations
+/// they depend on are unchanged, and that the metavariables generated for them during lowering
+/// are the same as in the previous version.
+pub fn check_incrementally(
+    prg: Rc<Module>,
+    info_table: &TypeInfoTable,
+    eval_config: &EvalConfig,
+    previous: &Module,
+    reused: &HashSet<String>,
+) -> Result<Module, TypeError> {
+    check_module(prg, info_table, eval_config, Some((previous, reused))).map_err(first_error)
+}
+
+/// Infer the type of the standalone expression `exp` in the context of the checked module `prg`
+///
+/// The expression is elaborated in the same way as the bodies of declarations.
+/// The metavariables in `meta_vars` are those which have been generated while lowering `exp`.
+pub fn infer_exp_with_lookup_table(
+    exp: &Exp,
+    prg: Rc<Module>,
+    info_table: &TypeInfoTable,
+    eval_config: &EvalConfig,
+    meta_vars: HashMap<MetaVar, MetaVarState>,
+) -> Result<Exp, TypeError> {
+    let mut ctx = Ctx::new(meta_vars, info_table.clone(), prg, eval_config);
+    let mut exp = exp.infer(&mut ctx)?;
+    exp.zonk(&ctx.meta_vars)
+        .map_err(|err| TypeError::Impossible { message: err.to_string(), span: None })?;
+    check_metavars_solved(&ctx.meta_vars)?;
+    Ok(exp)
+}
+
+/// The errors of a module which fails to check, see [check_all_with_lookup_table]
+#[derive(Debug, Clone)]
+pub struct ModuleErrors {
+    /// The errors in the order in which they have been reported
+    pub errors: Vec<TypeError>,
+    /// The names of the declarations which failed to check
+    ///
+    /// This is empty if the errors do not belong to a single declaration, such as the errors
+    /// about metavariables which could not be solved.
+    pub failed_decls: Vec<String>,
+}
+
+impl From<TypeError> for ModuleErrors {
+    fn from(err: TypeError) -> Self {
+        ModuleErrors { errors: vec![err], failed_decls: vec![] }
+    }
+}
+
+impl ModuleErrors {
+    fn push(&mut self, decl: &Decl, err: TypeError) {
+        let name = &decl.ident().id;
+        if !self.failed_decls.contains(name) {
+            self.failed_decls.push(name.clone());
+        }
+        self.errors.push(err);
+    }
+}
+
+/// The error which is reported first, see [check_module]
+fn first_error(mut errors: ModuleErrors) -> TypeError {
+    errors.errors.remove(0)
+}
+
+/// Check a module, returning the errors of all declarations if any of them fails to check
+///
+/// The termination errors of all declarations are reported before the type errors.
+fn check_module(
+    prg: Rc<Module>,
+    info_table: &TypeInfoTable,
+    eval_config: &EvalConfig,
+    previous: Option<(&Module, &HashSet<String>)>,
+) -> Result<Module, ModuleErrors> {
     log::debug!("Checking module: {}", prg.uri);
 
-    let mut ctx = Ctx::new(prg.meta_vars.clone(), info_table.clone(), prg.clone());
+    let mut errors = ModuleErrors { errors: vec![], failed_decls: vec![] };
+    for decl in prg.decls.iter() {
+        if reused_decl(decl, previous).is_none() {
+            if let Err(err) = check_termination(decl, info_table) {
+                errors.push(decl, err);
+            }
+        }
+    }
 
-    let mut decls = prg
-        .decls
-        .iter()
-        .map(|decl| decl.check_wf(&mut ctx))
-        .collect::<Result<Vec<_>, TypeError>>()?;
+    // The metavariables of reused declarations have been solved in the previous version
+    let mut meta_vars = prg.meta_vars.clone();
+    if let Some((previous_module, _)) = previous {
+        let reused_spans: Vec<Span> = prg
+            .decls
+            .iter()
+            .filter(|decl| reused_decl(decl, previous).is_some())
+            .filter_map(|decl| decl.span())
+            .collect();
+        for (mv, state) in previous_module.meta_vars.iter() {
+            let contains_mv = |span: &Span| {
+                mv.span.is_some_and(|mv_span| {
+                    span.start() <= mv_span.start() && mv_span.end() <= span.end()
+                })
+            };
+            if reused_spans.iter().any(contains_mv) {
+                meta_vars.insert(*mv, state.clone());
+            }
+        }
+    }
+
+    let mut ctx = Ctx::new(meta_vars, info_table.clone(), prg.clone(), eval_config);
+
+    let mut decls = vec![];
+    for decl in prg.decls.iter() {
+        match reused_decl(decl, previous) {
+            Some(decl) => decls.push(decl.clone()),
+            None => match decl.check_wf(&mut ctx) {
+                Ok(decl) => decls.push(decl),
+                Err(err) => {
+                    errors.push(decl, err);
+                    // The failed declaration may have left variables in the context
+                    ctx.vars = TypeCtx::empty();
+                }
+            },
+        }
+    }
+    // The metavariables of failed declarations are usually unsolved, which is not reported
+    if !errors.errors.is_empty() {
+        return Err(errors);
+    }
 
     decls
         .zonk(&ctx.meta_vars)
@@ -41,9 +185,22 @@ pub fn check_with_lookup_table(
         use_decls: prg.use_decls.clone(),
         decls,
         meta_vars: ctx.meta_vars.clone(),
+        comments: prg.comments.clone(),
     })
 }
 
+/// The previously typechecked version of `decl`, if it can be reused
+fn reused_decl<'a>(
+    decl: &Decl,
+    previous: Option<(&'a Module, &HashSet<String>)>,
+) -> Option<&'a Decl> {
+    let (previous, reused) = previous?;
+    if !reused.contains(&decl.ident().id) {
+        return None;
+    }
+    previous.decls.iter().find(|prev| prev.ident().id == decl.ident().id)
+}
+
 /// Check that there are no unresolved metavariables that remain after typechecking.
 pub fn check_metavars_solved(meta_vars: &HashMap<MetaVar, MetaVarState>) -> Result<(), TypeError> {
     let mut unsolved: HashSet<MetaVar> = HashSet::default();
@@ -117,3 +274,43 @@ impl CheckToplevel for Decl {
         Ok(out)
     }
 }
+
+#[cfg(test)]
+mod check_all_tests {
+    use ast::test_support::{module, param, type_univ, uri, var};
+
+    use crate::typechecker::type_info_table::build::build_type_info_table;
+
+    use super::*;
+
+    /// `let name(x: Type): <typ> { Type }`, where `typ` may refer to `x`
+    fn tl_let(name: &str, typ: Box<Exp>) -> Decl {
+        Decl::Let(Let {
+            span: None,
+            doc: None,
+            name: IdBind::from_string(name),
+            attr: Attributes::default(),
+            params: Telescope { params: vec![param("x", type_univ())] },
+            typ,
+            body: type_univ(),
+        })
+    }
+
+    #[test]
+    fn all_declarations_are_checked() {
+        let module = module(vec![
+            tl_let("a", var("x", 0, 0)),
+            tl_let("b", type_univ()),
+            tl_let("c", var("x", 0, 0)),
+        ]);
+        let mut info_table = TypeInfoTable::default();
+        info_table.insert(uri(), build_type_info_table(&module));
+        let module = Rc::new(module);
+
+        let config = EvalConfig::default();
+        let errors = check_all_with_lookup_table(module.clone(), &info_table, &config).unwrap_err();
+        assert_eq!(errors.errors.len(), 2);
+        assert_eq!(errors.failed_decls, vec!["a".to_owned(), "c".to_owned()]);
+        assert!(check_with_lookup_table(module, &info_table, &config).is_err());
+    }
+}
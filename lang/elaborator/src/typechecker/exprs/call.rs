@@ -42,9 +42,11 @@ impl CheckInfer for Call {
                     &ctx.type_info_table.lookup_ctor_or_codef(&name.clone())?;
                 let args_out = check_args(args, &name.clone(), ctx, params, *span)?;
                 let typ_out = typ
-                    .subst_under_ctx(vec![params.len()].into(), &vec![args.args.clone()])
+                    .subst_under_ctx(vec![params.len()].into(), &vec![args_out.args.clone()])
                     .to_exp();
-                let typ_nf = typ_out.normalize(&ctx.type_info_table, &mut ctx.env())?;
+                let typ_nf = typ_out
+                    .normalize(&ctx.evaluator(), &mut ctx.env())
+                    .map_err(|err| err.while_elaborating(*span))?;
                 Ok(Call {
                     span: *span,
                     kind: *kind,
@@ -59,8 +61,10 @@ impl CheckInfer for Call {
                 let typ = typ.clone();
                 let args_out = check_args(args, &name.clone(), ctx, &params, *span)?;
                 let typ_out =
-                    typ.subst_under_ctx(vec![params.len()].into(), &vec![args.args.clone()]);
-                let typ_nf = typ_out.normalize(&ctx.type_info_table, &mut ctx.env())?;
+                    typ.subst_under_ctx(vec![params.len()].into(), &vec![args_out.args.clone()]);
+                let typ_nf = typ_out
+                    .normalize(&ctx.evaluator(), &mut ctx.env())
+                    .map_err(|err| err.while_elaborating(*span))?;
                 Ok(Call {
                     span: *span,
                     kind: *kind,
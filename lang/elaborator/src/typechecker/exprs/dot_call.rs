@@ -43,15 +43,19 @@ impl CheckInfer for DotCall {
 
         let self_param_out = self_param
             .typ
-            .subst_under_ctx(vec![params.len()].into(), &vec![args.args.clone()])
+            .subst_under_ctx(vec![params.len()].into(), &vec![args_out.args.clone()])
             .to_exp();
-        let self_param_nf = self_param_out.normalize(&ctx.type_info_table, &mut ctx.env())?;
+        let self_param_nf = self_param_out
+            .normalize(&ctx.evaluator(), &mut ctx.env())
+            .map_err(|err| err.while_elaborating(*span))?;
 
         let exp_out = exp.check(ctx, &self_param_nf)?;
 
-        let subst = vec![args.to_exps(), vec![exp.clone()]];
+        let subst = vec![args_out.to_exps(), vec![exp.clone()]];
         let typ_out = ret_typ.subst_under_ctx(vec![params.len(), 1].into(), &subst);
-        let typ_out_nf = typ_out.normalize(&ctx.type_info_table, &mut ctx.env())?;
+        let typ_out_nf = typ_out
+            .normalize(&ctx.evaluator(), &mut ctx.env())
+            .map_err(|err| err.while_elaborating(*span))?;
 
         Ok(DotCall {
             span: *span,
@@ -13,7 +13,7 @@ use crate::result::TypeError;
 
 impl CheckInfer for Hole {
     fn check(&self, ctx: &mut Ctx, t: &Exp) -> Result<Self, TypeError> {
-        let Hole { span, kind, metavar, args, solution, .. } = self;
+        let Hole { span, kind, name, metavar, args, solution, .. } = self;
         let args: Vec<Vec<Box<Exp>>> = args
             .iter()
             .map(|subst| subst.iter().map(|exp| exp.infer(ctx)).collect::<Result<Vec<_>, _>>())
@@ -21,6 +21,7 @@ impl CheckInfer for Hole {
         Ok(Hole {
             span: *span,
             kind: *kind,
+            name: name.clone(),
             metavar: *metavar,
             inferred_type: Some(Box::new(t.clone())),
             inferred_ctx: Some(ctx.vars.clone()),
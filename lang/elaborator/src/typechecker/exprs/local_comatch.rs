@@ -31,8 +31,12 @@ impl CheckInfer for LocalComatch {
             });
         }
 
-        let with_expected_type =
-            WithExpectedType { cases, label: None, expected_type: expected_type_app.clone() };
+        let cases = fill_in_defaults(cases, &expected_type_app, ctx)?;
+        let with_expected_type = WithExpectedType {
+            cases: &cases,
+            label: None,
+            expected_type: expected_type_app.clone(),
+        };
 
         with_expected_type.check_exhaustiveness(ctx)?;
         let cases = with_expected_type.check_type(ctx)?;
@@ -53,6 +57,55 @@ impl CheckInfer for LocalComatch {
     }
 }
 
+/// Add a cocase for every destructor of the expected codata type which is not matched by any of
+/// the `cases` but has a default implementation.
+///
+/// Since the default implementation only refers to the parameters of the destructor,
+/// it can be used as the body of the cocase without any shifting.
+pub fn fill_in_defaults(
+    cases: &[Case],
+    expected_type: &TypCtor,
+    ctx: &Ctx,
+) -> Result<Vec<Case>, TypeError> {
+    let codata = ctx.type_info_table.lookup_codata(&expected_type.name)?;
+    let mut cases = cases.to_vec();
+
+    for dtor in &codata.dtors {
+        let Some(default) = &dtor.default else {
+            continue;
+        };
+        if cases.iter().any(|case| case.pattern.name.id == dtor.name.id) {
+            continue;
+        }
+        let params = dtor
+            .params
+            .params
+            .iter()
+            .map(|Param { name, .. }| ParamInst {
+                span: None,
+                info: None,
+                name: name.clone(),
+                typ: None,
+            })
+            .collect();
+        cases.push(Case {
+            span: None,
+            pattern: Pattern {
+                is_copattern: true,
+                name: IdBound {
+                    span: None,
+                    id: dtor.name.id.clone(),
+                    uri: expected_type.name.uri.clone(),
+                },
+                params: TelescopeInst { params },
+            },
+            body: Some(default.clone()),
+        });
+    }
+
+    Ok(cases)
+}
+
 /// This struct is used to share code between the typechecking of local and global comatches.
 pub struct WithExpectedType<'a> {
     pub cases: &'a Vec<Case>,
@@ -93,7 +146,20 @@ impl WithExpectedType<'_> {
             || !dtors_duplicate.is_empty()
         {
             return Err(TypeError::invalid_match(
-                dtors_missing.map(|i| &i.id).cloned().collect(),
+                codata
+                    .dtors
+                    .iter()
+                    .filter(|dtor| !dtors_actual.contains(&dtor.name))
+                    .map(|dtor| Pattern {
+                        is_copattern: true,
+                        name: IdBound {
+                            span: None,
+                            id: dtor.name.id.clone(),
+                            uri: self.expected_type.name.uri.clone(),
+                        },
+                        params: dtor.params.instantiate(),
+                    })
+                    .collect(),
                 dtors_exessive.map(|i| &i.id).cloned().collect(),
                 dtors_duplicate.into_iter().map(|i| i.id).collect(),
                 &self.expected_type.span(),
@@ -142,11 +208,9 @@ impl WithExpectedType<'_> {
             // of the destructor declaration.
             // TODO: Why can't we do this once *before* we repeatedly look them up in the context?
             let def_args = def_args
-                .normalize(&ctx.type_info_table, &mut LevelCtx::from(vec![params.len()]).env())?;
-            let ret_typ = ret_typ.normalize(
-                &ctx.type_info_table,
-                &mut LevelCtx::from(vec![params.len(), 1]).env(),
-            )?;
+                .normalize(&ctx.evaluator(), &mut LevelCtx::from(vec![params.len()]).env())?;
+            let ret_typ = ret_typ
+                .normalize(&ctx.evaluator(), &mut LevelCtx::from(vec![params.len(), 1]).env())?;
 
             let name = name.clone();
             let params = params.clone();
@@ -277,7 +341,7 @@ impl WithExpectedType<'_> {
                                     let mut ret_typ = ret_typ.subst(&mut subst_ctx, &subst);
                                     ret_typ.shift((-1, 0));
                                     ret_typ.normalize(
-                                        &ctx.type_info_table,
+                                        &ctx.evaluator(),
                                         &mut LevelCtx::from(vec![*n_label_args, params.len()])
                                             .env(),
                                     )?
@@ -303,13 +367,12 @@ impl WithExpectedType<'_> {
                                 .ok_yes()?;
 
                                 ctx.fork::<Result<_, TypeError>, _>(|ctx| {
-                                    let type_info_table = ctx.type_info_table.clone();
-                                    ctx.subst(&type_info_table, &unif)?;
+                                    ctx.subst(&unif)?;
                                     let body = body.subst(&mut ctx.levels(), &unif);
 
                                     let t_subst = ret_typ_nf.subst(&mut ctx.levels(), &unif);
                                     let t_nf =
-                                        t_subst.normalize(&ctx.type_info_table, &mut ctx.env())?;
+                                        t_subst.normalize(&ctx.evaluator(), &mut ctx.env())?;
 
                                     let body_out = body.check(ctx, &t_nf)?;
 
@@ -0,0 +1,117 @@
+//! Bidirectional type checking for local let bindings
+
+use crate::normalizer::env::ToEnv;
+use crate::normalizer::normalize::Normalize;
+use ast::ctx::values::Binder;
+use ast::ctx::BindContext;
+use ast::*;
+
+use super::super::ctx::*;
+use super::CheckInfer;
+use crate::result::TypeError;
+
+impl CheckInfer for LocalLet {
+    /// The *checking* rule for local let bindings is:
+    /// ```text
+    ///            P, Γ ⊢ e ⇒ σ
+    ///            P, Γ, x: σ := e ⊢ b ⇐ τ
+    ///           ──────────────────────────────────
+    ///            P, Γ ⊢ (let x := e in b) ⇐ τ
+    /// ```
+    /// The bound expression `e` is checked against the annotated type instead of inferred,
+    /// if there is one. While checking `b`, the variable `x` unfolds to `e`.
+    fn check(&self, ctx: &mut Ctx, t: &Exp) -> Result<Self, TypeError> {
+        let LocalLet { span, name, body, .. } = self;
+        let (typ_out, bound_out, bound_type) = check_bound(self, ctx)?;
+        let binder = let_binder(name, &bound_out, &bound_type);
+        let t_shifted = shift_and_clone(t, (1, 0));
+        let body_out = ctx.bind_single(&binder, |ctx| body.check(ctx, &t_shifted))?;
+        Ok(LocalLet {
+            span: *span,
+            name: name.clone(),
+            typ: typ_out,
+            bound: bound_out,
+            body: body_out,
+            bound_type: Some(bound_type),
+            inferred_type: Some(Box::new(t.clone())),
+        })
+    }
+
+    /// The *inference* rule for local let bindings is:
+    /// ```text
+    ///            P, Γ ⊢ e ⇒ σ
+    ///            P, Γ, x: σ := e ⊢ b ⇒ τ
+    ///           ──────────────────────────────────
+    ///            P, Γ ⊢ (let x := e in b) ⇒ τ[e/x]
+    /// ```
+    fn infer(&self, ctx: &mut Ctx) -> Result<Self, TypeError> {
+        let LocalLet { span, name, body, .. } = self;
+        let (typ_out, bound_out, bound_type) = check_bound(self, ctx)?;
+        let binder = let_binder(name, &bound_out, &bound_type);
+        let (body_out, mut typ) = ctx.bind_single(&binder, |ctx| {
+            let body_out = body.infer(ctx)?;
+            let typ = body_out.typ().ok_or(TypeError::Impossible {
+                message: "Expected inferred type".to_owned(),
+                span: None,
+            })?;
+            // The bound variable does not escape its scope
+            let mut levels = ctx.levels();
+            let lvl = Lvl { fst: levels.len() - 1, snd: 0 };
+            let subst = Assign { lvl, exp: shift_and_clone(&bound_out, (1, 0)) };
+            Ok::<_, TypeError>((body_out, typ.subst(&mut levels, &subst)))
+        })?;
+        typ.shift((-1, 0));
+        let typ_nf = typ
+            .normalize(&ctx.evaluator(), &mut ctx.env())
+            .map_err(|err| err.while_elaborating(*span))?;
+        Ok(LocalLet {
+            span: *span,
+            name: name.clone(),
+            typ: typ_out,
+            bound: bound_out,
+            body: body_out,
+            bound_type: Some(bound_type),
+            inferred_type: Some(typ_nf),
+        })
+    }
+}
+
+/// Check the bound expression of `this` against its annotated type, or infer its type
+///
+/// Returns the elaborated annotation, the elaborated bound expression and its normalized type.
+fn check_bound(
+    this: &LocalLet,
+    ctx: &mut Ctx,
+) -> Result<(Option<Box<Exp>>, Box<Exp>, Box<Exp>), TypeError> {
+    let LocalLet { typ, bound, .. } = this;
+    match typ {
+        Some(typ) => {
+            let typ_out = typ.check(ctx, &Box::new(TypeUniv::new().into()))?;
+            let typ_nf = typ
+                .normalize(&ctx.evaluator(), &mut ctx.env())
+                .map_err(|err| err.while_elaborating(typ.span()))?;
+            let bound_out = bound.check(ctx, &typ_nf)?;
+            Ok((Some(typ_out), bound_out, typ_nf))
+        }
+        None => {
+            let bound_out = bound.infer(ctx)?;
+            let typ = bound_out.typ().ok_or(TypeError::Impossible {
+                message: "Expected inferred type".to_owned(),
+                span: None,
+            })?;
+            Ok((None, bound_out, typ))
+        }
+    }
+}
+
+/// The binder of the variable `name`, which unfolds to `bound`
+///
+/// The binder is a telescope of its own, so its type and value are shifted accordingly.
+fn let_binder(name: &VarBind, bound: &Exp, typ: &Exp) -> Binder {
+    let binder = Binder {
+        name: name.clone(),
+        typ: Box::new(typ.clone()),
+        value: Some(Box::new(bound.clone())),
+    };
+    shift_and_clone(&binder, (1, 0))
+}
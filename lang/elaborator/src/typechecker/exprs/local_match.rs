@@ -2,6 +2,8 @@
 
 use std::collections::HashSet;
 
+use codespan::Span;
+
 use ast::ctx::values::Binder;
 use ast::ctx::{BindContext, LevelCtx};
 use ast::*;
@@ -11,7 +13,7 @@ use crate::normalizer::env::ToEnv;
 use crate::normalizer::normalize::Normalize;
 use crate::result::TypeError;
 use crate::typechecker::exprs::CheckTelescope;
-use crate::typechecker::type_info_table::CtorMeta;
+use crate::typechecker::type_info_table::{CtorMeta, TypeInfoTable};
 use crate::unifier::constraints::Constraint;
 use crate::unifier::unify::*;
 
@@ -26,6 +28,11 @@ use super::CheckInfer;
 impl CheckInfer for LocalMatch {
     fn check(&self, ctx: &mut Ctx, t: &Exp) -> Result<Self, TypeError> {
         let LocalMatch { span, name, on_exp, motive, cases, .. } = self;
+        if let Exp::Hole(Hole { kind: MetaVarKind::Inserted, metavar, .. }) = &**on_exp {
+            if cases.is_empty() {
+                return self.check_absurd(ctx, t, *metavar);
+            }
+        }
         let on_exp_out = on_exp.infer(ctx)?;
         let typ_app_nf = on_exp_out
             .typ()
@@ -44,10 +51,29 @@ impl CheckInfer for LocalMatch {
             // Pattern matching with motive
             Some(m) => {
                 let Motive { span: info, param, ret_typ } = m;
-                let mut self_t_nf =
-                    typ_app.to_exp().normalize(&ctx.type_info_table, &mut ctx.env())?;
+                let mut self_t_nf = typ_app.to_exp().normalize(&ctx.evaluator(), &mut ctx.env())?;
                 self_t_nf.shift((1, 0));
-                let self_binder = Binder { name: param.name.clone(), typ: self_t_nf.clone() };
+                let self_binder =
+                    Binder { name: param.name.clone(), typ: self_t_nf.clone(), value: None };
+
+                let ret_typ = match ret_typ {
+                    Some(ret_typ) => ret_typ.clone(),
+                    // `with` abstraction: Abstract the scrutinee in the expected type
+                    None => {
+                        let mut on_exp_nf =
+                            on_exp_out.normalize(&ctx.evaluator(), &mut ctx.env())?;
+                        on_exp_nf.shift((1, 0));
+                        let var = Variable {
+                            span: None,
+                            idx: Idx { fst: 0, snd: 0 },
+                            name: VarBound::from_string(&param.name.id),
+                            inferred_type: None,
+                        };
+                        let mut ret_typ = Box::new(shift_and_clone(t, (1, 0)));
+                        abstract_exp(&mut ret_typ, &on_exp_nf, &var);
+                        ret_typ
+                    }
+                };
 
                 // Typecheck the motive
                 let ret_typ_out = ctx.bind_single(&self_binder, |ctx| {
@@ -60,11 +86,14 @@ impl CheckInfer for LocalMatch {
                 let subst = Assign { lvl: Lvl { fst: subst_ctx.len() - 1, snd: 0 }, exp: on_exp };
                 let mut motive_t = ret_typ.subst(&mut subst_ctx, &subst);
                 motive_t.shift((-1, 0));
-                let motive_t_nf = motive_t.normalize(&ctx.type_info_table, &mut ctx.env())?;
-                convert(subst_ctx, &mut ctx.meta_vars, motive_t_nf, t, span)?;
+                let motive_t_nf = motive_t
+                    .normalize(&ctx.evaluator(), &mut ctx.env())
+                    .map_err(|err| err.while_elaborating(*info))?;
+                convert(subst_ctx, &mut ctx.meta_vars, motive_t_nf, t, span)
+                    .map_err(|err| err.motive_mismatch(*info, on_exp_out.span()))?;
 
                 body_t = ctx.bind_single(&self_binder, |ctx| {
-                    ret_typ.normalize(&ctx.type_info_table, &mut ctx.env())
+                    ret_typ.normalize(&ctx.evaluator(), &mut ctx.env())
                 })?;
                 motive_out = Some(Motive {
                     span: *info,
@@ -74,7 +103,7 @@ impl CheckInfer for LocalMatch {
                         name: param.name.clone(),
                         typ: Box::new(typ_app.to_exp()).into(),
                     },
-                    ret_typ: ret_typ_out,
+                    ret_typ: Some(ret_typ_out),
                 });
             }
             // Pattern matching without motive
@@ -84,7 +113,11 @@ impl CheckInfer for LocalMatch {
             }
         };
 
-        let with_scrutinee_type = WithScrutineeType { cases, scrutinee_type: typ_app_nf.clone() };
+        let with_scrutinee_type = WithScrutineeType {
+            cases,
+            scrutinee_type: typ_app_nf.clone(),
+            motive_span: motive.as_ref().and_then(|motive| motive.span),
+        };
         with_scrutinee_type.check_exhaustiveness(ctx)?;
         let cases = with_scrutinee_type.check_type(ctx, &body_t)?;
 
@@ -105,9 +138,118 @@ impl CheckInfer for LocalMatch {
     }
 }
 
+impl LocalMatch {
+    /// Check `absurd`, which is lowered to an empty match on the inserted hole `metavar`
+    ///
+    /// The hole is solved by the innermost variable in the context whose type is a data type
+    /// without constructors, which is then matched on.
+    fn check_absurd(&self, ctx: &mut Ctx, t: &Exp, metavar: MetaVar) -> Result<Self, TypeError> {
+        let hypothesis = ctx
+            .vars
+            .iter()
+            .enumerate()
+            .flat_map(|(fst, binders)| {
+                binders.iter().enumerate().map(move |(snd, binder)| (Lvl { fst, snd }, binder))
+            })
+            .filter(|(_, binder)| is_empty_type(&ctx.type_info_table, &binder.typ))
+            .last()
+            .map(|(lvl, binder)| (lvl, binder.name.clone()));
+        let Some((lvl, name)) = hypothesis else {
+            return Err(TypeError::NoAbsurdHypothesis { span: self.span.to_miette() });
+        };
+        let on_exp: Box<Exp> = Box::new(
+            Variable {
+                span: self.span,
+                idx: ctx.vars.lvl_to_idx(lvl),
+                name: VarBound::from_string(&name.id),
+                inferred_type: None,
+            }
+            .into(),
+        );
+        ctx.meta_vars
+            .insert(metavar, MetaVarState::Solved { ctx: ctx.levels(), solution: on_exp.clone() });
+        LocalMatch { on_exp, ..self.clone() }.check(ctx, t)
+    }
+}
+
+/// Whether `typ` is a data type without constructors
+fn is_empty_type(type_info_table: &TypeInfoTable, typ: &Exp) -> bool {
+    match typ {
+        Exp::TypCtor(TypCtor { name, .. }) => {
+            type_info_table.lookup_data(name).is_ok_and(|data| data.ctors.is_empty())
+        }
+        _ => false,
+    }
+}
+
+/// Replace every occurrence of `target` in `exp` by the variable `var`
+///
+/// Both `target` and `var` are shifted accordingly when going under binders.
+fn abstract_exp(exp: &mut Exp, target: &Exp, var: &Variable) {
+    if *exp == *target {
+        *exp = Exp::Variable(var.clone());
+        return;
+    }
+
+    match exp {
+        Exp::Variable(_) | Exp::TypeUniv(_) | Exp::Hole(_) => {}
+        Exp::TypCtor(TypCtor { args, .. }) | Exp::Call(Call { args, .. }) => {
+            abstract_args(args, target, var)
+        }
+        Exp::DotCall(DotCall { exp, args, .. }) => {
+            abstract_exp(exp, target, var);
+            abstract_args(args, target, var);
+        }
+        Exp::Anno(Anno { exp, typ, .. }) => {
+            abstract_exp(exp, target, var);
+            abstract_exp(typ, target, var);
+        }
+        Exp::LocalMatch(LocalMatch { on_exp, motive, cases, .. }) => {
+            abstract_exp(on_exp, target, var);
+            if let Some(Motive { ret_typ: Some(ret_typ), .. }) = motive {
+                let target = shift_and_clone(target, (1, 0));
+                let var = shift_and_clone(var, (1, 0));
+                abstract_exp(ret_typ, &target, &var);
+            }
+            abstract_cases(cases, target, var);
+        }
+        Exp::LocalComatch(LocalComatch { cases, .. }) => abstract_cases(cases, target, var),
+        Exp::LocalLet(LocalLet { typ, bound, body, .. }) => {
+            if let Some(typ) = typ {
+                abstract_exp(typ, target, var);
+            }
+            abstract_exp(bound, target, var);
+            let target = shift_and_clone(target, (1, 0));
+            let var = shift_and_clone(var, (1, 0));
+            abstract_exp(body, &target, &var);
+        }
+    }
+}
+
+fn abstract_args(args: &mut Args, target: &Exp, var: &Variable) {
+    for arg in args.args.iter_mut() {
+        match arg {
+            Arg::UnnamedArg(exp) | Arg::NamedArg(_, exp) => abstract_exp(exp, target, var),
+            Arg::InsertedImplicitArg(_) => {}
+        }
+    }
+}
+
+fn abstract_cases(cases: &mut [Case], target: &Exp, var: &Variable) {
+    let target = shift_and_clone(target, (1, 0));
+    let var = shift_and_clone(var, (1, 0));
+    for case in cases.iter_mut() {
+        if let Some(body) = &mut case.body {
+            abstract_exp(body, &target, &var);
+        }
+    }
+}
+
 pub struct WithScrutineeType<'a> {
     pub cases: &'a Vec<Case>,
     pub scrutinee_type: TypCtor,
+    /// The span of the motive of a local match, which determines the types of the cases
+    pub motive_span: Option<Span>,
 }
 
 /// Check a pattern match
@@ -139,7 +281,19 @@ impl WithScrutineeType<'_> {
             || !ctors_duplicate.is_empty()
         {
             return Err(TypeError::invalid_match(
-                ctors_missing.map(|i| &i.id).cloned().collect(),
+                data.ctors
+                    .iter()
+                    .filter(|ctor| !ctors_actual.contains(&ctor.name))
+                    .map(|ctor| Pattern {
+                        is_copattern: false,
+                        name: IdBound {
+                            span: None,
+                            id: ctor.name.id.clone(),
+                            uri: self.scrutinee_type.name.uri.clone(),
+                        },
+                        params: ctor.params.instantiate(),
+                    })
+                    .collect(),
                 ctors_undeclared.map(|i| &i.id).cloned().collect(),
                 ctors_duplicate.into_iter().map(|i| i.id).collect(),
                 &self.scrutinee_type.span(),
@@ -177,7 +331,7 @@ impl WithScrutineeType<'_> {
             // Normalize the arguments of the constructor type.
             // They will later be unified with the arguments of the scrutinee type.
             let def_args_nf = LevelCtx::empty().bind_iter(params.params.iter(), |ctx_| {
-                def_args.normalize(&ctx.type_info_table, &mut ctx_.env())
+                def_args.normalize(&ctx.evaluator(), &mut ctx_.env())
             })?;
 
             // To check each individual case, we need to substitute the constructor for the self parameter
@@ -264,15 +418,18 @@ impl WithScrutineeType<'_> {
                                     .ok_yes()?;
 
                             ctx.fork::<Result<_, TypeError>, _>(|ctx| {
-                                let type_info_table = ctx.type_info_table.clone();
-                                ctx.subst(&type_info_table, &unif)?;
+                                ctx.subst(&unif)?;
                                 let body = body.subst(&mut ctx.levels(), &unif);
 
                                 let t_subst = t.subst(&mut ctx.levels(), &unif);
-                                let t_nf =
-                                    t_subst.normalize(&ctx.type_info_table, &mut ctx.env())?;
-
-                                let body_out = body.check(ctx, &t_nf)?;
+                                let t_nf = t_subst.normalize(&ctx.evaluator(), &mut ctx.env())?;
+
+                                let body_out = match self.motive_span {
+                                    Some(motive_span) => body.check(ctx, &t_nf).map_err(|err| {
+                                        err.case_motive_mismatch(&t_nf, Some(motive_span), span)
+                                    })?,
+                                    None => body.check(ctx, &t_nf)?,
+                                };
 
                                 Ok(Some(body_out))
                             })?
@@ -3,6 +3,7 @@ pub mod call;
 pub mod dot_call;
 pub mod hole;
 pub mod local_comatch;
+pub mod local_let;
 pub mod local_match;
 pub mod typ_ctor;
 pub mod type_univ;
@@ -19,6 +20,7 @@ use ast::ctx::LevelCtx;
 use ast::*;
 
 use super::ctx::*;
+use super::util::first_unbound_var;
 use crate::normalizer::{env::ToEnv, normalize::Normalize};
 use crate::result::TypeError;
 
@@ -75,6 +77,7 @@ impl CheckInfer for Exp {
             Exp::Hole(e) => Ok(e.check(ctx, t)?.into()),
             Exp::LocalMatch(e) => Ok(e.check(ctx, t)?.into()),
             Exp::LocalComatch(e) => Ok(e.check(ctx, t)?.into()),
+            Exp::LocalLet(e) => Ok(e.check(ctx, t)?.into()),
         }
     }
 
@@ -89,6 +92,7 @@ impl CheckInfer for Exp {
             Exp::Hole(e) => Ok(e.infer(ctx)?.into()),
             Exp::LocalMatch(e) => Ok(e.infer(ctx)?.into()),
             Exp::LocalComatch(e) => Ok(e.infer(ctx)?.into()),
+            Exp::LocalLet(e) => Ok(e.infer(ctx)?.into()),
         };
         trace!(
             "{} |- {} => {}",
@@ -134,15 +138,34 @@ fn check_args(
         });
     }
 
-    let Telescope { params } =
-        params.subst_in_telescope(LevelCtx::empty(), &vec![this.args.clone()]);
+    // Omitted arguments are replaced by the default values of their parameters.
+    // Since default values may refer to earlier parameters, they are instantiated one by one.
+    let mut args_in = this.args.clone();
+    for idx in 0..args_in.len() {
+        let Arg::InsertedImplicitArg(hole) = &args_in[idx] else {
+            continue;
+        };
+        let Telescope { params } =
+            params.subst_in_telescope(LevelCtx::empty(), &vec![args_in.clone()]);
+        let Some(default) = params[idx].default.clone() else {
+            continue;
+        };
+        ctx.meta_vars.insert(
+            hole.metavar,
+            MetaVarState::Solved { ctx: ctx.levels(), solution: default.clone() },
+        );
+        args_in[idx] = Arg::UnnamedArg(default);
+    }
+
+    let Telescope { params } = params.subst_in_telescope(LevelCtx::empty(), &vec![args_in.clone()]);
 
-    let args = this
-        .args
+    let args = args_in
         .iter()
         .zip(params)
         .map(|(exp, Param { typ, .. })| {
-            let typ = typ.normalize(&ctx.type_info_table, &mut ctx.env())?;
+            let typ = typ
+                .normalize(&ctx.evaluator(), &mut ctx.env())
+                .map_err(|err| err.while_elaborating(exp.span().or(span)))?;
             exp.check(ctx, &typ)
         })
         .collect::<Result<_, _>>()?;
@@ -205,7 +228,9 @@ impl CheckTelescope for TelescopeInst {
                 let ParamInst { span, name, .. } = param_actual;
                 let Param { typ, .. } = param_expected;
                 let typ_out = typ.check(ctx, &Box::new(TypeUniv::new().into()))?;
-                let typ_nf = typ.normalize(&ctx.type_info_table, &mut ctx.env())?;
+                let typ_nf = typ
+                    .normalize(&ctx.evaluator(), &mut ctx.env())
+                    .map_err(|err| err.while_elaborating(*span))?;
                 let mut params_out = params_out;
                 let param_out = ParamInst {
                     span: *span,
@@ -214,7 +239,7 @@ impl CheckTelescope for TelescopeInst {
                     typ: typ_out.into(),
                 };
                 params_out.push(param_out);
-                let elem = Binder { name: param_actual.name.clone(), typ: typ_nf };
+                let elem = Binder { name: param_actual.name.clone(), typ: typ_nf, value: None };
                 Result::<_, TypeError>::Ok(BindElem { elem, ret: params_out })
             },
             |ctx, params| f(ctx, TelescopeInst { params }),
@@ -236,12 +261,27 @@ impl InferTelescope for Telescope {
             params.iter(),
             vec![],
             |ctx, mut params_out, param| {
-                let Param { implicit, typ, name } = param;
+                let Param { implicit, lazy, typ, name, default } = param;
+                // The type of a parameter may only refer to earlier parameters of the telescope
+                if let Some(var) = first_unbound_var(typ, &mut ctx.levels()) {
+                    return Err(TypeError::ill_scoped_parameter(name, &var));
+                }
                 let typ_out = typ.check(ctx, &Box::new(TypeUniv::new().into()))?;
-                let typ_nf = typ.normalize(&ctx.type_info_table, &mut ctx.env())?;
-                let param_out = Param { implicit: *implicit, name: name.clone(), typ: typ_out };
+                let typ_nf = typ
+                    .normalize(&ctx.evaluator(), &mut ctx.env())
+                    .map_err(|err| err.while_elaborating(typ.span()))?;
+                // The default value may only refer to the earlier parameters
+                let default_out =
+                    default.as_ref().map(|default| default.check(ctx, &typ_nf)).transpose()?;
+                let param_out = Param {
+                    implicit: *implicit,
+                    lazy: *lazy,
+                    name: name.clone(),
+                    typ: typ_out,
+                    default: default_out,
+                };
                 params_out.push(param_out);
-                let elem = Binder { name: param.name.clone(), typ: typ_nf };
+                let elem = Binder { name: param.name.clone(), typ: typ_nf, value: None };
                 Result::<_, TypeError>::Ok(BindElem { elem, ret: params_out })
             },
             |ctx, params| f(ctx, Telescope { params }),
@@ -259,13 +299,62 @@ impl InferTelescope for SelfParam {
     ) -> Result<T, TypeError> {
         let SelfParam { info, name, typ } = self;
 
-        let typ_nf = typ.to_exp().normalize(&ctx.type_info_table, &mut ctx.env())?;
+        let typ_nf = typ
+            .to_exp()
+            .normalize(&ctx.evaluator(), &mut ctx.env())
+            .map_err(|err| err.while_elaborating(typ.span))?;
         let typ_out = typ.infer(ctx)?;
         let param_out = SelfParam { info: *info, name: name.clone(), typ: typ_out };
-        let elem =
-            Binder { name: name.clone().unwrap_or_else(|| VarBind::from_string("")), typ: typ_nf };
+        let elem = Binder {
+            name: name.clone().unwrap_or_else(|| VarBind::from_string("")),
+            typ: typ_nf,
+            value: None,
+        };
 
         // We need to shift the self parameter type here because we treat it as a 1-element telescope
         ctx.bind_single(&shift_and_clone(&elem, (1, 0)), |ctx| f(ctx, param_out))
     }
 }
+
+#[cfg(test)]
+mod infer_telescope_tests {
+    use std::rc::Rc;
+
+    use ast::test_support::{module, param, type_univ, var};
+
+    use crate::normalizer::evaluator::EvalConfig;
+    use crate::TypeInfoTable;
+
+    use super::*;
+
+    fn empty_ctx() -> Ctx {
+        Ctx::new(
+            HashMap::default(),
+            TypeInfoTable::default(),
+            Rc::new(module(vec![])),
+            &EvalConfig::default(),
+        )
+    }
+
+    #[test]
+    fn test_backward_reference() {
+        // (a: Type, x: a)
+        let tel = Telescope { params: vec![param("a", type_univ()), param("x", var("a", 0, 0))] };
+        let res = tel.infer_telescope(&mut empty_ctx(), |_, params_out| Ok(params_out));
+        assert!(res.is_ok())
+    }
+
+    #[test]
+    fn test_forward_reference() {
+        // (x: a, a: Type)
+        let tel = Telescope { params: vec![param("x", var("a", 0, 0)), param("a", type_univ())] };
+        let res = tel.infer_telescope(&mut empty_ctx(), |_, params_out| Ok(params_out));
+        match res {
+            Err(TypeError::IllScopedParameter { name, references, .. }) => {
+                assert_eq!(name, "x");
+                assert_eq!(references, "a");
+            }
+            _ => panic!("Expected IllScopedParameter error"),
+        }
+    }
+}
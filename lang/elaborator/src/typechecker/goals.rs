@@ -0,0 +1,242 @@
+//! Goals of named holes
+//!
+//! A named hole `?name` is a goal which the programmer can refer to by its name.
+//! The names of holes are unique within each declaration, so a goal is identified by the name
+//! of its declaration together with its own name.
+
+use ast::ctx::values::TypeCtx;
+use ast::*;
+
+/// A named hole of a typechecked module
+#[derive(Debug, Clone)]
+pub struct Goal {
+    /// The name of the declaration which contains the hole
+    pub decl: String,
+    /// The name of the hole, without the leading `?`
+    pub name: String,
+    /// The metavariable of the hole
+    pub metavar: MetaVar,
+    /// The expected type of the hole
+    pub typ: Option<Box<Exp>>,
+    /// The context of the variables which are in scope at the hole
+    pub ctx: Option<TypeCtx>,
+}
+
+/// Collect the goals of all named holes in a typechecked module, ordered by their location
+pub fn goals(module: &Module) -> Vec<Goal> {
+    let mut out = vec![];
+    for decl in module.decls.iter() {
+        decl.goals(&decl.ident().id, &mut out);
+    }
+    out
+}
+
+/// Collect the goals of all named holes in a standalone expression, ordered by their location
+///
+/// The goals are attributed to a declaration named `decl`.
+pub fn exp_goals(exp: &Exp, decl: &str) -> Vec<Goal> {
+    let mut out = vec![];
+    exp.goals(decl, &mut out);
+    out
+}
+
+trait Goals {
+    fn goals(&self, decl: &str, out: &mut Vec<Goal>);
+}
+
+impl<T: Goals> Goals for Box<T> {
+    fn goals(&self, decl: &str, out: &mut Vec<Goal>) {
+        (**self).goals(decl, out)
+    }
+}
+
+impl<T: Goals> Goals for Option<T> {
+    fn goals(&self, decl: &str, out: &mut Vec<Goal>) {
+        if let Some(x) = self {
+            x.goals(decl, out)
+        }
+    }
+}
+
+impl<T: Goals> Goals for Vec<T> {
+    fn goals(&self, decl: &str, out: &mut Vec<Goal>) {
+        for x in self {
+            x.goals(decl, out)
+        }
+    }
+}
+
+impl Goals for Decl {
+    fn goals(&self, decl: &str, out: &mut Vec<Goal>) {
+        match self {
+            Decl::Data(Data { typ, ctors, .. }) => {
+                typ.goals(decl, out);
+                ctors.goals(decl, out);
+            }
+            Decl::Codata(Codata { typ, dtors, .. }) => {
+                typ.goals(decl, out);
+                dtors.goals(decl, out);
+            }
+            Decl::Def(Def { params, self_param, ret_typ, cases, .. }) => {
+                params.goals(decl, out);
+                self_param.typ.goals(decl, out);
+                ret_typ.goals(decl, out);
+                cases.goals(decl, out);
+            }
+            Decl::Codef(Codef { params, typ, cases, .. }) => {
+                params.goals(decl, out);
+                typ.goals(decl, out);
+                cases.goals(decl, out);
+            }
+            Decl::Let(Let { params, typ, body, .. }) => {
+                params.goals(decl, out);
+                typ.goals(decl, out);
+                body.goals(decl, out);
+            }
+        }
+    }
+}
+
+impl Goals for Ctor {
+    fn goals(&self, decl: &str, out: &mut Vec<Goal>) {
+        self.params.goals(decl, out);
+        self.typ.goals(decl, out);
+    }
+}
+
+impl Goals for Dtor {
+    fn goals(&self, decl: &str, out: &mut Vec<Goal>) {
+        let Dtor { params, self_param, ret_typ, default, .. } = self;
+        params.goals(decl, out);
+        self_param.typ.goals(decl, out);
+        ret_typ.goals(decl, out);
+        default.goals(decl, out);
+    }
+}
+
+impl Goals for Telescope {
+    fn goals(&self, decl: &str, out: &mut Vec<Goal>) {
+        for param in self.params.iter() {
+            param.typ.goals(decl, out);
+            if let Some(default) = &param.default {
+                default.goals(decl, out);
+            }
+        }
+    }
+}
+
+impl Goals for Case {
+    fn goals(&self, decl: &str, out: &mut Vec<Goal>) {
+        self.body.goals(decl, out);
+    }
+}
+
+impl Goals for Args {
+    fn goals(&self, decl: &str, out: &mut Vec<Goal>) {
+        for arg in self.args.iter() {
+            match arg {
+                Arg::UnnamedArg(exp) | Arg::NamedArg(_, exp) => exp.goals(decl, out),
+                Arg::InsertedImplicitArg(_) => {}
+            }
+        }
+    }
+}
+
+impl Goals for TypCtor {
+    fn goals(&self, decl: &str, out: &mut Vec<Goal>) {
+        self.args.goals(decl, out);
+    }
+}
+
+impl Goals for Exp {
+    fn goals(&self, decl: &str, out: &mut Vec<Goal>) {
+        match self {
+            Exp::Hole(Hole { name: Some(name), metavar, inferred_type, inferred_ctx, .. }) => {
+                out.push(Goal {
+                    decl: decl.to_owned(),
+                    name: name.clone(),
+                    metavar: *metavar,
+                    typ: inferred_type.clone(),
+                    ctx: inferred_ctx.clone(),
+                });
+            }
+            Exp::Variable(_) | Exp::TypeUniv(_) | Exp::Hole(_) => {}
+            Exp::TypCtor(typ) => typ.goals(decl, out),
+            Exp::Call(Call { args, .. }) => args.goals(decl, out),
+            Exp::DotCall(DotCall { exp, args, .. }) => {
+                exp.goals(decl, out);
+                args.goals(decl, out);
+            }
+            Exp::Anno(Anno { exp, typ, .. }) => {
+                exp.goals(decl, out);
+                typ.goals(decl, out);
+            }
+            Exp::LocalMatch(LocalMatch { on_exp, motive, cases, .. }) => {
+                on_exp.goals(decl, out);
+                if let Some(Motive { ret_typ, .. }) = motive {
+                    ret_typ.goals(decl, out);
+                }
+                cases.goals(decl, out);
+            }
+            Exp::LocalComatch(LocalComatch { cases, .. }) => cases.goals(decl, out),
+            Exp::LocalLet(LocalLet { typ, bound, body, .. }) => {
+                typ.goals(decl, out);
+                bound.goals(decl, out);
+                body.goals(decl, out);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod goals_tests {
+    use url::Url;
+
+    use super::*;
+
+    fn hole(name: Option<&str>, id: u64) -> Box<Exp> {
+        let kind = MetaVarKind::CanSolve;
+        Box::new(Exp::Hole(Hole {
+            span: None,
+            kind,
+            name: name.map(ToOwned::to_owned),
+            metavar: MetaVar { span: None, kind, id },
+            inferred_type: None,
+            inferred_ctx: None,
+            args: vec![],
+            solution: None,
+        }))
+    }
+
+    #[test]
+    fn collect_named_holes() {
+        let uri = Url::parse("inmemory:///scratch.pol").unwrap();
+        let typ = Box::new(Exp::TypeUniv(TypeUniv { span: None }));
+        let body = Box::new(Exp::Anno(Anno {
+            span: None,
+            exp: hole(Some("body"), 1),
+            typ: hole(None, 0),
+            normalized_type: None,
+        }));
+        let decl = Decl::Let(Let {
+            span: None,
+            doc: None,
+            name: IdBind::from_string("example"),
+            attr: Attributes::default(),
+            params: Telescope { params: vec![] },
+            typ,
+            body,
+        });
+        let module = Module {
+            uri,
+            use_decls: vec![],
+            decls: vec![decl],
+            meta_vars: HashMap::default(),
+            comments: vec![],
+        };
+
+        let goals = goals(&module);
+        assert_eq!(goals.len(), 1);
+        assert_eq!((goals[0].decl.as_str(), goals[0].name.as_str()), ("example", "body"));
+    }
+}
@@ -1,8 +1,14 @@
 pub mod ctx;
 pub mod decls;
 pub mod exprs;
+pub mod goals;
+pub mod positivity;
+pub mod termination;
 pub mod type_info_table;
 pub mod util;
 
 pub use crate::result::TypeError;
-pub use decls::check_with_lookup_table;
+pub use decls::{
+    check_all_with_lookup_table, check_incrementally, check_with_lookup_table,
+    infer_exp_with_lookup_table, ModuleErrors,
+};
@@ -0,0 +1,100 @@
+//! Strict positivity checking of data type declarations
+//!
+//! A data type `D` is strictly positive if the type of every constructor argument either
+//! does not mention `D` at all, or is `D(..)` applied to indices which do not mention `D`.
+//!
+//! Occurrences of `D` in the arguments of other type constructors are rejected.
+//! This conservatively rules out negative occurrences such as `Fun(D, X)`,
+//! but also some strictly positive nested occurrences such as `List(D)`.
+//! Codata types are not checked, since their destructors may consume the type being defined.
+
+use ast::*;
+use miette_util::ToMiette;
+
+use super::TypeError;
+
+/// Check that `data_type_name` occurs strictly positively in the arguments of `ctor`
+pub fn check_positivity(data_type_name: &IdBind, ctor: &Ctor) -> Result<(), TypeError> {
+    let Ctor { name, params, .. } = ctor;
+
+    for param in params.params.iter() {
+        if !is_strictly_positive(data_type_name, &param.typ) {
+            return Err(TypeError::NonStrictlyPositive {
+                data_name: data_type_name.id.clone(),
+                ctor_name: name.id.clone(),
+                span: param.typ.span().to_miette(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+fn is_strictly_positive(data_type_name: &IdBind, typ: &Exp) -> bool {
+    match typ {
+        Exp::TypCtor(TypCtor { name, args, .. }) if name == data_type_name => {
+            !args.mentions(data_type_name)
+        }
+        Exp::Anno(Anno { exp, .. }) => is_strictly_positive(data_type_name, exp),
+        _ => !typ.mentions(data_type_name),
+    }
+}
+
+trait Mentions {
+    /// Whether the type constructor `name` occurs in `self`
+    fn mentions(&self, name: &IdBind) -> bool;
+}
+
+impl<T: Mentions> Mentions for Box<T> {
+    fn mentions(&self, name: &IdBind) -> bool {
+        (**self).mentions(name)
+    }
+}
+
+impl<T: Mentions> Mentions for Option<T> {
+    fn mentions(&self, name: &IdBind) -> bool {
+        self.as_ref().is_some_and(|x| x.mentions(name))
+    }
+}
+
+impl<T: Mentions> Mentions for Vec<T> {
+    fn mentions(&self, name: &IdBind) -> bool {
+        self.iter().any(|x| x.mentions(name))
+    }
+}
+
+impl Mentions for Args {
+    fn mentions(&self, name: &IdBind) -> bool {
+        self.args.iter().any(|arg| match arg {
+            Arg::UnnamedArg(exp) | Arg::NamedArg(_, exp) => exp.mentions(name),
+            Arg::InsertedImplicitArg(_) => false,
+        })
+    }
+}
+
+impl Mentions for Case {
+    fn mentions(&self, name: &IdBind) -> bool {
+        self.body.mentions(name)
+    }
+}
+
+impl Mentions for Exp {
+    fn mentions(&self, name: &IdBind) -> bool {
+        match self {
+            Exp::Variable(_) | Exp::TypeUniv(_) | Exp::Hole(_) => false,
+            Exp::TypCtor(TypCtor { name: other, args, .. }) => other == name || args.mentions(name),
+            Exp::Call(Call { args, .. }) => args.mentions(name),
+            Exp::DotCall(DotCall { exp, args, .. }) => exp.mentions(name) || args.mentions(name),
+            Exp::Anno(Anno { exp, typ, .. }) => exp.mentions(name) || typ.mentions(name),
+            Exp::LocalMatch(LocalMatch { on_exp, motive, cases, .. }) => {
+                on_exp.mentions(name)
+                    || motive.as_ref().is_some_and(|motive| motive.ret_typ.mentions(name))
+                    || cases.mentions(name)
+            }
+            Exp::LocalComatch(LocalComatch { cases, .. }) => cases.mentions(name),
+            Exp::LocalLet(LocalLet { typ, bound, body, .. }) => {
+                typ.mentions(name) || bound.mentions(name) || body.mentions(name)
+            }
+        }
+    }
+}
@@ -0,0 +1,295 @@
+//! Structural termination checking of toplevel definitions and codefinitions
+//!
+//! A definition `d` is accepted if every recursive call `e.d(..)` in its cases is on a variable
+//! `e` which is structurally smaller than the scrutinee of `d`.
+//! The variables bound by the patterns of `d` are structurally smaller than the scrutinee,
+//! and so are the variables bound by the patterns of a local match on a smaller variable.
+//!
+//! A codefinition `C` is accepted if no recursive call `C(..)` in its cocases is immediately
+//! destructed, since the evaluation of a call of `C` is blocked until it is destructed.
+//!
+//! Recursive calls in the arguments of lazy constructor parameters are always accepted,
+//! since they are only evaluated on demand. Mutual recursion is not taken into account.
+
+use std::collections::HashSet;
+
+use ast::ctx::{BindContext, LevelCtx};
+use ast::*;
+use codespan::Span;
+use miette_util::ToMiette;
+
+use super::type_info_table::TypeInfoTable;
+use super::TypeError;
+
+/// A recursive call which the termination check has to justify
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecursiveCall {
+    pub span: Option<Span>,
+    /// The name of the structurally smaller variable on which the call is made.
+    /// This is `None` if the call is not decreasing.
+    pub decreasing_on: Option<String>,
+}
+
+/// Check that the declaration `decl` terminates
+///
+/// The check is skipped for declarations with the attribute `#[no_termination_check]`.
+pub fn check_termination(decl: &Decl, info_table: &TypeInfoTable) -> Result<(), TypeError> {
+    let (name, attr) = match decl {
+        Decl::Def(Def { name, attr, .. }) | Decl::Codef(Codef { name, attr, .. }) => (name, attr),
+        _ => return Ok(()),
+    };
+    if attr.attrs.contains(&Attribute::NoTerminationCheck) {
+        return Ok(());
+    }
+    match recursive_calls(decl, info_table).into_iter().find(|call| call.decreasing_on.is_none()) {
+        Some(RecursiveCall { span, .. }) => {
+            Err(TypeError::PossiblyNonTerminating { name: name.id.clone(), span: span.to_miette() })
+        }
+        None => Ok(()),
+    }
+}
+
+/// The recursive calls of the declaration `decl` in the order in which they occur
+///
+/// For definitions, these are all recursive calls outside of lazy constructor arguments.
+/// For codefinitions, only the recursive calls which are destructed immediately are returned,
+/// since all other calls are guarded.
+pub fn recursive_calls(decl: &Decl, info_table: &TypeInfoTable) -> Vec<RecursiveCall> {
+    let (rec, params, cases) = match decl {
+        Decl::Def(Def { name, params, cases, .. }) => (Recursion::Def(&name.id), params, cases),
+        Decl::Codef(Codef { name, params, cases, .. }) => {
+            (Recursion::Codef(&name.id), params, cases)
+        }
+        _ => return vec![],
+    };
+
+    let mut checker = Checker { rec, info_table, calls: vec![] };
+    let mut ctx = LevelCtx::empty();
+    ctx.bind_iter(params.params.iter().map(|_| ()), |ctx| {
+        for Case { pattern, body, .. } in cases.iter() {
+            ctx.bind_iter(pattern.params.params.iter().map(|_| ()), |ctx| {
+                // For definitions, all pattern variables are smaller than the scrutinee
+                let smaller = match rec {
+                    Recursion::Def(_) => pattern_vars(ctx, &pattern.params),
+                    Recursion::Codef(_) => HashSet::new(),
+                };
+                body.check(&mut checker, ctx, &smaller)
+            })
+        }
+    });
+    checker.calls
+}
+
+/// The levels of the variables bound by the innermost telescope `params` in `ctx`
+fn pattern_vars(ctx: &LevelCtx, params: &TelescopeInst) -> HashSet<Lvl> {
+    (0..params.params.len()).map(|snd| Lvl { fst: ctx.len() - 1, snd }).collect()
+}
+
+#[derive(Clone, Copy)]
+enum Recursion<'a> {
+    /// The name of a definition whose recursive calls must be structurally decreasing
+    Def(&'a str),
+    /// The name of a codefinition whose recursive calls must not be destructed immediately
+    Codef(&'a str),
+}
+
+struct Checker<'a> {
+    rec: Recursion<'a>,
+    info_table: &'a TypeInfoTable,
+    /// The recursive calls found so far
+    calls: Vec<RecursiveCall>,
+}
+
+impl Checker<'_> {
+    /// Whether the parameters of the constructor `name` are lazy
+    fn lazy_params(&self, name: &IdBound) -> Vec<bool> {
+        match self.info_table.lookup_ctor(name) {
+            Ok(meta) => meta.params.params.iter().map(|param| param.lazy).collect(),
+            Err(_) => vec![],
+        }
+    }
+}
+
+/// The variable `exp` if it is structurally smaller than the scrutinee
+fn smaller_var<'a>(exp: &'a Exp, ctx: &LevelCtx, smaller: &HashSet<Lvl>) -> Option<&'a VarBound> {
+    match exp {
+        Exp::Variable(Variable { idx, name, .. }) => {
+            smaller.contains(&ctx.idx_to_lvl(*idx)).then_some(name)
+        }
+        Exp::Anno(Anno { exp, .. }) => smaller_var(exp, ctx, smaller),
+        _ => None,
+    }
+}
+
+trait CheckTermination {
+    /// Record the recursive calls in `self`
+    ///
+    /// `ctx` contains the binders between the root of the declaration and `self`.
+    /// `smaller` contains the levels of the variables which are structurally smaller than the
+    /// scrutinee of the declaration.
+    fn check(&self, checker: &mut Checker, ctx: &mut LevelCtx, smaller: &HashSet<Lvl>);
+}
+
+impl<T: CheckTermination> CheckTermination for Box<T> {
+    fn check(&self, checker: &mut Checker, ctx: &mut LevelCtx, smaller: &HashSet<Lvl>) {
+        (**self).check(checker, ctx, smaller)
+    }
+}
+
+impl<T: CheckTermination> CheckTermination for Option<T> {
+    fn check(&self, checker: &mut Checker, ctx: &mut LevelCtx, smaller: &HashSet<Lvl>) {
+        if let Some(x) = self {
+            x.check(checker, ctx, smaller)
+        }
+    }
+}
+
+impl CheckTermination for Args {
+    fn check(&self, checker: &mut Checker, ctx: &mut LevelCtx, smaller: &HashSet<Lvl>) {
+        for arg in self.args.iter() {
+            match arg {
+                Arg::UnnamedArg(exp) | Arg::NamedArg(_, exp) => exp.check(checker, ctx, smaller),
+                Arg::InsertedImplicitArg(_) => {}
+            }
+        }
+    }
+}
+
+impl CheckTermination for Exp {
+    fn check(&self, checker: &mut Checker, ctx: &mut LevelCtx, smaller: &HashSet<Lvl>) {
+        match self {
+            Exp::Variable(_) | Exp::TypeUniv(_) | Exp::Hole(_) => {}
+            Exp::TypCtor(TypCtor { args, .. }) => args.check(checker, ctx, smaller),
+            Exp::Call(Call { kind: CallKind::Constructor, name, args, .. }) => {
+                let lazy = checker.lazy_params(name);
+                for (i, arg) in args.args.iter().enumerate() {
+                    match arg {
+                        // Lazy arguments are only evaluated on demand
+                        _ if lazy.get(i).copied().unwrap_or(false) => {}
+                        Arg::UnnamedArg(exp) | Arg::NamedArg(_, exp) => {
+                            exp.check(checker, ctx, smaller)
+                        }
+                        Arg::InsertedImplicitArg(_) => {}
+                    }
+                }
+            }
+            Exp::Call(Call { args, .. }) => args.check(checker, ctx, smaller),
+            Exp::DotCall(DotCall { span, exp, name, args, .. }) => {
+                match (checker.rec, &**exp) {
+                    (Recursion::Def(def), _) if name.id == def => {
+                        let decreasing_on =
+                            smaller_var(exp, ctx, smaller).map(|var| var.id.clone());
+                        checker.calls.push(RecursiveCall { span: *span, decreasing_on });
+                    }
+                    (Recursion::Codef(codef), Exp::Call(call)) if call.name.id == codef => {
+                        checker.calls.push(RecursiveCall { span: *span, decreasing_on: None });
+                    }
+                    _ => {}
+                }
+                exp.check(checker, ctx, smaller);
+                args.check(checker, ctx, smaller)
+            }
+            Exp::Anno(Anno { exp, typ, .. }) => {
+                exp.check(checker, ctx, smaller);
+                typ.check(checker, ctx, smaller)
+            }
+            Exp::LocalMatch(LocalMatch { on_exp, motive, cases, .. }) => {
+                on_exp.check(checker, ctx, smaller);
+                if let Some(Motive { ret_typ, .. }) = motive {
+                    ctx.bind_single((), |ctx| ret_typ.check(checker, ctx, smaller));
+                }
+                // Matching on a smaller variable binds smaller variables
+                let on_smaller = smaller_var(on_exp, ctx, smaller).is_some();
+                for Case { pattern, body, .. } in cases.iter() {
+                    ctx.bind_iter(pattern.params.params.iter().map(|_| ()), |ctx| {
+                        let mut smaller = smaller.clone();
+                        if on_smaller {
+                            smaller.extend(pattern_vars(ctx, &pattern.params));
+                        }
+                        body.check(checker, ctx, &smaller)
+                    })
+                }
+            }
+            Exp::LocalComatch(LocalComatch { cases, .. }) => {
+                for Case { pattern, body, .. } in cases.iter() {
+                    ctx.bind_iter(pattern.params.params.iter().map(|_| ()), |ctx| {
+                        body.check(checker, ctx, smaller)
+                    })
+                }
+            }
+            Exp::LocalLet(LocalLet { typ, bound, body, .. }) => {
+                typ.check(checker, ctx, smaller);
+                bound.check(checker, ctx, smaller);
+                // A variable bound to a smaller variable is smaller as well
+                let bound_smaller = smaller_var(bound, ctx, smaller).is_some();
+                ctx.bind_single((), |ctx| {
+                    let mut smaller = smaller.clone();
+                    if bound_smaller {
+                        smaller.insert(Lvl { fst: ctx.len() - 1, snd: 0 });
+                    }
+                    body.check(checker, ctx, &smaller)
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod termination_tests {
+    use ast::test_support::{case, ctor, dot_call, id, pattern, var};
+
+    use super::*;
+
+    /// The recursive call `exp.d`
+    fn rec_call(exp: Box<Exp>) -> Box<Exp> {
+        dot_call(DotCallKind::Definition, exp, "d", vec![])
+    }
+
+    /// `def Nat.d: Nat { Z => Z, S(x) => body }`
+    fn def(body: Box<Exp>, attrs: Vec<Attribute>) -> Decl {
+        let nat = TypCtor { span: None, name: id("Nat"), args: Args { args: vec![] } };
+        Decl::Def(Def {
+            span: None,
+            doc: None,
+            name: IdBind::from_string("d"),
+            attr: Attributes { attrs },
+            params: Telescope { params: vec![] },
+            self_param: SelfParam { info: None, name: None, typ: nat.clone() },
+            ret_typ: Box::new(nat.into()),
+            cases: vec![
+                case(pattern(false, "Z", &[]), ctor("Z", vec![])),
+                case(pattern(false, "S", &["x"]), body),
+            ],
+        })
+    }
+
+    #[test]
+    fn structural_recursion() {
+        let decl = def(rec_call(var("x", 0, 0)), vec![]);
+        assert!(check_termination(&decl, &TypeInfoTable::default()).is_ok());
+    }
+
+    #[test]
+    fn non_structural_recursion() {
+        let decl = def(rec_call(ctor("Z", vec![])), vec![]);
+        let res = check_termination(&decl, &TypeInfoTable::default());
+        assert!(matches!(res, Err(TypeError::PossiblyNonTerminating { .. })));
+    }
+
+    #[test]
+    fn decrease_evidence() {
+        let decl = def(rec_call(var("x", 0, 0)), vec![]);
+        let calls = recursive_calls(&decl, &TypeInfoTable::default());
+        assert_eq!(calls, vec![RecursiveCall { span: None, decreasing_on: Some("x".to_owned()) }]);
+
+        let decl = def(rec_call(ctor("Z", vec![])), vec![Attribute::NoTerminationCheck]);
+        let calls = recursive_calls(&decl, &TypeInfoTable::default());
+        assert_eq!(calls, vec![RecursiveCall { span: None, decreasing_on: None }]);
+    }
+
+    #[test]
+    fn no_termination_check() {
+        let decl = def(rec_call(ctor("Z", vec![])), vec![Attribute::NoTerminationCheck]);
+        assert!(check_termination(&decl, &TypeInfoTable::default()).is_ok());
+    }
+}
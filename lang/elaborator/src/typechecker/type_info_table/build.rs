@@ -61,13 +61,14 @@ impl BuildTypeInfoTable for Codata {
 
 impl BuildTypeInfoTable for Dtor {
     fn build(&self, info_table: &mut ModuleTypeInfoTable) {
-        let Dtor { name, params, self_param, ret_typ, .. } = self;
+        let Dtor { name, params, self_param, ret_typ, default, .. } = self;
         info_table.map_dtor.insert(
             name.id.clone(),
             DtorMeta {
                 params: params.clone(),
                 self_param: self_param.clone(),
                 ret_typ: ret_typ.clone(),
+                default: default.clone(),
             },
         );
     }
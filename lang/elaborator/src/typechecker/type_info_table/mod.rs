@@ -60,10 +60,16 @@ pub struct DtorMeta {
     pub params: Telescope,
     pub self_param: SelfParam,
     pub ret_typ: Box<Exp>,
+    pub default: Option<Box<Exp>>,
 }
 
 impl From<Dtor> for DtorMeta {
     fn from(dtor: Dtor) -> Self {
-        DtorMeta { params: dtor.params, self_param: dtor.self_param, ret_typ: dtor.ret_typ }
+        DtorMeta {
+            params: dtor.params,
+            self_param: dtor.self_param,
+            ret_typ: dtor.ret_typ,
+            default: dtor.default,
+        }
     }
 }
@@ -1,10 +1,11 @@
 use codespan::Span;
 use log::trace;
 
-use ast::ctx::LevelCtx;
+use ast::ctx::{BindContext, LevelCtx};
 use ast::*;
 use printer::types::Print;
 
+use crate::normalizer::occurs::occurs_lvl;
 use crate::unifier::{constraints::Constraint, unify::unify};
 
 use super::TypeError;
@@ -12,14 +13,66 @@ use super::TypeError;
 // Checks whether the codata type contains destructors with a self parameter
 pub fn uses_self(codata: &Codata) -> Result<bool, TypeError> {
     for dtor in &codata.dtors {
-        let mut ctx = LevelCtx::from(vec![dtor.params.len(), 1]);
-        if dtor.ret_typ.occurs(&mut ctx, Lvl { fst: 1, snd: 0 }) {
+        let ctx = LevelCtx::from(vec![dtor.params.len(), 1]);
+        if occurs_lvl(&ctx, Lvl { fst: 1, snd: 0 }, &dtor.ret_typ) {
             return Ok(true);
         }
     }
     Ok(false)
 }
 
+/// Find the first variable in `exp` which is not bound in the context `ctx`.
+///
+/// Such a variable can only arise from a parameter type which refers to a
+/// later parameter of the same telescope, or from a malformed syntax tree.
+pub fn first_unbound_var(exp: &Exp, ctx: &mut LevelCtx) -> Option<Variable> {
+    match exp {
+        Exp::Variable(var) => {
+            let Idx { fst, snd } = var.idx;
+            let is_bound = fst < ctx.len() && snd < ctx.bound[ctx.len() - 1 - fst].len();
+            (!is_bound).then(|| var.clone())
+        }
+        Exp::TypCtor(TypCtor { args, .. }) | Exp::Call(Call { args, .. }) => {
+            first_unbound_var_args(args, ctx)
+        }
+        Exp::DotCall(DotCall { exp, args, .. }) => {
+            first_unbound_var(exp, ctx).or_else(|| first_unbound_var_args(args, ctx))
+        }
+        Exp::Anno(Anno { exp, typ, .. }) => {
+            first_unbound_var(exp, ctx).or_else(|| first_unbound_var(typ, ctx))
+        }
+        Exp::TypeUniv(_) => None,
+        Exp::LocalMatch(LocalMatch { on_exp, motive, cases, .. }) => first_unbound_var(on_exp, ctx)
+            .or_else(|| {
+                motive.as_ref().and_then(|Motive { ret_typ, .. }| {
+                    let ret_typ = ret_typ.as_ref()?;
+                    ctx.bind_single((), |ctx| first_unbound_var(ret_typ, ctx))
+                })
+            })
+            .or_else(|| first_unbound_var_cases(cases, ctx)),
+        Exp::LocalComatch(LocalComatch { cases, .. }) => first_unbound_var_cases(cases, ctx),
+        Exp::LocalLet(LocalLet { typ, bound, body, .. }) => typ
+            .as_ref()
+            .and_then(|typ| first_unbound_var(typ, ctx))
+            .or_else(|| first_unbound_var(bound, ctx))
+            .or_else(|| ctx.bind_single((), |ctx| first_unbound_var(body, ctx))),
+        Exp::Hole(Hole { args, .. }) => {
+            args.iter().flatten().find_map(|exp| first_unbound_var(exp, ctx))
+        }
+    }
+}
+
+fn first_unbound_var_args(args: &Args, ctx: &mut LevelCtx) -> Option<Variable> {
+    args.args.iter().find_map(|arg| first_unbound_var(&arg.exp(), ctx))
+}
+
+fn first_unbound_var_cases(cases: &[Case], ctx: &mut LevelCtx) -> Option<Variable> {
+    cases.iter().find_map(|Case { pattern, body, .. }| {
+        let body = body.as_ref()?;
+        ctx.bind_iter(pattern.params.params.iter(), |ctx| first_unbound_var(body, ctx))
+    })
+}
+
 pub fn convert(
     ctx: LevelCtx,
     meta_vars: &mut HashMap<MetaVar, MetaVarState>,
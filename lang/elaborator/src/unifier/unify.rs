@@ -1,13 +1,16 @@
 use std::collections::HashSet;
+use std::rc::Rc;
 
 use ast::ctx::LevelCtx;
-use ast::{occurs_in, Variable};
+use ast::Variable;
 use codespan::Span;
 use ctx::GenericCtx;
 
-use crate::result::TypeError;
+use crate::normalizer::occurs::occurs_exp;
+use crate::result::{TypeError, UnificationStep};
 use crate::unifier::dec::{Dec, No, Yes};
 use ast::*;
+use miette_util::ToMiette;
 use printer::{DocAllocator, Print};
 
 use super::constraints::Constraint;
@@ -54,7 +57,7 @@ pub fn unify(
     vars_are_rigid: bool,
     while_elaborating_span: &Option<Span>,
 ) -> Result<Dec<Unificator>, TypeError> {
-    let mut ctx = Ctx::new(vec![constraint], ctx.clone(), vars_are_rigid);
+    let mut ctx = Ctx::new(constraint, ctx.clone(), vars_are_rigid);
     let res = match ctx.unify(meta_vars, while_elaborating_span)? {
         Yes(_) => Yes(ctx.unif),
         No(()) => No(()),
@@ -62,9 +65,15 @@ pub fn unify(
     Ok(res)
 }
 
+/// The subgoals which led to a constraint, starting with the outermost one
+///
+/// Subgoals are only recorded if trace logging is enabled, since this requires printing
+/// every constraint which is decomposed.
+type Trace = Rc<Vec<UnificationStep>>;
+
 struct Ctx {
-    /// Constraints that have not yet been solved
-    constraints: Vec<Constraint>,
+    /// Constraints that have not yet been solved, together with the subgoals which led to them
+    constraints: Vec<(Constraint, Trace)>,
     /// A cache of solved constraints. We can skip solving a constraint
     /// if we have seen it before
     done: HashSet<Constraint>,
@@ -75,6 +84,10 @@ struct Ctx {
     /// treat two distinct variables as unifiable. In that case we call the unifier
     /// and enable this boolean flag in order to treat all variables as rigid.
     vars_are_rigid: bool,
+    /// The subgoals which led to the constraint which is currently being solved
+    trace: Trace,
+    /// Whether subgoals are recorded, see [Trace]
+    tracing: bool,
 }
 
 /// Tests whether the hole is in Miller's pattern fragment, i.e. whether it is applied
@@ -98,14 +111,32 @@ fn is_solvable(h: &Hole) -> bool {
     true
 }
 
+/// Tests whether the variable occurs in `exp` beneath constructors and type constructors only.
+fn occurs_beneath_ctors(idx: Idx, exp: &Exp) -> bool {
+    let args = match exp {
+        Exp::Call(Call { kind: CallKind::Constructor, args, .. }) => args,
+        Exp::TypCtor(TypCtor { args, .. }) => args,
+        _ => return false,
+    };
+    args.args.iter().any(|arg| match arg {
+        Arg::UnnamedArg(exp) | Arg::NamedArg(_, exp) => match &**exp {
+            Exp::Variable(Variable { idx: other, .. }) => *other == idx,
+            exp => occurs_beneath_ctors(idx, exp),
+        },
+        Arg::InsertedImplicitArg(_) => false,
+    })
+}
+
 impl Ctx {
-    fn new(constraints: Vec<Constraint>, ctx: LevelCtx, vars_are_rigid: bool) -> Self {
+    fn new(constraint: Constraint, ctx: LevelCtx, vars_are_rigid: bool) -> Self {
         Self {
-            constraints,
+            constraints: vec![(constraint, Trace::default())],
             done: HashSet::default(),
             ctx,
             unif: Unificator::empty(),
             vars_are_rigid,
+            trace: Trace::default(),
+            tracing: log::log_enabled!(log::Level::Trace),
         }
     }
 
@@ -114,7 +145,8 @@ impl Ctx {
         meta_vars: &mut HashMap<MetaVar, MetaVarState>,
         while_elaborating_span: &Option<Span>,
     ) -> Result<Dec, TypeError> {
-        while let Some(constraint) = self.constraints.pop() {
+        while let Some((constraint, trace)) = self.constraints.pop() {
+            self.trace = trace;
             match self.unify_eqn(&constraint, meta_vars, while_elaborating_span)? {
                 Yes(_) => {
                     self.done.insert(constraint);
@@ -157,13 +189,19 @@ impl Ctx {
                                     &Box::new(Exp::Hole(h.clone())),
                                     &Box::new(e.clone()),
                                     while_elaborating_span,
-                                ));
+                                )
+                                .with_unification_trace(&self.trace));
                             }
                         }
                     }
 
                     Ok(Yes(()))
                 }
+                (Exp::LocalComatch(comatch), e) | (e, Exp::LocalComatch(comatch))
+                    if self.is_eta_expandable(e) =>
+                {
+                    self.eta_expand(comatch, e)
+                }
                 (
                     Exp::Variable(Variable { idx: idx_1, .. }),
                     Exp::Variable(Variable { idx: idx_2, .. }),
@@ -196,7 +234,7 @@ impl Ctx {
                 ) if name == name2 => {
                     let constraint =
                         Constraint::EqualityArgs { lhs: args.clone(), rhs: args2.clone() };
-                    self.add_constraint(constraint)
+                    self.add_subgoal(constraint, || format!("the arguments of {}", name.id))
                 }
                 (Exp::TypCtor(TypCtor { name, .. }), Exp::TypCtor(TypCtor { name: name2, .. }))
                     if name != name2 =>
@@ -209,7 +247,7 @@ impl Ctx {
                 ) if name == name2 => {
                     let constraint =
                         Constraint::EqualityArgs { lhs: args.clone(), rhs: args2.clone() };
-                    self.add_constraint(constraint)
+                    self.add_subgoal(constraint, || format!("the arguments of {}", name.id))
                 }
                 (Exp::Call(Call { name, .. }), Exp::Call(Call { name: name2, .. }))
                     if name != name2 =>
@@ -220,13 +258,11 @@ impl Ctx {
                     Exp::DotCall(DotCall { exp, name, args, .. }),
                     Exp::DotCall(DotCall { exp: exp2, name: name2, args: args2, .. }),
                 ) if name == name2 => {
-                    self.add_constraint(Constraint::Equality {
-                        lhs: exp.clone(),
-                        rhs: exp2.clone(),
-                    })?;
+                    let constraint = Constraint::Equality { lhs: exp.clone(), rhs: exp2.clone() };
+                    self.add_subgoal(constraint, || format!("the destructee of .{}", name.id))?;
                     let constraint =
                         Constraint::EqualityArgs { lhs: args.clone(), rhs: args2.clone() };
-                    self.add_constraint(constraint)
+                    self.add_subgoal(constraint, || format!("the arguments of .{}", name.id))
                 }
                 (Exp::TypeUniv(_), Exp::TypeUniv(_)) => Ok(Yes(())),
                 (Exp::Anno(Anno { exp, .. }), rhs) => self.add_constraint(Constraint::Equality {
@@ -241,31 +277,105 @@ impl Ctx {
                     Exp::LocalComatch(LocalComatch { name: name_lhs, cases: cases_lhs, .. }),
                     Exp::LocalComatch(LocalComatch { name: name_rhs, cases: cases_rhs, .. }),
                 ) if name_lhs == name_rhs => {
-                    let new_eqns =
-                        zip_cases_by_xtors(cases_lhs, cases_rhs).filter_map(|(lhs, rhs)| {
-                            if let (Some(lhs), Some(rhs)) = (lhs.body, rhs.body) {
-                                Some(Constraint::Equality { lhs, rhs })
-                            } else {
-                                None
-                            }
-                        });
-                    self.add_constraints(new_eqns)
+                    for (lhs, rhs) in zip_cases_by_xtors(cases_lhs, cases_rhs) {
+                        if let (Some(lhs_body), Some(rhs_body)) = (lhs.body, rhs.body) {
+                            let constraint = Constraint::Equality { lhs: lhs_body, rhs: rhs_body };
+                            let name = &lhs.pattern.name.id;
+                            self.add_subgoal(constraint, || format!("the cocase .{name}"))?;
+                        }
+                    }
+                    Ok(Yes(()))
                 }
-                (_, _) => Err(TypeError::cannot_decide(lhs, rhs, while_elaborating_span)),
+                (_, _) => Err(TypeError::cannot_decide(lhs, rhs, while_elaborating_span)
+                    .with_unification_trace(&self.trace)),
             },
             Constraint::EqualityArgs { lhs, rhs } => {
-                let new_eqns =
-                    lhs.args.iter().cloned().zip(rhs.args.iter().cloned()).map(|(lhs, rhs)| {
-                        Constraint::Equality { lhs: lhs.exp().clone(), rhs: rhs.exp().clone() }
-                    });
-                self.add_constraints(new_eqns)?;
+                for (i, (lhs, rhs)) in lhs.args.iter().zip(rhs.args.iter()).enumerate() {
+                    let constraint = Constraint::Equality { lhs: lhs.exp(), rhs: rhs.exp() };
+                    self.add_subgoal(constraint, || format!("argument {}", i + 1))?;
+                }
                 Ok(Yes(()))
             }
         }
     }
 
+    /// Whether the neutral term `exp` is compared to a comatch by eta-expanding it
+    ///
+    /// Variables are only expanded if they are rigid, since they can be assigned otherwise.
+    fn is_eta_expandable(&self, exp: &Exp) -> bool {
+        match exp {
+            Exp::Variable(_) => self.vars_are_rigid,
+            Exp::DotCall(_) | Exp::LocalMatch(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Compare the comatch `comatch` to the neutral term `exp` of codata type
+    ///
+    /// Since codata types are eta-equal, `exp` is equal to the comatch which applies the
+    /// destructor of each cocase to it. So for every cocase `.d(x_1, ..., x_n) => body`, we add
+    /// the constraint `body = exp.d(x_1, ..., x_n)`. This also covers the eta-equality of
+    /// functions, i.e. `\x. f.ap(x) = f`.
+    /// If the body is itself an application of `.d`, its inserted implicit arguments are not
+    /// compared, since they are determined by the type of `exp`.
+    fn eta_expand(&mut self, comatch: &LocalComatch, exp: &Exp) -> Result<Dec, TypeError> {
+        for case in comatch.cases.iter() {
+            let Case { pattern, body: Some(body), .. } = case else {
+                continue;
+            };
+            let name = &pattern.name;
+            // The cocase binds its parameters, so `exp` has to be shifted into its body
+            let exp = Box::new(shift_and_clone(exp, (1, 0)));
+            let params = &pattern.params.params;
+            let vars = params.iter().enumerate().map(|(i, param)| {
+                Box::new(Exp::Variable(Variable {
+                    span: None,
+                    idx: Idx { fst: 0, snd: params.len() - 1 - i },
+                    name: VarBound::from_string(&param.name.id),
+                    inferred_type: None,
+                }))
+            });
+            match &**body {
+                Exp::DotCall(DotCall { exp: body_exp, name: body_name, args, .. })
+                    if body_name == name && args.args.len() == params.len() =>
+                {
+                    let constraint = Constraint::Equality { lhs: body_exp.clone(), rhs: exp };
+                    self.add_subgoal(constraint, || format!("the destructee of .{}", name.id))?;
+                    for (i, (arg, var)) in args.args.iter().zip(vars).enumerate() {
+                        if matches!(arg, Arg::InsertedImplicitArg(_)) {
+                            continue;
+                        }
+                        let constraint = Constraint::Equality { lhs: arg.exp(), rhs: var };
+                        self.add_subgoal(constraint, || {
+                            format!("argument {} of .{}", i + 1, name.id)
+                        })?;
+                    }
+                }
+                _ => {
+                    let args = Args { args: vars.map(Arg::UnnamedArg).collect() };
+                    let expanded = Box::new(Exp::DotCall(DotCall {
+                        span: None,
+                        kind: DotCallKind::Destructor,
+                        exp,
+                        name: name.clone(),
+                        args,
+                        inferred_type: None,
+                    }));
+                    let constraint = Constraint::Equality { lhs: body.clone(), rhs: expanded };
+                    self.add_subgoal(constraint, || format!("the cocase .{}", name.id))?;
+                }
+            }
+        }
+        Ok(Yes(()))
+    }
+
     fn add_assignment(&mut self, idx: Idx, exp: Box<Exp>) -> Result<Dec, TypeError> {
-        if occurs_in(&mut self.ctx, idx, &exp) {
+        if occurs_exp(idx, &exp) {
+            // An equation `x = C(.., x, ..)` has no solution, since `x` cannot be a proper
+            // subterm of itself. This is a conflict just like a mismatch of constructors.
+            if occurs_beneath_ctors(idx, &exp) {
+                return Ok(No(()));
+            }
             return Err(TypeError::occurs_check_failed(idx, &exp));
         }
         let insert_lvl = self.ctx.idx_to_lvl(idx);
@@ -291,7 +401,30 @@ impl Ctx {
         &mut self,
         iter: I,
     ) -> Result<Dec, TypeError> {
-        self.constraints.extend(iter.into_iter().filter(|eqn| !self.done.contains(eqn)));
+        let Ctx { constraints, done, trace, .. } = self;
+        constraints.extend(
+            iter.into_iter().filter(|eqn| !done.contains(eqn)).map(|eqn| (eqn, trace.clone())),
+        );
+        Ok(Yes(()))
+    }
+
+    /// Add the constraint `eqn`, which is the `subgoal` of the constraint currently being solved
+    fn add_subgoal(
+        &mut self,
+        eqn: Constraint,
+        subgoal: impl FnOnce() -> String,
+    ) -> Result<Dec, TypeError> {
+        if self.done.contains(&eqn) {
+            return Ok(Yes(()));
+        }
+        let trace = if self.tracing {
+            let mut trace = (*self.trace).clone();
+            trace.push(unification_step(subgoal(), &eqn));
+            Rc::new(trace)
+        } else {
+            self.trace.clone()
+        };
+        self.constraints.push((eqn, trace));
         Ok(Yes(()))
     }
 
@@ -344,6 +477,25 @@ impl Print for Unificator {
     }
 }
 
+fn unification_step(subgoal: String, eqn: &Constraint) -> UnificationStep {
+    match eqn {
+        Constraint::Equality { lhs, rhs } => UnificationStep {
+            subgoal,
+            lhs: lhs.print_to_string(None),
+            rhs: rhs.print_to_string(None),
+            lhs_span: lhs.span().to_miette(),
+            rhs_span: rhs.span().to_miette(),
+        },
+        Constraint::EqualityArgs { lhs, rhs } => UnificationStep {
+            subgoal,
+            lhs: lhs.print_to_string(None),
+            rhs: rhs.print_to_string(None),
+            lhs_span: None,
+            rhs_span: None,
+        },
+    }
+}
+
 fn zip_cases_by_xtors(
     cases_lhs: &[Case],
     cases_rhs: &[Case],
@@ -31,6 +31,10 @@ pub struct Ctx {
     next_label_id: usize,
     /// Set of user-annotated label names
     user_labels: HashSet<Ident>,
+    /// Names of the named holes in the current declaration
+    hole_names: HashSet<String>,
+    /// Names of the declarations whose `where` clauses are in scope, the innermost one last
+    where_scope: Vec<String>,
     /// Counter for unique meta variables
     next_meta_var: u64,
     /// Meta variables
@@ -40,13 +44,16 @@ pub struct Ctx {
 }
 
 impl Ctx {
-    pub fn empty(uri: Url, symbol_table: SymbolTable) -> Self {
+    pub fn empty(uri: Url, mut symbol_table: SymbolTable) -> Self {
+        symbol_table.set_current_module(uri.clone());
         Self {
             local_map: HashMap::default(),
             symbol_table,
             levels: Vec::new(),
             next_label_id: 0,
             user_labels: HashSet::default(),
+            hole_names: HashSet::default(),
+            where_scope: Vec::new(),
             next_meta_var: 0,
             meta_vars: HashMap::default(),
             uri,
@@ -59,6 +66,39 @@ impl Ctx {
         Some(self.level_to_index(lvl))
     }
 
+    /// The name of a declaration declared at the current position
+    ///
+    /// Declarations in the `where` clause of another declaration are lifted to the top level
+    /// and named `parent::name`, see [Ctx::with_where_scope].
+    pub fn decl_name(&self, name: &Ident) -> ast::IdBind {
+        let id = match self.where_scope.last() {
+            Some(parent) => ast::where_decl_name(parent, &name.id),
+            None => name.id.clone(),
+        };
+        ast::IdBind { span: Some(name.span), id }
+    }
+
+    /// Run `f` with the declarations in the `where` clause of the declaration `name` in scope
+    pub fn with_where_scope<T>(&mut self, name: &str, f: impl FnOnce(&mut Self) -> T) -> T {
+        self.where_scope.push(name.to_owned());
+        let res = f(self);
+        self.where_scope.pop();
+        res
+    }
+
+    /// Resolve the name of a top-level declaration
+    ///
+    /// Declarations in the `where` clauses which are in scope take precedence over
+    /// top-level declarations of the same name, the innermost clause first.
+    pub fn resolve_global(&self, name: &Ident) -> Ident {
+        self.where_scope
+            .iter()
+            .rev()
+            .map(|parent| Ident { span: name.span, id: ast::where_decl_name(parent, &name.id) })
+            .find(|local| self.symbol_table.lookup_exists(local))
+            .unwrap_or_else(|| name.clone())
+    }
+
     pub fn unique_label(
         &mut self,
         user_name: Option<Ident>,
@@ -114,6 +154,22 @@ impl Ctx {
         mv
     }
 
+    /// Forget the names of the named holes of the previous declaration
+    pub fn reset_hole_names(&mut self) {
+        self.hole_names.clear();
+    }
+
+    /// Register the name of a named hole, which must be unique within its declaration
+    pub fn declare_hole_name(&mut self, name: &str, span: Span) -> Result<(), LoweringError> {
+        if !self.hole_names.insert(name.to_owned()) {
+            return Err(LoweringError::DuplicateHoleName {
+                name: name.to_owned(),
+                span: span.to_miette(),
+            });
+        }
+        Ok(())
+    }
+
     /// With every context Γ there is an associated substitution id_Γ which consists of
     /// all variables in Γ. This function computes the substitution id_Γ.
     /// This substitution is needed when lowering typed holes since they stand for unknown terms which
@@ -3,14 +3,16 @@ mod lower;
 mod result;
 mod symbol_table;
 
-use ast::{self};
+use ast::{self, HashMap};
 use parser::cst;
+use url::Url;
 
-use crate::lower::Lower;
+use crate::lower::{lower_decls, Lower};
 
 pub use ctx::*;
 pub use result::*;
 pub use symbol_table::build::build_symbol_table;
+pub use symbol_table::module_name;
 pub use symbol_table::DeclMeta;
 pub use symbol_table::ModuleSymbolTable;
 pub use symbol_table::SymbolTable;
@@ -25,7 +27,175 @@ pub fn lower_module_with_symbol_table(
     let mut ctx = Ctx::empty(prg.uri.clone(), symbol_table.clone());
 
     let use_decls = prg.use_decls.lower(&mut ctx)?;
-    let decls = prg.decls.lower(&mut ctx)?;
+    let decls = lower_decls(&prg.decls, &mut ctx)?;
+    let comments = prg.comments.lower(&mut ctx)?;
 
-    Ok(ast::Module { uri: prg.uri.clone(), use_decls, decls, meta_vars: ctx.meta_vars })
+    Ok(ast::Module { uri: prg.uri.clone(), use_decls, decls, meta_vars: ctx.meta_vars, comments })
+}
+
+/// Lower a standalone expression in the context of the module `uri`
+///
+/// The symbol table has to contain the symbols of the module and of all its dependencies.
+/// Returns the lowered expression together with the metavariables generated for it.
+pub fn lower_exp_with_symbol_table(
+    uri: &Url,
+    exp: &cst::exp::Exp,
+    symbol_table: &SymbolTable,
+) -> Result<(ast::Exp, HashMap<ast::MetaVar, ast::MetaVarState>), LoweringError> {
+    let mut ctx = Ctx::empty(uri.clone(), symbol_table.clone());
+    let exp = exp.lower(&mut ctx)?;
+    Ok((exp, ctx.meta_vars))
+}
+
+#[cfg(test)]
+mod roundtrip_tests {
+    use std::sync::Arc;
+
+    use printer::Print;
+
+    use super::*;
+
+    const PRELUDE: &str = r#"
+        codata Fun(a b: Type) { Fun(a, b).ap(implicit a b: Type, x: a): b }
+        data Nat { Z, S(n: Nat) }
+        data Bool { T, F }
+        codata Stream { .head: Nat, .tail: Stream }
+        def Nat.add(m: Nat): Nat { Z => m, S(n) => S(n.add(m)) }
+        data Bin { BNil, B0(rest: Bin), B1(rest: Bin) }
+        data Char { MkChar(code: Bin) }
+        data String { SNil, SCons(head: Char, tail: String) }
+        data List(a: Type) { Nil(a: Type): List(a), Cons(a: Type, x: a, xs: List(a)): List(a) }
+    "#;
+
+    fn lower(uri: &Url, symbol_table: &SymbolTable, s: &str) -> ast::Exp {
+        let exp = parser::parse_exp(s).unwrap_or_else(|err| panic!("Failed to parse {s}: {err}"));
+        let (exp, _) = lower_exp_with_symbol_table(uri, &exp, symbol_table)
+            .unwrap_or_else(|err| panic!("Failed to lower {s}: {err}"));
+        exp
+    }
+
+    /// Parse, lower and print each expression, and check that the printed expression is
+    /// lowered to the same expression again
+    fn roundtrip(corpus: &[(&str, &str)]) {
+        let uri = Url::parse("inmemory:///scratch.pol").unwrap();
+        let module = parser::parse_module(uri.clone(), PRELUDE).unwrap();
+        let mut symbol_table = SymbolTable::default();
+        symbol_table.insert(uri.clone(), Arc::new(build_symbol_table(&module).unwrap()));
+
+        for (input, expected) in corpus {
+            let exp = lower(&uri, &symbol_table, input);
+            let printed = exp.print_to_string(None);
+            assert_eq!(&printed, expected, "Unexpected output when printing {input}");
+            assert_eq!(lower(&uri, &symbol_table, &printed), exp, "{input} does not roundtrip");
+        }
+    }
+
+    #[test]
+    fn roundtrip_fun() {
+        roundtrip(&[
+            ("Nat -> Nat -> Nat", "Nat -> Nat -> Nat"),
+            ("Nat -> (Nat -> Nat)", "Nat -> Nat -> Nat"),
+            ("(Nat -> Nat) -> Nat", "(Nat -> Nat) -> Nat"),
+            ("((Nat -> Nat) -> Nat) -> Nat", "((Nat -> Nat) -> Nat) -> Nat"),
+            ("Nat -> (Nat -> Nat) -> Nat", "Nat -> (Nat -> Nat) -> Nat"),
+            ("(Fun(Nat, Nat))", "Nat -> Nat"),
+        ])
+    }
+
+    #[test]
+    fn roundtrip_anno() {
+        roundtrip(&[
+            ("(Z : Nat)", "Z : Nat"),
+            ("S((Z : Nat))", "S(Z : Nat)"),
+            ("(Z : Nat).add(Z)", "(Z : Nat).add(Z)"),
+            ("(Nat -> Nat) : Type", "(Nat -> Nat) : Type"),
+            ("(\\x. x) : Nat -> Nat", "(\\x. x) : Nat -> Nat"),
+            ("\\x. (x : Nat)", "\\x. x : Nat"),
+        ])
+    }
+
+    #[test]
+    fn roundtrip_dot_call() {
+        roundtrip(&[
+            ("(Z.add(Z)).add(Z)", "Z.add(Z).add(Z)"),
+            ("(\\x. x).ap(Z)", "(\\x. x).ap(Z)"),
+            ("\\f. (f.ap(Z))", "\\f. f.ap(Z)"),
+            (
+                "(Z.match { Z => T, S(n) => F }).match { T => Z, F => Z }",
+                "Z.match {\n    Z => T,\n    S(n) => F\n}.match {\n    T => Z,\n    F => Z\n}",
+            ),
+            (
+                "((Z : Nat).match { Z => T, S(n) => F })",
+                "(Z : Nat).match {\n    Z => T,\n    S(n) => F\n}",
+            ),
+        ])
+    }
+
+    #[test]
+    fn roundtrip_nat_lit() {
+        roundtrip(&[
+            ("2", "2"),
+            ("S(2)", "S(2)"),
+            ("S(S(Z))", "S(S(Z))"),
+            ("S(Z.add(1))", "S(Z.add(1))"),
+        ])
+    }
+
+    #[test]
+    fn roundtrip_char_lit() {
+        roundtrip(&[
+            ("'a'", "'a'"),
+            ("'\\0'", "'\\0'"),
+            ("'\\u{10FFFF}'", "'\\u{10ffff}'"),
+            ("MkChar(B1(B1(BNil)))", "MkChar(B1(B1(BNil)))"),
+            ("MkChar(B0(BNil))", "MkChar(B0(BNil))"),
+            ("SCons('中', \"\")", "SCons('中', \"\")"),
+        ])
+    }
+
+    #[test]
+    fn roundtrip_list_lit() {
+        roundtrip(&[
+            ("[1, 2]", "[1, 2]"),
+            ("[[T], []]", "[[T], []]"),
+            ("Cons(_, T, Nil(_))", "Cons(_, T, Nil(_))"),
+        ])
+    }
+
+    #[test]
+    fn roundtrip_lambda() {
+        roundtrip(&[
+            ("\\x. \\y. x.add(y)", "\\x y. x.add(y)"),
+            ("\\x. (\\y. y).ap(x)", "\\x. (\\y. y).ap(x)"),
+            ("comatch { .head => Z, .tail => ? }", "comatch {\n    .head => Z,\n    .tail => ?\n}"),
+        ])
+    }
+
+    #[test]
+    fn roundtrip_comments() {
+        let uri = Url::parse("inmemory:///scratch.pol").unwrap();
+        let input = "-- Header\n\ndata Bool { T, F } -- Booleans\n\n-- Naturals\n\
+                     data Nat { Z, S(n: Nat) }\n\n-- Footer";
+        let module = parser::parse_module(uri.clone(), input).unwrap();
+        let mut symbol_table = SymbolTable::default();
+        symbol_table.insert(uri.clone(), Arc::new(build_symbol_table(&module).unwrap()));
+        let module = lower_module_with_symbol_table(&module, &symbol_table).unwrap();
+        assert_eq!(module.print_to_string(None), input);
+    }
+
+    #[test]
+    fn roundtrip_fun_of_variables() {
+        let uri = Url::parse("inmemory:///scratch.pol").unwrap();
+        let lower_module = |s: &str| {
+            let module = parser::parse_module(uri.clone(), s).unwrap();
+            let mut symbol_table = SymbolTable::default();
+            symbol_table.insert(uri.clone(), Arc::new(build_symbol_table(&module).unwrap()));
+            lower_module_with_symbol_table(&module, &symbol_table).unwrap()
+        };
+        let input = "codata Fun(a b: Type) { Fun(a, b).ap(implicit a b: Type, x: a): b }\n\n\
+                     let f(a b c: Type): Type { a -> (b -> c) }";
+        let printed = lower_module(input).print_to_string(None);
+        assert!(printed.contains("a -> b -> c"), "Unexpected output {printed}");
+        assert_eq!(lower_module(&printed).print_to_string(None), printed);
+    }
 }
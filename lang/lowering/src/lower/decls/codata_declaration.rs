@@ -37,9 +37,12 @@ fn lower_destructor(
     type_arity: usize,
 ) -> Result<ast::Dtor, LoweringError> {
     log::trace!("Lowering destructor: {:?}", dtor.name);
-    let cst::decls::Dtor { span, doc, name, params, destructee, ret_typ } = dtor;
+    let cst::decls::Dtor { span, doc, name, params, destructee, ret_typ, default } = dtor;
 
     lower_telescope(params, ctx, |ctx, params| {
+        // The self parameter is not in scope in the default implementation
+        let default = default.lower(ctx)?;
+
         // If the type constructor does not take any arguments, it can be left out
         let on_typ = match &destructee.typ {
             Some(on_typ) => on_typ.clone(),
@@ -74,6 +77,7 @@ fn lower_destructor(
                 params,
                 self_param,
                 ret_typ: ret_typ.lower(ctx)?,
+                default,
             })
         })
     })
@@ -1,4 +1,3 @@
-use ast::IdBind;
 use miette_util::ToMiette;
 use parser::cst::{self};
 
@@ -13,19 +12,23 @@ impl Lower for cst::decls::Codef {
 
         let cst::decls::Codef { span, doc, name, attr, params, typ, cases, .. } = self;
 
-        lower_telescope(params, ctx, |ctx, params| {
-            let typ = typ.lower(ctx)?;
-            let typ_ctor = typ
-                .to_typctor()
-                .ok_or(LoweringError::ExpectedTypCtor { span: span.to_miette() })?;
-            Ok(ast::Codef {
-                span: Some(*span),
-                doc: doc.lower(ctx)?,
-                name: IdBind { span: Some(name.span), id: name.id.clone() },
-                attr: attr.lower(ctx)?,
-                params,
-                typ: typ_ctor,
-                cases: cases.lower(ctx)?,
+        let name = ctx.decl_name(name);
+
+        ctx.with_where_scope(&name.id, |ctx| {
+            lower_telescope(params, ctx, |ctx, params| {
+                let typ = typ.lower(ctx)?;
+                let typ_ctor = typ
+                    .to_typctor()
+                    .ok_or(LoweringError::ExpectedTypCtor { span: span.to_miette() })?;
+                Ok(ast::Codef {
+                    span: Some(*span),
+                    doc: doc.lower(ctx)?,
+                    name: name.clone(),
+                    attr: attr.lower(ctx)?,
+                    params,
+                    typ: typ_ctor,
+                    cases: cases.lower(ctx)?,
+                })
             })
         })
     }
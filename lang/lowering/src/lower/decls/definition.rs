@@ -1,4 +1,3 @@
-use ast::IdBind;
 use parser::cst::{self};
 
 use super::super::*;
@@ -11,22 +10,25 @@ impl Lower for cst::decls::Def {
     fn lower(&self, ctx: &mut Ctx) -> Result<Self::Target, LoweringError> {
         log::trace!("Lowering definition: {}", self.name.id);
 
-        let cst::decls::Def { span, doc, name, attr, params, scrutinee, ret_typ, cases } = self;
+        let cst::decls::Def { span, doc, name, attr, params, scrutinee, ret_typ, cases, .. } = self;
 
         let self_param: cst::decls::SelfParam = scrutinee.clone().into();
+        let name = ctx.decl_name(name);
 
-        lower_telescope(params, ctx, |ctx, params| {
-            let cases = cases.lower(ctx)?;
-            lower_self_param(&self_param, ctx, |ctx, self_param| {
-                Ok(ast::Def {
-                    span: Some(*span),
-                    doc: doc.lower(ctx)?,
-                    name: IdBind { span: Some(name.span), id: name.id.clone() },
-                    attr: attr.lower(ctx)?,
-                    params,
-                    self_param,
-                    ret_typ: ret_typ.lower(ctx)?,
-                    cases,
+        ctx.with_where_scope(&name.id, |ctx| {
+            lower_telescope(params, ctx, |ctx, params| {
+                let cases = cases.lower(ctx)?;
+                lower_self_param(&self_param, ctx, |ctx, self_param| {
+                    Ok(ast::Def {
+                        span: Some(*span),
+                        doc: doc.lower(ctx)?,
+                        name: name.clone(),
+                        attr: attr.lower(ctx)?,
+                        params,
+                        self_param,
+                        ret_typ: ret_typ.lower(ctx)?,
+                        cases,
+                    })
                 })
             })
         })
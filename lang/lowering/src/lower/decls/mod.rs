@@ -32,6 +32,8 @@ fn parse_attribute(s: String) -> ast::Attribute {
         "omit_print" => ast::Attribute::OmitPrint,
         "transparent" => ast::Attribute::Transparent,
         "opaque" => ast::Attribute::Opaque,
+        "no_termination_check" => ast::Attribute::NoTerminationCheck,
+        "entry" => ast::Attribute::Entry,
         v => ast::Attribute::Other(v.to_string()),
     }
 }
@@ -56,6 +58,24 @@ impl Lower for cst::decls::UseDecl {
     }
 }
 
+// Comments
+//
+//
+
+impl Lower for cst::decls::Comment {
+    type Target = ast::Comment;
+
+    fn lower(&self, _ctx: &mut Ctx) -> Result<Self::Target, LoweringError> {
+        let cst::decls::Comment { span, text, is_end_of_line, blank_line_after } = self;
+        Ok(ast::Comment {
+            span: *span,
+            text: text.clone(),
+            is_end_of_line: *is_end_of_line,
+            blank_line_after: *blank_line_after,
+        })
+    }
+}
+
 // Declarations
 //
 //
@@ -64,6 +84,7 @@ impl Lower for cst::decls::Decl {
     type Target = ast::Decl;
 
     fn lower(&self, ctx: &mut Ctx) -> Result<Self::Target, LoweringError> {
+        ctx.reset_hole_names();
         let decl = match self {
             cst::decls::Decl::Data(data) => data.lower(ctx)?.into(),
             cst::decls::Decl::Codata(codata) => codata.lower(ctx)?.into(),
@@ -75,6 +96,36 @@ impl Lower for cst::decls::Decl {
     }
 }
 
+/// Lower the declarations of a module
+///
+/// The declarations in the `where` clause of a declaration are lifted to the top level
+/// and directly follow the declaration.
+pub fn lower_decls(
+    decls: &[cst::decls::Decl],
+    ctx: &mut Ctx,
+) -> Result<Vec<ast::Decl>, LoweringError> {
+    let mut out = Vec::with_capacity(decls.len());
+    for decl in decls {
+        lower_with_where_decls(decl, ctx, &mut out)?;
+    }
+    Ok(out)
+}
+
+fn lower_with_where_decls(
+    decl: &cst::decls::Decl,
+    ctx: &mut Ctx,
+    out: &mut Vec<ast::Decl>,
+) -> Result<(), LoweringError> {
+    let lowered = decl.lower(ctx)?;
+    let name = lowered.ident().id.clone();
+    out.push(lowered);
+    ctx.with_where_scope(&name, |ctx| {
+        decl.where_decls()
+            .iter()
+            .try_for_each(|where_decl| lower_with_where_decls(where_decl, ctx, out))
+    })
+}
+
 // Self Parameter
 //
 //
@@ -98,7 +149,9 @@ fn lower_self_param<T, F: FnOnce(&mut Ctx, ast::SelfParam) -> Result<T, Lowering
                 ctx,
                 ast::SelfParam {
                     info: Some(*span),
-                    name: name.clone().map(|name| ast::VarBind { span: None, id: name.id }),
+                    name: name
+                        .clone()
+                        .map(|name| ast::VarBind { span: Some(name.span), id: name.id }),
                     typ: typ_ctor,
                 },
             )
@@ -116,19 +169,23 @@ fn desugar_telescope(tel: &cst::decls::Telescope) -> cst::decls::Telescope {
     cst::decls::Telescope(params)
 }
 fn desugar_param(param: &cst::decls::Param) -> Vec<cst::decls::Param> {
-    let cst::decls::Param { implicit, name, names, typ } = param;
+    let cst::decls::Param { implicit, lazy, name, names, typ, default } = param;
     let mut params: Vec<cst::decls::Param> = vec![cst::decls::Param {
         implicit: *implicit,
+        lazy: *lazy,
         name: name.clone(),
         names: vec![],
         typ: typ.clone(),
+        default: default.clone(),
     }];
     for extra_name in names {
         params.push(cst::decls::Param {
             implicit: *implicit,
+            lazy: *lazy,
             name: extra_name.clone(),
             names: vec![],
             typ: typ.clone(),
+            default: default.clone(),
         });
     }
     params
@@ -138,6 +195,8 @@ fn desugar_param(param: &cst::decls::Param) -> Vec<cst::decls::Param> {
 ///
 /// Execute a function `f` under the context where all binders
 /// of the telescope are in scope.
+/// Every explicit parameter which follows a parameter with a default value must have a default
+/// value as well, such that only trailing arguments can be omitted.
 fn lower_telescope<T, F>(
     tele: &cst::decls::Telescope,
     ctx: &mut Ctx,
@@ -152,16 +211,29 @@ where
         Ok(vec![]),
         |ctx, params_out, param| {
             let mut params_out = params_out?;
-            let cst::decls::Param { implicit, name, names: _, typ } = param; // The `names` field has been removed by `desugar_telescope`.
+            let cst::decls::Param { implicit, lazy, name, names: _, typ, default } = param; // The `names` field has been removed by `desugar_telescope`.
             let typ_out = typ.lower(ctx)?;
+            let default_out = default.lower(ctx)?;
             let name = match name {
                 BindingSite::Var { name, .. } => name.clone(),
                 BindingSite::Wildcard { span } => {
                     parser::cst::ident::Ident { span: *span, id: "_".to_owned() }
                 }
             };
+            if default.is_none() && !implicit && params_out.iter().any(|p| p.default.is_some()) {
+                return Err(LoweringError::MissingDefault {
+                    name: name.clone(),
+                    span: name.span.to_miette(),
+                });
+            }
             let name = VarBind { span: Some(name.span), id: name.id.clone() };
-            let param_out = ast::Param { implicit: *implicit, name, typ: typ_out };
+            let param_out = ast::Param {
+                implicit: *implicit,
+                lazy: *lazy,
+                name,
+                typ: typ_out,
+                default: default_out,
+            };
             params_out.push(param_out);
             Ok(params_out)
         },
@@ -1,4 +1,3 @@
-use ast::IdBind;
 use parser::cst::{self};
 
 use super::super::*;
@@ -10,17 +9,20 @@ impl Lower for cst::decls::Let {
     fn lower(&self, ctx: &mut Ctx) -> Result<Self::Target, LoweringError> {
         log::trace!("Lowering top-level let: {}", self.name.id);
 
-        let cst::decls::Let { span, doc, name, attr, params, typ, body } = self;
+        let cst::decls::Let { span, doc, name, attr, params, typ, body, .. } = self;
+        let name = ctx.decl_name(name);
 
-        lower_telescope(params, ctx, |ctx, params| {
-            Ok(ast::Let {
-                span: Some(*span),
-                doc: doc.lower(ctx)?,
-                name: IdBind { span: Some(name.span), id: name.id.clone() },
-                attr: attr.lower(ctx)?,
-                params,
-                typ: typ.lower(ctx)?,
-                body: body.lower(ctx)?,
+        ctx.with_where_scope(&name.id, |ctx| {
+            lower_telescope(params, ctx, |ctx, params| {
+                Ok(ast::Let {
+                    span: Some(*span),
+                    doc: doc.lower(ctx)?,
+                    name: name.clone(),
+                    attr: attr.lower(ctx)?,
+                    params,
+                    typ: typ.lower(ctx)?,
+                    body: body.lower(ctx)?,
+                })
             })
         })
     }
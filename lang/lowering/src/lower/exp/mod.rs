@@ -13,6 +13,7 @@ use parser::cst;
 use parser::cst::decls::Telescope;
 use parser::cst::exp::BindingSite;
 use parser::cst::ident::Ident;
+use url::Url;
 
 use crate::ctx::*;
 use crate::result::*;
@@ -33,8 +34,16 @@ impl Lower for cst::exp::Exp {
             cst::exp::Exp::LocalComatch(e) => e.lower(ctx),
             cst::exp::Exp::Hole(e) => e.lower(ctx),
             cst::exp::Exp::NatLit(e) => e.lower(ctx),
+            cst::exp::Exp::StrLit(e) => e.lower(ctx),
+            cst::exp::Exp::CharLit(e) => e.lower(ctx),
             cst::exp::Exp::Fun(e) => e.lower(ctx),
             cst::exp::Exp::Lam(e) => e.lower(ctx),
+            cst::exp::Exp::IfLet(e) => e.lower(ctx),
+            cst::exp::Exp::LocalLet(e) => e.lower(ctx),
+            cst::exp::Exp::Tuple(e) => e.lower(ctx),
+            cst::exp::Exp::ListLit(e) => e.lower(ctx),
+            cst::exp::Exp::Projection(e) => e.lower(ctx),
+            cst::exp::Exp::Absurd(e) => e.lower(ctx),
         }
     }
 }
@@ -63,15 +72,18 @@ fn lower_telescope_inst<T, F: FnOnce(&mut Ctx, ast::TelescopeInst) -> Result<T,
         |ctx, params| f(ctx, params.map(|params| ast::TelescopeInst { params })?),
     )
 }
-/// Lowers a list of arguments, ensuring that named arguments match the expected parameter names.
+/// Lowers a list of arguments, matching named arguments with the expected parameters by name.
 ///
 /// This function processes a mix of named and unnamed arguments provided by the user (`given`)
-/// and matches them against the expected parameters (`expected`). It handles implicit parameters
-/// by inserting fresh metavariables when necessary and ensures that named arguments correspond to the correct
-/// parameters as declared.
+/// and matches them against the expected parameters (`expected`). Named arguments may be given
+/// in any order, while unnamed arguments are matched with the remaining explicit parameters
+/// from left to right. It handles implicit parameters by inserting fresh metavariables when
+/// no argument is given for them, and likewise for parameters with a default value.
+/// The lowered arguments are in the order of the parameters.
 ///
 /// # Parameters
 ///
+/// - `span`: The source span of the given argument list.
 /// - `given`: A slice of `cst::exp::Arg` representing the arguments provided by the user.
 /// - `expected`: A `Telescope` containing the expected parameters.
 /// - `ctx`: A mutable reference to the current context (`Ctx`), used for tracking variables and generating fresh metavariables.
@@ -80,16 +92,20 @@ fn lower_telescope_inst<T, F: FnOnce(&mut Ctx, ast::TelescopeInst) -> Result<T,
 ///
 /// - `Ok(ast::Args)`: The successfully lowered arguments.
 /// - `Err(LoweringError)`: An error indicating issues such as missing arguments, too many arguments,
-///   mismatched named arguments, or improper use of wildcards.
+///   unknown or ambiguous named arguments, or improper use of wildcards.
 ///
 /// # Errors
 ///
 /// This function may return a `LoweringError` in the following cases:
 ///
-/// - **MissingArgForParam**: A required argument is missing for a parameter.
+/// - **MissingArgForParam**: No argument is given for a parameter without a default value.
 /// - **TooManyArgs**: More arguments are provided than there are expected parameters.
-/// - **MismatchedNamedArgs**: A named argument does not match the expected parameter name.
+/// - **UnknownArgName**: A named argument does not match any of the expected parameter names.
+/// - **DuplicateArg**: More than one argument is given for the same parameter.
+/// - **MismatchedNamedArgs**: An unnamed argument follows a named argument for a later parameter,
+///   such that the order in which the arguments are written is ambiguous.
 /// - **NamedArgForWildcard**: A named argument is provided for a wildcard parameter, which is not allowed.
+/// - **UnexpectedImplicitArg**: An argument marked with `@` is not given for an implicit parameter.
 ///
 /// # Example
 ///
@@ -102,20 +118,23 @@ fn lower_telescope_inst<T, F: FnOnce(&mut Ctx, ast::TelescopeInst) -> Result<T,
 /// }
 ///
 /// let example1 : List {
+///     Cons(tail := Nil, head := True)
+/// }
+///
+/// let example2 : List {
 ///     Cons(x := True, xs := Nil)
 /// }
 /// ```
 ///
-/// In this example, an error is thrown because the named arguments `x` and `xs` do not match
-/// the expected parameter names `head` and `tail`.
+/// In this example, `example1` is lowered to `Cons(True, Nil)`, while an error is thrown for
+/// `example2` because the named arguments `x` and `xs` do not match the expected parameter
+/// names `head` and `tail`.
 fn lower_args(
     span: Span,
     given: &[cst::exp::Arg],
     expected: Telescope,
     ctx: &mut Ctx,
 ) -> Result<ast::Args, LoweringError> {
-    let mut args_out = vec![];
-
     // Ensure that the number of given arguments does not exceed the number of expected parameters.
     // Some expected parameters might be implicit and not require corresponding given arguments.
     if given.len() > expected.len() {
@@ -124,110 +143,123 @@ fn lower_args(
         return Err(err);
     }
 
-    // Create a peekable iterator over the given arguments to allow lookahead.
-    let mut given_iter = given.iter().peekable();
-
-    /// Processes a single argument, matching it against the expected parameter binding site.
-    ///
-    /// This function consumes one argument from the `given` iterator and attempts to match it with the
-    /// expected binding site (`expected_bs`). It handles both named and unnamed arguments and ensures
-    /// that named arguments match the expected parameter names.
-    ///
-    /// # Parameters
-    ///
-    /// - `span`: The source span of the given argument list.
-    /// - `given`: A mutable iterator over the given arguments.
-    /// - `expected_bs`: The binding site of the expected parameter.
-    /// - `args_out`: A mutable vector to collect the lowered arguments.
-    /// - `ctx`: A mutable reference to the current context.
-    ///
-    /// # Returns
-    ///
-    /// - `Ok(())`: The argument was successfully processed and added to `args_out`.
-    /// - `Err(LoweringError)`: An error occurred while processing the argument.
-    fn pop_arg<'a>(
-        span: Span,
-        given: &mut impl Iterator<Item = &'a cst::exp::Arg>,
-        expected_bs: &BindingSite,
-        args_out: &mut Vec<ast::Arg>,
-        ctx: &mut Ctx,
-    ) -> Result<(), LoweringError> {
-        let Some(arg) = given.next() else {
-            return Err(LoweringError::MissingArgForParam {
-                expected: bs_to_name(expected_bs).to_owned(),
-                span: span.to_miette(),
-            });
-        };
+    // Each parameter can have multiple names (e.g., aliases), each of which expects an argument.
+    let expected_bss: Vec<(&BindingSite, bool)> = expected
+        .0
+        .iter()
+        .flat_map(|param| {
+            std::iter::once(&param.name)
+                .chain(param.names.iter())
+                .map(move |bs| (bs, param.implicit))
+        })
+        .collect();
+    let has_default: Vec<bool> = expected
+        .0
+        .iter()
+        .flat_map(|param| std::iter::repeat(param.default.is_some()).take(param.names.len() + 1))
+        .collect();
+    let has_name = |bs: &BindingSite, given_name: &Ident| match bs {
+        BindingSite::Var { name, .. } => name.id == given_name.id,
+        BindingSite::Wildcard { .. } => false,
+    };
+
+    let mut assigned: Vec<Option<ast::Arg>> = vec![None; expected_bss.len()];
+    // The next parameter which may be given an unnamed argument
+    let mut next = 0;
+    // The named argument for the last parameter so far, together with the index of the parameter
+    let mut last_named: Option<(usize, &Ident, &cst::exp::Exp)> = None;
+
+    for arg in given {
+        // Unnamed arguments skip implicit parameters and parameters which are given by name
+        while next < expected_bss.len() && (assigned[next].is_some() || expected_bss[next].1) {
+            next += 1;
+        }
         match arg {
             cst::exp::Arg::UnnamedArg(exp) => {
-                args_out.push(ast::Arg::UnnamedArg(exp.lower(ctx)?));
+                if next == expected_bss.len() {
+                    return Err(LoweringError::TooManyArgs { span: exp.span().to_miette() });
+                }
+                // The argument would be read as an argument for a later parameter
+                if let Some((idx, given_name, given_exp)) = last_named {
+                    if idx > next {
+                        return Err(LoweringError::MismatchedNamedArgs {
+                            given: given_name.clone(),
+                            expected: bs_to_name(expected_bss[next].0),
+                            span: given_exp.span().to_miette(),
+                        });
+                    }
+                }
+                assigned[next] = Some(ast::Arg::UnnamedArg(exp.lower(ctx)?));
             }
-            cst::exp::Arg::NamedArg(name, exp) => {
-                let expected_name = match &expected_bs {
-                    BindingSite::Var { name, .. } => name,
-                    BindingSite::Wildcard { span } => {
+            cst::exp::Arg::NamedArg(given_name, exp)
+            | cst::exp::Arg::ImplicitArg(given_name, exp) => {
+                let Some(idx) = expected_bss.iter().position(|(bs, _)| has_name(bs, given_name))
+                else {
+                    // Report the wildcard parameter at which the argument would be expected
+                    if let Some((BindingSite::Wildcard { span }, _)) = expected_bss.get(next) {
                         return Err(LoweringError::NamedArgForWildcard {
-                            given: name.clone(),
+                            given: given_name.clone(),
                             span: span.to_miette(),
                         });
                     }
+                    return Err(LoweringError::UnknownArgName {
+                        name: given_name.clone(),
+                        span: exp.span().to_miette(),
+                    });
                 };
-                if name.id != expected_name.id {
-                    return Err(LoweringError::MismatchedNamedArgs {
-                        given: name.to_owned(),
-                        expected: expected_name.to_owned(),
+                // Arguments marked with `@` may only be given for implicit parameters.
+                if matches!(arg, cst::exp::Arg::ImplicitArg(..)) && !expected_bss[idx].1 {
+                    return Err(LoweringError::UnexpectedImplicitArg {
+                        name: given_name.clone(),
+                        span: exp.span().to_miette(),
+                    });
+                }
+                if assigned[idx].is_some() {
+                    return Err(LoweringError::DuplicateArg {
+                        name: given_name.clone(),
                         span: exp.span().to_miette(),
                     });
                 }
-                let name = VarBound { span: Some(name.span), id: name.id.clone() };
-                args_out.push(ast::Arg::NamedArg(name, exp.lower(ctx)?));
+                if !matches!(last_named, Some((last, _, _)) if last >= idx) {
+                    last_named = Some((idx, given_name, exp));
+                }
+                let name = VarBound { span: Some(given_name.span), id: given_name.id.clone() };
+                assigned[idx] = Some(ast::Arg::NamedArg(name, exp.lower(ctx)?));
             }
         }
-        Ok(())
     }
 
-    for expected_param in expected.0.iter() {
-        // Each parameter can have multiple names (e.g., aliases).
-        let names_iter = std::iter::once(&expected_param.name).chain(expected_param.names.iter());
-        for expected_bs in names_iter {
-            if expected_param.implicit {
-                if let Some(cst::exp::Arg::NamedArg(given_name, exp)) = given_iter.peek() {
-                    let BindingSite::Var { name: expected_name, .. } = &expected_bs else {
-                        return Err(LoweringError::NamedArgForWildcard {
-                            given: given_name.clone(),
-                            span: exp.span().to_miette(),
-                        });
-                    };
-                    if expected_name == given_name {
-                        pop_arg(span, &mut given_iter, expected_bs, &mut args_out, ctx)?;
-                        continue;
-                    }
-                }
-
+    let mut args_out = vec![];
+    for (((expected_bs, implicit), has_default), arg) in
+        expected_bss.into_iter().zip(has_default).zip(assigned)
+    {
+        match arg {
+            Some(arg) => args_out.push(arg),
+            // The elaborator replaces the holes for omitted arguments by their default values
+            None if implicit || has_default => {
                 let mv = ctx.fresh_metavar(Some(span), MetaVarKind::Inserted);
                 let args = ctx.subst_from_ctx();
                 let hole = Hole {
                     span: None,
                     kind: ast::MetaVarKind::Inserted,
+                    name: None,
                     metavar: mv,
                     inferred_type: None,
                     inferred_ctx: None,
                     args,
                     solution: None,
                 };
-
                 args_out.push(ast::Arg::InsertedImplicitArg(hole));
-            } else {
-                pop_arg(span, &mut given_iter, expected_bs, &mut args_out, ctx)?;
+            }
+            None => {
+                return Err(LoweringError::MissingArgForParam {
+                    expected: bs_to_name(expected_bs),
+                    span: span.to_miette(),
+                });
             }
         }
     }
 
-    // Check for any extra arguments that were not matched to parameters.
-    if let Some(extra_arg) = given_iter.next() {
-        return Err(LoweringError::TooManyArgs { span: extra_arg.span().to_miette() });
-    }
-
     // All arguments have been successfully processed.
     Ok(ast::Args { args: args_out })
 }
@@ -296,59 +328,86 @@ impl Lower for cst::exp::Call {
 
         // If we find the identifier in the global context then we have to lower
         // it to a call or a type constructor.
+        let name = &ctx.resolve_global(name);
         let (meta, uri) = ctx.symbol_table.lookup(name)?;
-        match meta {
-            DeclMeta::Data { params, .. } | DeclMeta::Codata { params, .. } => {
-                let name = IdBound { span: Some(name.span), id: name.id.clone(), uri: uri.clone() };
-                Ok(ast::Exp::TypCtor(ast::TypCtor {
-                    span: Some(*span),
-                    name,
-                    args: lower_args(*span, args, params.clone(), ctx)?,
-                }))
-            }
-            DeclMeta::Def { .. } | DeclMeta::Dtor { .. } => {
-                Err(LoweringError::MustUseAsDotCall { name: name.clone(), span: span.to_miette() })
-            }
-            DeclMeta::Ctor { params, .. } => {
-                let name = IdBound { span: Some(name.span), id: name.id.clone(), uri: uri.clone() };
-                Ok(ast::Exp::Call(ast::Call {
-                    span: Some(*span),
-                    kind: ast::CallKind::Constructor,
-                    name,
-                    args: lower_args(*span, args, params.clone(), ctx)?,
-                    inferred_type: None,
-                }))
-            }
-            DeclMeta::Codef { params, .. } => {
-                let name = IdBound { span: Some(name.span), id: name.id.clone(), uri: uri.clone() };
-                Ok(ast::Exp::Call(ast::Call {
-                    span: Some(*span),
-                    kind: ast::CallKind::Codefinition,
-                    name,
-                    args: lower_args(*span, args, params.clone(), ctx)?,
-                    inferred_type: None,
-                }))
-            }
-            DeclMeta::Let { params, .. } => {
-                let name = IdBound { span: Some(name.span), id: name.id.clone(), uri: uri.clone() };
-                Ok(ast::Exp::Call(ast::Call {
-                    span: Some(*span),
-                    kind: ast::CallKind::LetBound,
-                    name,
-                    args: lower_args(*span, args, params.clone(), ctx)?,
-                    inferred_type: None,
-                }))
-            }
+        let (meta, uri) = (meta.clone(), uri.clone());
+        lower_global(*span, name, args, meta, uri, ctx)
+    }
+}
+
+/// Lower the application of the top-level declaration `name` which is declared in the module `uri`
+fn lower_global(
+    span: Span,
+    name: &Ident,
+    args: &[cst::exp::Arg],
+    meta: DeclMeta,
+    uri: Url,
+    ctx: &mut Ctx,
+) -> Result<ast::Exp, LoweringError> {
+    let name_bound = IdBound { span: Some(name.span), id: name.id.clone(), uri };
+    match meta {
+        DeclMeta::Data { params, .. } | DeclMeta::Codata { params, .. } => {
+            Ok(ast::Exp::TypCtor(ast::TypCtor {
+                span: Some(span),
+                name: name_bound,
+                args: lower_args(span, args, params, ctx)?,
+            }))
         }
+        DeclMeta::Def { .. } | DeclMeta::Dtor { .. } => {
+            Err(LoweringError::MustUseAsDotCall { name: name.clone(), span: span.to_miette() })
+        }
+        DeclMeta::Ctor { params, .. } => Ok(ast::Exp::Call(ast::Call {
+            span: Some(span),
+            kind: ast::CallKind::Constructor,
+            name: name_bound,
+            args: lower_args(span, args, params, ctx)?,
+            inferred_type: None,
+        })),
+        DeclMeta::Codef { params, .. } => Ok(ast::Exp::Call(ast::Call {
+            span: Some(span),
+            kind: ast::CallKind::Codefinition,
+            name: name_bound,
+            args: lower_args(span, args, params, ctx)?,
+            inferred_type: None,
+        })),
+        DeclMeta::Let { params, .. } => Ok(ast::Exp::Call(ast::Call {
+            span: Some(span),
+            kind: ast::CallKind::LetBound,
+            name: name_bound,
+            args: lower_args(span, args, params, ctx)?,
+            inferred_type: None,
+        })),
     }
 }
 
+/// The module which qualifies the name of a [cst::exp::DotCall], e.g. `nat` in `nat.Z`
+///
+/// The receiver is only a module name if it does not refer to a local variable or a declaration.
+fn qualifier<'a>(exp: &'a cst::exp::Exp, ctx: &Ctx) -> Option<&'a Ident> {
+    let cst::exp::Exp::Call(cst::exp::Call { name, args, .. }) = exp else {
+        return None;
+    };
+    let is_module = args.is_empty()
+        && ctx.lookup_local(name).is_none()
+        && !ctx.symbol_table.lookup_exists(&ctx.resolve_global(name))
+        && ctx.symbol_table.is_module(name);
+    is_module.then_some(name)
+}
+
 impl Lower for cst::exp::DotCall {
     type Target = ast::Exp;
 
     fn lower(&self, ctx: &mut Ctx) -> Result<Self::Target, LoweringError> {
         let cst::exp::DotCall { span, exp, name, args } = self;
 
+        // A qualified name `module.name` refers to a declaration of an imported module
+        if let Some(module) = qualifier(exp, ctx) {
+            let (meta, uri) = ctx.symbol_table.lookup_qualified(module, name)?;
+            let (meta, uri) = (meta.clone(), uri.clone());
+            return lower_global(*span, name, args, meta, uri, ctx);
+        }
+
+        let name = &ctx.resolve_global(name);
         let (meta, uri) = ctx.symbol_table.lookup(name)?;
         let (meta, uri) = (meta.clone(), uri.clone());
 
@@ -401,6 +460,39 @@ impl Lower for cst::exp::TypeUniv {
     }
 }
 
+impl Lower for cst::exp::Absurd {
+    type Target = ast::Exp;
+
+    fn lower(&self, ctx: &mut Ctx) -> Result<Self::Target, LoweringError> {
+        let cst::exp::Absurd { span } = self;
+        // `absurd` is lowered to an empty match on an inserted hole.
+        // The elaborator replaces the hole by a variable of an empty type in the context.
+        let mv = ctx.fresh_metavar(Some(*span), MetaVarKind::Inserted);
+        let args = ctx.subst_from_ctx();
+        let on_exp = Hole {
+            span: Some(*span),
+            kind: MetaVarKind::Inserted,
+            name: None,
+            metavar: mv,
+            inferred_type: None,
+            inferred_ctx: None,
+            args,
+            solution: None,
+        };
+        Ok(ast::LocalMatch {
+            span: Some(*span),
+            ctx: None,
+            name: ctx.unique_label(None, span)?,
+            on_exp: Box::new(on_exp.into()),
+            motive: None,
+            ret_typ: None,
+            cases: vec![],
+            inferred_type: None,
+        }
+        .into())
+    }
+}
+
 impl Lower for cst::exp::LocalMatch {
     type Target = ast::Exp;
 
@@ -452,13 +544,17 @@ impl Lower for cst::exp::Hole {
     type Target = ast::Exp;
 
     fn lower(&self, ctx: &mut Ctx) -> Result<Self::Target, LoweringError> {
-        let cst::exp::Hole { span, kind, .. } = self;
+        let cst::exp::Hole { span, kind, name } = self;
         let kind = kind.lower(ctx)?;
+        if let Some(name) = name {
+            ctx.declare_hole_name(name, *span)?;
+        }
         let mv = ctx.fresh_metavar(Some(*span), kind);
         let args = ctx.subst_from_ctx();
         Ok(Hole {
             span: Some(*span),
             kind,
+            name: name.clone(),
             metavar: mv,
             inferred_type: None,
             inferred_ctx: None,
@@ -487,12 +583,13 @@ impl Lower for cst::exp::NatLit {
             _ => return Err(LoweringError::NatLiteralCannotBeDesugared { span: span.to_miette() }),
         };
 
+        let typ = literal_type(ctx, span, "Z", vec![]);
         let mut out = ast::Exp::Call(ast::Call {
             span: Some(*span),
             kind: call_kind,
             name: ast::IdBound { span: Some(*span), id: "Z".to_owned(), uri: uri.clone() },
             args: ast::Args { args: vec![] },
-            inferred_type: None,
+            inferred_type: typ.clone(),
         });
 
         let mut i = BigUint::from(0usize);
@@ -504,7 +601,88 @@ impl Lower for cst::exp::NatLit {
                 kind: call_kind,
                 name: ast::IdBound { span: Some(*span), id: "S".to_owned(), uri: uri.clone() },
                 args: ast::Args { args: vec![ast::Arg::UnnamedArg(Box::new(out))] },
-                inferred_type: None,
+                inferred_type: typ.clone(),
+            });
+        }
+
+        Ok(out)
+    }
+}
+
+impl Lower for cst::exp::CharLit {
+    type Target = ast::Exp;
+
+    fn lower(&self, ctx: &mut Ctx) -> Result<Self::Target, LoweringError> {
+        let cst::exp::CharLit { span, val } = self;
+
+        // A character is desugared to the constructor MkChar applied to its code point.
+        // The code point is a binary number with the least significant bit first, such that
+        // its size is logarithmic in the code point.
+        let uri = match ctx.symbol_table.lookup(&Ident { span: *span, id: "MkChar".to_owned() }) {
+            Ok((DeclMeta::Ctor { .. }, uri)) => uri.clone(),
+            _ => {
+                return Err(LoweringError::CharLiteralCannotBeDesugared { span: span.to_miette() })
+            }
+        };
+        // We assume that if BNil is declared alongside MkChar, then so are B0 and B1.
+        match ctx.symbol_table.lookup(&Ident { span: *span, id: "BNil".to_owned() }) {
+            Ok((DeclMeta::Ctor { .. }, bnil_uri)) if *bnil_uri == uri => {}
+            _ => {
+                return Err(LoweringError::CharLiteralCannotBeDesugared { span: span.to_miette() })
+            }
+        }
+        let typ = literal_type(ctx, span, "MkChar", vec![]);
+        let ctor = |id: &str, args: Vec<ast::Arg>, inferred_type: Option<Box<ast::Exp>>| {
+            ast::Exp::Call(ast::Call {
+                span: Some(*span),
+                kind: ast::CallKind::Constructor,
+                name: ast::IdBound { span: Some(*span), id: id.to_owned(), uri: uri.clone() },
+                args: ast::Args { args },
+                inferred_type,
+            })
+        };
+
+        let code = *val as u32;
+        let width = u32::BITS - code.leading_zeros();
+        let code = (0..width).rev().fold(ctor("BNil", vec![], None), |rest, i| {
+            let bit = if (code >> i) & 1 == 1 { "B1" } else { "B0" };
+            ctor(bit, vec![ast::Arg::UnnamedArg(Box::new(rest))], None)
+        });
+
+        Ok(ctor("MkChar", vec![ast::Arg::UnnamedArg(Box::new(code))], typ))
+    }
+}
+
+impl Lower for cst::exp::StrLit {
+    type Target = ast::Exp;
+
+    fn lower(&self, ctx: &mut Ctx) -> Result<Self::Target, LoweringError> {
+        let cst::exp::StrLit { span, val } = self;
+
+        // We assume that if SNil exists, then SCons exists as well.
+        let uri = match ctx.symbol_table.lookup(&Ident { span: *span, id: "SNil".to_owned() }) {
+            Ok((DeclMeta::Ctor { .. }, uri)) => uri.clone(),
+            _ => return Err(LoweringError::StrLiteralCannotBeDesugared { span: span.to_miette() }),
+        };
+
+        let typ = literal_type(ctx, span, "SNil", vec![]);
+        let mut out = ast::Exp::Call(ast::Call {
+            span: Some(*span),
+            kind: ast::CallKind::Constructor,
+            name: ast::IdBound { span: Some(*span), id: "SNil".to_owned(), uri: uri.clone() },
+            args: ast::Args { args: vec![] },
+            inferred_type: typ.clone(),
+        });
+
+        for c in val.chars().rev() {
+            let c = cst::exp::CharLit { span: *span, val: c }.lower(ctx)?;
+            let args = vec![ast::Arg::UnnamedArg(Box::new(c)), ast::Arg::UnnamedArg(Box::new(out))];
+            out = ast::Exp::Call(ast::Call {
+                span: Some(*span),
+                kind: ast::CallKind::Constructor,
+                name: ast::IdBound { span: Some(*span), id: "SCons".to_owned(), uri: uri.clone() },
+                args: ast::Args { args },
+                inferred_type: typ.clone(),
             });
         }
 
@@ -512,10 +690,57 @@ impl Lower for cst::exp::NatLit {
     }
 }
 
+/// The data type which declares the constructor `ctor`, applied to `args`
+///
+/// Desugared literals are annotated with their type, such that they are printed as literals
+/// again even before they are elaborated. The type is only known if `args` are exactly the
+/// parameters of the data type, e.g. no arguments for `Nat` and the element type for `List`.
+fn literal_type(ctx: &Ctx, span: &Span, ctor: &str, args: Vec<ast::Arg>) -> Option<Box<ast::Exp>> {
+    let Ok((DeclMeta::Ctor { data, .. }, uri)) =
+        ctx.symbol_table.lookup(&Ident { span: *span, id: ctor.to_owned() })
+    else {
+        return None;
+    };
+    match ctx.symbol_table.lookup(data) {
+        Ok((DeclMeta::Data { params, .. }, data_uri))
+            if params.len() == args.len() && data_uri == uri =>
+        {
+            Some(Box::new(
+                ast::TypCtor {
+                    span: None,
+                    name: IdBound { span: None, id: data.id.clone(), uri: uri.clone() },
+                    args: ast::Args { args },
+                }
+                .into(),
+            ))
+        }
+        _ => None,
+    }
+}
+
 impl Lower for cst::exp::Fun {
     type Target = ast::Exp;
     fn lower(&self, ctx: &mut Ctx) -> Result<Self::Target, LoweringError> {
-        let cst::exp::Fun { span, from, to } = self;
+        let cst::exp::Fun { span, var, from, to } = self;
+
+        // A dependent function type `(x : a) -> b` is sugar for `Pi(a, \x. b)`.
+        // If `x` does not occur in `b`, it is an ordinary function type `a -> b`.
+        if let Some(var @ cst::exp::BindingSite::Var { name, .. }) = var {
+            if mentions(to, name) {
+                let lam =
+                    cst::exp::Lam { span: *span, params: vec![var.clone()], body: to.clone() };
+                let pi = cst::exp::Call {
+                    span: *span,
+                    name: Ident { span: *span, id: "Pi".to_owned() },
+                    args: vec![
+                        cst::exp::Arg::UnnamedArg(from.clone()),
+                        cst::exp::Arg::UnnamedArg(Box::new(cst::exp::Exp::Lam(lam))),
+                    ],
+                };
+                return cst::exp::Exp::Call(pi).lower(ctx);
+            }
+        }
+
         let (_, uri) = ctx.symbol_table.lookup(&Ident { span: *span, id: "Fun".to_owned() })?;
         Ok(ast::TypCtor {
             span: Some(*span),
@@ -531,11 +756,211 @@ impl Lower for cst::exp::Fun {
     }
 }
 
+/// Whether the identifier `name` occurs anywhere in `exp`
+///
+/// This over-approximates whether the variable `name` occurs free in `exp`,
+/// since shadowing is not taken into account.
+fn mentions(exp: &cst::exp::Exp, name: &Ident) -> bool {
+    use cst::exp::*;
+
+    let in_args = |args: &[Arg]| {
+        args.iter().any(|arg| match arg {
+            Arg::UnnamedArg(exp) | Arg::NamedArg(_, exp) | Arg::ImplicitArg(_, exp) => {
+                mentions(exp, name)
+            }
+        })
+    };
+    let in_body = |body: &Option<Box<Exp>>| body.as_ref().is_some_and(|exp| mentions(exp, name));
+    match exp {
+        Exp::Call(Call { name: id, args, .. }) => id.id == name.id || in_args(args),
+        Exp::DotCall(DotCall { exp, args, .. }) => mentions(exp, name) || in_args(args),
+        Exp::Anno(Anno { exp, typ, .. }) => mentions(exp, name) || mentions(typ, name),
+        Exp::TypeUniv(_)
+        | Exp::Hole(_)
+        | Exp::NatLit(_)
+        | Exp::StrLit(_)
+        | Exp::CharLit(_)
+        | Exp::Absurd(_) => false,
+        Exp::LocalMatch(LocalMatch { on_exp, motive, cases, .. }) => {
+            mentions(on_exp, name)
+                || motive.as_ref().is_some_and(|motive| in_body(&motive.ret_typ))
+                || cases.iter().any(|case| in_body(&case.body))
+        }
+        Exp::LocalComatch(LocalComatch { cases, .. }) => {
+            cases.iter().any(|case| in_body(&case.body))
+        }
+        Exp::Fun(Fun { from, to, .. }) => mentions(from, name) || mentions(to, name),
+        Exp::Lam(Lam { body, .. }) => mentions(body, name),
+        Exp::IfLet(IfLet { on_exp, then_branch, else_branch, .. }) => {
+            mentions(on_exp, name) || mentions(then_branch, name) || mentions(else_branch, name)
+        }
+        Exp::LocalLet(LocalLet { bound, typ, body, .. }) => {
+            mentions(bound, name) || in_body(typ) || mentions(body, name)
+        }
+        Exp::Tuple(Tuple { elems, .. }) | Exp::ListLit(ListLit { elems, .. }) => {
+            elems.iter().any(|exp| mentions(exp, name))
+        }
+        Exp::Projection(Projection { exp, .. }) => mentions(exp, name),
+    }
+}
+
+impl Lower for cst::exp::Tuple {
+    type Target = ast::Exp;
+
+    fn lower(&self, ctx: &mut Ctx) -> Result<Self::Target, LoweringError> {
+        let cst::exp::Tuple { span, elems } = self;
+
+        // A tuple is desugared to the constructor MkPair of the data type Pair.
+        // The last two explicit parameters of MkPair are the components of the pair,
+        // all other explicit arguments are left to be inferred.
+        let (params, uri) =
+            match ctx.symbol_table.lookup(&Ident { span: *span, id: "MkPair".to_owned() }) {
+                Ok((DeclMeta::Ctor { params, data }, uri))
+                    if data.id == "Pair" && params.explicit_len() >= 2 =>
+                {
+                    (params.clone(), uri.clone())
+                }
+                _ => return Err(LoweringError::TupleCannotBeDesugared { span: span.to_miette() }),
+            };
+
+        // Tuples with more than two elements are desugared to nested pairs, i.e.
+        // (a, b, c) is desugared in the same way as (a, (b, c))
+        let (fst, rest) = elems.split_first().expect("Tuples have at least two elements");
+        let snd = match rest {
+            [snd] => snd.clone(),
+            _ => Box::new(cst::exp::Exp::Tuple(cst::exp::Tuple {
+                span: *span,
+                elems: rest.to_vec(),
+            })),
+        };
+        let mut args = holes(*span, params.explicit_len() - 2);
+        args.push(cst::exp::Arg::UnnamedArg(fst.clone()));
+        args.push(cst::exp::Arg::UnnamedArg(snd));
+
+        Ok(ast::Exp::Call(ast::Call {
+            span: Some(*span),
+            kind: ast::CallKind::Constructor,
+            name: ast::IdBound { span: Some(*span), id: "MkPair".to_owned(), uri },
+            args: lower_args(*span, &args, params, ctx)?,
+            inferred_type: None,
+        }))
+    }
+}
+
+impl Lower for cst::exp::ListLit {
+    type Target = ast::Exp;
+
+    fn lower(&self, ctx: &mut Ctx) -> Result<Self::Target, LoweringError> {
+        let cst::exp::ListLit { span, elems } = self;
+
+        // A list is desugared to the constructors Nil and Cons of the data type List.
+        // The last two explicit parameters of Cons are the head and the tail of the list,
+        // all other explicit arguments are left to be inferred.
+        let lookup = |name: &str, min_len: usize| {
+            let name = Ident { span: *span, id: name.to_owned() };
+            match ctx.symbol_table.lookup(&name) {
+                Ok((DeclMeta::Ctor { params, data }, uri))
+                    if data.id == "List" && params.explicit_len() >= min_len =>
+                {
+                    Ok((params.clone(), uri.clone()))
+                }
+                _ => Err(LoweringError::ListCannotBeDesugared { span: span.to_miette() }),
+            }
+        };
+
+        // A list with at least one element is desugared in the same way as a `Cons` of
+        // its head and the remaining list, i.e. [a, b] is desugared to Cons(_, a, [b])
+        let (name, params, uri, args) = match elems.split_first() {
+            None => {
+                let (params, uri) = lookup("Nil", 0)?;
+                let args = holes(*span, params.explicit_len());
+                ("Nil", params, uri, args)
+            }
+            Some((head, tail)) => {
+                let (params, uri) = lookup("Cons", 2)?;
+                let tail = cst::exp::ListLit { span: *span, elems: tail.to_vec() };
+                let mut args = holes(*span, params.explicit_len() - 2);
+                args.push(cst::exp::Arg::UnnamedArg(head.clone()));
+                args.push(cst::exp::Arg::UnnamedArg(Box::new(cst::exp::Exp::ListLit(tail))));
+                ("Cons", params, uri, args)
+            }
+        };
+
+        // The holes for the element type are the arguments of the type `List` as well
+        let args = lower_args(*span, &args, params, ctx)?;
+        let type_args = match name {
+            "Nil" => &args.args[..],
+            _ => &args.args[..args.args.len() - 2],
+        };
+        let typ = literal_type(ctx, span, name, type_args.to_vec());
+
+        Ok(ast::Exp::Call(ast::Call {
+            span: Some(*span),
+            kind: ast::CallKind::Constructor,
+            name: ast::IdBound { span: Some(*span), id: name.to_owned(), uri },
+            args,
+            inferred_type: typ,
+        }))
+    }
+}
+
+impl Lower for cst::exp::Projection {
+    type Target = ast::Exp;
+
+    fn lower(&self, ctx: &mut Ctx) -> Result<Self::Target, LoweringError> {
+        let cst::exp::Projection { span, exp, index } = self;
+
+        // The projections .1 and .2 are desugared to .fst and .snd,
+        // where all explicit arguments are left to be inferred.
+        let name = if *index == BigUint::from(1usize) {
+            "fst"
+        } else if *index == BigUint::from(2usize) {
+            "snd"
+        } else {
+            return Err(LoweringError::InvalidProjection {
+                index: index.clone(),
+                span: span.to_miette(),
+            });
+        };
+        let name = Ident { span: *span, id: name.to_owned() };
+        let params = match ctx.symbol_table.lookup(&name) {
+            Ok((DeclMeta::Def { params } | DeclMeta::Dtor { params }, _)) => params.clone(),
+            _ => {
+                return Err(LoweringError::ProjectionCannotBeDesugared {
+                    name: name.id,
+                    span: span.to_miette(),
+                })
+            }
+        };
+        let args = holes(*span, params.explicit_len());
+
+        cst::exp::DotCall { span: *span, exp: exp.clone(), name, args }.lower(ctx)
+    }
+}
+
+/// `n` arguments `_` which have to be solved during elaboration
+fn holes(span: Span, n: usize) -> Vec<cst::exp::Arg> {
+    let hole = cst::exp::Hole { span, kind: cst::exp::HoleKind::MustSolve, name: None };
+    (0..n).map(|_| cst::exp::Arg::UnnamedArg(Box::new(cst::exp::Exp::Hole(hole.clone())))).collect()
+}
+
 impl Lower for cst::exp::Lam {
     type Target = ast::Exp;
 
     fn lower(&self, ctx: &mut Ctx) -> Result<Self::Target, LoweringError> {
-        let cst::exp::Lam { span, var, body } = self;
+        let cst::exp::Lam { span, params, body } = self;
+
+        // A lambda abstraction `\x y. e` with several parameters is sugar for `\x. \y. e`
+        let (var, params) = params.split_first().ok_or_else(|| LoweringError::Impossible {
+            message: "Lambda abstraction without parameters".to_owned(),
+            span: Some(span.to_miette()),
+        })?;
+        let body = if params.is_empty() {
+            body.clone()
+        } else {
+            let lam = cst::exp::Lam { span: *span, params: params.to_vec(), body: body.clone() };
+            Box::new(cst::exp::Exp::Lam(lam))
+        };
 
         let case = cst::exp::Case {
             span: *span,
@@ -547,7 +972,7 @@ impl Lower for cst::exp::Lam {
                     var.clone(),
                 ],
             },
-            body: Some(body.clone()),
+            body: Some(body),
         };
         let comatch = cst::exp::Exp::LocalComatch(cst::exp::LocalComatch {
             span: *span,
@@ -559,6 +984,100 @@ impl Lower for cst::exp::Lam {
     }
 }
 
+impl Lower for cst::exp::IfLet {
+    type Target = ast::Exp;
+
+    fn lower(&self, ctx: &mut Ctx) -> Result<Self::Target, LoweringError> {
+        let cst::exp::IfLet { span, pattern, on_exp, then_branch, else_branch } = self;
+
+        let data = match ctx.symbol_table.lookup(&pattern.name)? {
+            (DeclMeta::Ctor { data, .. }, _) => data.clone(),
+            _ => {
+                return Err(LoweringError::ExpectedCtor {
+                    name: pattern.name.clone(),
+                    span: pattern.name.span.to_miette(),
+                })
+            }
+        };
+        let ctors = match ctx.symbol_table.lookup(&data)? {
+            (DeclMeta::Data { ctors, .. }, _) => ctors.clone(),
+            _ => {
+                return Err(LoweringError::Impossible {
+                    message: format!("Expected {} to be a data type", data.id),
+                    span: Some(data.span.to_miette()),
+                })
+            }
+        };
+
+        // The matched constructor binds the pattern variables in the then branch.
+        // All other constructors fall back to the else branch without binding any variables.
+        let cases = ctors
+            .iter()
+            .map(|ctor| {
+                if *ctor == pattern.name {
+                    return Ok(cst::exp::Case {
+                        span: *span,
+                        pattern: pattern.clone(),
+                        body: Some(then_branch.clone()),
+                    });
+                }
+                let n_params = match ctx.symbol_table.lookup(ctor)? {
+                    (DeclMeta::Ctor { params, .. }, _) => params.0.len(),
+                    _ => 0,
+                };
+                Ok(cst::exp::Case {
+                    span: *span,
+                    pattern: cst::exp::Pattern {
+                        name: Ident { span: *span, id: ctor.id.clone() },
+                        params: vec![cst::exp::BindingSite::Wildcard { span: *span }; n_params],
+                    },
+                    body: Some(else_branch.clone()),
+                })
+            })
+            .collect::<Result<_, LoweringError>>()?;
+
+        let local_match = cst::exp::Exp::LocalMatch(cst::exp::LocalMatch {
+            span: *span,
+            name: None,
+            on_exp: on_exp.clone(),
+            motive: None,
+            cases,
+        });
+        local_match.lower(ctx)
+    }
+}
+
+impl Lower for cst::exp::LocalLet {
+    type Target = ast::Exp;
+
+    fn lower(&self, ctx: &mut Ctx) -> Result<Self::Target, LoweringError> {
+        let cst::exp::LocalLet { span, name, bound, typ, body } = self;
+
+        // The bound variable is not in scope in the bound expression
+        let bound = bound.lower(ctx).map_err(|err| match err {
+            LoweringError::UndefinedIdent { name: undefined, span, .. }
+                if undefined == bs_to_name(name) =>
+            {
+                LoweringError::RecursiveLet { name: undefined, span }
+            }
+            err => err,
+        })?;
+        let typ = typ.lower(ctx)?;
+        let body = ctx.bind_single(name, |ctx| body.lower(ctx))?;
+
+        Ok(ast::LocalLet {
+            span: Some(*span),
+            name: ast::VarBind { span: Some(bs_to_span(name)), id: bs_to_name(name).id },
+            typ,
+            bound,
+            body,
+            bound_type: None,
+            inferred_type: None,
+        }
+        .into())
+    }
+}
+
 fn bs_to_name(bs: &cst::exp::BindingSite) -> Ident {
     match bs {
         BindingSite::Var { name, .. } => name.clone(),
@@ -3,6 +3,8 @@ use super::result::*;
 mod decls;
 mod exp;
 
+pub use decls::lower_decls;
+
 pub trait Lower {
     type Target;
 
@@ -1,4 +1,5 @@
 use miette::{Diagnostic, SourceSpan};
+use num_bigint::BigUint;
 use parser::cst::ident::Ident;
 use thiserror::Error;
 
@@ -10,6 +11,8 @@ pub enum LoweringError {
         name: Ident,
         #[label]
         span: SourceSpan,
+        #[help]
+        help: Option<String>,
     },
     #[error("Duplicate definition of {}", name.id)]
     #[diagnostic(code("L-002"))]
@@ -94,8 +97,113 @@ pub enum LoweringError {
         #[label]
         span: SourceSpan,
     },
+    #[error("{} is not a constructor and cannot be matched on in if let", name.id)]
+    #[diagnostic(code("L-015"))]
+    ExpectedCtor {
+        name: Ident,
+        #[label]
+        span: SourceSpan,
+    },
+    #[error("The let binding of {} cannot refer to itself", name.id)]
+    #[diagnostic(code("L-016"))]
+    RecursiveLet {
+        name: Ident,
+        #[label]
+        span: SourceSpan,
+    },
+    #[error("Literal cannot be desugared because MkChar/BNil/B0/B1 are not in program")]
+    #[diagnostic(code("L-017"))]
+    CharLiteralCannotBeDesugared {
+        #[label]
+        span: SourceSpan,
+    },
+    #[error("Literal cannot be desugared because SNil/SCons are not in program")]
+    #[diagnostic(code("L-018"))]
+    StrLiteralCannotBeDesugared {
+        #[label]
+        span: SourceSpan,
+    },
+    #[error("The hole ?{name} occurs more than once in the same declaration")]
+    #[diagnostic(code("L-019"))]
+    DuplicateHoleName {
+        name: String,
+        #[label]
+        span: SourceSpan,
+    },
+    #[error("Tuple cannot be desugared because MkPair is not a constructor in program")]
+    #[diagnostic(code("L-020"))]
+    TupleCannotBeDesugared {
+        #[label]
+        span: SourceSpan,
+    },
+    #[error("Projection .{index} does not exist, only .1 and .2 are supported")]
+    #[diagnostic(code("L-021"))]
+    InvalidProjection {
+        index: BigUint,
+        #[label]
+        span: SourceSpan,
+    },
+    #[error("Projection cannot be desugared because {name} is not in program")]
+    #[diagnostic(code("L-022"))]
+    ProjectionCannotBeDesugared {
+        name: String,
+        #[label]
+        span: SourceSpan,
+    },
+    #[error("There is no implicit parameter {} at this position", name.id)]
+    #[diagnostic(code("L-023"))]
+    UnexpectedImplicitArg {
+        name: Ident,
+        #[label]
+        span: SourceSpan,
+    },
+    #[error("{} is declared in more than one imported module: {modules}", name.id)]
+    #[diagnostic(code("L-024"), help("Qualify the name with the module it refers to"))]
+    AmbiguousName {
+        name: Ident,
+        modules: String,
+        #[label]
+        span: SourceSpan,
+    },
+    #[error("The module name {name} refers to more than one imported module")]
+    #[diagnostic(code("L-025"))]
+    AmbiguousModule {
+        name: String,
+        #[label]
+        span: SourceSpan,
+    },
+    #[error("There is no parameter named {}", name.id)]
+    #[diagnostic(code("L-026"))]
+    UnknownArgName {
+        name: Ident,
+        #[label]
+        span: SourceSpan,
+    },
+    #[error("More than one argument is given for parameter {}", name.id)]
+    #[diagnostic(code("L-027"))]
+    DuplicateArg {
+        name: Ident,
+        #[label]
+        span: SourceSpan,
+    },
+    #[error("Parameter {} must have a default value", name.id)]
+    #[diagnostic(
+        code("L-028"),
+        help("Parameters without a default value cannot follow a parameter with a default value")
+    )]
+    MissingDefault {
+        name: Ident,
+        #[label]
+        span: SourceSpan,
+    },
+    #[error("List cannot be desugared because Nil/Cons are not in program")]
+    #[diagnostic(code("L-029"))]
+    ListCannotBeDesugared {
+        #[label]
+        span: SourceSpan,
+    },
     #[error("An unexpected internal error occurred: {message}")]
-    #[diagnostic(code("L-XXX"))]
+    #[diagnostic(code("L-000"))]
     /// This error should not occur.
     /// Some internal invariant has been violated.
     Impossible {
@@ -104,3 +212,40 @@ pub enum LoweringError {
         span: Option<SourceSpan>,
     },
 }
+
+impl LoweringError {
+    /// The codes of all variants, together with the templates of their messages
+    ///
+    /// This list must be extended whenever a variant is added, see `driver::error_index`.
+    pub const CODES: &'static [(&'static str, &'static str)] = &[
+        ("L-001", "Undefined identifier {}"),
+        ("L-002", "Duplicate definition of {}"),
+        ("L-003", "{} must be used as destructor"),
+        ("L-004", "{} cannot be used as a destructor"),
+        ("L-005", "Arguments to type constructor {} must be provided for {}"),
+        ("L-007", "The annotated label {name} is shadowed by a local variable"),
+        ("L-008", "The annotated label {name} is not unique"),
+        ("L-009", "Expected a type constructor"),
+        ("L-010", "Literal cannot be desugared because S/Z are not in program"),
+        ("L-011", "Mismatched named arguments: given {}, expected {}"),
+        ("L-012", "Used named argument {} for wildcard parameter"),
+        ("L-013", "Missing argument for parameter {}"),
+        ("L-014", "Too many arguments provided"),
+        ("L-015", "{} is not a constructor and cannot be matched on in if let"),
+        ("L-016", "The let binding of {} cannot refer to itself"),
+        ("L-017", "Literal cannot be desugared because MkChar/BNil/B0/B1 are not in program"),
+        ("L-018", "Literal cannot be desugared because SNil/SCons are not in program"),
+        ("L-019", "The hole ?{name} occurs more than once in the same declaration"),
+        ("L-020", "Tuple cannot be desugared because MkPair is not a constructor in program"),
+        ("L-021", "Projection .{index} does not exist, only .1 and .2 are supported"),
+        ("L-022", "Projection cannot be desugared because {name} is not in program"),
+        ("L-023", "There is no implicit parameter {} at this position"),
+        ("L-024", "{} is declared in more than one imported module: {modules}"),
+        ("L-025", "The module name {name} refers to more than one imported module"),
+        ("L-026", "There is no parameter named {}"),
+        ("L-027", "More than one argument is given for parameter {}"),
+        ("L-028", "Parameter {} must have a default value"),
+        ("L-029", "List cannot be desugared because Nil/Cons are not in program"),
+        ("L-000", "An unexpected internal error occurred: {message}"),
+    ];
+}
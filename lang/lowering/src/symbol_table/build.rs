@@ -1,5 +1,6 @@
 use ast::HashMap;
 use decls::*;
+use ident::Ident;
 use miette_util::ToMiette;
 use parser::cst::*;
 
@@ -46,34 +47,37 @@ impl BuildSymbolTable for Data {
                 });
             }
             None => {
-                let meta = DeclMeta::Data { params: params.clone() };
+                let ctors = ctors.iter().map(|ctor| ctor.name.clone()).collect();
+                let meta = DeclMeta::Data { params: params.clone(), ctors };
                 symbol_table.insert(name.clone(), meta);
             }
         }
         for ctor in ctors {
-            ctor.build(symbol_table)?;
+            build_ctor(ctor, name, symbol_table)?;
         }
         Ok(())
     }
 }
 
-impl BuildSymbolTable for Ctor {
-    fn build(&self, symbol_table: &mut ModuleSymbolTable) -> Result<(), LoweringError> {
-        let Ctor { span, name, params, .. } = self;
-        match symbol_table.get(name) {
-            Some(_) => {
-                return Err(LoweringError::AlreadyDefined {
-                    name: name.to_owned(),
-                    span: span.to_miette(),
-                });
-            }
-            None => {
-                let meta = DeclMeta::Ctor { params: params.clone() };
-                symbol_table.insert(name.clone(), meta);
-            }
+fn build_ctor(
+    ctor: &Ctor,
+    data: &Ident,
+    symbol_table: &mut ModuleSymbolTable,
+) -> Result<(), LoweringError> {
+    let Ctor { span, name, params, .. } = ctor;
+    match symbol_table.get(name) {
+        Some(_) => {
+            return Err(LoweringError::AlreadyDefined {
+                name: name.to_owned(),
+                span: span.to_miette(),
+            });
+        }
+        None => {
+            let meta = DeclMeta::Ctor { params: params.clone(), data: data.clone() };
+            symbol_table.insert(name.clone(), meta);
         }
-        Ok(())
     }
+    Ok(())
 }
 
 impl BuildSymbolTable for Codata {
@@ -119,7 +123,7 @@ impl BuildSymbolTable for Dtor {
 
 impl BuildSymbolTable for Def {
     fn build(&self, symbol_table: &mut ModuleSymbolTable) -> Result<(), LoweringError> {
-        let Def { span, name, params, .. } = self;
+        let Def { span, name, params, where_decls, .. } = self;
 
         match symbol_table.get(name) {
             Some(_) => {
@@ -133,13 +137,13 @@ impl BuildSymbolTable for Def {
                 symbol_table.insert(name.clone(), meta);
             }
         }
-        Ok(())
+        build_where_decls(name, where_decls, symbol_table)
     }
 }
 
 impl BuildSymbolTable for Codef {
     fn build(&self, symbol_table: &mut ModuleSymbolTable) -> Result<(), LoweringError> {
-        let Codef { span, name, params, .. } = self;
+        let Codef { span, name, params, where_decls, .. } = self;
 
         match symbol_table.get(name) {
             Some(_) => {
@@ -153,13 +157,13 @@ impl BuildSymbolTable for Codef {
                 symbol_table.insert(name.clone(), meta);
             }
         }
-        Ok(())
+        build_where_decls(name, where_decls, symbol_table)
     }
 }
 
 impl BuildSymbolTable for Let {
     fn build(&self, symbol_table: &mut ModuleSymbolTable) -> Result<(), LoweringError> {
-        let Let { span, name, params, .. } = self;
+        let Let { span, name, params, where_decls, .. } = self;
         match symbol_table.get(name) {
             Some(_) => {
                 return Err(LoweringError::AlreadyDefined {
@@ -172,6 +176,25 @@ impl BuildSymbolTable for Let {
                 symbol_table.insert(name.clone(), meta);
             }
         }
-        Ok(())
+        build_where_decls(name, where_decls, symbol_table)
+    }
+}
+
+/// Register the declarations in the `where` clause of `parent` under the names `parent::name`
+fn build_where_decls(
+    parent: &Ident,
+    where_decls: &[Decl],
+    symbol_table: &mut ModuleSymbolTable,
+) -> Result<(), LoweringError> {
+    for decl in where_decls {
+        let mut decl = decl.clone();
+        let name = match &mut decl {
+            Decl::Def(Def { name, .. }) | Decl::Codef(Codef { name, .. }) => name,
+            Decl::Let(Let { name, .. }) => name,
+            Decl::Data(Data { name, .. }) | Decl::Codata(Codata { name, .. }) => name,
+        };
+        name.id = ast::where_decl_name(&parent.id, &name.id);
+        decl.build(symbol_table)?;
     }
+    Ok(())
 }
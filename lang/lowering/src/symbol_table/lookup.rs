@@ -4,7 +4,7 @@ use url::Url;
 
 use crate::LoweringError;
 
-use super::{DeclMeta, SymbolTable};
+use super::{module_name, DeclMeta, SymbolTable};
 
 impl SymbolTable {
     /// Check whether the identifier already exists in any of the symbol tables.
@@ -17,13 +17,142 @@ impl SymbolTable {
         false
     }
 
+    /// Look up an unqualified name
+    ///
+    /// Declarations of the current module take precedence over imported declarations.
+    /// If the name is declared in multiple imported modules, it is ambiguous.
     pub fn lookup(&self, name: &Ident) -> Result<(&DeclMeta, &Url), LoweringError> {
-        for (module_uri, symbol_table) in self.map.iter() {
-            match symbol_table.get(name) {
-                Some(meta) => return Ok((meta, module_uri)),
-                None => continue,
+        if let Some(current) = &self.current {
+            if let Some(meta) = self.map.get(current).and_then(|table| table.get(name)) {
+                return Ok((meta, current));
             }
         }
-        Err(LoweringError::UndefinedIdent { name: name.clone(), span: name.span.to_miette() })
+
+        let mut found: Vec<(&DeclMeta, &Url)> = self
+            .map
+            .iter()
+            .filter_map(|(uri, symbol_table)| symbol_table.get(name).map(|meta| (meta, uri)))
+            .collect();
+        match found.len() {
+            0 => {
+                let candidates = self.map.values().flat_map(|symbol_table| symbol_table.keys());
+                Err(LoweringError::UndefinedIdent {
+                    name: name.clone(),
+                    span: name.span.to_miette(),
+                    help: did_you_mean(&name.id, candidates.map(|ident| ident.id.as_str())),
+                })
+            }
+            1 => Ok(found.remove(0)),
+            _ => {
+                let mut modules: Vec<String> = found
+                    .iter()
+                    .map(|(_, uri)| {
+                        self.current
+                            .as_ref()
+                            .and_then(|current| current.make_relative(uri))
+                            .unwrap_or_else(|| uri.to_string())
+                    })
+                    .collect();
+                modules.sort();
+                Err(LoweringError::AmbiguousName {
+                    name: name.clone(),
+                    modules: modules.join(", "),
+                    span: name.span.to_miette(),
+                })
+            }
+        }
+    }
+
+    /// Whether `name` is the name of an imported module or the current module
+    pub fn is_module(&self, name: &Ident) -> bool {
+        self.map.keys().any(|uri| module_name(uri) == Some(name.id.as_str()))
+    }
+
+    /// Look up the qualified name `module.name`
+    pub fn lookup_qualified(
+        &self,
+        module: &Ident,
+        name: &Ident,
+    ) -> Result<(&DeclMeta, &Url), LoweringError> {
+        let mut found: Vec<(&DeclMeta, &Url)> = self
+            .map
+            .iter()
+            .filter(|(uri, _)| module_name(uri) == Some(module.id.as_str()))
+            .filter_map(|(uri, symbol_table)| symbol_table.get(name).map(|meta| (meta, uri)))
+            .collect();
+        match found.len() {
+            0 => {
+                let candidates = self
+                    .map
+                    .iter()
+                    .filter(|(uri, _)| module_name(uri) == Some(module.id.as_str()))
+                    .flat_map(|(_, symbol_table)| symbol_table.keys());
+                let help = did_you_mean(&name.id, candidates.map(|ident| ident.id.as_str()));
+                let qualified = Ident { span: name.span, id: format!("{}.{}", module.id, name.id) };
+                Err(LoweringError::UndefinedIdent {
+                    span: qualified.span.to_miette(),
+                    name: qualified,
+                    help,
+                })
+            }
+            1 => Ok(found.remove(0)),
+            _ => Err(LoweringError::AmbiguousModule {
+                name: module.id.clone(),
+                span: module.span.to_miette(),
+            }),
+        }
+    }
+}
+
+/// Suggest the candidate which is most similar to the undefined name `name`, if any
+///
+/// Only candidates within an edit distance of a third of the length of `name` are suggested,
+/// such that short names do not lead to arbitrary suggestions.
+fn did_you_mean<'a>(name: &str, candidates: impl Iterator<Item = &'a str>) -> Option<String> {
+    let max_distance = name.chars().count() / 3;
+    let (_, best) = candidates
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min()?;
+    Some(format!("Did you mean `{best}`?"))
+}
+
+/// The Levenshtein distance between `lhs` and `rhs`
+fn edit_distance(lhs: &str, rhs: &str) -> usize {
+    let rhs: Vec<char> = rhs.chars().collect();
+    // The distances between the prefix of `lhs` processed so far and all prefixes of `rhs`
+    let mut row: Vec<usize> = (0..=rhs.len()).collect();
+    for (i, l) in lhs.chars().enumerate() {
+        let mut diag = row[0];
+        row[0] = i + 1;
+        for (j, r) in rhs.iter().enumerate() {
+            let subst = diag + usize::from(l != *r);
+            diag = row[j + 1];
+            row[j + 1] = subst.min(row[j] + 1).min(diag + 1);
+        }
+    }
+    row[rhs.len()]
+}
+
+#[cfg(test)]
+mod did_you_mean_tests {
+    use super::*;
+
+    #[test]
+    fn edit_distances() {
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("Nat", "Nat"), 0);
+        assert_eq!(edit_distance("Nta", "Nat"), 2);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn suggest_closest_name() {
+        let candidates = ["Cons", "Nil", "concat", "reverse"];
+        let suggest = |name| did_you_mean(name, candidates.iter().copied());
+        assert_eq!(suggest("Cosn").as_deref(), None);
+        assert_eq!(suggest("Const").as_deref(), Some("Did you mean `Cons`?"));
+        assert_eq!(suggest("revrse").as_deref(), Some("Did you mean `reverse`?"));
+        assert_eq!(suggest("Ni").as_deref(), None);
     }
 }
@@ -17,21 +17,36 @@ pub type ModuleSymbolTable = HashMap<Ident, DeclMeta>;
 pub struct SymbolTable {
     // Maps modules to their respective symbol tables.
     map: HashMap<Url, Arc<ModuleSymbolTable>>,
+    // The module whose declarations take precedence over imported declarations.
+    current: Option<Url>,
 }
 
 impl SymbolTable {
     pub fn insert(&mut self, url: Url, other: Arc<ModuleSymbolTable>) {
         self.map.insert(url, other);
     }
+
+    /// Set the module whose declarations take precedence over imported declarations
+    pub fn set_current_module(&mut self, url: Url) {
+        self.current = Some(url);
+    }
+}
+
+/// The name by which the declarations of the module at `url` can be qualified
+///
+/// This is the name of the file without the `.pol` extension, e.g. `nat` for `std/data/nat.pol`.
+pub fn module_name(url: &Url) -> Option<&str> {
+    let file = url.path_segments()?.next_back()?;
+    Some(file.strip_suffix(".pol").unwrap_or(file))
 }
 
 #[derive(Clone, Debug)]
 pub enum DeclMeta {
-    Data { params: Telescope },
+    Data { params: Telescope, ctors: Vec<Ident> },
     Codata { params: Telescope },
     Def { params: Telescope },
     Codef { params: Telescope },
-    Ctor { params: Telescope },
+    Ctor { params: Telescope, data: Ident },
     Dtor { params: Telescope },
     Let { params: Telescope },
 }
@@ -1,5 +1,7 @@
 use tower_lsp::lsp_types::*;
 
+use super::semantic_tokens::legend;
+
 pub fn capabilities() -> ServerCapabilities {
     let text_document_sync = {
         let options = TextDocumentSyncOptions {
@@ -18,12 +20,47 @@ pub fn capabilities() -> ServerCapabilities {
 
     let definition_provider = Some(OneOf::Left(true));
 
+    let references_provider = Some(OneOf::Left(true));
+
+    let workspace_symbol_provider = Some(OneOf::Left(true));
+
+    let rename_provider = Some(OneOf::Left(true));
+
+    let folding_range_provider = Some(FoldingRangeProviderCapability::Simple(true));
+
+    let inlay_hint_provider = Some(OneOf::Left(true));
+
+    let completion_provider = Some(CompletionOptions {
+        trigger_characters: Some(vec![".".to_owned()]),
+        ..Default::default()
+    });
+
+    let signature_help_provider = Some(SignatureHelpOptions {
+        trigger_characters: Some(vec!["(".to_owned(), ",".to_owned()]),
+        ..Default::default()
+    });
+
+    let semantic_tokens_provider =
+        Some(SemanticTokensServerCapabilities::SemanticTokensOptions(SemanticTokensOptions {
+            legend: legend(),
+            full: Some(SemanticTokensFullOptions::Bool(true)),
+            ..Default::default()
+        }));
+
     ServerCapabilities {
         text_document_sync,
         hover_provider,
         code_action_provider,
         document_formatting_provider,
         definition_provider,
+        references_provider,
+        workspace_symbol_provider,
+        rename_provider,
+        folding_range_provider,
+        completion_provider,
+        signature_help_provider,
+        inlay_hint_provider,
+        semantic_tokens_provider,
         ..Default::default()
     }
 }
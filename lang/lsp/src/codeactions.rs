@@ -2,7 +2,7 @@
 use std::collections::HashMap;
 use tower_lsp::{jsonrpc, lsp_types::*};
 
-use driver::Xfunc;
+use driver::{Database, Edit, Extraction, FillHole, MatchCompletion, Xfunc};
 
 use super::conversion::*;
 use super::server::*;
@@ -32,36 +32,90 @@ pub async fn code_action(
         None
     };
 
+    let mut res = vec![];
+
     if let Some(item) = item {
-        let Ok(Xfunc { title, edits }) =
-            db.xfunc(&text_document.uri.from_lsp(), item.type_name()).await
-        else {
-            return Ok(None);
-        };
-        let edits = edits
-            .into_iter()
-            .map(|edit| TextEdit {
-                range: db
-                    .span_to_locations(&text_document.uri.from_lsp(), edit.span)
-                    .unwrap()
-                    .to_lsp(),
-                new_text: edit.text,
-            })
-            .collect();
+        if let Ok(Xfunc { title, edits }) =
+            db.xfunc(&text_document.uri.from_lsp(), item.type_name(), None).await
+        {
+            res.push(code_action_with_edits(
+                &db,
+                &text_document.uri,
+                title,
+                CodeActionKind::REFACTOR_REWRITE,
+                edits,
+            ));
+        }
+    }
 
-        #[allow(clippy::mutable_key_type)]
-        let mut changes = HashMap::new();
-        changes.insert(text_document.uri, edits);
+    if let Some(span) = span.filter(|span| span.start() != span.end()) {
+        if let Ok(Some(Extraction { title, edits })) =
+            db.selection_to_def(&text_document.uri.from_lsp(), span).await
+        {
+            res.push(code_action_with_edits(
+                &db,
+                &text_document.uri,
+                title,
+                CodeActionKind::REFACTOR_EXTRACT,
+                edits,
+            ));
+        }
+    }
 
-        let res = vec![CodeActionOrCommand::CodeAction(CodeAction {
-            title,
-            kind: Some(CodeActionKind::REFACTOR_REWRITE),
-            edit: Some(WorkspaceEdit { changes: Some(changes), ..Default::default() }),
-            ..Default::default()
-        })];
+    if let Some(span) = span {
+        if let Ok(fills) = db.fill_hole(&text_document.uri.from_lsp(), span.start()).await {
+            for FillHole { title, edits } in fills {
+                res.push(code_action_with_edits(
+                    &db,
+                    &text_document.uri,
+                    title,
+                    CodeActionKind::QUICKFIX,
+                    edits,
+                ));
+            }
+        }
+    }
 
-        Ok(Some(res))
-    } else {
-        Ok(Some(vec![]))
+    if let Some(span) = span {
+        if let Ok(Some(MatchCompletion { title, edits })) =
+            db.complete_match(&text_document.uri.from_lsp(), span.start()).await
+        {
+            res.push(code_action_with_edits(
+                &db,
+                &text_document.uri,
+                title,
+                CodeActionKind::QUICKFIX,
+                edits,
+            ));
+        }
     }
+
+    Ok(Some(res))
+}
+
+fn code_action_with_edits(
+    db: &Database,
+    uri: &Uri,
+    title: String,
+    kind: CodeActionKind,
+    edits: Vec<Edit>,
+) -> CodeActionOrCommand {
+    let edits = edits
+        .into_iter()
+        .map(|edit| TextEdit {
+            range: db.span_to_locations(&uri.from_lsp(), edit.span).unwrap().to_lsp(),
+            new_text: edit.text,
+        })
+        .collect();
+
+    #[allow(clippy::mutable_key_type)]
+    let mut changes = HashMap::new();
+    changes.insert(uri.clone(), edits);
+
+    CodeActionOrCommand::CodeAction(CodeAction {
+        title,
+        kind: Some(kind),
+        edit: Some(WorkspaceEdit { changes: Some(changes), ..Default::default() }),
+        ..Default::default()
+    })
 }
@@ -0,0 +1,66 @@
+//! Implementation of the completion functionality of the LSP server
+
+use driver::*;
+use tower_lsp::{jsonrpc, lsp_types::*};
+
+use super::conversion::*;
+use super::server::*;
+
+pub async fn completion(
+    server: &Server,
+    params: CompletionParams,
+) -> jsonrpc::Result<Option<CompletionResponse>> {
+    let pos_params = params.text_document_position;
+    let text_document = pos_params.text_document;
+
+    server
+        .client
+        .log_message(
+            MessageType::INFO,
+            format!("Completion request: {}", text_document.uri.from_lsp()),
+        )
+        .await;
+
+    let pos = pos_params.position;
+    let mut db = server.database.write().await;
+    let uri = text_document.uri.from_lsp();
+    let Some(idx) = db.location_to_index(&uri, pos.from_lsp()) else {
+        return Ok(None);
+    };
+
+    let completions = db.completion(&uri, idx).await;
+    // The completions are ranked, so we keep their order
+    let items = completions
+        .into_iter()
+        .enumerate()
+        .map(|(rank, completion)| CompletionItem {
+            sort_text: Some(format!("{rank:05}")),
+            ..to_completion_item(completion)
+        })
+        .collect();
+    Ok(Some(CompletionResponse::Array(items)))
+}
+
+fn to_completion_item(completion: Completion) -> CompletionItem {
+    let Completion { label, detail, doc, kind } = completion;
+    let documentation = doc.map(|doc| {
+        Documentation::MarkupContent(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: doc.join("\n"),
+        })
+    });
+    CompletionItem {
+        label,
+        kind: Some(match kind {
+            CompletionKind::TypeCtor => CompletionItemKind::CLASS,
+            CompletionKind::Ctor => CompletionItemKind::CONSTRUCTOR,
+            CompletionKind::Dtor => CompletionItemKind::METHOD,
+            CompletionKind::Def | CompletionKind::Codef | CompletionKind::Let => {
+                CompletionItemKind::FUNCTION
+            }
+        }),
+        detail: Some(detail),
+        documentation,
+        ..Default::default()
+    }
+}
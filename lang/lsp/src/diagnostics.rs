@@ -5,7 +5,10 @@ use tower_lsp::lsp_types;
 use url::Url;
 
 use driver::Database;
+use driver::DriverError;
 use driver::Error;
+use driver::ModuleDiagnostic;
+use driver::Warning;
 use miette_util::FromMiette;
 
 use crate::conversion::ToLsp;
@@ -23,38 +26,84 @@ pub trait Diagnostics {
 
 impl Diagnostics for Database {
     fn error_diagnostics(&self, uri: &Url, error: Error) -> Vec<lsp_types::Diagnostic> {
-        // Compute the range where the error should be displayed.
-        // The range is computed from the first available label, otherwise
-        // the default range is used, which corresponds to the beginning of the
-        // file.
-        let span = get_span(&error);
-        let range = span
-            .and_then(|x| self.span_to_locations(uri, x.from_miette()))
-            .map(ToLsp::to_lsp)
-            .unwrap_or_default();
+        // The `use` declarations of an import cycle are located in different modules
+        if let Error::Driver(DriverError::ImportCycle { imports, .. }) = &error {
+            let mut diag = diagnostic(self, uri, &error);
+            if let Some(import) = imports.iter().find(|import| import.uri == *uri) {
+                diag.range = self
+                    .span_to_locations(uri, import.span.from_miette())
+                    .map(ToLsp::to_lsp)
+                    .unwrap_or_default();
+            }
+            return vec![diag];
+        }
+        // Multiple syntax errors are reported as related diagnostics of the first one
+        let related = error.related().into_iter().flatten();
+        std::iter::once(&error as &dyn Diagnostic)
+            .chain(related)
+            .map(|diag| diagnostic(self, uri, diag))
+            .collect()
+    }
+}
+
+/// The errors and warnings for the module at `uri`
+///
+/// All declarations of the module are checked, so an error in one declaration does not hide
+/// the errors in the others.
+pub async fn module_diagnostics(db: &mut Database, uri: &Url) -> Vec<lsp_types::Diagnostic> {
+    let diagnostics = db.check_all(uri).await;
+    diagnostics
+        .into_iter()
+        .flat_map(|diag| match diag {
+            ModuleDiagnostic::Error(err) => db.error_diagnostics(uri, err),
+            ModuleDiagnostic::Warning(warning) => warning_diagnostics(db, uri, &warning),
+        })
+        .collect()
+}
+
+fn warning_diagnostics(db: &Database, uri: &Url, warning: &Warning) -> Vec<lsp_types::Diagnostic> {
+    // The shadowed bindings of a variable are reported as related diagnostics
+    let related = warning.related().into_iter().flatten();
+    std::iter::once(warning as &dyn Diagnostic)
+        .chain(related)
+        .map(|diag| diagnostic(db, uri, diag))
+        .collect()
+}
+
+fn diagnostic(db: &Database, uri: &Url, error: &dyn Diagnostic) -> lsp_types::Diagnostic {
+    // Compute the range where the error should be displayed.
+    // The range is computed from the first available label, otherwise
+    // the default range is used, which corresponds to the beginning of the
+    // file.
+    let span = get_span(error);
+    let range = span
+        .and_then(|x| db.span_to_locations(uri, x.from_miette()))
+        .map(ToLsp::to_lsp)
+        .unwrap_or_default();
 
-        // Compute the message.
-        let message = error.to_string();
+    // Compute the message, followed by the help text if there is one.
+    let message = match error.help() {
+        Some(help) => format!("{error}\nhelp: {help}"),
+        None => error.to_string(),
+    };
 
-        let diag = lsp_types::Diagnostic {
-            range,
-            message,
-            severity: match error.severity() {
-                Some(sev) => Some(sev.to_lsp()),
-                None => Some(lsp_types::DiagnosticSeverity::ERROR),
-            },
-            code: error.code().map(|x| NumberOrString::String(format!("{x}"))),
-            code_description: None,
-            source: None,
-            related_information: None,
-            tags: None,
-            data: None,
-        };
-        vec![diag]
+    lsp_types::Diagnostic {
+        range,
+        message,
+        severity: match error.severity() {
+            Some(sev) => Some(sev.to_lsp()),
+            None => Some(lsp_types::DiagnosticSeverity::ERROR),
+        },
+        code: error.code().map(|x| NumberOrString::String(format!("{x}"))),
+        code_description: None,
+        source: None,
+        related_information: None,
+        tags: None,
+        data: None,
     }
 }
 
-fn get_span<T: Diagnostic>(err: &T) -> Option<SourceSpan> {
+fn get_span(err: &dyn Diagnostic) -> Option<SourceSpan> {
     match err.labels() {
         Some(spans) => {
             let x = spans.into_iter().last();
@@ -0,0 +1,41 @@
+//! Implementation of the folding ranges of the LSP server
+
+use tower_lsp::{jsonrpc, lsp_types::*};
+
+use super::conversion::*;
+use super::server::*;
+
+pub async fn folding_range(
+    server: &Server,
+    params: FoldingRangeParams,
+) -> jsonrpc::Result<Option<Vec<FoldingRange>>> {
+    let text_document = params.text_document;
+
+    server
+        .client
+        .log_message(
+            MessageType::INFO,
+            format!("Folding range request: {}", text_document.uri.from_lsp()),
+        )
+        .await;
+
+    let mut db = server.database.write().await;
+    let uri = text_document.uri.from_lsp();
+    let spans = db.folding_ranges(&uri).await;
+    let ranges = spans
+        .into_iter()
+        .filter_map(|span| {
+            let (start, end) = db.span_to_locations(&uri, span)?;
+            // Ranges within a single line cannot be folded
+            (start.line < end.line).then(|| FoldingRange {
+                start_line: start.line.to_usize() as u32,
+                start_character: None,
+                end_line: end.line.to_usize() as u32,
+                end_character: None,
+                kind: Some(FoldingRangeKind::Region),
+                collapsed_text: None,
+            })
+        })
+        .collect();
+    Ok(Some(ranges))
+}
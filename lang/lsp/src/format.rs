@@ -1,11 +1,11 @@
 //! Implementation of the formatting functionality of the LSP server
+use printer::PrintCfg;
 use tower_lsp::jsonrpc::Result;
 use tower_lsp::lsp_types::*;
 
 use crate::conversion::FromLsp;
 
 use super::server::*;
-use printer::{Print, PrintCfg};
 
 pub async fn formatting(
     server: &Server,
@@ -23,8 +23,10 @@ pub async fn formatting(
 
     let mut db = server.database.write().await;
 
-    let prg = match db.ust(&text_document.uri.from_lsp()).await {
-        Ok(prg) => prg,
+    let cfg = print_cfg(&params.options);
+    let formatted_prog = match db.format(&text_document.uri.from_lsp(), Some(&cfg)).await {
+        Ok(formatted_prog) => formatted_prog,
+        // Leave work in progress untouched if the buffer cannot be parsed
         Err(_) => return Ok(None),
     };
 
@@ -33,20 +35,19 @@ pub async fn formatting(
         end: Position { line: u32::MAX, character: u32::MAX },
     };
 
-    let cfg = PrintCfg {
-        width: 100,
-        latex: false,
-        omit_decl_sep: false,
-        de_bruijn: false,
-        indent: 4,
-        print_lambda_sugar: true,
-        print_function_sugar: true,
-        print_metavar_ids: false,
-    };
-
-    let formatted_prog: String = prg.print_to_string(Some(&cfg));
-
     let text_edit: TextEdit = TextEdit { range: rng, new_text: formatted_prog };
 
     Ok(Some(vec![text_edit]))
 }
+
+/// The printer configuration requested by the client
+///
+/// The indentation follows the tab size of the client.
+/// Clients can request a line width via the formatting property `lineWidth`.
+fn print_cfg(options: &FormattingOptions) -> PrintCfg {
+    let mut cfg = PrintCfg { indent: options.tab_size as isize, ..Default::default() };
+    if let Some(FormattingProperty::Number(width)) = options.properties.get("lineWidth") {
+        cfg.width = (*width).max(1) as usize;
+    }
+    cfg
+}
@@ -51,11 +51,8 @@ trait ToJumpTarget {
 impl ToJumpTarget for InfoContent {
     fn to_jump_target(&self) -> Option<(Uri, Span)> {
         match self {
-            InfoContent::TypeCtorInfo(i) => i.to_jump_target(),
-            InfoContent::CallInfo(i) => i.to_jump_target(),
-            InfoContent::DotCallInfo(i) => i.to_jump_target(),
             InfoContent::UseInfo(i) => i.to_jump_target(),
-            _ => None,
+            _ => self.definition_site().map(|(url, span)| (url.to_lsp(), *span)),
         }
     }
 }
@@ -65,24 +62,3 @@ impl ToJumpTarget for UseInfo {
         Some((self.uri.to_lsp(), Span::default()))
     }
 }
-
-impl ToJumpTarget for TypeCtorInfo {
-    fn to_jump_target(&self) -> Option<(Uri, Span)> {
-        let TypeCtorInfo { definition_site, .. } = self;
-        definition_site.clone().map(|(url, span)| (url.to_lsp(), span))
-    }
-}
-
-impl ToJumpTarget for CallInfo {
-    fn to_jump_target(&self) -> Option<(Uri, Span)> {
-        let CallInfo { definition_site, .. } = self;
-        definition_site.clone().map(|(url, span)| (url.to_lsp(), span))
-    }
-}
-
-impl ToJumpTarget for DotCallInfo {
-    fn to_jump_target(&self) -> Option<(Uri, Span)> {
-        let DotCallInfo { definition_site, .. } = self;
-        definition_site.clone().map(|(url, span)| (url.to_lsp(), span))
-    }
-}
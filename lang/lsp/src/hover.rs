@@ -39,14 +39,16 @@ fn info_to_hover(db: &Database, uri: &Uri, info: Info) -> Hover {
 
 fn ctx_to_markdown(ctx: &Ctx, value: &mut String) {
     value.push_str("**Context**\n\n");
-    value.push_str("| | |\n");
-    value.push_str("|-|-|\n");
-    for Binder { name, typ } in ctx.bound.iter().rev().flatten() {
+    value.push_str("| | | |\n");
+    value.push_str("|-|-|-|\n");
+    for Binder { name, lvl, typ } in ctx.bound.iter().rev().flatten() {
         if name == "_" {
             continue;
         }
         value.push_str("| ");
         value.push_str(name);
+        value.push_str(" | ");
+        value.push_str(&lvl.to_string());
         value.push_str(" | `");
         value.push_str(typ);
         value.push_str("` |\n");
@@ -55,24 +57,36 @@ fn ctx_to_markdown(ctx: &Ctx, value: &mut String) {
 
 fn goal_to_markdown(goal_type: &str, value: &mut String) {
     value.push_str("**Goal**\n\n");
-    value.push_str("```\n");
-    value.push_str(goal_type);
-    value.push_str("\n```\n");
+    code_block(goal_type, value);
 }
 
-fn string_to_language_string(s: String) -> MarkedString {
-    MarkedString::LanguageString(LanguageString { language: "pol".to_owned(), value: s })
+/// The language id under which the client registers polarity source files
+const LANGUAGE_ID: &str = "polarity";
+
+/// Render source code as a fenced code block so that clients can syntax-highlight it
+fn code_block(code: &str, value: &mut String) {
+    value.push_str("```");
+    value.push_str(LANGUAGE_ID);
+    value.push('\n');
+    value.push_str(code);
+    value.push_str("\n```\n");
 }
 
-fn add_doc_comment(builder: &mut Vec<MarkedString>, doc: Option<Vec<String>>) {
+/// Doc comments are rendered as prose between the heading and the type of the declaration
+fn add_doc_comment(value: &mut String, doc: Option<Vec<String>>) {
     if let Some(doc) = doc {
-        builder.push(MarkedString::String("---".to_owned()));
+        value.push('\n');
         for d in doc {
-            builder.push(MarkedString::String(d))
+            value.push_str(&d);
+            value.push('\n');
         }
     }
 }
 
+fn markdown(value: String) -> HoverContents {
+    HoverContents::Markup(MarkupContent { kind: MarkupKind::Markdown, value })
+}
+
 // Transforming HoverContent to the correct LSP library type.
 //
 //
@@ -111,100 +125,103 @@ impl ToHoverContent for InfoContent {
 
 impl ToHoverContent for VariableInfo {
     fn to_hover_content(self) -> HoverContents {
-        let VariableInfo { typ, name } = self;
-        let header = MarkedString::String(format!("Bound variable: `{}`", name));
-        let typ = string_to_language_string(typ);
-        HoverContents::Array(vec![header, typ])
+        let VariableInfo { typ, name, .. } = self;
+        let mut value = format!("Bound variable: `{}`\n\n", name);
+        code_block(&typ, &mut value);
+        markdown(value)
     }
 }
 
 impl ToHoverContent for TypeCtorInfo {
     fn to_hover_content(self) -> HoverContents {
         let TypeCtorInfo { name, doc, .. } = self;
-        let mut content: Vec<MarkedString> = Vec::new();
-        content.push(MarkedString::String(format!("Type constructor: `{}`", name)));
-        add_doc_comment(&mut content, doc);
-        HoverContents::Array(content)
+        let mut value = format!("Type constructor: `{}`\n", name);
+        add_doc_comment(&mut value, doc);
+        markdown(value)
     }
 }
 
 impl ToHoverContent for CallInfo {
     fn to_hover_content(self) -> HoverContents {
         let CallInfo { kind, typ, name, doc, .. } = self;
-        let mut content: Vec<MarkedString> = Vec::new();
-        content.push(match kind {
-            CallKind::Constructor => MarkedString::String(format!("Constructor: `{}`", name)),
-            CallKind::Codefinition => MarkedString::String(format!("Codefinition: `{}`", name)),
-            CallKind::LetBound => MarkedString::String(format!("Let-bound definition: `{}`", name)),
-        });
-        add_doc_comment(&mut content, doc);
-        content.push(MarkedString::String("---".to_owned()));
-        content.push(string_to_language_string(typ));
-        HoverContents::Array(content)
+        let mut value = match kind {
+            CallKind::Constructor => format!("Constructor: `{}`\n", name),
+            CallKind::Codefinition => format!("Codefinition: `{}`\n", name),
+            CallKind::LetBound => format!("Let-bound definition: `{}`\n", name),
+        };
+        add_doc_comment(&mut value, doc);
+        value.push('\n');
+        code_block(&typ, &mut value);
+        markdown(value)
     }
 }
 
 impl ToHoverContent for DotCallInfo {
     fn to_hover_content(self) -> HoverContents {
-        let DotCallInfo { kind, name, typ, doc, .. } = self;
-        let mut content: Vec<MarkedString> = Vec::new();
-        content.push(match kind {
-            DotCallKind::Destructor => MarkedString::String(format!("Destructor: `{}`", name)),
-            DotCallKind::Definition => MarkedString::String(format!("Definition: `{}`", name)),
-        });
-        add_doc_comment(&mut content, doc);
-        content.push(MarkedString::String("---".to_owned()));
-        content.push(string_to_language_string(typ));
-        HoverContents::Array(content)
+        let DotCallInfo { kind, name, typ, doc, recursive_call, .. } = self;
+        let mut value = match kind {
+            DotCallKind::Destructor => format!("Destructor: `{}`\n", name),
+            DotCallKind::Definition => format!("Definition: `{}`\n", name),
+        };
+        add_doc_comment(&mut value, doc);
+        value.push('\n');
+        code_block(&typ, &mut value);
+        if let Some(call) = recursive_call {
+            // The evidence of the termination check for this recursive call
+            value.push_str("\n---\n\n");
+            match call.decreasing_on {
+                Some(var) => {
+                    value.push_str(&format!("Recursive call: decreasing on argument `{var}`\n"))
+                }
+                None => value.push_str("Recursive call: no decrease found\n"),
+            }
+        }
+        markdown(value)
     }
 }
 
 impl ToHoverContent for TypeUnivInfo {
     fn to_hover_content(self) -> HoverContents {
-        let content: Vec<MarkedString> = vec![
-            MarkedString::String("Universe: `Type`".to_owned()),
-            MarkedString::String("---".to_owned()),
-            MarkedString::String(
-                "The impredicative universe whose terms are types or the universe `Type` itself."
-                    .to_owned(),
-            ),
-        ];
-        HoverContents::Array(content)
+        let mut value = "Universe: `Type`\n\n---\n\n".to_owned();
+        value.push_str(
+            "The impredicative universe whose terms are types or the universe `Type` itself.\n",
+        );
+        markdown(value)
     }
 }
 
 impl ToHoverContent for AnnoInfo {
     fn to_hover_content(self) -> HoverContents {
         let AnnoInfo { typ } = self;
-        let header = MarkedString::String("Annotated term".to_owned());
-        let typ = string_to_language_string(typ);
-        HoverContents::Array(vec![header, typ])
+        let mut value = "Annotated term\n\n".to_owned();
+        code_block(&typ, &mut value);
+        markdown(value)
     }
 }
 
 impl ToHoverContent for LocalMatchInfo {
     fn to_hover_content(self) -> HoverContents {
-        let LocalMatchInfo { typ } = self;
-        let header = MarkedString::String("Local match".to_owned());
-        let typ = string_to_language_string(typ);
-        HoverContents::Array(vec![header, typ])
+        let LocalMatchInfo { typ, cases: _ } = self;
+        let mut value = "Local match\n\n".to_owned();
+        code_block(&typ, &mut value);
+        markdown(value)
     }
 }
 
 impl ToHoverContent for LocalComatchInfo {
     fn to_hover_content(self) -> HoverContents {
         let LocalComatchInfo { typ } = self;
-        let header = MarkedString::String("Local comatch".to_owned());
-        let typ = string_to_language_string(typ);
-        HoverContents::Array(vec![header, typ])
+        let mut value = "Local comatch\n\n".to_owned();
+        code_block(&typ, &mut value);
+        markdown(value)
     }
 }
 
 impl ToHoverContent for HoleInfo {
     fn to_hover_content(self) -> HoverContents {
-        let HoleInfo { metavar, goal, ctx, args, metavar_state } = self;
+        let HoleInfo { metavar, goal, ctx, args, metavar_state, hole: _ } = self;
+        let mut value = String::new();
         if let Some(ctx) = ctx {
-            let mut value = String::new();
             match metavar {
                 Some(mv) => value.push_str(&format!("Hole: `{}`\n\n", mv)),
                 None => value.push_str("Hole: `?`\n\n"),
@@ -215,15 +232,15 @@ impl ToHoverContent for HoleInfo {
             value.push_str("\n\nArguments:\n\n");
             let args_str = args.iter().cloned().map(comma_separated).map(|s| format!("({})", s));
             let args_str = format!("({})", comma_separated(args_str));
-            value.push_str(&args_str);
+            code_block(&args_str, &mut value);
             if let Some(solution) = metavar_state {
                 value.push_str("\n\nSolution:\n\n");
-                value.push_str(&solution);
+                code_block(&solution, &mut value);
             }
-            HoverContents::Markup(MarkupContent { kind: MarkupKind::Markdown, value })
         } else {
-            HoverContents::Scalar(string_to_language_string(goal))
+            code_block(&goal, &mut value);
         }
+        markdown(value)
     }
 }
 
@@ -234,69 +251,73 @@ impl ToHoverContent for HoleInfo {
 impl ToHoverContent for DataInfo {
     fn to_hover_content(self) -> HoverContents {
         let DataInfo { name, doc, params } = self;
-        let mut content: Vec<MarkedString> = Vec::new();
-        content.push(MarkedString::String(format!("Data declaration: `{name}`")));
-        add_doc_comment(&mut content, doc);
+        let mut value = format!("Data declaration: `{name}`\n");
+        add_doc_comment(&mut value, doc);
         if !params.is_empty() {
-            content.push(MarkedString::String("---".to_owned()).to_owned());
-            content.push(MarkedString::String(format!("Parameters: `{}`", params)))
+            value.push_str("\nParameters:\n\n");
+            code_block(&params, &mut value);
         }
-        HoverContents::Array(content)
+        markdown(value)
     }
 }
 
 impl ToHoverContent for CtorInfo {
     fn to_hover_content(self) -> HoverContents {
         let CtorInfo { name, doc } = self;
-        let mut content: Vec<MarkedString> = Vec::new();
-        content.push(MarkedString::String(format!("Constructor: `{}`", name)));
-        add_doc_comment(&mut content, doc);
-        HoverContents::Array(content)
+        let mut value = format!("Constructor: `{}`\n", name);
+        add_doc_comment(&mut value, doc);
+        markdown(value)
     }
 }
 
 impl ToHoverContent for CodataInfo {
     fn to_hover_content(self) -> HoverContents {
         let CodataInfo { name, doc, params } = self;
-        let mut content: Vec<MarkedString> = Vec::new();
-        content.push(MarkedString::String(format!("Codata declaration: `{}`", name)));
-        add_doc_comment(&mut content, doc);
+        let mut value = format!("Codata declaration: `{}`\n", name);
+        add_doc_comment(&mut value, doc);
         if !params.is_empty() {
-            content.push(MarkedString::String("---".to_owned()).to_owned());
-            content.push(MarkedString::String(format!("Parameters: `{}`", params)))
+            value.push_str("\nParameters:\n\n");
+            code_block(&params, &mut value);
         }
-        HoverContents::Array(content)
+        markdown(value)
     }
 }
 
 impl ToHoverContent for DtorInfo {
     fn to_hover_content(self) -> HoverContents {
         let DtorInfo { name, doc } = self;
-        let mut content: Vec<MarkedString> = Vec::new();
-        content.push(MarkedString::String(format!("Destructor: `{}`", name)));
-        add_doc_comment(&mut content, doc);
-        HoverContents::Array(content)
+        let mut value = format!("Destructor: `{}`\n", name);
+        add_doc_comment(&mut value, doc);
+        markdown(value)
     }
 }
 
 impl ToHoverContent for DefInfo {
     fn to_hover_content(self) -> HoverContents {
-        let header = MarkedString::String("Definition".to_owned());
-        HoverContents::Scalar(header)
+        let DefInfo { name, doc, cases: _ } = self;
+        let mut value = format!("Definition: `{}`\n", name);
+        add_doc_comment(&mut value, doc);
+        markdown(value)
     }
 }
 
 impl ToHoverContent for CodefInfo {
     fn to_hover_content(self) -> HoverContents {
-        let header = MarkedString::String("Codefinition".to_owned());
-        HoverContents::Scalar(header)
+        let CodefInfo { name, doc } = self;
+        let mut value = format!("Codefinition: `{}`\n", name);
+        add_doc_comment(&mut value, doc);
+        markdown(value)
     }
 }
 
 impl ToHoverContent for LetInfo {
     fn to_hover_content(self) -> HoverContents {
-        let header = MarkedString::String("Let-binding".to_owned());
-        HoverContents::Scalar(header)
+        let LetInfo { name, doc, typ } = self;
+        let mut value = format!("Let-binding: `{}`\n", name);
+        add_doc_comment(&mut value, doc);
+        value.push('\n');
+        code_block(&typ, &mut value);
+        markdown(value)
     }
 }
 
@@ -307,8 +328,7 @@ impl ToHoverContent for LetInfo {
 impl ToHoverContent for UseInfo {
     fn to_hover_content(self) -> HoverContents {
         let UseInfo { path, .. } = self;
-        let content = MarkedString::String(format!("Import module `{}`", path));
-        HoverContents::Scalar(content)
+        markdown(format!("Import module `{}`", path))
     }
 }
 
@@ -0,0 +1,50 @@
+//! Implementation of the inlay hints functionality of the LSP server
+
+use tower_lsp::{jsonrpc, lsp_types::*};
+
+use super::conversion::*;
+use super::server::*;
+
+pub async fn inlay_hint(
+    server: &Server,
+    params: InlayHintParams,
+) -> jsonrpc::Result<Option<Vec<InlayHint>>> {
+    let text_document = params.text_document;
+
+    server
+        .client
+        .log_message(
+            MessageType::INFO,
+            format!("Inlay hint request: {}", text_document.uri.from_lsp()),
+        )
+        .await;
+
+    let mut db = server.database.write().await;
+    let uri = text_document.uri.from_lsp();
+    let Some(start) = db.location_to_index(&uri, params.range.start.from_lsp()) else {
+        return Ok(None);
+    };
+    // The visible range may extend beyond the end of the document
+    let end = db
+        .location_to_index(&uri, params.range.end.from_lsp())
+        .unwrap_or(codespan::ByteIndex(u32::MAX));
+
+    let hints = db.inlay_hints(&uri, codespan::Span::new(start, end)).await;
+    let hints = hints
+        .into_iter()
+        .filter_map(|driver::InlayHint { idx, label }| {
+            let position = db.index_to_location(&uri, idx)?.to_lsp();
+            Some(InlayHint {
+                position,
+                label: InlayHintLabel::String(format!(": {label}")),
+                kind: Some(InlayHintKind::TYPE),
+                text_edits: None,
+                tooltip: None,
+                padding_left: None,
+                padding_right: None,
+                data: None,
+            })
+        })
+        .collect();
+    Ok(Some(hints))
+}
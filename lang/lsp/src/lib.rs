@@ -1,10 +1,18 @@
 mod capabilities;
 mod codeactions;
+mod completion;
 mod conversion;
 mod diagnostics;
+mod folding;
 mod format;
 mod gotodefinition;
 mod hover;
+mod inlay_hints;
+mod references;
+mod rename;
+mod semantic_tokens;
 mod server;
+mod signature_help;
+mod symbols;
 
 pub use server::*;
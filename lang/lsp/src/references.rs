@@ -0,0 +1,39 @@
+//! Implementation of the find-references functionality of the LSP server
+
+use tower_lsp::{jsonrpc, lsp_types::*};
+
+use super::conversion::*;
+use super::server::*;
+
+pub async fn references(
+    server: &Server,
+    params: ReferenceParams,
+) -> jsonrpc::Result<Option<Vec<Location>>> {
+    let pos_params = params.text_document_position;
+    let text_document = pos_params.text_document;
+
+    server
+        .client
+        .log_message(
+            MessageType::INFO,
+            format!("References request: {}", text_document.uri.from_lsp()),
+        )
+        .await;
+
+    let pos = pos_params.position;
+    let mut db = server.database.write().await;
+    let uri = text_document.uri.from_lsp();
+    let Some(idx) = db.location_to_index(&uri, pos.from_lsp()) else {
+        return Ok(None);
+    };
+
+    let spans = db.references(&uri, idx, params.context.include_declaration).await;
+    let locations = spans
+        .into_iter()
+        .filter_map(|span| {
+            let range = db.span_to_locations(&uri, span).map(ToLsp::to_lsp)?;
+            Some(Location { uri: text_document.uri.clone(), range })
+        })
+        .collect();
+    Ok(Some(locations))
+}
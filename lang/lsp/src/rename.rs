@@ -0,0 +1,50 @@
+//! Implementation of the rename refactoring of the LSP server
+
+use std::collections::HashMap;
+
+use tower_lsp::{jsonrpc, lsp_types::*};
+
+use super::conversion::*;
+use super::server::*;
+
+pub async fn rename(
+    server: &Server,
+    params: RenameParams,
+) -> jsonrpc::Result<Option<WorkspaceEdit>> {
+    let pos_params = params.text_document_position;
+    let text_document = pos_params.text_document;
+
+    server
+        .client
+        .log_message(MessageType::INFO, format!("Rename request: {}", text_document.uri.from_lsp()))
+        .await;
+
+    let pos = pos_params.position;
+    let mut db = server.database.write().await;
+    let uri = text_document.uri.from_lsp();
+    let Some(idx) = db.location_to_index(&uri, pos.from_lsp()) else {
+        return Ok(None);
+    };
+
+    let renaming = db
+        .rename(&uri, idx, &params.new_name)
+        .await
+        .map_err(|err| jsonrpc::Error::invalid_params(err.to_string()))?;
+    let Some(renaming) = renaming else {
+        return Ok(None);
+    };
+
+    #[allow(clippy::mutable_key_type)]
+    let mut changes = HashMap::new();
+    for (uri, edits) in renaming {
+        let edits = edits
+            .into_iter()
+            .filter_map(|edit| {
+                let range = db.span_to_locations(&uri, edit.span).map(ToLsp::to_lsp)?;
+                Some(TextEdit { range, new_text: edit.text })
+            })
+            .collect();
+        changes.insert(uri.to_lsp(), edits);
+    }
+    Ok(Some(WorkspaceEdit { changes: Some(changes), ..Default::default() }))
+}
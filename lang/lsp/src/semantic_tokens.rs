@@ -0,0 +1,83 @@
+//! Implementation of the semantic tokens functionality of the LSP server
+
+use tower_lsp::{jsonrpc, lsp_types::*};
+
+use driver::SemanticTokenKind;
+
+use super::conversion::*;
+use super::server::*;
+
+/// The token types in the order of their indices in the legend
+const TOKEN_TYPES: [SemanticTokenKind; 6] = [
+    SemanticTokenKind::Keyword,
+    SemanticTokenKind::TypeCtor,
+    SemanticTokenKind::Ctor,
+    SemanticTokenKind::Dtor,
+    SemanticTokenKind::Variable,
+    SemanticTokenKind::Hole,
+];
+
+pub fn legend() -> SemanticTokensLegend {
+    SemanticTokensLegend {
+        token_types: TOKEN_TYPES.iter().map(|kind| token_type(*kind)).collect(),
+        token_modifiers: vec![],
+    }
+}
+
+fn token_type(kind: SemanticTokenKind) -> SemanticTokenType {
+    match kind {
+        SemanticTokenKind::Keyword => SemanticTokenType::KEYWORD,
+        SemanticTokenKind::TypeCtor => SemanticTokenType::TYPE,
+        SemanticTokenKind::Ctor => SemanticTokenType::ENUM_MEMBER,
+        SemanticTokenKind::Dtor => SemanticTokenType::METHOD,
+        SemanticTokenKind::Variable => SemanticTokenType::VARIABLE,
+        SemanticTokenKind::Hole => SemanticTokenType::new("hole"),
+    }
+}
+
+pub async fn semantic_tokens_full(
+    server: &Server,
+    params: SemanticTokensParams,
+) -> jsonrpc::Result<Option<SemanticTokensResult>> {
+    let text_document = params.text_document;
+
+    server
+        .client
+        .log_message(
+            MessageType::INFO,
+            format!("Semantic tokens request: {}", text_document.uri.from_lsp()),
+        )
+        .await;
+
+    let mut db = server.database.write().await;
+    let uri = text_document.uri.from_lsp();
+    let tokens = db.semantic_tokens(&uri).await;
+
+    // The positions of the tokens are encoded relative to the previous token
+    let mut data = Vec::with_capacity(tokens.len());
+    let mut prev = Position { line: 0, character: 0 };
+    for token in tokens {
+        let Some(Range { start, end }) = db.span_to_locations(&uri, token.span).map(ToLsp::to_lsp)
+        else {
+            continue;
+        };
+        // Tokens spanning multiple lines are not supported by all clients
+        if start.line != end.line {
+            continue;
+        }
+        let delta_line = start.line - prev.line;
+        let delta_start =
+            if delta_line == 0 { start.character - prev.character } else { start.character };
+        let token_type = TOKEN_TYPES.iter().position(|kind| *kind == token.kind).unwrap_or(0);
+        data.push(SemanticToken {
+            delta_line,
+            delta_start,
+            length: end.character - start.character,
+            token_type: token_type as u32,
+            token_modifiers_bitset: 0,
+        });
+        prev = start;
+    }
+
+    Ok(Some(SemanticTokensResult::Tokens(SemanticTokens { result_id: None, data })))
+}
@@ -69,15 +69,32 @@ impl LanguageServer for Server {
             )
             .await;
 
-        let source_mut = db.file_source_mut();
-        assert!(source_mut.manage(&text_document.uri.from_lsp()));
-        source_mut.write_string(&text_document.uri.from_lsp(), &text_document.text).await.unwrap();
+        let res = db.did_open(&text_document.uri.from_lsp(), &text_document.text).await;
 
-        let res = db.ast(&text_document.uri.from_lsp()).await.map(|_| ());
-        let diags = db.diagnostics(&text_document.uri.from_lsp(), res);
+        let diags = match res {
+            Ok(()) => module_diagnostics(&mut db, &text_document.uri.from_lsp()).await,
+            Err(err) => db.diagnostics(&text_document.uri.from_lsp(), Err(err)),
+        };
         self.send_diagnostics(text_document.uri, diags).await;
     }
 
+    async fn did_close(&self, params: DidCloseTextDocumentParams) {
+        let text_document = params.text_document;
+        let mut db = self.database.write().await;
+
+        self.client
+            .log_message(
+                MessageType::INFO,
+                format!("Closed file: {}", text_document.uri.from_lsp()),
+            )
+            .await;
+
+        db.did_close(&text_document.uri.from_lsp());
+
+        // Diagnostics of a closed document would otherwise linger in the editor
+        self.send_diagnostics(text_document.uri, vec![]).await;
+    }
+
     async fn did_change(&self, params: DidChangeTextDocumentParams) {
         let text_document = params.text_document;
         let mut content_changes = params.content_changes;
@@ -98,12 +115,10 @@ impl LanguageServer for Server {
 
         let res = db.invalidate(&text_document.uri.from_lsp()).await;
 
-        let res = match res {
-            Ok(()) => db.ast(&text_document.uri.from_lsp()).await.map(|_| ()),
-            Err(_) => Ok(()),
+        let diags = match res {
+            Ok(()) => module_diagnostics(&mut db, &text_document.uri.from_lsp()).await,
+            Err(_) => vec![],
         };
-
-        let diags = db.diagnostics(&text_document.uri.from_lsp(), res);
         self.send_diagnostics(text_document.uri, diags).await;
     }
 
@@ -114,6 +129,21 @@ impl LanguageServer for Server {
         super::gotodefinition::goto_definition(self, params).await
     }
 
+    async fn references(&self, params: ReferenceParams) -> Result<Option<Vec<Location>>> {
+        super::references::references(self, params).await
+    }
+
+    async fn rename(&self, params: RenameParams) -> Result<Option<WorkspaceEdit>> {
+        super::rename::rename(self, params).await
+    }
+
+    async fn symbol(
+        &self,
+        params: WorkspaceSymbolParams,
+    ) -> Result<Option<WorkspaceSymbolResponse>> {
+        super::symbols::symbol(self, params).await
+    }
+
     async fn hover(&self, params: HoverParams) -> jsonrpc::Result<Option<Hover>> {
         super::hover::hover(self, params).await
     }
@@ -125,9 +155,35 @@ impl LanguageServer for Server {
         super::codeactions::code_action(self, params).await
     }
 
+    async fn completion(
+        &self,
+        params: CompletionParams,
+    ) -> jsonrpc::Result<Option<CompletionResponse>> {
+        super::completion::completion(self, params).await
+    }
+
+    async fn signature_help(&self, params: SignatureHelpParams) -> Result<Option<SignatureHelp>> {
+        super::signature_help::signature_help(self, params).await
+    }
+
     async fn formatting(&self, params: DocumentFormattingParams) -> Result<Option<Vec<TextEdit>>> {
         super::format::formatting(self, params).await
     }
+
+    async fn folding_range(&self, params: FoldingRangeParams) -> Result<Option<Vec<FoldingRange>>> {
+        super::folding::folding_range(self, params).await
+    }
+
+    async fn inlay_hint(&self, params: InlayHintParams) -> Result<Option<Vec<InlayHint>>> {
+        super::inlay_hints::inlay_hint(self, params).await
+    }
+
+    async fn semantic_tokens_full(
+        &self,
+        params: SemanticTokensParams,
+    ) -> Result<Option<SemanticTokensResult>> {
+        super::semantic_tokens::semantic_tokens_full(self, params).await
+    }
 }
 
 impl Server {
@@ -0,0 +1,59 @@
+//! Implementation of the signature help of the LSP server
+
+use driver::Signature;
+use tower_lsp::{jsonrpc, lsp_types::*};
+
+use super::conversion::*;
+use super::server::*;
+
+pub async fn signature_help(
+    server: &Server,
+    params: SignatureHelpParams,
+) -> jsonrpc::Result<Option<SignatureHelp>> {
+    let pos_params = params.text_document_position_params;
+    let text_document = pos_params.text_document;
+
+    server
+        .client
+        .log_message(
+            MessageType::INFO,
+            format!("Signature help request: {}", text_document.uri.from_lsp()),
+        )
+        .await;
+
+    let pos = pos_params.position;
+    let mut db = server.database.write().await;
+    let uri = text_document.uri.from_lsp();
+    let Some(idx) = db.location_to_index(&uri, pos.from_lsp()) else {
+        return Ok(None);
+    };
+
+    let Some(Signature { label, params, doc, active_param }) = db.signature_help(&uri, idx).await
+    else {
+        return Ok(None);
+    };
+    let documentation = doc.map(|doc| {
+        Documentation::MarkupContent(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: doc.join("\n"),
+        })
+    });
+    let parameters = params
+        .into_iter()
+        .map(|param| ParameterInformation {
+            label: ParameterLabel::Simple(param),
+            documentation: None,
+        })
+        .collect();
+    let active_parameter = active_param.map(|idx| idx as u32);
+    Ok(Some(SignatureHelp {
+        signatures: vec![SignatureInformation {
+            label,
+            documentation,
+            parameters: Some(parameters),
+            active_parameter,
+        }],
+        active_signature: Some(0),
+        active_parameter,
+    }))
+}
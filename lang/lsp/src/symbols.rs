@@ -0,0 +1,48 @@
+//! Implementation of the workspace symbol search of the LSP server
+
+use tower_lsp::{jsonrpc, lsp_types::*};
+
+use ast::DeclKind;
+use driver::WorkspaceSymbol;
+
+use super::conversion::*;
+use super::server::*;
+
+pub async fn symbol(
+    server: &Server,
+    params: WorkspaceSymbolParams,
+) -> jsonrpc::Result<Option<WorkspaceSymbolResponse>> {
+    server
+        .client
+        .log_message(MessageType::INFO, format!("Workspace symbol request: {}", params.query))
+        .await;
+
+    let mut db = server.database.write().await;
+    let symbols = db.workspace_symbols(&params.query).await;
+    let symbols = symbols
+        .into_iter()
+        .filter_map(|WorkspaceSymbol { name, kind, uri, span }| {
+            let range = db.span_to_locations(&uri, span).map(ToLsp::to_lsp)?;
+            #[allow(deprecated)]
+            Some(SymbolInformation {
+                name,
+                kind: symbol_kind(kind),
+                tags: None,
+                deprecated: None,
+                location: Location { uri: uri.to_lsp(), range },
+                container_name: None,
+            })
+        })
+        .collect();
+    Ok(Some(WorkspaceSymbolResponse::Flat(symbols)))
+}
+
+fn symbol_kind(kind: DeclKind) -> SymbolKind {
+    match kind {
+        DeclKind::Data => SymbolKind::ENUM,
+        DeclKind::Codata => SymbolKind::INTERFACE,
+        DeclKind::Def => SymbolKind::METHOD,
+        DeclKind::Codef => SymbolKind::CONSTRUCTOR,
+        DeclKind::Let => SymbolKind::FUNCTION,
+    }
+}
@@ -0,0 +1,106 @@
+//! Collection of the regular comments of a module
+//!
+//! Regular comments are skipped by the lexer, so they only occur in the gaps between tokens.
+//! Since these gaps consist of whitespace and comments only, every `--` in a gap starts a
+//! comment which extends to the end of its line.
+
+use codespan::Span;
+
+use crate::cst::decls::Comment;
+use crate::lexer::{LexicalError, Spanned, Token};
+
+type SpannedToken = Spanned<Token, usize, LexicalError>;
+
+/// The regular comments of `s`, ordered by their location
+///
+/// `tokens` are the tokens of `s`, which must not contain lexical errors.
+pub fn comments(s: &str, tokens: &[SpannedToken]) -> Vec<Comment> {
+    // A shebang line at the very start of the input is not a comment
+    let start = if s.starts_with("#!") { line_end(s, 0) } else { 0 };
+    let mut gaps = vec![];
+    let mut prev_end = start;
+    for (start, _, end) in tokens.iter().flatten() {
+        gaps.push((prev_end, *start));
+        prev_end = *end;
+    }
+    gaps.push((prev_end, s.len()));
+
+    let mut out = vec![];
+    for (mut pos, end) in gaps {
+        while let Some(offset) = s.get(pos..end).and_then(|gap| gap.find("--")) {
+            let start = pos + offset;
+            pos = line_end(s, start).min(end);
+            out.push(Comment {
+                span: span(start, pos),
+                text: s[start..pos].trim_end().to_owned(),
+                is_end_of_line: !s[line_start(s, start)..start].trim().is_empty(),
+                blank_line_after: s[pos..]
+                    .chars()
+                    .take_while(|c| c.is_whitespace())
+                    .filter(|c| *c == '\n')
+                    .nth(1)
+                    .is_some(),
+            });
+        }
+    }
+    out
+}
+
+/// The index of the line break which ends the line containing `idx`
+fn line_end(s: &str, idx: usize) -> usize {
+    s[idx..].find(['\n', '\r']).map_or(s.len(), |offset| idx + offset)
+}
+
+/// The index of the first character of the line containing `idx`
+fn line_start(s: &str, idx: usize) -> usize {
+    s[..idx].rfind(['\n', '\r']).map_or(0, |offset| offset + 1)
+}
+
+fn span(l: usize, r: usize) -> Span {
+    Span::new(l as u32, r as u32)
+}
+
+#[cfg(test)]
+mod comments_tests {
+    use url::Url;
+
+    use crate::cst::decls::Comment;
+    use crate::parse_module;
+
+    fn comments(s: &str) -> Vec<Comment> {
+        let uri = Url::parse("inmemory:///scratch.pol").unwrap();
+        parse_module(uri, s).unwrap().comments
+    }
+
+    #[test]
+    fn line_comments() {
+        let comments = comments("-- A\n-- B\n\ndata Bool { T, F }\n-- C");
+        let texts: Vec<_> = comments.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(texts, vec!["-- A", "-- B", "-- C"]);
+        assert!(comments.iter().all(|c| !c.is_end_of_line));
+        assert_eq!(
+            comments.iter().map(|c| c.blank_line_after).collect::<Vec<_>>(),
+            vec![false, true, false]
+        );
+    }
+
+    #[test]
+    fn end_of_line_comment() {
+        let comments = comments("data Bool { T, F } -- Booleans \n");
+        assert_eq!(comments.len(), 1);
+        assert_eq!(comments[0].text, "-- Booleans");
+        assert!(comments[0].is_end_of_line);
+    }
+
+    #[test]
+    fn doc_comments_and_strings_are_not_comments() {
+        let comments = comments("-- | Doc\nlet s: String { \"--\" }");
+        assert!(comments.is_empty());
+    }
+
+    #[test]
+    fn shebang_is_not_a_comment() {
+        let comments = comments("#!/usr/bin/env pol run -- x\ndata Bool { T, F }");
+        assert!(comments.is_empty());
+    }
+}
@@ -25,6 +25,24 @@ pub struct Module {
     pub use_decls: Vec<UseDecl>,
     /// Declarations contained in the module other than imports.
     pub decls: Vec<Decl>,
+    /// Regular comments of the module, ordered by their location.
+    pub comments: Vec<Comment>,
+}
+
+/// A regular comment, i.e. a comment which is not a doc comment
+///
+/// ```text
+/// -- This is a comment
+/// ```
+#[derive(Debug, Clone)]
+pub struct Comment {
+    pub span: Span,
+    /// The text of the comment including the leading `--`
+    pub text: String,
+    /// Whether the comment follows other code on the same line
+    pub is_end_of_line: bool,
+    /// Whether the comment is followed by an empty line
+    pub blank_line_after: bool,
 }
 
 /// A use declaration
@@ -47,6 +65,18 @@ pub enum Decl {
     Let(Let),
 }
 
+impl Decl {
+    /// The local helper definitions in the `where` clause of the declaration
+    pub fn where_decls(&self) -> &[Decl] {
+        match self {
+            Decl::Data(_) | Decl::Codata(_) => &[],
+            Decl::Def(Def { where_decls, .. }) => where_decls,
+            Decl::Codef(Codef { where_decls, .. }) => where_decls,
+            Decl::Let(Let { where_decls, .. }) => where_decls,
+        }
+    }
+}
+
 /// Data type declaration
 ///
 /// ```text
@@ -92,7 +122,10 @@ pub struct Codata {
 ///               |  \------------ params
 ///               \--------------- name
 /// ```
-/// The `typ` of the constructor is optional.
+/// The `typ` of the constructor is optional if `F` has no parameters.
+/// For indexed families, `typ` fixes the indices of each constructor individually, e.g.
+/// `VCons(n: Nat, x: Nat, xs: Vec(n)) : Vec(S(n))`, and matching on the constructor
+/// refines the indices accordingly.
 #[derive(Debug, Clone)]
 pub struct Ctor {
     pub span: Span,
@@ -105,12 +138,13 @@ pub struct Ctor {
 /// Declaration of a destructor within the context of a codata type declaration.
 ///
 /// ```text
-/// codata F(...) { (self: F(...)).d(...) : t }
-///                 ^^^^^^^^^^^^^^ ^  ^     ^
-///                       |        |  |     \----- ret_typ
-///                       |        |  \----------- params
-///                       |        \-------------- name
-///                       \----------------------- destructee
+/// codata F(...) { (self: F(...)).d(...) : t => e }
+///                 ^^^^^^^^^^^^^^ ^  ^     ^      ^
+///                       |        |  |     |      \-- default
+///                       |        |  |     \--------- ret_typ
+///                       |        |  \--------------- params
+///                       |        \------------------ name
+///                       \--------------------------- destructee
 /// ```
 #[derive(Debug, Clone)]
 pub struct Dtor {
@@ -120,6 +154,8 @@ pub struct Dtor {
     pub params: Telescope,
     pub destructee: Destructee,
     pub ret_typ: Box<exp::Exp>,
+    /// Default implementation which is used if a comatch does not contain a case for this destructor
+    pub default: Option<Box<exp::Exp>>,
 }
 
 /// Destructee within the context of a destructor declaration in a codata type.
@@ -158,6 +194,8 @@ pub struct Def {
     pub scrutinee: Scrutinee,
     pub ret_typ: Box<exp::Exp>,
     pub cases: Vec<exp::Case<Pattern>>,
+    /// Local helper definitions in the `where` clause
+    pub where_decls: Vec<Decl>,
 }
 
 /// Scrutinee within a toplevel definition
@@ -194,6 +232,8 @@ pub struct Codef {
     pub params: Telescope,
     pub typ: exp::Call,
     pub cases: Vec<exp::Case<Copattern>>,
+    /// Local helper definitions in the `where` clause
+    pub where_decls: Vec<Decl>,
 }
 
 /// Toplevel let-bound expression.
@@ -215,20 +255,27 @@ pub struct Let {
     pub params: Telescope,
     pub typ: Box<exp::Exp>,
     pub body: Box<exp::Exp>,
+    /// Local helper definitions in the `where` clause
+    pub where_decls: Vec<Decl>,
 }
 
 /// A `Param` can either be a single parameter, like `x : T`, or a list of parameters, like `x y z: T`.
 /// The parameter list can be optionally prefixed with the "implicit" keyword: `implicit x : T` or `implicit x y z: T`
+/// and with the "lazy" keyword: `lazy x : T` or `implicit lazy x : T`
 #[derive(Debug, Clone)]
 pub struct Param {
     /// Whether the "implicit" keyword was used.
     pub implicit: bool,
+    /// Whether the "lazy" keyword was used.
+    pub lazy: bool,
     /// The obligatory parameter name.
     pub name: exp::BindingSite,
     /// A possible list of additional parameter names.
     pub names: Vec<exp::BindingSite>,
     /// The type of the parameter(s).
     pub typ: Box<exp::Exp>,
+    /// The default value for the parameter(s), which is used if the argument is omitted.
+    pub default: Option<Box<exp::Exp>>,
 }
 
 /// Wrapper type signifying the wrapped parameters have telescope
@@ -245,6 +292,11 @@ impl Telescope {
     pub fn len(&self) -> usize {
         self.0.iter().map(|param| param.names.len() + 1).sum()
     }
+
+    /// The number of parameters which have to be passed explicitly
+    pub fn explicit_len(&self) -> usize {
+        self.0.iter().filter(|param| !param.implicit).map(|param| param.names.len() + 1).sum()
+    }
 }
 
 pub type Params = Vec<Param>;
@@ -32,10 +32,12 @@ pub struct Case<P> {
 /// Arguments in an argument list can either be unnamed or named.
 /// Example for named arguments: `f(x := 1, y := 2)`
 /// Example for unnamed arguments: `f(1, 2)``
+/// Example for implicit arguments: `f(@a := Nat, 1)`
 #[derive(Debug, Clone)]
 pub enum Arg {
     UnnamedArg(Box<Exp>),
     NamedArg(Ident, Box<Exp>),
+    ImplicitArg(Ident, Box<Exp>),
 }
 
 impl Arg {
@@ -43,6 +45,7 @@ impl Arg {
         match self {
             Arg::UnnamedArg(exp) => exp.span(),
             Arg::NamedArg(_, exp) => exp.span(),
+            Arg::ImplicitArg(_, exp) => exp.span(),
         }
     }
 }
@@ -57,8 +60,16 @@ pub enum Exp {
     LocalComatch(LocalComatch),
     Hole(Hole),
     NatLit(NatLit),
+    StrLit(StrLit),
+    CharLit(CharLit),
     Fun(Fun),
     Lam(Lam),
+    IfLet(IfLet),
+    LocalLet(LocalLet),
+    Tuple(Tuple),
+    ListLit(ListLit),
+    Projection(Projection),
+    Absurd(Absurd),
 }
 
 impl Exp {
@@ -72,8 +83,16 @@ impl Exp {
             Exp::LocalComatch(local_comatch) => local_comatch.span,
             Exp::Hole(hole) => hole.span,
             Exp::NatLit(nat_lit) => nat_lit.span,
+            Exp::StrLit(str_lit) => str_lit.span,
+            Exp::CharLit(char_lit) => char_lit.span,
             Exp::Fun(fun) => fun.span,
             Exp::Lam(lam) => lam.span,
+            Exp::IfLet(if_let) => if_let.span,
+            Exp::LocalLet(local_let) => local_let.span,
+            Exp::Tuple(tuple) => tuple.span,
+            Exp::ListLit(list_lit) => list_lit.span,
+            Exp::Projection(projection) => projection.span,
+            Exp::Absurd(absurd) => absurd.span,
         }
     }
 }
@@ -109,6 +128,12 @@ pub struct TypeUniv {
     pub span: Span,
 }
 
+#[derive(Debug, Clone)]
+/// The expression `absurd`, which eliminates a variable of an empty type in the context
+pub struct Absurd {
+    pub span: Span,
+}
+
 #[derive(Debug, Clone)]
 /// Pattern match, e.g. expr.match { A => .., ..}
 pub struct LocalMatch {
@@ -140,6 +165,8 @@ pub enum HoleKind {
 pub struct Hole {
     pub span: Span,
     pub kind: HoleKind,
+    /// The name of a named hole `?name`, which can be used to refer to the goal
+    pub name: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -150,24 +177,112 @@ pub struct NatLit {
 }
 
 #[derive(Debug, Clone)]
-/// Function arrow (syntactic sugar), e.g. a -> b
+/// Literal for a string, e.g. "hello"
+pub struct StrLit {
+    pub span: Span,
+    pub val: String,
+}
+
+#[derive(Debug, Clone)]
+/// Literal for a character, e.g. 'a'
+pub struct CharLit {
+    pub span: Span,
+    pub val: char,
+}
+
+#[derive(Debug, Clone)]
+/// Function arrow (syntactic sugar), e.g. a -> b or (x : a) -> b
 pub struct Fun {
     pub span: Span,
+    /// The variable bound in `to` by a dependent domain `(x : a)`, if any
+    pub var: Option<BindingSite>,
     pub from: Box<Exp>,
     pub to: Box<Exp>,
 }
 
+impl Fun {
+    /// The function type `from -> to`
+    ///
+    /// If `from` is a parenthesized annotation `(x : a)` of a variable or `_`, it is the
+    /// dependent domain `a` which binds `x` in `to`.
+    pub fn new(span: Span, from: Box<Exp>, to: Box<Exp>) -> Fun {
+        let var = match &*from {
+            Exp::Anno(Anno { exp, .. }) => match &**exp {
+                Exp::Call(Call { span, name, args }) if args.is_empty() => {
+                    Some(BindingSite::Var { span: *span, name: name.clone() })
+                }
+                Exp::Hole(Hole { span, kind: HoleKind::MustSolve, name: None }) => {
+                    Some(BindingSite::Wildcard { span: *span })
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+        match (var, *from) {
+            (Some(var), Exp::Anno(Anno { typ, .. })) => Fun { span, var: Some(var), from: typ, to },
+            (_, from) => Fun { span, var: None, from: Box::new(from), to },
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
-/// Lambda abstractions (syntactic sugar), e.g. \x. e
+/// Lambda abstractions (syntactic sugar), e.g. \x. e or \x y. e
 pub struct Lam {
     pub span: Span,
-    pub var: BindingSite,
+    /// The bound variables, which are never empty
+    pub params: Vec<BindingSite>,
+    pub body: Box<Exp>,
+}
+
+#[derive(Debug, Clone)]
+/// Refutable pattern binding with a fallback (syntactic sugar), e.g. if let Cons(x, xs) := e then a else b
+pub struct IfLet {
+    pub span: Span,
+    pub pattern: Pattern,
+    pub on_exp: Box<Exp>,
+    pub then_branch: Box<Exp>,
+    pub else_branch: Box<Exp>,
+}
+
+#[derive(Debug, Clone)]
+/// Local let binding, e.g. let x : t := e in body
+pub struct LocalLet {
+    pub span: Span,
+    pub name: BindingSite,
+    pub bound: Box<Exp>,
+    pub typ: Option<Box<Exp>>,
     pub body: Box<Exp>,
 }
 
+#[derive(Debug, Clone)]
+/// Tuple (syntactic sugar), e.g. (a, b, c)
+///
+/// A tuple always has at least two elements, since `(a)` is a parenthesized expression.
+pub struct Tuple {
+    pub span: Span,
+    pub elems: Vec<Box<Exp>>,
+}
+
+#[derive(Debug, Clone)]
+/// Literal for a list (syntactic sugar), e.g. [a, b, c]
+pub struct ListLit {
+    pub span: Span,
+    pub elems: Vec<Box<Exp>>,
+}
+
+#[derive(Debug, Clone)]
+/// Projection on the components of a pair (syntactic sugar), e.g. e.1 or e.2
+pub struct Projection {
+    pub span: Span,
+    pub exp: Box<Exp>,
+    pub index: BigUint,
+}
+
 #[derive(Debug, Clone)]
 pub struct Motive {
     pub span: Span,
     pub param: BindingSite,
-    pub ret_typ: Box<Exp>,
+    /// The return type of the match, e.g. `t` in `e.match as x => t { .. }`.
+    /// It is `None` for a `with` abstraction, e.g. `e.match with x { .. }`.
+    pub ret_typ: Option<Box<Exp>>,
 }
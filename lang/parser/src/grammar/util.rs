@@ -1,5 +1,30 @@
 use codespan::Span;
+use lalrpop_util::ParseError;
+
+use crate::lexer::{unescape, LexicalError, Token};
 
 pub fn span(l: usize, r: usize) -> Span {
     Span::new(l as u32, r as u32)
 }
+
+/// Accept the identifier `i` only if it is the contextual keyword `kw`
+pub fn contextual_keyword(
+    kw: &str,
+    l: usize,
+    i: String,
+    r: usize,
+) -> Result<(), ParseError<usize, Token, LexicalError>> {
+    if i == kw {
+        Ok(())
+    } else {
+        Err(ParseError::UnrecognizedToken {
+            token: (l, Token::Ident(i), r),
+            expected: vec![format!("\"{kw}\"")],
+        })
+    }
+}
+
+/// Resolve the escape sequences in the contents `s` of a string literal
+pub fn string_literal(s: String) -> Result<String, ParseError<usize, Token, LexicalError>> {
+    unescape(&s).map_err(|error| ParseError::User { error })
+}
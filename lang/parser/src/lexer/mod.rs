@@ -7,6 +7,7 @@ use num_bigint::BigUint;
 pub enum LexicalError {
     #[default]
     InvalidToken,
+    InvalidEscapeSequence,
 }
 
 impl fmt::Display for LexicalError {
@@ -35,6 +36,8 @@ pub enum Token {
     Match,
     #[token("as")]
     As,
+    #[token("with")]
+    With,
     #[token("comatch")]
     Comatch,
     #[token("absurd")]
@@ -43,8 +46,14 @@ pub enum Token {
     Type,
     #[token("implicit")]
     Implicit,
+    #[token("lazy")]
+    Lazy,
     #[token("use")]
     Use,
+    #[token("if")]
+    If,
+    #[token("where")]
+    Where,
 
     // Parens, Braces and Brackets
     //
@@ -87,6 +96,8 @@ pub enum Token {
     Hash,
     #[token("_")]
     Underscore,
+    #[token("@")]
+    At,
 
     // Identifiers
     //
@@ -99,6 +110,9 @@ pub enum Token {
     // _ is contained in the category Pc (Punctuation connector)
     #[regex(r"[\p{Ll}\p{Lu}\p{Sm}][\p{Ll}\p{Lu}\p{Sm}\p{No}0-9_']*", |lex| lex.slice().to_string())]
     Ident(String),
+    /// A named hole `?name`, which carries the name without the question mark
+    #[regex(r"\?[\p{Ll}\p{Lu}\p{Sm}][\p{Ll}\p{Lu}\p{Sm}\p{No}0-9_']*", |lex| lex.slice()[1..].to_string())]
+    NamedHole(String),
 
     // Literals
     //
@@ -114,6 +128,13 @@ pub enum Token {
         inner.to_string()
     })]
     StringLit(String),
+    #[regex(r"'([^'\\]|\\[^u]|\\u\{[0-9a-fA-F]*\})'", |lex| {
+        let slice = lex.slice();
+        // Remove the surrounding quotation marks
+        let inner = &slice[1..slice.len()-1];
+        unescape(inner)?.chars().next().ok_or(LexicalError::InvalidEscapeSequence)
+    })]
+    CharLit(char),
 
     // DocComments
     //
@@ -122,12 +143,71 @@ pub enum Token {
     DocComment(String),
 }
 
+impl Token {
+    /// Whether this token is a keyword
+    pub fn is_keyword(&self) -> bool {
+        use Token::*;
+        match self {
+            Data | Codata | Def | Codef | Let | Match | As | With | Comatch | Absurd | Type => true,
+            Implicit | Lazy | Use | If | Where => true,
+            _ => false,
+        }
+    }
+}
+
 impl fmt::Display for Token {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{:?}", self)
     }
 }
 
+/// Resolve the escape sequences in the contents of a string or character literal
+///
+/// The supported escape sequences are `\n`, `\r`, `\t`, `\0`, `\\`, `\"`, `\'` and `\u{...}`,
+/// where `...` are up to six hexadecimal digits.
+pub fn unescape(s: &str) -> Result<String, LexicalError> {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        let c = match chars.next() {
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('0') => '\0',
+            Some('\\') => '\\',
+            Some('"') => '"',
+            Some('\'') => '\'',
+            Some('u') => unescape_unicode(&mut chars)?,
+            _ => return Err(LexicalError::InvalidEscapeSequence),
+        };
+        out.push(c);
+    }
+    Ok(out)
+}
+
+/// Resolve the remainder `{...}` of a unicode escape sequence `\u{...}`
+fn unescape_unicode(chars: &mut std::str::Chars) -> Result<char, LexicalError> {
+    if chars.next() != Some('{') {
+        return Err(LexicalError::InvalidEscapeSequence);
+    }
+    let mut hex = String::new();
+    loop {
+        match chars.next() {
+            Some('}') => break,
+            Some(c) if c.is_ascii_hexdigit() && hex.len() < 6 => hex.push(c),
+            _ => return Err(LexicalError::InvalidEscapeSequence),
+        }
+    }
+    u32::from_str_radix(&hex, 16)
+        .ok()
+        .and_then(char::from_u32)
+        .ok_or(LexicalError::InvalidEscapeSequence)
+}
+
 pub type Spanned<Tok, Loc, Error> = Result<(Loc, Tok, Loc), Error>;
 
 pub struct Lexer<'input> {
@@ -138,7 +218,13 @@ pub struct Lexer<'input> {
 impl<'input> Lexer<'input> {
     pub fn new(input: &'input str) -> Self {
         // the Token::lexer() method is provided by the Logos trait
-        Self { token_stream: Token::lexer(input).spanned() }
+        let mut lexer = Token::lexer(input);
+        // A shebang line (e.g. `#!/usr/bin/env pol run`) is skipped, but only at the very start
+        // of the input. Elsewhere, `#!` is lexed as usual, which results in an error.
+        if input.starts_with("#!") {
+            lexer.bump(input.find(['\n', '\r']).unwrap_or(input.len()));
+        }
+        Self { token_stream: lexer.spanned() }
     }
 }
 
@@ -152,7 +238,7 @@ impl Iterator for Lexer<'_> {
 
 #[cfg(test)]
 mod lexer_tests {
-    use super::{Lexer, Token};
+    use super::{unescape, Lexer, LexicalError, Token};
 
     #[test]
     fn string_lit_simple() {
@@ -174,4 +260,72 @@ mod lexer_tests {
         let mut lexer = Lexer::new(str);
         assert_eq!(lexer.next().unwrap().unwrap().1, Token::StringLit("h\\\"i".to_string()))
     }
+
+    #[test]
+    fn char_lit_simple() {
+        let mut lexer = Lexer::new("'a'");
+        assert_eq!(lexer.next().unwrap().unwrap().1, Token::CharLit('a'))
+    }
+
+    #[test]
+    fn char_lit_escaped() {
+        let mut lexer = Lexer::new(r"'\'' '\n' '\u{1F600}'");
+        assert_eq!(lexer.next().unwrap().unwrap().1, Token::CharLit('\''));
+        assert_eq!(lexer.next().unwrap().unwrap().1, Token::CharLit('\n'));
+        assert_eq!(lexer.next().unwrap().unwrap().1, Token::CharLit('😀'))
+    }
+
+    #[test]
+    fn char_lit_invalid_escape() {
+        let mut lexer = Lexer::new(r"'\q'");
+        assert_eq!(lexer.next().unwrap(), Err(LexicalError::InvalidEscapeSequence))
+    }
+
+    #[test]
+    fn unescape_string() {
+        assert_eq!(unescape(r#"a\"b\\c\nd\u{41}"#), Ok("a\"b\\c\ndA".to_owned()));
+        assert_eq!(unescape(r"\u{110000}"), Err(LexicalError::InvalidEscapeSequence));
+        assert_eq!(unescape(r"\u{41"), Err(LexicalError::InvalidEscapeSequence));
+    }
+
+    #[test]
+    fn keywords() {
+        let tokens: Vec<_> = Lexer::new("data codata Type Typ").map(Result::unwrap).collect();
+        let keywords: Vec<_> = tokens.iter().map(|(_, token, _)| token.is_keyword()).collect();
+        assert_eq!(keywords, vec![true, true, true, false])
+    }
+
+    #[test]
+    fn identifiers() {
+        assert!(crate::is_identifier("foo'"));
+        assert!(crate::is_identifier("x_1"));
+        assert!(!crate::is_identifier("data"));
+        assert!(!crate::is_identifier("foo bar"));
+        assert!(!crate::is_identifier(" foo"));
+        assert!(!crate::is_identifier(""));
+    }
+
+    #[test]
+    fn named_hole() {
+        let mut lexer = Lexer::new("?goal1 ? x");
+        assert_eq!(lexer.next().unwrap().unwrap().1, Token::NamedHole("goal1".to_string()));
+        assert_eq!(lexer.next().unwrap().unwrap().1, Token::QuestionMark);
+    }
+
+    #[test]
+    fn shebang_first_line() {
+        let str = "#!/usr/bin/env pol run\ndata";
+        let mut lexer = Lexer::new(str);
+        assert_eq!(lexer.next().unwrap().unwrap(), (23, Token::Data, 27));
+        assert!(lexer.next().is_none())
+    }
+
+    #[test]
+    fn shebang_not_first_line() {
+        let str = "data\n#!/usr/bin/env pol run";
+        let mut lexer = Lexer::new(str);
+        assert_eq!(lexer.next().unwrap().unwrap().1, Token::Data);
+        assert_eq!(lexer.next().unwrap().unwrap().1, Token::Hash);
+        assert_eq!(lexer.next().unwrap(), Err(LexicalError::InvalidToken))
+    }
 }
@@ -1,8 +1,12 @@
+mod comments;
 pub mod cst;
 mod grammar;
 mod lexer;
+mod recovery;
 mod result;
 
+use std::ops::Range;
+
 use lexer::Lexer;
 use url::Url;
 
@@ -15,9 +19,41 @@ pub fn parse_exp(s: &str) -> Result<Box<cst::exp::Exp>, ParseError> {
     parser.parse(lexer).map_err(From::from)
 }
 
-pub fn parse_module(uri: Url, s: &str) -> Result<cst::decls::Module, ParseError> {
-    let lexer = Lexer::new(s);
+/// The locations of all keywords in `s`
+///
+/// Keywords are not part of the syntax tree, so this is used for syntax highlighting.
+/// Tokens which cannot be lexed are skipped.
+pub fn keyword_spans(s: &str) -> Vec<Range<usize>> {
+    Lexer::new(s)
+        .filter_map(|token| match token {
+            Ok((start, token, end)) if token.is_keyword() => Some(start..end),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Whether `s` consists of exactly one identifier, which is not a keyword
+pub fn is_identifier(s: &str) -> bool {
+    let mut tokens = Lexer::new(s);
+    matches!(
+        (tokens.next(), tokens.next()),
+        (Some(Ok((0, lexer::Token::Ident(_), end))), None) if end == s.len()
+    )
+}
+
+/// Parse a module
+///
+/// If the module contains syntax errors, the parser recovers at the next declaration and
+/// reports the syntax errors of all declarations, ordered by their location.
+/// Regular comments are not part of the grammar, they are collected separately.
+pub fn parse_module(uri: Url, s: &str) -> Result<cst::decls::Module, Vec<ParseError>> {
+    let tokens: Vec<_> = Lexer::new(s).collect();
     let parser = ModuleContentsParser::new();
-    let (use_decls, decls) = parser.parse(lexer)?;
-    Ok(cst::decls::Module { uri, use_decls, decls })
+    match parser.parse(tokens.iter().cloned()) {
+        Ok((use_decls, decls)) => {
+            let comments = comments::comments(s, &tokens);
+            Ok(cst::decls::Module { uri, use_decls, decls, comments })
+        }
+        Err(err) => Err(recovery::recover(&parser, &tokens, err)),
+    }
 }
@@ -0,0 +1,128 @@
+//! Recovery from syntax errors at declaration boundaries
+//!
+//! When a module fails to parse, the tokens following the first syntax error are split into
+//! chunks which start at the keywords `data`, `codata`, `def` and `codef`.
+//! Every chunk is then parsed on its own, so that a syntax error in one declaration does not
+//! hide the syntax errors in later declarations.
+//! Since the tokens keep their original locations, the spans of all errors remain accurate.
+
+use crate::grammar::cst::ModuleContentsParser;
+use crate::lexer::{LexicalError, Spanned, Token};
+use crate::ParseError;
+
+type SpannedToken = Spanned<Token, usize, LexicalError>;
+type LalrpopError = lalrpop_util::ParseError<usize, Token, LexicalError>;
+
+/// Collect the syntax errors of all declarations after the first syntax error `first`
+pub fn recover(
+    parser: &ModuleContentsParser,
+    tokens: &[SpannedToken],
+    first: LalrpopError,
+) -> Vec<ParseError> {
+    let Some(error_idx) = error_index(tokens, &first) else {
+        return vec![first.into()];
+    };
+
+    // The declarations starting after the first error, excluding the chunk at the very start of
+    // the module since its error would be reported twice
+    let starts: Vec<usize> = (0..tokens.len())
+        .filter(|idx| is_decl_keyword(tokens, *idx))
+        .map(|idx| decl_start(tokens, idx))
+        .filter(|start| *start >= error_idx && *start > 0)
+        .collect();
+    let ends = starts.iter().skip(1).copied().chain(std::iter::once(tokens.len()));
+
+    let mut errors: Vec<ParseError> = vec![first.into()];
+    for (start, end) in starts.iter().copied().zip(ends) {
+        if let Err(err) = parser.parse(tokens[start..end].iter().cloned()) {
+            errors.push(err.into());
+        }
+    }
+    errors
+}
+
+/// The index of the token at which the error `err` occurred
+///
+/// Returns `None` if the location of the error is not known.
+fn error_index(tokens: &[SpannedToken], err: &LalrpopError) -> Option<usize> {
+    use lalrpop_util::ParseError::*;
+    let offset = match err {
+        InvalidToken { location } | UnrecognizedEof { location, .. } => *location,
+        UnrecognizedToken { token: (start, _, _), .. } | ExtraToken { token: (start, _, _) } => {
+            *start
+        }
+        // Lexical errors are reported for the first token which could not be lexed.
+        // Errors raised by the actions of the grammar do not carry a location.
+        User { .. } => return tokens.iter().position(|token| token.is_err()),
+    };
+    let idx =
+        tokens.iter().position(|token| matches!(token, Ok((start, _, _)) if *start >= offset));
+    Some(idx.unwrap_or(tokens.len()))
+}
+
+fn is_decl_keyword(tokens: &[SpannedToken], idx: usize) -> bool {
+    matches!(
+        tokens.get(idx),
+        Some(Ok((_, Token::Data | Token::Codata | Token::Def | Token::Codef, _)))
+    )
+}
+
+fn is_token(tokens: &[SpannedToken], idx: usize, expected: &Token) -> bool {
+    matches!(tokens.get(idx), Some(Ok((_, token, _))) if token == expected)
+}
+
+/// The index of the first token of the declaration whose keyword is located at `keyword`
+///
+/// A declaration starts with optional doc comments and attributes `#[..]` before its keyword.
+fn decl_start(tokens: &[SpannedToken], keyword: usize) -> usize {
+    let mut start = keyword;
+    if start > 0 && is_token(tokens, start - 1, &Token::RBracket) {
+        let open = (0..start - 1).rev().find(|idx| is_token(tokens, *idx, &Token::LBracket));
+        match open {
+            Some(open) if open > 0 && is_token(tokens, open - 1, &Token::Hash) => start = open - 1,
+            _ => {}
+        }
+    }
+    while start > 0 && matches!(tokens.get(start - 1), Some(Ok((_, Token::DocComment(_), _)))) {
+        start -= 1;
+    }
+    start
+}
+
+#[cfg(test)]
+mod recovery_tests {
+    use url::Url;
+
+    use crate::{parse_module, ParseError};
+
+    fn parse_errors(s: &str) -> Vec<ParseError> {
+        let uri = Url::parse("inmemory:///scratch.pol").unwrap();
+        parse_module(uri, s).err().unwrap_or_default()
+    }
+
+    #[test]
+    fn recover_all_broken_defs() {
+        let s = "data Bool { T, F }\n\
+                 def Bool.a: Bool { T => }\n\
+                 def Bool.b: Bool { T => T,, }\n\
+                 -- | Negation\n\
+                 def Bool.c Bool { T => F }\n";
+        assert_eq!(parse_errors(s).len(), 3);
+    }
+
+    #[test]
+    fn recover_missing_brace() {
+        let s = "data Bool { T, F\n\
+                 #[transparent]\n\
+                 let x: Bool { T }\n\
+                 codef Foo: Bool { .x => }\n";
+        assert_eq!(parse_errors(s).len(), 2);
+    }
+
+    #[test]
+    fn no_recovery_without_errors() {
+        assert!(
+            parse_errors("data Bool { T, F }\ndef Bool.not: Bool { T => F, F => T }").is_empty()
+        );
+    }
+}
@@ -54,6 +54,19 @@ pub enum ParseError {
     User { error: LexicalError },
 }
 
+impl ParseError {
+    /// The codes of all variants, together with the templates of their messages
+    ///
+    /// This list must be extended whenever a variant is added, see `driver::error_index`.
+    pub const CODES: &'static [(&'static str, &'static str)] = &[
+        ("P-001", "Invalid token"),
+        ("P-002", "Unexpected end of file. Expected {expected}"),
+        ("P-003", "Unexpected \"{token}\", expected {expected}"),
+        ("P-004", "Excessive \"{token}\""),
+        ("P-005", "{error}"),
+    ];
+}
+
 impl From<lalrpop_util::ParseError<usize, Token, LexicalError>> for ParseError {
     fn from(err: lalrpop_util::ParseError<usize, Token, LexicalError>) -> Self {
         use lalrpop_util::ParseError::*;
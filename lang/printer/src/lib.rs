@@ -7,11 +7,14 @@ pub use pretty::termcolor::WriteColor;
 pub use pretty::DocAllocator;
 
 mod render;
+pub mod source_map;
 pub mod theme;
 pub mod tokens;
 pub mod types;
 pub mod util;
 
+pub use source_map::SourceMap;
 pub use types::*;
 
-pub const DEFAULT_WIDTH: usize = 100;
+/// The line width used if no other width is configured
+pub const DEFAULT_WIDTH: usize = 80;
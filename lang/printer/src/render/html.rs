@@ -50,13 +50,17 @@ where
             Anno::BraceOpen => "",
             Anno::BraceClose => "",
             Anno::Error => "<span class=\"error\">",
+            Anno::Source { .. } => "",
         };
         self.upstream.write_all(out.as_bytes())
     }
 
     fn pop_annotation(&mut self) -> Result<(), Self::Error> {
         let res = match self.anno_stack.last() {
-            Some(Anno::Backslash) | Some(Anno::BraceOpen) | Some(Anno::BraceClose) => Ok(()),
+            Some(Anno::Backslash)
+            | Some(Anno::BraceOpen)
+            | Some(Anno::BraceClose)
+            | Some(Anno::Source { .. }) => Ok(()),
             _ => self.upstream.write_all("</span>".as_bytes()),
         };
         self.anno_stack.pop();
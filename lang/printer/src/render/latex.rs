@@ -51,13 +51,14 @@ where
             // Escape a closing brace that follows immediately
             Anno::BraceClose => r"\",
             Anno::Error => r"\textcolor{polRed}{",
+            Anno::Source { .. } => "",
         };
         self.upstream.write_all(out.as_bytes())
     }
 
     fn pop_annotation(&mut self) -> Result<(), Self::Error> {
         let res = match self.anno_stack.last() {
-            Some(Anno::BraceOpen) | Some(Anno::BraceClose) => Ok(()),
+            Some(Anno::BraceOpen) | Some(Anno::BraceClose) | Some(Anno::Source { .. }) => Ok(()),
             _ => self.upstream.write_all("}".as_bytes()),
         };
         self.anno_stack.pop();
@@ -0,0 +1,132 @@
+use std::io;
+
+use crate::tokens::math;
+use crate::types::*;
+
+/// Render a document in LaTeX math notation
+///
+/// Every line of the document is a row of an `align*` environment, so that the cases of
+/// matches and comatches are aligned with each other.
+/// The `align*` environment itself is not emitted by this renderer.
+pub struct RenderLatexMath<W> {
+    anno_stack: Vec<Anno>,
+    upstream: W,
+}
+
+impl<W> RenderLatexMath<W> {
+    pub fn new(upstream: W) -> RenderLatexMath<W> {
+        RenderLatexMath { anno_stack: Vec::new(), upstream }
+    }
+}
+
+impl<W> RenderLatexMath<W>
+where
+    W: io::Write,
+{
+    /// Write an identifier, rendering trailing digits as a subscript, e.g. `x_0` for `x0`
+    fn write_ident(&mut self, ident: &str) -> io::Result<()> {
+        let base = ident.trim_end_matches(|c: char| c.is_ascii_digit());
+        let digits = &ident[base.len()..];
+        let base = base.replace('_', math::UNDERSCORE);
+        // Multi-letter identifiers are set as a single word unless they are already annotated
+        if base.chars().count() > 1 && self.anno_stack.is_empty() {
+            write!(self.upstream, r"\mathit{{{base}}}")?;
+        } else {
+            self.upstream.write_all(base.as_bytes())?;
+        }
+        match digits.len() {
+            0 => Ok(()),
+            _ if base.is_empty() => self.upstream.write_all(digits.as_bytes()),
+            1 => write!(self.upstream, "_{digits}"),
+            _ => write!(self.upstream, "_{{{digits}}}"),
+        }
+    }
+
+    /// Write text verbatim, escaping the characters which are special in LaTeX
+    fn write_text(&mut self, s: &str) -> io::Result<()> {
+        for c in s.chars() {
+            match c {
+                '\\' => self.upstream.write_all(br"\textbackslash{}")?,
+                '{' | '}' | '_' | '#' | '%' | '&' | '$' => write!(self.upstream, r"\{c}")?,
+                c => write!(self.upstream, "{c}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<W> pretty::Render for RenderLatexMath<W>
+where
+    W: io::Write,
+{
+    type Error = io::Error;
+
+    fn write_str(&mut self, s: &str) -> io::Result<usize> {
+        self.write_str_all(s)?;
+        Ok(s.len())
+    }
+
+    fn write_str_all(&mut self, s: &str) -> io::Result<()> {
+        if matches!(self.anno_stack.last(), Some(Anno::Comment)) {
+            return self.write_text(s);
+        }
+        if let Some(token) = math::translate(s) {
+            return write!(self.upstream, "{token} ");
+        }
+        let mut rest = s;
+        while let Some(c) = rest.chars().next() {
+            let len = if c.is_alphanumeric() || c == '_' {
+                let len =
+                    rest.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(rest.len());
+                self.write_ident(&rest[..len])?;
+                len
+            } else {
+                match c {
+                    // Every line is a row of the `align*` environment
+                    '\n' => self.upstream.write_all(b"\\\\\n&")?,
+                    ' ' => self.upstream.write_all(br"\ ")?,
+                    c => self.write_text(&c.to_string())?,
+                }
+                c.len_utf8()
+            };
+            rest = &rest[len..];
+        }
+        Ok(())
+    }
+
+    fn fail_doc(&self) -> Self::Error {
+        io::Error::new(io::ErrorKind::Other, "Document failed to render")
+    }
+}
+
+impl<W> pretty::RenderAnnotated<'_, Anno> for RenderLatexMath<W>
+where
+    W: io::Write,
+{
+    fn push_annotation(&mut self, anno: &Anno) -> Result<(), Self::Error> {
+        self.anno_stack.push(*anno);
+        let out = match anno {
+            Anno::Keyword => r"\mathbf{",
+            Anno::Ctor => r"\mathsf{",
+            Anno::Dtor => r"\mathit{",
+            Anno::Type => r"\mathsf{",
+            Anno::Comment => r"\text{",
+            Anno::Error => r"\textcolor{red}{",
+            // Backslashes and braces are translated to math notation
+            Anno::Backslash | Anno::BraceOpen | Anno::BraceClose | Anno::Source { .. } => "",
+        };
+        self.upstream.write_all(out.as_bytes())
+    }
+
+    fn pop_annotation(&mut self) -> Result<(), Self::Error> {
+        let res = match self.anno_stack.last() {
+            Some(Anno::Backslash)
+            | Some(Anno::BraceOpen)
+            | Some(Anno::BraceClose)
+            | Some(Anno::Source { .. }) => Ok(()),
+            _ => self.upstream.write_all("}".as_bytes()),
+        };
+        self.anno_stack.pop();
+        res
+    }
+}
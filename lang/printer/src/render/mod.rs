@@ -1,7 +1,11 @@
 mod html;
 mod latex;
+mod latex_math;
+mod source_map;
 mod termcolor;
 
 pub use html::*;
 pub use latex::*;
+pub use latex_math::*;
+pub use source_map::*;
 pub use termcolor::*;
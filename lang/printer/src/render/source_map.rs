@@ -0,0 +1,74 @@
+use std::io;
+
+use crate::source_map::{SourceMap, SourceMapEntry};
+use crate::types::*;
+
+/// Render a document without any styling, recording the source locations of its nodes
+///
+/// The source locations are taken from the [Anno::Source] annotations of the document.
+pub struct RenderSourceMap<W> {
+    /// The open annotations together with the output position at which they were opened
+    anno_stack: Vec<(Anno, usize)>,
+    /// The number of bytes written so far
+    pos: usize,
+    source_map: SourceMap,
+    upstream: W,
+}
+
+impl<W> RenderSourceMap<W> {
+    pub fn new(upstream: W) -> RenderSourceMap<W> {
+        RenderSourceMap {
+            anno_stack: Vec::new(),
+            pos: 0,
+            source_map: SourceMap::default(),
+            upstream,
+        }
+    }
+
+    /// Return the rendered output together with the recorded source map
+    pub fn finish(self) -> (W, SourceMap) {
+        (self.upstream, self.source_map)
+    }
+}
+
+impl<W> pretty::Render for RenderSourceMap<W>
+where
+    W: io::Write,
+{
+    type Error = io::Error;
+
+    fn write_str(&mut self, s: &str) -> io::Result<usize> {
+        self.write_str_all(s)?;
+        Ok(s.len())
+    }
+
+    fn write_str_all(&mut self, s: &str) -> io::Result<()> {
+        self.upstream.write_all(s.as_bytes())?;
+        self.pos += s.len();
+        Ok(())
+    }
+
+    fn fail_doc(&self) -> Self::Error {
+        io::Error::new(io::ErrorKind::Other, "Document failed to render")
+    }
+}
+
+impl<W> pretty::RenderAnnotated<'_, Anno> for RenderSourceMap<W>
+where
+    W: io::Write,
+{
+    fn push_annotation(&mut self, anno: &Anno) -> Result<(), Self::Error> {
+        self.anno_stack.push((*anno, self.pos));
+        Ok(())
+    }
+
+    fn pop_annotation(&mut self) -> Result<(), Self::Error> {
+        if let Some((Anno::Source { start, end }, output_start)) = self.anno_stack.pop() {
+            self.source_map.entries.push(SourceMapEntry {
+                output: output_start..self.pos,
+                source: start as usize..end as usize,
+            });
+        }
+        Ok(())
+    }
+}
@@ -72,6 +72,7 @@ impl Anno {
             Anno::BraceOpen => Default::default(),
             Anno::BraceClose => Default::default(),
             Anno::Error => ERROR.spec(),
+            Anno::Source { .. } => Default::default(),
         }
     }
 }
@@ -0,0 +1,42 @@
+//! Mappings from printed documents back to the source code they were printed from
+
+use std::ops::Range;
+
+/// A mapping from byte ranges of a printed document to byte ranges of the source code
+///
+/// Every node of the syntax tree which has a source location contributes one entry.
+/// Since nodes are nested, the output ranges of the entries may be nested as well.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceMap {
+    /// The entries in the order in which the printing of their nodes was completed,
+    /// such that inner nodes precede the nodes which contain them
+    pub entries: Vec<SourceMapEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceMapEntry {
+    /// The byte range of the printed output
+    pub output: Range<usize>,
+    /// The byte range of the source code from which the output was printed
+    pub source: Range<usize>,
+}
+
+impl SourceMap {
+    /// The source range of the innermost node whose output contains the byte offset `idx`
+    pub fn to_source(&self, idx: usize) -> Option<Range<usize>> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.output.contains(&idx))
+            .min_by_key(|entry| entry.output.len())
+            .map(|entry| entry.source.clone())
+    }
+
+    /// The output range of the innermost node whose source range contains the byte offset `idx`
+    pub fn to_output(&self, idx: usize) -> Option<Range<usize>> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.source.contains(&idx))
+            .min_by_key(|entry| entry.source.len())
+            .map(|entry| entry.output.clone())
+    }
+}
@@ -54,12 +54,18 @@ pub const CODEF: &str = "codef";
 /// The keyword `let`
 pub const LET: &str = "let";
 
+/// The keyword `in`
+pub const IN: &str = "in";
+
 /// The keyword `match`
 pub const MATCH: &str = "match";
 
 /// The keyword `as`
 pub const AS: &str = "as";
 
+/// The keyword `with`
+pub const WITH: &str = "with";
+
 /// The keyword `comatch`
 pub const COMATCH: &str = "comatch";
 
@@ -72,5 +78,60 @@ pub const TYPE: &str = "Type";
 /// The keyword `implicit`
 pub const IMPLICIT: &str = "implicit";
 
+/// The keyword `lazy`
+pub const LAZY: &str = "lazy";
+
 /// The keyword `use`
 pub const USE: &str = "use";
+
+/// The keyword `where`
+pub const WHERE: &str = "where";
+
+/// The symbols and keywords used when printing in LaTeX math notation
+///
+/// Tokens which are not listed here are printed as in the surface language.
+pub mod math {
+    /// The symbol `->`
+    pub const ARROW: &str = r"\to";
+
+    /// The symbol `=>`
+    pub const FAT_ARROW: &str = r"\Rightarrow";
+
+    /// The symbol `:=`
+    pub const COLONEQ: &str = r"\mathrel{:=}";
+
+    /// The symbol `#`
+    pub const HASH: &str = r"\#";
+
+    /// The symbol `_`
+    pub const UNDERSCORE: &str = r"\_";
+
+    /// The backslash of the lambda sugar `\x. e`
+    pub const BACKSLASH: &str = r"\lambda";
+
+    /// The symbol `{`
+    pub const BRACE_OPEN: &str = r"\{";
+
+    /// The symbol `}`
+    pub const BRACE_CLOSE: &str = r"\}";
+
+    /// The keyword `Type`
+    pub const TYPE: &str = r"\mathcal{U}";
+
+    /// Translate a token of the surface language to math notation
+    pub fn translate(token: &str) -> Option<&'static str> {
+        let out = match token {
+            super::ARROW => ARROW,
+            super::FAT_ARROW => FAT_ARROW,
+            super::COLONEQ => COLONEQ,
+            super::HASH => HASH,
+            super::UNDERSCORE => UNDERSCORE,
+            "\\" => BACKSLASH,
+            "{" => BRACE_OPEN,
+            "}" => BRACE_CLOSE,
+            super::TYPE => TYPE,
+            _ => return None,
+        };
+        Some(out)
+    }
+}
@@ -6,7 +6,7 @@ use pretty::{
     DocAllocator,
 };
 
-use crate::{render, tokens::COMMA};
+use crate::{render, source_map::SourceMap, tokens::COMMA};
 
 #[derive(Debug, Clone, Copy)]
 pub enum Anno {
@@ -19,14 +19,35 @@ pub enum Anno {
     BraceOpen,
     BraceClose,
     Error,
+    /// The byte range of the source code from which the annotated document was printed.
+    /// These annotations are only emitted if [PrintCfg::source_map] is set.
+    Source {
+        start: u32,
+        end: u32,
+    },
 }
 
 pub type Alloc<'a> = pretty::Arena<'a, Anno>;
 pub type Builder<'a> = pretty::DocBuilder<'a, Alloc<'a>, Anno>;
 
 /// Operator precedences
+///
+/// A higher precedence binds tighter. An expression is enclosed in parentheses if it is printed
+/// in a context whose precedence is higher than the precedence of the expression itself.
 pub type Precedence = u32;
 
+/// The precedences of the syntactic forms of expressions, from the loosest to the tightest
+pub mod prec {
+    use super::Precedence;
+
+    /// Annotations `e : t`, function types `a -> b` and lambdas `\x. e`,
+    /// which extend as far to the right as possible
+    pub const EXP: Precedence = 0;
+    /// Dot calls `e.d(..)` and local matches `e.match { .. }`,
+    /// as well as applications `C(..)` and atoms, which bind tighter than `->` and `:`
+    pub const OPS: Precedence = 1;
+}
+
 /// We implement the `Print` trait for all types that we want to prettyprint.
 /// It is sufficient to implement either the `print` or the `print_prec` function, depending
 /// on whether you need information about operator precedences or not.
@@ -56,6 +77,9 @@ pub trait Print {
     fn print_colored<W: WriteColor>(&self, cfg: &PrintCfg, out: &mut W) -> io::Result<()> {
         let alloc = Alloc::new();
         let doc_builder = self.print(cfg, &alloc);
+        if !cfg.use_color {
+            return doc_builder.1.render(cfg.width, out);
+        }
         doc_builder.render_raw(cfg.width, &mut render::RenderTermcolor::new(out))
     }
 
@@ -65,6 +89,24 @@ pub trait Print {
         doc_builder.render_raw(cfg.width, &mut render::RenderLatex::new(out))
     }
 
+    fn print_latex_math<W: io::Write>(&self, cfg: &PrintCfg, out: &mut W) -> io::Result<()> {
+        let alloc = Alloc::new();
+        let doc_builder = self.print(cfg, &alloc);
+        doc_builder.render_raw(cfg.width, &mut render::RenderLatexMath::new(out))
+    }
+
+    /// Print in LaTeX math notation as an `align*` environment, e.g. for inclusion in a paper
+    fn print_to_latex(&self, cfg: Option<&PrintCfg>) -> String {
+        let mut buf = Vec::new();
+        let def = PrintCfg::default();
+        // Braces and backslashes are translated to math notation rather than escaped
+        let cfg = PrintCfg { latex: false, ..cfg.unwrap_or(&def).clone() };
+        buf.extend_from_slice(b"\\begin{align*}\n&");
+        self.print_latex_math(&cfg, &mut buf).expect("Failed to print to string");
+        buf.extend_from_slice(b"\n\\end{align*}\n");
+        String::from_utf8(buf).expect("Failed to convert Vec<u8> to String")
+    }
+
     fn print_html<W: io::Write>(&self, cfg: &PrintCfg, out: &mut W) -> io::Result<()> {
         let alloc = Alloc::new();
         let doc_builder = self.print(cfg, &alloc);
@@ -87,6 +129,19 @@ pub trait Print {
         unsafe { String::from_utf8_unchecked(buf) }
     }
 
+    /// Print to a string together with a [SourceMap] from the output to the source locations
+    /// of the printed nodes
+    fn print_with_source_map(&self, cfg: Option<&PrintCfg>) -> (String, SourceMap) {
+        let def = PrintCfg::default();
+        let cfg = PrintCfg { source_map: true, ..cfg.unwrap_or(&def).clone() };
+        let alloc = Alloc::new();
+        let doc_builder = self.print(&cfg, &alloc);
+        let mut renderer = render::RenderSourceMap::new(Vec::new());
+        doc_builder.render_raw(cfg.width, &mut renderer).expect("Failed to print to string");
+        let (buf, source_map) = renderer.finish();
+        (String::from_utf8(buf).expect("Failed to convert Vec<u8> to String"), source_map)
+    }
+
     fn print_to_colored_string(&self, cfg: Option<&PrintCfg>) -> String {
         let buf: Vec<u8> = Vec::new();
         let mut ansi = Ansi::new(buf);
@@ -105,7 +160,10 @@ pub trait Print {
             indent: 4,
             print_lambda_sugar: true,
             print_function_sugar: true,
+            print_nat_sugar: true,
             print_metavar_ids: true,
+            use_color: true,
+            source_map: false,
         };
         self.print_to_colored_string(Some(&TRACE_CFG))
     }
@@ -228,8 +286,14 @@ pub struct PrintCfg {
     pub print_lambda_sugar: bool,
     /// Whether to print the syntactic sugar "a -> b".
     pub print_function_sugar: bool,
+    /// Whether to print the natural numbers "S(S(Z))" as numeric literals "2".
+    pub print_nat_sugar: bool,
     /// Whether to print the ids of metavariables
     pub print_metavar_ids: bool,
+    /// Whether to emit colors when printing to a `WriteColor`
+    pub use_color: bool,
+    /// Whether to annotate the printed nodes with their source locations, see [SourceMap]
+    pub source_map: bool,
 }
 
 impl Default for PrintCfg {
@@ -242,7 +306,10 @@ impl Default for PrintCfg {
             indent: 4,
             print_lambda_sugar: true,
             print_function_sugar: true,
+            print_nat_sugar: true,
             print_metavar_ids: false,
+            use_color: true,
+            source_map: false,
         }
     }
 }
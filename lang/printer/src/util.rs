@@ -20,6 +20,27 @@ where
     fn is_nil(&self) -> bool;
 }
 
+pub trait ParensIfExt<'a, D>
+where
+    D: ?Sized + DocAllocator<'a, Anno>,
+{
+    /// Enclose the document in parentheses if `cond` holds
+    fn parens_if(self, cond: bool) -> pretty::DocBuilder<'a, D, Anno>;
+}
+
+pub trait SourceAnnoExt<'a, D>
+where
+    D: ?Sized + DocAllocator<'a, Anno>,
+{
+    /// Annotate the document with the byte range `(start, end)` of the source code it was
+    /// printed from, if [PrintCfg::source_map] is set
+    fn source_anno(
+        self,
+        cfg: &PrintCfg,
+        source: Option<(u32, u32)>,
+    ) -> pretty::DocBuilder<'a, D, Anno>;
+}
+
 impl<'a, D> BracesExt<'a, D> for pretty::DocBuilder<'a, D, Anno>
 where
     D: ?Sized + DocAllocator<'a, Anno>,
@@ -41,6 +62,35 @@ where
     }
 }
 
+impl<'a, D> ParensIfExt<'a, D> for pretty::DocBuilder<'a, D, Anno>
+where
+    D: ?Sized + DocAllocator<'a, Anno>,
+{
+    fn parens_if(self, cond: bool) -> pretty::DocBuilder<'a, D, Anno> {
+        if cond {
+            self.parens()
+        } else {
+            self
+        }
+    }
+}
+
+impl<'a, D> SourceAnnoExt<'a, D> for pretty::DocBuilder<'a, D, Anno>
+where
+    D: ?Sized + DocAllocator<'a, Anno>,
+{
+    fn source_anno(
+        self,
+        cfg: &PrintCfg,
+        source: Option<(u32, u32)>,
+    ) -> pretty::DocBuilder<'a, D, Anno> {
+        match source {
+            Some((start, end)) if cfg.source_map => self.annotate(Anno::Source { start, end }),
+            _ => self,
+        }
+    }
+}
+
 impl<'a, D, A> IsNilExt<'a, D, A> for pretty::DocBuilder<'a, D, A>
 where
     D: ?Sized + DocAllocator<'a, A>,
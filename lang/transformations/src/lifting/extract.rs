@@ -0,0 +1,220 @@
+use codespan::Span;
+
+use ast::ctx::values::{Binder, TypeCtx};
+use ast::ctx::BindContext;
+use ast::*;
+
+use crate::Rename;
+
+use super::fv::*;
+
+/// Extract the expression at `span` of type `typ` in `module` into a new top-level let binding
+///
+/// The new let binding is parameterized over all free variables of the selected
+/// expression and its type. Since a `def` in this language must always match on a
+/// self parameter, a closed expression is extracted into a (transparent) `let`.
+/// Returns `None` if `span` does not coincide with the span of a single expression.
+pub fn extract(module: &Module, span: Span, typ: Box<Exp>) -> Option<ExtractResult> {
+    let mut ctx = Ctx { span, vars: TypeCtx::empty() };
+
+    let (decl, exp) = module.decls.iter().find_map(|decl| {
+        let exp = match decl {
+            Decl::Def(def) => def.extract(&mut ctx),
+            Decl::Codef(codef) => codef.extract(&mut ctx),
+            Decl::Let(tl_let) => tl_let.extract(&mut ctx),
+            Decl::Data(_) | Decl::Codata(_) => None,
+        }?;
+        Some((decl, exp))
+    })?;
+
+    let Found { exp, vars } = exp;
+    let levels = vars.levels();
+
+    // Collect the free variables in the expression and its type
+    // Build a telescope of the types of the free variables
+    let FreeVarsResult { telescope, subst, args } =
+        free_vars(&exp, &vars).union(free_vars(&typ, &vars)).telescope(&levels);
+
+    // Substitute the new parameters for the free variables
+    let body = exp.subst(&mut levels.clone(), &subst.in_body());
+    let typ = typ.subst(&mut levels.clone(), &subst.in_body());
+
+    let name = unique_let_name(module);
+
+    let mut new_decl = Let {
+        span: None,
+        doc: None,
+        name: name.clone(),
+        attr: Attributes { attrs: vec![Attribute::Transparent] },
+        params: telescope,
+        typ,
+        body,
+    };
+    new_decl.rename();
+
+    // Replace the selected expression by a call of the new let binding
+    let call = Exp::Call(Call {
+        span: None,
+        kind: CallKind::LetBound,
+        name: IdBound { span: None, id: name.id.clone(), uri: module.uri.clone() },
+        args,
+        inferred_type: None,
+    });
+
+    Some(ExtractResult { decl_span: decl.span(), new_decl, call })
+}
+
+/// Result of extracting an expression
+pub struct ExtractResult {
+    /// Span of the declaration which contains the extracted expression
+    pub decl_span: Option<Span>,
+    /// The new top-level let binding
+    pub new_decl: Let,
+    /// The call of the new let binding which replaces the extracted expression
+    pub call: Exp,
+}
+
+/// Generate a name for the extracted let binding which is not yet used in the module
+fn unique_let_name(module: &Module) -> IdBind {
+    let is_used = |id: &str| {
+        module
+            .lookup_decl(&IdBound { span: None, id: id.to_owned(), uri: module.uri.clone() })
+            .is_some()
+    };
+    let id = (0..)
+        .map(|i| if i == 0 { "extracted".to_owned() } else { format!("extracted{i}") })
+        .find(|id| !is_used(id))
+        .unwrap();
+    IdBind::from_string(&id)
+}
+
+struct Ctx {
+    /// The span of the expression which should be extracted
+    span: Span,
+    /// Typing of the bound variables in scope
+    vars: TypeCtx,
+}
+
+/// The expression that has been found, together with its context
+struct Found {
+    exp: Box<Exp>,
+    vars: TypeCtx,
+}
+
+impl BindContext for Ctx {
+    type Ctx = TypeCtx;
+
+    fn ctx_mut(&mut self) -> &mut Self::Ctx {
+        &mut self.vars
+    }
+}
+
+/// Find the expression at the span of the [Ctx] together with the typing context in which it
+/// occurs, which is not recorded by the elaborator
+trait Extract {
+    fn extract(&self, ctx: &mut Ctx) -> Option<Found>;
+}
+
+impl<T: Extract> Extract for Box<T> {
+    fn extract(&self, ctx: &mut Ctx) -> Option<Found> {
+        (**self).extract(ctx)
+    }
+}
+
+impl<T: Extract> Extract for Option<T> {
+    fn extract(&self, ctx: &mut Ctx) -> Option<Found> {
+        self.as_ref().and_then(|x| x.extract(ctx))
+    }
+}
+
+impl<T: Extract> Extract for Vec<T> {
+    fn extract(&self, ctx: &mut Ctx) -> Option<Found> {
+        self.iter().find_map(|x| x.extract(ctx))
+    }
+}
+
+fn param_binders(params: &Telescope) -> Vec<Binder> {
+    params
+        .params
+        .iter()
+        .map(|Param { name, typ, .. }| Binder { name: name.clone(), typ: typ.clone(), value: None })
+        .collect()
+}
+
+impl Extract for Def {
+    fn extract(&self, ctx: &mut Ctx) -> Option<Found> {
+        let Def { params, cases, .. } = self;
+        ctx.bind_iter(param_binders(params).iter(), |ctx| cases.extract(ctx))
+    }
+}
+
+impl Extract for Codef {
+    fn extract(&self, ctx: &mut Ctx) -> Option<Found> {
+        let Codef { params, cases, .. } = self;
+        ctx.bind_iter(param_binders(params).iter(), |ctx| cases.extract(ctx))
+    }
+}
+
+impl Extract for Let {
+    fn extract(&self, ctx: &mut Ctx) -> Option<Found> {
+        let Let { params, body, .. } = self;
+        ctx.bind_iter(param_binders(params).iter(), |ctx| body.extract(ctx))
+    }
+}
+
+impl Extract for Case {
+    fn extract(&self, ctx: &mut Ctx) -> Option<Found> {
+        let Case { pattern, body, .. } = self;
+        // After elaboration, every pattern parameter is annotated with its normalized type
+        let binders = pattern
+            .params
+            .params
+            .iter()
+            .map(|ParamInst { name, info, .. }| {
+                Some(Binder { name: name.clone(), typ: info.clone()?, value: None })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        ctx.bind_iter(binders.iter(), |ctx| body.extract(ctx))
+    }
+}
+
+impl Extract for Args {
+    fn extract(&self, ctx: &mut Ctx) -> Option<Found> {
+        self.args.iter().find_map(|arg| match arg {
+            Arg::UnnamedArg(exp) | Arg::NamedArg(_, exp) => exp.extract(ctx),
+            Arg::InsertedImplicitArg(_) => None,
+        })
+    }
+}
+
+impl Extract for Exp {
+    fn extract(&self, ctx: &mut Ctx) -> Option<Found> {
+        if self.span() == Some(ctx.span) {
+            return Some(Found { exp: Box::new(self.clone()), vars: ctx.vars.clone() });
+        }
+
+        match self {
+            Exp::Variable(_) | Exp::TypeUniv(_) | Exp::Hole(_) => None,
+            Exp::TypCtor(TypCtor { args, .. }) | Exp::Call(Call { args, .. }) => args.extract(ctx),
+            Exp::DotCall(DotCall { exp, args, .. }) => {
+                exp.extract(ctx).or_else(|| args.extract(ctx))
+            }
+            Exp::Anno(Anno { exp, typ, .. }) => exp.extract(ctx).or_else(|| typ.extract(ctx)),
+            Exp::LocalMatch(LocalMatch { on_exp, cases, .. }) => {
+                on_exp.extract(ctx).or_else(|| cases.extract(ctx))
+            }
+            Exp::LocalComatch(LocalComatch { cases, .. }) => cases.extract(ctx),
+            Exp::LocalLet(LocalLet { name, typ, bound, body, bound_type, .. }) => {
+                typ.extract(ctx).or_else(|| bound.extract(ctx)).or_else(|| {
+                    // After elaboration, the type of the bound expression is annotated
+                    let binder = Binder {
+                        name: name.clone(),
+                        typ: bound_type.clone()?,
+                        value: Some(bound.clone()),
+                    };
+                    ctx.bind_single(&shift_and_clone(&binder, (1, 0)), |ctx| body.extract(ctx))
+                })
+            }
+        }
+    }
+}
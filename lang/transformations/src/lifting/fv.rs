@@ -5,7 +5,8 @@ use derivative::Derivative;
 use ast::ctx::values::TypeCtx;
 use ast::ctx::*;
 use ast::*;
-use ast::{Hole, Occurs, TypeUniv, Variable};
+use ast::{Hole, TypeUniv, Variable};
+use elaborator::normalizer::occurs::occurs_lvl;
 
 /// Find all free variables
 pub fn free_vars<T: FV>(arg: &T, ctx: &TypeCtx) -> FreeVars {
@@ -61,6 +62,11 @@ impl FV for Exp {
                 on_exp.visit_fv(v);
                 motive.visit_fv(v)
             }
+            Exp::LocalLet(LocalLet { typ, bound, body, .. }) => {
+                typ.visit_fv(v);
+                bound.visit_fv(v);
+                v.bind_single((), |v| body.visit_fv(v))
+            }
         }
     }
 }
@@ -194,8 +200,13 @@ impl FreeVars {
 
             let typ = typ.subst(&mut ctx, &subst.in_param());
 
-            let param =
-                Param { implicit: false, name: VarBind::from_string(&name), typ: typ.clone() };
+            let param = Param {
+                implicit: false,
+                lazy: false,
+                name: VarBind::from_string(&name),
+                typ: typ.clone(),
+                default: None,
+            };
             let arg = Arg::UnnamedArg(Box::new(Exp::Variable(Variable {
                 span: None,
                 idx: base_ctx.lvl_to_idx(fv.lvl),
@@ -284,8 +295,8 @@ impl PartialOrd for FreeVar {
 
 impl Ord for FreeVar {
     fn cmp(&self, other: &Self) -> cmp::Ordering {
-        let self_occurs_in_other = other.typ.occurs(&mut other.ctx.clone(), self.lvl);
-        let other_occurs_in_self = self.typ.occurs(&mut self.ctx.clone(), other.lvl);
+        let self_occurs_in_other = occurs_lvl(&other.ctx, self.lvl, &other.typ);
+        let other_occurs_in_self = occurs_lvl(&self.ctx, other.lvl, &self.typ);
         assert!(!(self_occurs_in_other && other_occurs_in_self));
         if self_occurs_in_other {
             cmp::Ordering::Less
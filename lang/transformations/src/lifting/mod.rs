@@ -10,8 +10,10 @@ use ast::*;
 
 use crate::Rename;
 
+mod extract;
 mod fv;
 
+pub use extract::*;
 use fv::*;
 
 /// Lift local (co)matches for `name` in `module` to top-level (co)definitions
@@ -83,7 +85,7 @@ impl Lift for Module {
     type Target = Module;
 
     fn lift(&self, ctx: &mut Ctx) -> Self::Target {
-        let Module { uri, use_decls, decls, meta_vars } = self;
+        let Module { uri, use_decls, decls, meta_vars, comments } = self;
 
         let decls = decls.iter().map(|decl| decl.lift(ctx)).collect();
 
@@ -92,6 +94,7 @@ impl Lift for Module {
             use_decls: use_decls.clone(),
             decls,
             meta_vars: meta_vars.clone(),
+            comments: comments.clone(),
         }
     }
 }
@@ -169,14 +172,23 @@ impl Lift for Dtor {
     type Target = Dtor;
 
     fn lift(&self, ctx: &mut Ctx) -> Self::Target {
-        let Dtor { span, doc, name, params, self_param, ret_typ } = self;
+        let Dtor { span, doc, name, params, self_param, ret_typ, default } = self;
 
         params.lift_telescope(ctx, |ctx, params| {
+            let default = default.lift(ctx);
             let (self_param, ret_typ) = self_param.lift_telescope(ctx, |ctx, self_param| {
                 let ret_typ = ret_typ.lift(ctx);
                 (self_param, ret_typ)
             });
-            Dtor { span: *span, doc: doc.clone(), name: name.clone(), params, self_param, ret_typ }
+            Dtor {
+                span: *span,
+                doc: doc.clone(),
+                name: name.clone(),
+                params,
+                self_param,
+                ret_typ,
+                default,
+            }
         })
     }
 }
@@ -288,6 +300,7 @@ impl Lift for Exp {
             Exp::Hole(e) => e.lift(ctx).into(),
             Exp::LocalMatch(e) => e.lift(ctx),
             Exp::LocalComatch(e) => e.lift(ctx),
+            Exp::LocalLet(e) => e.lift(ctx),
         }
     }
 }
@@ -368,10 +381,11 @@ impl Lift for Hole {
     type Target = Hole;
 
     fn lift(&self, _ctx: &mut Ctx) -> Self::Target {
-        let Hole { span, kind, metavar, args, .. } = self;
+        let Hole { span, kind, name, metavar, args, .. } = self;
         Hole {
             span: *span,
             kind: *kind,
+            name: name.clone(),
             metavar: *metavar,
             inferred_type: None,
             inferred_ctx: None,
@@ -416,6 +430,23 @@ impl Lift for LocalComatch {
         )
     }
 }
+impl Lift for LocalLet {
+    type Target = Exp;
+
+    fn lift(&self, ctx: &mut Ctx) -> Self::Target {
+        let LocalLet { span, name, typ, bound, body, .. } = self;
+        Exp::LocalLet(LocalLet {
+            span: *span,
+            name: name.clone(),
+            typ: typ.lift(ctx),
+            bound: bound.lift(ctx),
+            body: ctx.bind_single((), |ctx| body.lift(ctx)),
+            bound_type: None,
+            inferred_type: None,
+        })
+    }
+}
+
 impl Lift for Motive {
     type Target = Motive;
 
@@ -490,9 +521,15 @@ impl Lift for Param {
     type Target = Param;
 
     fn lift(&self, ctx: &mut Ctx) -> Self::Target {
-        let Param { implicit, name, typ } = self;
+        let Param { implicit, lazy, name, typ, default } = self;
 
-        Param { implicit: *implicit, name: name.clone(), typ: typ.lift(ctx) }
+        Param {
+            implicit: *implicit,
+            lazy: *lazy,
+            name: name.clone(),
+            typ: typ.lift(ctx),
+            default: default.lift(ctx),
+        }
     }
 }
 
@@ -579,7 +616,7 @@ impl Ctx {
         let cases = cases.subst(&mut self.ctx, &subst.in_body());
         let self_typ = self_typ.subst(&mut self.ctx, &subst.in_body());
         let def_ret_typ = match &motive {
-            Some(m) => m.lift(self).subst(&mut self.ctx, &subst.in_body()).ret_typ,
+            Some(m) => m.lift(self).subst(&mut self.ctx, &subst.in_body()).ret_typ.unwrap(),
             None => shift_and_clone(
                 &ret_typ.clone().unwrap().lift(self).subst(&mut self.ctx, &subst.in_body()),
                 (1, 0),
@@ -89,6 +89,7 @@ impl Rename for Dtor {
         self.params.rename_in_ctx(ctx);
         ctx.bind_iter(self.params.params.clone().into_iter(), |new_ctx| {
             self.self_param.rename_in_ctx(new_ctx);
+            self.default.rename_in_ctx(new_ctx);
 
             new_ctx.bind_single(self.self_param.clone(), |new_ctx| {
                 self.ret_typ.rename_in_ctx(new_ctx);
@@ -151,6 +152,7 @@ impl Rename for Telescope {
 impl Rename for Param {
     fn rename_in_ctx(&mut self, ctx: &mut Ctx) {
         self.typ.rename_in_ctx(ctx);
+        self.default.rename_in_ctx(ctx);
         self.name = ctx.disambiguate_name(self.name.clone());
     }
 }
@@ -200,6 +202,7 @@ impl Rename for Exp {
             Exp::TypeUniv(e) => e.rename_in_ctx(ctx),
             Exp::Call(e) => e.rename_in_ctx(ctx),
             Exp::LocalMatch(e) => e.rename_in_ctx(ctx),
+            Exp::LocalLet(e) => e.rename_in_ctx(ctx),
             Exp::DotCall(e) => e.rename_in_ctx(ctx),
         }
     }
@@ -256,6 +259,19 @@ impl Rename for LocalMatch {
     }
 }
 
+impl Rename for LocalLet {
+    fn rename_in_ctx(&mut self, ctx: &mut Ctx) {
+        self.typ.rename_in_ctx(ctx);
+        self.bound.rename_in_ctx(ctx);
+        self.bound_type.rename_in_ctx(ctx);
+        self.inferred_type.rename_in_ctx(ctx);
+        self.name = ctx.disambiguate_name(self.name.clone());
+        ctx.bind_single(self.name.clone(), |new_ctx| {
+            self.body.rename_in_ctx(new_ctx);
+        })
+    }
+}
+
 impl Rename for TypCtor {
     fn rename_in_ctx(&mut self, ctx: &mut Ctx) {
         self.args.rename_in_ctx(ctx);
@@ -70,6 +70,12 @@ impl Ctx {
     }
 }
 
+impl ContextElem<Ctx> for VarBind {
+    fn as_element(&self) -> <Ctx as Context>::Elem {
+        self.to_owned()
+    }
+}
+
 impl ContextElem<Ctx> for Param {
     fn as_element(&self) -> <Ctx as Context>::Elem {
         self.name.to_owned()
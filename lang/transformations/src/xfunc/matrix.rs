@@ -175,6 +175,25 @@ impl BuildMatrix for ast::Codef {
 }
 
 impl XData {
+    /// The slice of the matrix which consists of the row or column of the constructor or
+    /// destructor `name` only, or `None` if the type has no such constructor or destructor
+    pub fn slice(&self, name: &str) -> Option<XData> {
+        let XData { ctors, dtors, exprs, .. } = self;
+        let (ctors, dtors) = if let Some(ctor) = ctors.get(name) {
+            (HashMap::from_iter([(name.to_owned(), ctor.clone())]), dtors.clone())
+        } else if let Some(dtor) = dtors.get(name) {
+            (ctors.clone(), HashMap::from_iter([(name.to_owned(), dtor.clone())]))
+        } else {
+            return None;
+        };
+        let exprs = exprs
+            .iter()
+            .filter(|(key, _)| key.ctor == name || key.dtor == name)
+            .map(|(key, body)| (key.clone(), body.clone()))
+            .collect();
+        Some(XData { ctors, dtors, exprs, ..self.clone() })
+    }
+
     pub fn as_data(&self, uri: &Url) -> (ast::Data, Vec<ast::Def>) {
         let XData { name, doc, typ, ctors, dtors, exprs, .. } = self;
 
@@ -4,7 +4,7 @@ use thiserror::Error;
 #[derive(Error, Diagnostic, Debug, Clone)]
 pub enum XfuncError {
     #[error("An unexpected internal error occurred: {message}")]
-    #[diagnostic(code("E-XXX"))]
+    #[diagnostic(code("X-000"))]
     /// This error should not occur.
     /// Some internal invariant has been violated.
     Impossible {
@@ -12,4 +12,56 @@ pub enum XfuncError {
         #[label]
         span: Option<SourceSpan>,
     },
+    #[error("{name} cannot be moved out of its where clause, since {name} is already defined")]
+    #[diagnostic(code("X-001"))]
+    WhereDeclClash {
+        name: String,
+        #[label]
+        span: Option<SourceSpan>,
+    },
+    #[error("{name} of {type_name} cannot be xfunctionalized individually")]
+    #[diagnostic(
+        code("X-002"),
+        help("Only constructors of data types and destructors of codata types are supported")
+    )]
+    NotAnXtor {
+        name: String,
+        type_name: String,
+        #[label]
+        span: Option<SourceSpan>,
+    },
+    #[error("Undefined type {name}")]
+    #[diagnostic(code("X-003"))]
+    UndefinedType { name: String },
+    #[error("{name} is not a data or codata type")]
+    #[diagnostic(code("X-004"), help("Only data and codata types can be xfunctionalized"))]
+    NotAType {
+        name: String,
+        #[label]
+        span: Option<SourceSpan>,
+    },
+    #[error("{name} has no constructors or destructors")]
+    #[diagnostic(code("X-005"))]
+    NoMembers {
+        name: String,
+        #[label]
+        span: Option<SourceSpan>,
+    },
+}
+
+impl XfuncError {
+    /// The codes of all variants, together with the templates of their messages
+    ///
+    /// This list must be extended whenever a variant is added, see `driver::error_index`.
+    pub const CODES: &'static [(&'static str, &'static str)] = &[
+        ("X-000", "An unexpected internal error occurred: {message}"),
+        (
+            "X-001",
+            "{name} cannot be moved out of its where clause, since {name} is already defined",
+        ),
+        ("X-002", "{name} of {type_name} cannot be xfunctionalized individually"),
+        ("X-003", "Undefined type {name}"),
+        ("X-004", "{name} is not a data or codata type"),
+        ("X-005", "{name} has no constructors or destructors"),
+    ];
 }
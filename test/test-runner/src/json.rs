@@ -0,0 +1,114 @@
+//! Machine-readable output of the test runner
+//!
+//! With `--format json`, the runner prints one JSON object per executed testcase on its own line
+//! (newline-delimited JSON), instead of the human-readable report.
+
+use serde_derive::Serialize;
+
+use crate::phases::{render_report, Failure};
+use crate::runner::CaseResult;
+
+#[derive(Serialize)]
+struct CaseReport<'a> {
+    suite: &'a str,
+    name: &'a str,
+    path: String,
+    status: Status,
+    /// The time it took to run the testcase in milliseconds
+    duration_ms: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    failure: Option<FailureReport<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Pass,
+    Fail,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum FailureReport<'a> {
+    Mismatch { expected: &'a str, actual: &'a str, diff: Vec<DiffLine<'a>> },
+    ExpectedFailure { got: &'a str },
+    ExpectedSuccess { got: String },
+    Panic { msg: &'a str },
+}
+
+/// One line of a line-based diff from the expected to the actual output
+#[derive(Serialize)]
+struct DiffLine<'a> {
+    op: DiffOp,
+    line: &'a str,
+}
+
+#[derive(Serialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+enum DiffOp {
+    /// The line is present in both the expected and the actual output
+    Equal,
+    /// The line is only present in the expected output
+    Remove,
+    /// The line is only present in the actual output
+    Add,
+}
+
+/// Render the result of a testcase as a single line of JSON
+pub fn case_report(suite: &str, case_result: &CaseResult) -> String {
+    let CaseResult { case, result, duration, .. } = case_result;
+    let failure = result.as_ref().err().map(|failure| match failure {
+        Failure::Mismatch { expected, actual } => {
+            FailureReport::Mismatch { expected, actual, diff: diff(expected, actual) }
+        }
+        Failure::ExpectedFailure { got } => FailureReport::ExpectedFailure { got },
+        Failure::ExpectedSuccess { got } => {
+            FailureReport::ExpectedSuccess { got: render_report(got, false) }
+        }
+        Failure::Panic { msg } => FailureReport::Panic { msg },
+    });
+    let report = CaseReport {
+        suite,
+        name: &case.name,
+        path: case.path.display().to_string(),
+        status: if failure.is_some() { Status::Fail } else { Status::Pass },
+        duration_ms: duration.as_secs_f64() * 1000.0,
+        failure,
+    };
+    serde_json::to_string(&report).expect("Failed to serialize test report")
+}
+
+/// Compute a line-based diff from `expected` to `actual` via their longest common subsequence
+fn diff<'a>(expected: &'a str, actual: &'a str) -> Vec<DiffLine<'a>> {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+
+    // lcs[i][j] is the length of the longest common subsequence of expected[i..] and actual[j..]
+    let mut lcs = vec![vec![0usize; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            lcs[i][j] = if expected[i] == actual[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            out.push(DiffLine { op: DiffOp::Equal, line: expected[i] });
+            i += 1;
+            j += 1;
+        } else if j < actual.len() && (i == expected.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            out.push(DiffLine { op: DiffOp::Add, line: actual[j] });
+            j += 1;
+        } else {
+            out.push(DiffLine { op: DiffOp::Remove, line: expected[i] });
+            i += 1;
+        }
+    }
+    out
+}
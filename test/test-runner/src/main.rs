@@ -1,9 +1,11 @@
 mod index;
+mod json;
 mod phases;
 mod runner;
 mod suites;
+mod watch;
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 
 pub const TEST_SUITES_PATH: &str = "../suites";
 pub const EXAMPLES_PATH: &str = "../../examples";
@@ -17,6 +19,21 @@ pub struct Args {
     filter: Option<String>,
     #[clap(long, num_args = 0)]
     update_expected: bool,
+    /// Number of testcases which are run in parallel (defaults to the number of available cores)
+    #[clap(long, short)]
+    jobs: Option<usize>,
+    /// Print the N slowest testcases (10 if N is omitted) and the time spent per phase
+    #[clap(long, value_name = "N", num_args = 0..=1, default_missing_value = "10")]
+    timings: Option<usize>,
+    /// Only check that printing a module and parsing the output again yields the same module
+    #[clap(long)]
+    roundtrip: bool,
+    /// Run the testcases again whenever the testsuites, examples or standard library change
+    #[clap(long, conflicts_with = "update_expected")]
+    watch: bool,
+    /// Format in which the results are printed
+    #[clap(long, value_enum, default_value_t = Format::Human)]
+    format: Format,
     /// Enable trace logging
     #[clap(long)]
     trace: bool,
@@ -25,6 +42,14 @@ pub struct Args {
     debug: bool,
 }
 
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    /// Human-readable report, grouped by testsuite
+    Human,
+    /// One JSON object per testcase and line
+    Json,
+}
+
 fn main() {
     let args = Args::parse();
 
@@ -42,14 +67,30 @@ fn main() {
 
     builder.init();
 
+    if args.watch {
+        if let Err(err) = watch::watch(&args) {
+            eprintln!("{err}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
     let runner =
         runner::Runner::load(crate::TEST_SUITES_PATH, crate::EXAMPLES_PATH, crate::STDLIB_PATH);
-    let mut res = runner.run(&args);
+    let res = runner.run(&args);
     if args.update_expected {
         res.update_expected();
         println!("Updated expected outputs.");
     } else {
-        res.print();
+        match args.format {
+            Format::Human => {
+                res.print();
+                if let Some(n) = args.timings {
+                    res.print_timings(n);
+                }
+            }
+            Format::Json => res.print_json(),
+        }
     }
     if !res.success() {
         std::process::exit(1);
@@ -1,9 +1,11 @@
+use std::any::Any;
 use std::error::Error;
 use std::fmt;
 use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use driver::{Database, FileSource, FileSystemSource, InMemorySource};
+use driver::{Database, DriverError, FileSource, FileSystemSource, InMemorySource};
 use url::Url;
 
 use parser::cst;
@@ -16,6 +18,10 @@ use crate::{
 /// Terminal width for pretty-printing error messages.
 const TERMINAL_WIDTH: usize = 200;
 
+/// Number of evaluation steps each normalization may take.
+/// This is much stricter than the default, such that non-terminating testcases fail quickly.
+const FUEL: u64 = 10_000;
+
 pub trait Phase {
     type Out: TestOutput;
 
@@ -35,12 +41,16 @@ pub struct PartialRun<O> {
     result: Result<O, PhasesError>,
     /// A textual report about all the previously run phases.
     report_phases: Vec<PhaseReport>,
+    /// The point in time at which the run was started.
+    started: Instant,
 }
 
 #[allow(dead_code)]
 pub struct PhaseReport {
     pub name: &'static str,
     pub output: String,
+    /// The time it took to run the phase.
+    pub duration: Duration,
 }
 
 impl PartialRun<()> {
@@ -49,8 +59,15 @@ impl PartialRun<()> {
         let mut source = InMemorySource::new();
         source.insert(case.uri(), case.content().unwrap());
         let source = source.fallback_to(FileSystemSource::new(&case.path));
-        let database = Database::from_source(source);
-        PartialRun { case, database, result: Ok(()), report_phases: vec![] }
+        let mut database = Database::from_source(source);
+        database.eval_config.fuel = Some(FUEL);
+        PartialRun {
+            case,
+            database,
+            result: Ok(()),
+            report_phases: vec![],
+            started: Instant::now(),
+        }
     }
 }
 
@@ -86,17 +103,22 @@ where
                 // Run the phase and catch any panics that might occur.
                 // We need to use `AssertUnwindSafe` because the compiler can not automatically
                 // guarantee that passing mutable references across a catch_unwind boundary is safe.
+                let phase_started = Instant::now();
                 let run_result = catch_unwind(AssertUnwindSafe(|| {
                     tokio::runtime::Runtime::new()
                         .unwrap()
                         .block_on(P::run(&mut self.database, &self.case.uri()))
                 }));
+                let duration = phase_started.elapsed();
 
                 match run_result {
                     Ok(Ok(out2)) => {
                         // There was no panic and `run` returned with a result.
-                        self.report_phases
-                            .push(PhaseReport { name: phase.name(), output: out2.test_output() });
+                        self.report_phases.push(PhaseReport {
+                            name: phase.name(),
+                            output: out2.test_output(),
+                            duration,
+                        });
                         if !expect_success {
                             return Err(PhasesError::ExpectedFailure { got: out2.test_output() });
                         }
@@ -113,8 +135,11 @@ where
                             .unwrap()
                             .block_on(pretty_error(&mut self.database, &self.case.uri(), err));
                         // There was no panic and `run` returned with an error.
-                        self.report_phases
-                            .push(PhaseReport { name: phase.name(), output: report.to_string() });
+                        self.report_phases.push(PhaseReport {
+                            name: phase.name(),
+                            output: report.to_string(),
+                            duration,
+                        });
                         if expect_success {
                             return Err(PhasesError::ExpectedSuccess { got: report });
                         }
@@ -131,8 +156,9 @@ where
                         self.report_phases.push(PhaseReport {
                             name: phase.name(),
                             output: "Panic occurred".to_string(),
+                            duration,
                         });
-                        Err(PhasesError::Panic { msg: err.downcast::<&str>().unwrap().to_string() })
+                        Err(PhasesError::Panic { msg: panic_message(err) })
                     }
                 }
             });
@@ -142,6 +168,7 @@ where
             case: self.case,
             result,
             report_phases: self.report_phases,
+            started: self.started,
         }
     }
 
@@ -157,7 +184,20 @@ where
             Err(PhasesError::Panic { msg }) => Err(Failure::Panic { msg }),
         };
 
-        CaseResult { result, case: self.case }
+        let phase_durations =
+            self.report_phases.iter().map(|phase| (phase.name, phase.duration)).collect();
+        CaseResult { result, case: self.case, duration: self.started.elapsed(), phase_durations }
+    }
+}
+
+/// Extract the message from the payload of a caught panic
+pub fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<&str>() {
+        Ok(msg) => msg.to_string(),
+        Err(payload) => match payload.downcast::<String>() {
+            Ok(msg) => *msg,
+            Err(_) => "Unknown panic payload".to_owned(),
+        },
     }
 }
 
@@ -330,6 +370,53 @@ impl Phase for Print {
     }
 }
 
+// Roundtrip Phase
+//
+// This phase prettyprints a module, parses and lowers the output again, and checks
+// that the result is structurally equal to the original module, ignoring source locations.
+// Both modules are compared in the normalized form in which they are printed, so that
+// syntactic sugar which is expanded during lowering does not cause spurious differences.
+
+pub struct Roundtrip {
+    name: &'static str,
+}
+
+impl Phase for Roundtrip {
+    type Out = String;
+
+    fn new(name: &'static str) -> Self {
+        Self { name }
+    }
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    async fn run(db: &mut Database, uri: &Url) -> Result<Self::Out, driver::Error> {
+        let before = db.normalized_ust(uri).await?;
+        let output = db.print_to_string(uri).await?;
+        db.write_source(uri, &output).await?;
+        let after = db.normalized_ust(uri).await?;
+
+        if before.use_decls != after.use_decls || before.decls.len() != after.decls.len() {
+            return Err(DriverError::Impossible(format!(
+                "The printed module has different declarations:\n{output}"
+            ))
+            .into());
+        }
+        if let Some((decl, _)) =
+            before.decls.iter().zip(after.decls.iter()).find(|(before, after)| before != after)
+        {
+            return Err(DriverError::Impossible(format!(
+                "Printing and parsing {} yields a different declaration:\n{output}",
+                decl.ident().id
+            ))
+            .into());
+        }
+        Ok(output)
+    }
+}
+
 // Xfunc Phase
 //
 // This phase runs xfunctionalization on each type in the module, and tests
@@ -366,7 +453,7 @@ impl Phase for Xfunc {
         db.source.manage(&new_uri);
 
         for type_name in type_names.iter().map(|tn| &tn.id) {
-            let xfunc_out = db.xfunc(uri, type_name).await?;
+            let xfunc_out = db.xfunc(uri, type_name, None).await?;
             let new_source = db.edited(uri, xfunc_out.edits);
             db.write_source(&new_uri, &new_source.to_string()).await?;
             db.ast(&new_uri).await.map_err(|err| {
@@ -447,7 +534,7 @@ async fn pretty_error(db: &mut Database, uri: &Url, err: driver::Error) -> miett
     miette_error.with_source_code(miette::NamedSource::new(filename, source.to_owned()))
 }
 
-fn render_report(report: &miette::Report, colorize: bool) -> String {
+pub fn render_report(report: &miette::Report, colorize: bool) -> String {
     let theme = if colorize {
         miette::GraphicalTheme::unicode()
     } else {
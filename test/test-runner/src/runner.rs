@@ -1,5 +1,11 @@
 use std::io::Write;
+use std::num::NonZeroUsize;
+use std::panic::catch_unwind;
 use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
 
 use ast::HashMap;
 use printer::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
@@ -7,6 +13,7 @@ use printer::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
 use crate::Args;
 
 use super::index::Index;
+use super::json;
 use super::phases::*;
 use super::suites::{self, Case, Suite};
 
@@ -22,6 +29,10 @@ pub struct Runner {
 /// the `--filter` option was not passed on the command line.
 const ALL_GLOB: &str = "*";
 
+/// Stack size of the worker threads.
+/// Evaluation is recursive, so a testcase which exhausts its fuel may be deeply nested.
+const STACK_SIZE: usize = 256 * 1024 * 1024;
+
 /// Create a search index for all the testsuites
 fn create_index(suites: &HashMap<String, Suite>) -> Index {
     let mut index = Index::new();
@@ -56,23 +67,16 @@ impl Runner {
     }
 
     /// Run all the testsuites and compute the combined result.
+    ///
+    /// The testcases are distributed over `args.jobs` worker threads.
+    /// The results are reported in a deterministic order, independent of the order
+    /// in which the testcases complete.
     pub fn run(&self, args: &Args) -> RunResult {
-        let mut executed_cases: u32 = 0;
-        let mut failed_cases: u32 = 0;
-        let mut results: Vec<SuiteResult> = vec![];
-
-        for suite in self.suites.values() {
-            let result = self.run_suite(args, suite);
-
-            executed_cases += result.executed_cases;
-            failed_cases += result.failed_cases;
-            results.push(result);
-        }
-        RunResult { results, executed_cases, failed_cases }
+        self.run_selected(args, |_| true)
     }
 
-    /// Run one individual testsuite
-    pub fn run_suite(&self, args: &Args, suite: &suites::Suite) -> SuiteResult {
+    /// Run the testcases which match the `filter` of `args` and are selected by `selected`
+    pub fn run_selected(&self, args: &Args, selected: impl Fn(&Case) -> bool) -> RunResult {
         // We first have to filter out those cases which should not be run.
         let search_string = match &args.filter {
             None => ALL_GLOB,
@@ -80,33 +84,57 @@ impl Runner {
         };
         let matching_cases: Vec<Case> = self.index.searcher().search(search_string).collect();
 
+        let mut sorted_suites: Vec<&Suite> = self.suites.values().collect();
+        sorted_suites.sort_by(|x, y| x.name.cmp(&y.name));
+        let suite_cases: Vec<Vec<&Case>> = sorted_suites
+            .iter()
+            .map(|suite| {
+                suite
+                    .cases
+                    .iter()
+                    .filter(|case| matching_cases.contains(case) && selected(case))
+                    .collect()
+            })
+            .collect();
+
+        let jobs: Vec<(&suites::Config, &Case)> = sorted_suites
+            .iter()
+            .zip(suite_cases.iter())
+            .flat_map(|(suite, cases)| cases.iter().map(|case| (&suite.config, *case)))
+            .collect();
+        let num_threads = args.jobs.unwrap_or_else(default_jobs);
+        let started = Instant::now();
+        let mut case_results = run_parallel(num_threads, &jobs, args.roundtrip).into_iter();
+
         let mut executed_cases: u32 = 0;
         let mut failed_cases: u32 = 0;
-        let mut results: Vec<CaseResult> = vec![];
-
-        for case in &suite.cases {
-            if !matching_cases.contains(case) {
-                continue;
-            }
+        let mut results: Vec<SuiteResult> = vec![];
 
-            let case_result = self.run_case(&suite.config, case);
+        for (suite, cases) in sorted_suites.into_iter().zip(suite_cases) {
+            let results_for_suite: Vec<CaseResult> =
+                case_results.by_ref().take(cases.len()).collect();
+            let result = SuiteResult::new(suite.clone(), results_for_suite);
 
-            executed_cases += 1;
-            if case_result.result.is_err() {
-                failed_cases += 1;
-            }
-            results.push(case_result);
+            executed_cases += result.executed_cases;
+            failed_cases += result.failed_cases;
+            results.push(result);
         }
-        SuiteResult { suite: suite.clone(), results, executed_cases, failed_cases }
+        RunResult { results, executed_cases, failed_cases, wall_time: started.elapsed() }
     }
 
     /// Run one individual testcase within a testsuite
-    pub fn run_case(&self, config: &suites::Config, case: &Case) -> CaseResult {
-        PartialRun::start(case.clone())
+    ///
+    /// If `roundtrip` is set, only the phases up to lowering are run, followed by a check
+    /// that the prettyprinted module is parsed to a structurally equal module.
+    pub fn run_case(config: &suites::Config, case: &Case, roundtrip: bool) -> CaseResult {
+        let run = PartialRun::start(case.clone())
             .then(config, Parse::new("parse"))
             .then(config, Imports::new("imports"))
-            .then(config, Lower::new("lower"))
-            .then(config, Check::new("check"))
+            .then(config, Lower::new("lower"));
+        if roundtrip {
+            return run.then(config, Roundtrip::new("roundtrip")).report();
+        }
+        run.then(config, Check::new("check"))
             .then(config, Print::new("print"))
             .then(config, Parse::new("reparse"))
             .then(config, Imports::new("reimports"))
@@ -117,6 +145,56 @@ impl Runner {
     }
 }
 
+/// The number of worker threads if the `--jobs` option was not passed on the command line.
+fn default_jobs() -> usize {
+    thread::available_parallelism().map(NonZeroUsize::get).unwrap_or(1)
+}
+
+/// Run the given testcases on `num_threads` worker threads.
+///
+/// The results are returned in the same order as the testcases.
+/// A panic while running a testcase is reported as a failure of that testcase
+/// and does not affect the other testcases.
+fn run_parallel(
+    num_threads: usize,
+    jobs: &[(&suites::Config, &Case)],
+    roundtrip: bool,
+) -> Vec<CaseResult> {
+    let next_job = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<CaseResult>>> = Mutex::new(jobs.iter().map(|_| None).collect());
+
+    thread::scope(|scope| {
+        for _ in 0..num_threads.max(1) {
+            let worker = || loop {
+                let idx = next_job.fetch_add(1, Ordering::Relaxed);
+                let Some((config, case)) = jobs.get(idx) else {
+                    break;
+                };
+                let started = Instant::now();
+                let result = catch_unwind(|| Runner::run_case(config, case, roundtrip))
+                    .unwrap_or_else(|err| CaseResult {
+                        case: (*case).clone(),
+                        result: Err(Failure::Panic { msg: panic_message(err) }),
+                        duration: started.elapsed(),
+                        phase_durations: vec![],
+                    });
+                results.lock().unwrap()[idx] = Some(result);
+            };
+            thread::Builder::new()
+                .stack_size(STACK_SIZE)
+                .spawn_scoped(scope, worker)
+                .expect("Failed to spawn worker thread");
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|result| result.expect("Every testcase is run by some worker"))
+        .collect()
+}
+
 // Run Result
 //
 //
@@ -129,6 +207,8 @@ pub struct RunResult {
     executed_cases: u32,
     /// The number of cases that failed in all testsuites combined.
     failed_cases: u32,
+    /// The wall-clock time it took to run all testsuites.
+    wall_time: Duration,
 }
 
 impl RunResult {
@@ -137,21 +217,80 @@ impl RunResult {
     }
 
     pub fn update_expected(&self) {
-        for CaseResult { case, result } in self.case_results() {
+        for CaseResult { case, result, .. } in self.case_results() {
             if let Err(Failure::Mismatch { ref actual, .. }) = result {
                 case.set_expected(actual);
             }
         }
     }
 
+    /// Print one JSON object per executed testcase to stdout.
+    pub fn print_json(&self) {
+        for suite in &self.results {
+            for case_result in &suite.results {
+                println!("{}", json::case_report(&suite.suite.name, case_result));
+            }
+        }
+    }
+
+    /// Print the `n` slowest testcases, followed by the total time spent in each phase.
+    ///
+    /// The time spent normalizing terms is part of the time of the `check` and `recheck`
+    /// phases, since normalization happens during elaboration.
+    pub fn print_timings(&self, n: usize) {
+        let mut case_results: Vec<(&str, &CaseResult)> = self
+            .results
+            .iter()
+            .flat_map(|suite| suite.results.iter().map(|case| (suite.suite.name.as_str(), case)))
+            .collect();
+        case_results.sort_by(|(_, x), (_, y)| y.duration.cmp(&x.duration));
+
+        println!("Slowest {} testcases:", n.min(case_results.len()));
+        for (suite, CaseResult { case, duration, .. }) in case_results.iter().take(n) {
+            println!("    {:>10.2?}  {}/{}", duration, suite, case.name);
+        }
+        println!();
+
+        // The phases in the order in which they are run
+        let mut phases: Vec<(&str, Duration)> = vec![];
+        for (_, case_result) in case_results.iter() {
+            for (name, duration) in case_result.phase_durations.iter() {
+                match phases.iter_mut().find(|(other, _)| *other == *name) {
+                    Some((_, total)) => *total += *duration,
+                    None => phases.push((*name, *duration)),
+                }
+            }
+        }
+        let cumulative: Duration = case_results.iter().map(|(_, case)| case.duration).sum();
+
+        println!("Time spent per phase (summed over all testcases):");
+        for (name, duration) in phases {
+            println!("    {:>10.2?}  {}", duration, name);
+        }
+        println!("    {:>10.2?}  all phases", cumulative);
+        println!("Wall time: {:.2?}", self.wall_time);
+    }
+
     fn case_results(&self) -> impl Iterator<Item = &CaseResult> {
         self.results.iter().flat_map(|suite_res| suite_res.results.iter())
     }
 
-    pub fn print(&mut self) {
-        for suite in &mut self.results {
+    pub fn print(&self) {
+        for suite in &self.results {
+            suite.print()
+        }
+        self.print_total();
+    }
+
+    /// Print the results of the testsuites of which at least one testcase was executed
+    pub fn print_executed(&self) {
+        for suite in self.results.iter().filter(|suite| suite.executed_cases > 0) {
             suite.print()
         }
+        self.print_total();
+    }
+
+    fn print_total(&self) {
         println!(
             "In total: {}/{} successful",
             self.executed_cases - self.failed_cases,
@@ -177,10 +316,16 @@ pub struct SuiteResult {
 }
 
 impl SuiteResult {
-    pub fn print(&mut self) {
+    pub fn new(suite: Suite, mut results: Vec<CaseResult>) -> Self {
+        results.sort_by(|x, y| x.case.name.cmp(&y.case.name));
+        let executed_cases = results.len() as u32;
+        let failed_cases = results.iter().filter(|x| x.result.is_err()).count() as u32;
+        SuiteResult { suite, results, executed_cases, failed_cases }
+    }
+
+    pub fn print(&self) {
         let SuiteResult { suite, results, executed_cases, failed_cases } = self;
         println!("Suite \"{}\":", suite.name);
-        results.sort_by(|x, y| x.case.name.cmp(&y.case.name));
         results.iter().for_each(|x| x.print());
         println!("{}/{} successful", *executed_cases - *failed_cases, executed_cases);
         println!();
@@ -194,11 +339,15 @@ impl SuiteResult {
 pub struct CaseResult {
     pub case: Case,
     pub result: Result<(), Failure>,
+    /// The time it took to run all phases of the testcase.
+    pub duration: Duration,
+    /// The time it took to run each phase which was run.
+    pub phase_durations: Vec<(&'static str, Duration)>,
 }
 
 impl CaseResult {
     pub fn print(&self) {
-        let CaseResult { case, result } = self;
+        let CaseResult { case, result, .. } = self;
         let mut stdout = StandardStream::stdout(ColorChoice::Auto);
         match result {
             Ok(_) => {
@@ -55,7 +55,7 @@ impl Case {
         fs::write(self.expected_path(), s).unwrap();
     }
 
-    fn expected_path(&self) -> PathBuf {
+    pub fn expected_path(&self) -> PathBuf {
         self.path.parent().unwrap().join(format!("{}.expected", self.name))
     }
 }
@@ -0,0 +1,133 @@
+//! Re-running the testcases whenever the files they consist of change
+//!
+//! The testsuites, the examples and the standard library are watched for changes.
+//! After a change, only the testcases whose source, expected output or imported modules
+//! have changed are run again. Any other change, such as a change of the configuration
+//! of a testsuite, causes all testcases to be run again.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+use ast::HashSet;
+use driver::Database;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+
+use crate::runner::Runner;
+use crate::suites::Case;
+use crate::Args;
+
+/// The time to wait for further changes after a change, such that rapid saves lead to one run
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+type Events = Receiver<notify::Result<Event>>;
+
+/// Run the testcases matching the `filter` of `args` each time a file changes
+///
+/// This only returns if the files can no longer be watched.
+pub fn watch(args: &Args) -> notify::Result<()> {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    for path in [crate::TEST_SUITES_PATH, crate::EXAMPLES_PATH, crate::STDLIB_PATH] {
+        watcher.watch(Path::new(path), RecursiveMode::Recursive)?;
+    }
+
+    // All testcases are run in the first cycle
+    let mut changed: Option<HashSet<PathBuf>> = None;
+    loop {
+        // The testsuites are loaded again, since testcases may have been added or removed
+        let runner =
+            Runner::load(crate::TEST_SUITES_PATH, crate::EXAMPLES_PATH, crate::STDLIB_PATH);
+        let res = match &changed {
+            None => runner.run(args),
+            Some(changed) => runner.run_selected(args, |case| is_affected(case, changed)),
+        };
+
+        clear_screen();
+        if let Some(changed) = &changed {
+            println!("Changed files:");
+            for path in changed {
+                println!("    {}", path.display());
+            }
+            println!();
+        }
+        res.print_executed();
+        println!();
+        println!("Watching for changes...");
+
+        let paths = next_changes(&rx)?;
+        // Changes to files other than modules and expected outputs may affect all testcases
+        let only_testcases = paths.iter().all(|path| {
+            matches!(path.extension().and_then(|ext| ext.to_str()), Some("pol" | "expected"))
+        });
+        changed = only_testcases.then_some(paths);
+    }
+}
+
+/// Wait for the next changes and return the paths of the changed files
+///
+/// Changes which occur within [DEBOUNCE] of each other are combined.
+fn next_changes(events: &Events) -> notify::Result<HashSet<PathBuf>> {
+    let mut paths = HashSet::default();
+    let mut timeout = None;
+    loop {
+        let event = match timeout {
+            None => events.recv().map_err(|_| stopped())?,
+            Some(timeout) => match events.recv_timeout(timeout) {
+                Ok(event) => event,
+                Err(RecvTimeoutError::Timeout) => return Ok(paths),
+                Err(RecvTimeoutError::Disconnected) => return Err(stopped()),
+            },
+        };
+        let Event { kind, paths: event_paths, .. } = event?;
+        // Files which are only accessed have not changed
+        if matches!(kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)) {
+            paths.extend(event_paths.into_iter().map(canonicalize));
+            timeout = Some(DEBOUNCE);
+        }
+    }
+}
+
+fn stopped() -> notify::Error {
+    notify::Error::generic("Stopped watching for changes")
+}
+
+/// Whether the testcase has to be run again after the files in `changed` have changed
+fn is_affected(case: &Case, changed: &HashSet<PathBuf>) -> bool {
+    changed.contains(&canonicalize(case.path.clone()))
+        || changed.contains(&canonicalize(case.expected_path()))
+        || dependencies(case).iter().any(|path| changed.contains(path))
+}
+
+/// The paths of the modules which the testcase imports, directly or indirectly
+fn dependencies(case: &Case) -> HashSet<PathBuf> {
+    let mut db = Database::from_path(&case.path);
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let mut out = HashSet::default();
+    let mut todo = vec![case.uri()];
+    while let Some(uri) = todo.pop() {
+        // The imports of a module with syntax errors are unknown
+        let Ok(deps) = runtime.block_on(db.deps(&uri)) else {
+            continue;
+        };
+        for dep in deps {
+            if let Ok(path) = dep.to_file_path() {
+                if out.insert(canonicalize(path)) {
+                    todo.push(dep);
+                }
+            }
+        }
+    }
+    out
+}
+
+/// The canonical form of `path`, or `path` itself if the file does not exist (anymore)
+fn canonicalize(path: PathBuf) -> PathBuf {
+    path.canonicalize().unwrap_or(path)
+}
+
+fn clear_screen() {
+    print!("\x1B[2J\x1B[1;1H");
+    std::io::stdout().flush().unwrap();
+}